@@ -0,0 +1,51 @@
+//! Defer hydrating a heavy footer until it scrolls into view.
+//!
+//! The server renders the whole page, but the client only hydrates the header and the counter when the page loads. The
+//! footer has hundreds of interactive links, and the client doesn't run any of them until the user scrolls down to it.
+//! Open the performance tab of the browser devtools and compare the scripting time on load with and without the
+//! `DeferHydration` boundary.
+//!
+//! Run with:
+//!
+//! ```sh
+//! dx serve --platform web
+//! ```
+
+use dioxus::prelude::*;
+
+fn app() -> Element {
+    let mut count = use_signal(|| 0);
+
+    rsx! {
+        h1 { "Deferred hydration" }
+        button { onclick: move |_| count += 1, "Clicked {count} times" }
+        // Push the footer below the fold
+        div { style: "height: 150vh", "Scroll down to hydrate the footer" }
+        DeferHydration { on: HydrateOn::Visible, Footer {} }
+    }
+}
+
+#[component]
+fn Footer() -> Element {
+    let mut opened = use_signal(|| None);
+
+    rsx! {
+        footer {
+            if let Some(link) = opened() {
+                p { "Opened link {link}" }
+            }
+            for section in 0..20 {
+                section {
+                    h2 { "Section {section}" }
+                    for link in 0..25 {
+                        button { onclick: move |_| opened.set(Some(section * 25 + link)), "Link {link}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn main() {
+    dioxus::launch(app);
+}
@@ -0,0 +1,65 @@
+//! Upload an avatar image to a server function with a multipart form.
+//!
+//! The form posts directly to the server function, so it works before the page hydrates. The server function reads the
+//! file part as a stream and writes it to disk chunk by chunk instead of buffering the whole image in memory.
+
+use dioxus::prelude::*;
+
+fn app() -> Element {
+    rsx! {
+        h1 { "Upload an avatar" }
+        form {
+            action: "/api/upload_avatar",
+            method: "post",
+            enctype: "multipart/form-data",
+            input { r#type: "text", name: "username", placeholder: "Username" }
+            input { r#type: "file", name: "avatar", accept: "image/*" }
+            button { r#type: "submit", "Upload" }
+        }
+    }
+}
+
+#[server(input = MultipartFormData, endpoint = "upload_avatar")]
+pub async fn upload_avatar(data: MultipartData) -> Result<usize, ServerFnError> {
+    use tokio::io::AsyncWriteExt;
+
+    let Some(mut data) = data.into_inner() else {
+        return Err(ServerFnError::new("Expected a multipart form"));
+    };
+
+    let mut username = None;
+    let mut written = 0;
+    while let Some(mut field) = data.next_field().await? {
+        match field.name() {
+            Some("username") => username = Some(field.text().await?),
+            Some("avatar") => {
+                let username = username
+                    .clone()
+                    .ok_or_else(|| ServerFnError::new("Send the username before the avatar"))?;
+                // Never trust the client with a path
+                let file_name = std::path::Path::new(&username)
+                    .file_name()
+                    .ok_or_else(|| ServerFnError::new("Invalid username"))?
+                    .to_owned();
+
+                tokio::fs::create_dir_all("avatars").await?;
+                let mut file =
+                    tokio::fs::File::create(std::path::Path::new("avatars").join(file_name))
+                        .await?;
+                while let Some(chunk) = field.chunk().await? {
+                    file.write_all(&chunk).await?;
+                    written += chunk.len();
+                }
+                file.flush().await?;
+            }
+            _ => {}
+        }
+    }
+
+    tracing::info!("Saved an avatar with {written} bytes");
+    Ok(written)
+}
+
+fn main() {
+    dioxus::launch(app)
+}
@@ -0,0 +1,90 @@
+use crate::innerlude::*;
+
+/// Describe every node of the rendered tree with the path of components and elements that leads to it, in the order the
+/// nodes appear in the dom.
+///
+/// The server and the client walk their trees with this function to find the first node where hydration diverges.
+pub fn hydration_node_paths(dom: &VirtualDom) -> Vec<String> {
+    hydration_node_paths_skipping(dom, |_| false)
+}
+
+/// Like [`hydration_node_paths`], but leave out the nodes of every scope `skip` returns true for. The server uses this to
+/// leave out content that it renders without hydration markers.
+pub fn hydration_node_paths_skipping(
+    dom: &VirtualDom,
+    skip: impl Fn(&ScopeState) -> bool,
+) -> Vec<String> {
+    let mut paths = Vec::new();
+    walk_scope(dom, dom.base_scope(), &skip, &mut Vec::new(), &mut paths);
+    paths
+}
+
+fn walk_scope(
+    dom: &VirtualDom,
+    scope: &ScopeState,
+    skip: &dyn Fn(&ScopeState) -> bool,
+    path: &mut Vec<&'static str>,
+    paths: &mut Vec<String>,
+) {
+    if skip(scope) {
+        return;
+    }
+    path.push(scope.name());
+    if let Some(node) = scope.try_root_node() {
+        walk_vnode(dom, node, skip, path, paths);
+    }
+    path.pop();
+}
+
+fn walk_vnode(
+    dom: &VirtualDom,
+    vnode: &VNode,
+    skip: &dyn Fn(&ScopeState) -> bool,
+    path: &mut Vec<&'static str>,
+    paths: &mut Vec<String>,
+) {
+    for root in vnode.template.roots.iter() {
+        walk_template_node(dom, vnode, root, skip, path, paths);
+    }
+}
+
+fn walk_template_node(
+    dom: &VirtualDom,
+    vnode: &VNode,
+    node: &TemplateNode,
+    skip: &dyn Fn(&ScopeState) -> bool,
+    path: &mut Vec<&'static str>,
+    paths: &mut Vec<String>,
+) {
+    match node {
+        TemplateNode::Element { tag, children, .. } => {
+            path.push(tag);
+            paths.push(path.join("/"));
+            for child in children.iter() {
+                walk_template_node(dom, vnode, child, skip, path, paths);
+            }
+            path.pop();
+        }
+        TemplateNode::Text { .. } => push_leaf(path, "#text", paths),
+        TemplateNode::Dynamic { id } => match &vnode.dynamic_nodes[*id] {
+            DynamicNode::Text(_) => push_leaf(path, "#text", paths),
+            DynamicNode::Placeholder(_) => push_leaf(path, "#placeholder", paths),
+            DynamicNode::Component(component) => {
+                if let Some(scope) = component.mounted_scope(*id, vnode, dom) {
+                    walk_scope(dom, scope, skip, path, paths);
+                }
+            }
+            DynamicNode::Fragment(nodes) => {
+                for node in nodes {
+                    walk_vnode(dom, node, skip, path, paths);
+                }
+            }
+        },
+    }
+}
+
+fn push_leaf(path: &mut Vec<&'static str>, leaf: &'static str, paths: &mut Vec<String>) {
+    path.push(leaf);
+    paths.push(path.join("/"));
+    path.pop();
+}
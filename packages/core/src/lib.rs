@@ -29,6 +29,7 @@ mod tasks;
 mod virtual_dom;
 
 mod hotreload_utils;
+mod hydration_paths;
 
 /// Items exported from this module are used in macros and should not be used directly.
 #[doc(hidden)]
@@ -45,6 +46,9 @@ pub mod internal {
 
     #[doc(hidden)]
     pub use generational_box;
+
+    #[doc(hidden)]
+    pub use crate::hydration_paths::{hydration_node_paths, hydration_node_paths_skipping};
 }
 
 pub(crate) mod innerlude {
@@ -80,11 +84,11 @@ pub(crate) mod innerlude {
 
 pub use crate::innerlude::{
     fc_to_builder, generation, schedule_update, schedule_update_any, use_hook, vdom_is_rendering,
-    AnyValue, Attribute, AttributeValue, CapturedError, Component, ComponentFunction, DynamicNode,
-    Element, ElementId, Event, Fragment, HasAttributes, IntoDynNode, LaunchConfig, MarkerWrapper,
-    Mutation, Mutations, NoOpMutations, Ok, Properties, Result, Runtime, ScopeId, ScopeState,
-    SpawnIfAsync, Task, Template, TemplateAttribute, TemplateNode, VComponent, VNode, VNodeInner,
-    VPlaceholder, VText, VirtualDom, WriteMutations,
+    AnyValue, Attribute, AttributeValue, CapturedError, CapturedPanic, Component,
+    ComponentFunction, DynamicNode, Element, ElementId, Event, Fragment, HasAttributes,
+    IntoDynNode, LaunchConfig, MarkerWrapper, Mutation, Mutations, NoOpMutations, Ok, Properties,
+    Result, Runtime, ScopeId, ScopeState, SpawnIfAsync, Task, Template, TemplateAttribute,
+    TemplateNode, VComponent, VNode, VNodeInner, VPlaceholder, VText, VirtualDom, WriteMutations,
 };
 
 /// The purpose of this module is to alleviate imports of many common types
@@ -106,6 +106,11 @@ impl ScopeState {
         self.context_id
     }
 
+    /// Returns the name of the component that owns this [`ScopeState`].
+    pub fn name(&self) -> &'static str {
+        self.state().name
+    }
+
     pub(crate) fn state(&self) -> Ref<'_, Scope> {
         self.runtime.get_state(self.context_id).unwrap()
     }
@@ -30,3 +30,23 @@ fn ThrowChild() -> Element {
 
     rsx! { div {} }
 }
+
+#[test]
+fn downcast_captured_errors() {
+    // Captured errors remember the scope they were thrown from, so they can only be created inside of a component
+    let dom = VirtualDom::new(|| rsx! { div {} });
+    let error = dom.in_runtime(|| {
+        ScopeId::APP.in_runtime(|| {
+            dioxus_core::CapturedError::from(std::io::Error::new(
+                std::io::ErrorKind::AddrInUse,
+                "asd",
+            ))
+        })
+    });
+
+    assert_eq!(
+        error.downcast::<std::io::Error>().map(|error| error.kind()),
+        Some(std::io::ErrorKind::AddrInUse)
+    );
+    assert!(error.downcast::<std::fmt::Error>().is_none());
+}
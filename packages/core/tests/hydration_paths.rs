@@ -0,0 +1,82 @@
+//! Make sure the hydration paths describe the rendered tree so the server and client renders can be compared
+use dioxus::dioxus_core::internal::hydration_node_paths;
+use dioxus::prelude::*;
+use pretty_assertions::assert_eq;
+use std::cell::Cell;
+
+thread_local! {
+    // Lets the tests render the app like a client that disagrees with the server
+    static SHOW_FOOTER: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Render the app and return the paths below the root component
+fn render(app: fn() -> Element, show_footer: bool) -> Vec<String> {
+    SHOW_FOOTER.set(show_footer);
+    let mut dom = VirtualDom::new(app);
+    dom.rebuild_in_place();
+    hydration_node_paths(&dom)
+        .into_iter()
+        .map(|path| {
+            let (_, below_root) = path
+                .split_once("/root/")
+                .unwrap_or_else(|| panic!("{path}"));
+            below_root.to_string()
+        })
+        .collect()
+}
+
+fn app() -> Element {
+    rsx! {
+        main {
+            h1 { "Todos" }
+            for todo in ["Write tests", "Ship it"] {
+                Todo { text: todo }
+            }
+            if SHOW_FOOTER.get() {
+                footer { "Done" }
+            }
+        }
+    }
+}
+
+#[component]
+fn Todo(text: &'static str) -> Element {
+    rsx! {
+        li { "{text}" }
+    }
+}
+
+#[test]
+fn paths_follow_the_components_and_elements_to_each_node() {
+    assert_eq!(
+        render(app, false),
+        [
+            "main",
+            "main/h1",
+            "main/h1/#text",
+            "main/hydration_paths::Todo/li",
+            "main/hydration_paths::Todo/li/#text",
+            "main/hydration_paths::Todo/li",
+            "main/hydration_paths::Todo/li/#text",
+            "main/#placeholder",
+        ]
+    );
+}
+
+#[test]
+fn matching_renders_produce_the_same_paths() {
+    assert_eq!(render(app, false), render(app, false));
+}
+
+#[test]
+fn the_first_differing_path_is_the_mismatch() {
+    let server = render(app, false);
+    let client = render(app, true);
+    let mismatch = server
+        .iter()
+        .zip(client.iter())
+        .position(|(server, client)| server != client);
+    assert_eq!(mismatch, Some(7));
+    assert_eq!(server[7], "main/#placeholder");
+    assert_eq!(client[7], "main/footer");
+}
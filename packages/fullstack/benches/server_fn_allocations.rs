@@ -0,0 +1,102 @@
+//! Count the allocations the server function handler makes for every call
+//!
+//! Run with `cargo bench -p dioxus-fullstack --features axum --bench server_fn_allocations`
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{header::*, Request};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tower::ServiceExt;
+
+/// The system allocator, counting every allocation it makes
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[server(endpoint = "allocations_echo")]
+async fn echo(value: String) -> Result<String, ServerFnError> {
+    Ok(value)
+}
+
+/// A request with the headers a browser usually sends with a server function call
+fn request() -> Request<Body> {
+    Request::post("/api/allocations_echo")
+        .header(HOST, "localhost:8080")
+        .header(
+            USER_AGENT,
+            "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0",
+        )
+        .header(ACCEPT, "*/*")
+        .header(ACCEPT_LANGUAGE, "en-US,en;q=0.5")
+        .header(ACCEPT_ENCODING, "gzip, deflate, br, zstd")
+        .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .header(ORIGIN, "http://localhost:8080")
+        .header(REFERER, "http://localhost:8080/")
+        .header(
+            COOKIE,
+            "session=0123456789abcdef0123456789abcdef; theme=dark",
+        )
+        .header(CONNECTION, "keep-alive")
+        .body(Body::from("value=hello"))
+        .unwrap()
+}
+
+fn main() {
+    const CALLS: usize = 10_000;
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let router = Router::new().register_server_functions();
+
+    runtime.block_on(async {
+        // Warm up any lazily initialized state so it isn't counted
+        for _ in 0..100 {
+            let response = router.clone().oneshot(request()).await.unwrap();
+            axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+        }
+
+        let mut allocations = 0;
+        let mut allocated_bytes = 0;
+        for _ in 0..CALLS {
+            // Only count the handler, not building the request
+            let request = request();
+            let router = router.clone();
+            let start_allocations = ALLOCATIONS.load(Ordering::Relaxed);
+            let start_bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+            let response = router.oneshot(request).await.unwrap();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            allocations += ALLOCATIONS.load(Ordering::Relaxed) - start_allocations;
+            allocated_bytes += ALLOCATED_BYTES.load(Ordering::Relaxed) - start_bytes;
+            assert_eq!(body, "\"hello\"");
+        }
+
+        println!(
+            "server function call: {} allocations, {} bytes allocated per call (averaged over {CALLS} calls)",
+            allocations / CALLS,
+            allocated_bytes / CALLS
+        );
+    });
+}
@@ -0,0 +1,92 @@
+//! A bounded channel for streaming server function responses without buffering the whole response in memory
+
+use futures_channel::mpsc;
+use futures_util::Stream;
+
+/// Create a channel that streams items from a producer task into a server function response.
+///
+/// The channel holds at most `buffer` items. Once it is full, [`BackpressureSender::send`] waits until the client has
+/// read more of the response, so a slow client slows the producer down instead of making the server buffer everything
+/// it produced. If the client disconnects, `send` returns an error so the producer can stop early.
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// # use futures_util::StreamExt;
+/// # use bytes::Bytes;
+/// use server_fn::codec::{ByteStream, Streaming};
+///
+/// #[server(output = Streaming)]
+/// async fn export_rows() -> Result<ByteStream, ServerFnError> {
+///     // Keep at most 16 rows in memory at once
+///     let (mut sender, rows) = backpressure_channel::<Result<Bytes, ServerFnError>>(16);
+///     tokio::spawn(async move {
+///         for row in 0..1_000_000 {
+///             let line = format!("{row}\n");
+///             if sender.send(Ok(line.into())).await.is_err() {
+///                 // The client went away, stop reading from the database
+///                 break;
+///             }
+///         }
+///     });
+///     Ok(ByteStream::new(rows))
+/// }
+/// ```
+pub fn backpressure_channel<T>(buffer: usize) -> (BackpressureSender<T>, BackpressureReceiver<T>) {
+    // The sender always gets one guaranteed slot, so subtract it to keep the total at the buffer size
+    let (sender, receiver) = mpsc::channel(buffer.saturating_sub(1));
+    (
+        BackpressureSender { sender },
+        BackpressureReceiver { receiver },
+    )
+}
+
+/// The sending half of a [`backpressure_channel`]
+pub struct BackpressureSender<T> {
+    sender: mpsc::Sender<T>,
+}
+
+impl<T> BackpressureSender<T> {
+    /// Send an item to the client. Waits until the channel has room if the client is reading slower than items are produced.
+    /// Returns an error if the response was dropped because the client disconnected.
+    pub async fn send(&mut self, item: T) -> Result<(), ClientDisconnected> {
+        std::future::poll_fn(|cx| self.sender.poll_ready(cx))
+            .await
+            .map_err(|_| ClientDisconnected)?;
+        self.sender.start_send(item).map_err(|_| ClientDisconnected)
+    }
+
+    /// Check if the response was dropped because the client disconnected
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+}
+
+/// The receiving half of a [`backpressure_channel`]. Pass it to the streaming output of a server function.
+pub struct BackpressureReceiver<T> {
+    receiver: mpsc::Receiver<T>,
+}
+
+impl<T> Stream for BackpressureReceiver<T> {
+    type Item = T;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        std::pin::Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+/// The error [`BackpressureSender::send`] returns when the client disconnected before the response finished
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClientDisconnected;
+
+impl std::fmt::Display for ClientDisconnected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the client disconnected before the response finished")
+    }
+}
+
+impl std::error::Error for ClientDisconnected {}
@@ -0,0 +1,100 @@
+//! Server functions that respond with raw bytes like images or documents
+
+use bytes::Bytes;
+#[cfg(feature = "axum")]
+use server_fn::codec::IntoRes;
+use server_fn::codec::{Encoding, FromRes};
+use server_fn::response::ClientRes;
+use server_fn::ServerFnError;
+use std::str::FromStr;
+
+/// An output encoding for server functions that send raw bytes to the client instead of serializing their output. Use it
+/// with a [`BinaryResponse`] output.
+///
+/// The response body is the bytes exactly as the server function returned them, with the content type of the
+/// [`BinaryResponse`]. This makes the server function usable as a plain endpoint for images, PDFs or downloads, for example
+/// as the `src` of an image with a `GET` input encoding.
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// #[server(output = Binary)]
+/// async fn invoice(id: u32) -> Result<BinaryResponse, ServerFnError> {
+///     let pdf = std::fs::read(format!("invoices/{id}.pdf"))?;
+///     Ok(BinaryResponse::new("application/pdf", pdf))
+/// }
+///
+/// fn app() -> Element {
+///     let invoice = use_resource(|| invoice(1));
+///     let invoice = invoice.read();
+///     match &*invoice {
+///         Some(Ok(invoice)) => rsx! { "The invoice is {invoice.bytes().len()} bytes" },
+///         Some(Err(err)) => rsx! { "Failed to load the invoice: {err}" },
+///         None => rsx! { "Loading..." },
+///     }
+/// }
+/// ```
+pub struct Binary;
+
+impl Encoding for Binary {
+    const CONTENT_TYPE: &'static str = "application/octet-stream";
+    const METHOD: http::Method = http::Method::POST;
+}
+
+/// Raw bytes a server function sends to the client with the [`Binary`] encoding
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BinaryResponse {
+    content_type: String,
+    bytes: Bytes,
+}
+
+impl BinaryResponse {
+    /// Create a new response with the bytes and the `Content-Type` they are sent with
+    pub fn new(content_type: impl Into<String>, bytes: impl Into<Bytes>) -> Self {
+        Self {
+            content_type: content_type.into(),
+            bytes: bytes.into(),
+        }
+    }
+
+    /// Get the content type of the bytes. The client can't read the headers of the response, so responses the client
+    /// receives always have the `application/octet-stream` content type
+    pub fn content_type(&self) -> &str {
+        &self.content_type
+    }
+
+    /// Get the bytes
+    pub fn bytes(&self) -> &Bytes {
+        &self.bytes
+    }
+
+    /// Get the bytes and drop the content type
+    pub fn into_bytes(self) -> Bytes {
+        self.bytes
+    }
+}
+
+#[cfg(feature = "axum")]
+impl<CustErr> IntoRes<Binary, http::Response<axum::body::Body>, CustErr> for BinaryResponse
+where
+    CustErr: Send + 'static,
+{
+    async fn into_res(self) -> Result<http::Response<axum::body::Body>, ServerFnError<CustErr>> {
+        http::Response::builder()
+            .header(http::header::CONTENT_TYPE, self.content_type)
+            .header(http::header::CONTENT_LENGTH, self.bytes.len())
+            .body(axum::body::Body::from(self.bytes))
+            .map_err(|err| ServerFnError::Response(err.to_string()))
+    }
+}
+
+impl<CustErr, Response> FromRes<Binary, Response, CustErr> for BinaryResponse
+where
+    CustErr: FromStr + Send + 'static,
+    Response: ClientRes<CustErr> + Send,
+{
+    async fn from_res(res: Response) -> Result<Self, ServerFnError<CustErr>> {
+        Ok(Self::new(Binary::CONTENT_TYPE, res.try_into_bytes().await?))
+    }
+}
@@ -0,0 +1,73 @@
+//! Typed access to the client hints a browser sends with a request
+
+use http::request::Parts;
+
+/// The [client hints](https://developer.mozilla.org/en-US/docs/Web/HTTP/Client_hints) a browser sent with the current request.
+///
+/// Browsers only send most client hints after the server asks for them with an `Accept-CH` response header (or a
+/// `Critical-CH` header to get them on the very first request). Every hint is optional because the client may not
+/// support or may choose not to send it.
+///
+/// During SSR, the client hints for the current request are provided as a root context, so any component can read them
+/// with `try_consume_context::<ClientHints>()`.
+///
+/// # Example
+///
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// # fn main() {}
+/// #[component]
+/// fn HeroImage() -> Element {
+///     // Pick an image that matches the pixel density of the device on the first paint
+///     let dpr = try_consume_context::<ClientHints>()
+///         .and_then(|hints| hints.device_pixel_ratio)
+///         .unwrap_or(1.0);
+///     let src = if dpr > 1.5 { "/hero@2x.png" } else { "/hero.png" };
+///     rsx! { img { src } }
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClientHints {
+    /// The width of the layout viewport in CSS pixels from the `Sec-CH-Viewport-Width` or `Viewport-Width` header
+    pub viewport_width: Option<u32>,
+    /// The height of the layout viewport in CSS pixels from the `Sec-CH-Viewport-Height` header
+    pub viewport_height: Option<u32>,
+    /// The ratio of physical pixels to CSS pixels from the `Sec-CH-DPR` or `DPR` header
+    pub device_pixel_ratio: Option<f64>,
+    /// The approximate amount of device memory in gigabytes from the `Sec-CH-Device-Memory` or `Device-Memory` header
+    pub device_memory: Option<f64>,
+    /// If the client prefers reduced data usage from the `Save-Data` header
+    pub save_data: bool,
+}
+
+impl ClientHints {
+    /// Read the client hints from the parts of a request
+    pub fn from_parts(parts: &Parts) -> Self {
+        let header = |names: &[&str]| {
+            names.iter().find_map(|name| {
+                parts
+                    .headers
+                    .get(*name)
+                    .and_then(|value| value.to_str().ok())
+                    .map(|value| value.trim().to_string())
+            })
+        };
+
+        Self {
+            viewport_width: header(&["sec-ch-viewport-width", "viewport-width"])
+                .and_then(|width| width.parse().ok()),
+            viewport_height: header(&["sec-ch-viewport-height"])
+                .and_then(|height| height.parse().ok()),
+            device_pixel_ratio: header(&["sec-ch-dpr", "dpr"])
+                .and_then(|dpr| dpr.parse().ok())
+                .filter(|dpr: &f64| dpr.is_finite() && *dpr > 0.),
+            device_memory: header(&["sec-ch-device-memory", "device-memory"])
+                .and_then(|memory| memory.parse().ok())
+                .filter(|memory: &f64| memory.is_finite() && *memory > 0.),
+            save_data: header(&["save-data"])
+                .map(|save_data| save_data.eq_ignore_ascii_case("on"))
+                .unwrap_or(false),
+        }
+    }
+}
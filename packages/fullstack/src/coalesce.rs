@@ -0,0 +1,311 @@
+//! Share one render between identical page requests that arrive while it is still running
+
+use crate::prelude::DioxusServerContext;
+use dioxus_isrg::{IncrementalRendererError, RenderFreshness};
+use futures_util::{stream::BoxStream, Stream, StreamExt};
+use http::request::Parts;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Weak};
+use tokio::sync::Notify;
+
+/// A function that picks the key requests are coalesced by. Requests without a key are always rendered on their own
+pub(crate) type CoalesceKeyFn = Arc<dyn Fn(&Parts) -> Option<String> + Send + Sync>;
+
+/// The headers the default coalescing key includes because they change what the page renders
+const VARYING_HEADERS: &[&str] = &[
+    "accept-language",
+    "sec-ch-viewport-width",
+    "viewport-width",
+    "sec-ch-viewport-height",
+    "sec-ch-dpr",
+    "dpr",
+    "sec-ch-device-memory",
+    "device-memory",
+    "save-data",
+];
+
+/// The key [`ServeConfigBuilder::coalesce_renders`](crate::prelude::ServeConfigBuilder::coalesce_renders) coalesces
+/// requests by. `GET` requests are keyed by their path, query, `Accept-Language` header and client hints. Requests with a
+/// `Cookie` or `Authorization` header are likely personalized, so they are never coalesced.
+pub fn default_coalescing_key(parts: &Parts) -> Option<String> {
+    if parts.method != http::Method::GET
+        || parts.headers.contains_key(http::header::COOKIE)
+        || parts.headers.contains_key(http::header::AUTHORIZATION)
+    {
+        return None;
+    }
+
+    let mut key = parts.uri.path_and_query()?.to_string();
+    for name in VARYING_HEADERS {
+        key.push('\n');
+        for value in parts.headers.get_all(*name) {
+            key.push_str(&String::from_utf8_lossy(value.as_bytes()));
+        }
+    }
+    Some(key)
+}
+
+/// A chunk of a render. Errors are stored as their message so every request that shares the render can receive them
+type SharedChunk = Result<String, String>;
+
+/// The renders that are running for a config, keyed by the coalescing key of the request that started them
+#[derive(Clone, Default)]
+pub(crate) struct InFlightRenders {
+    renders: Arc<Mutex<HashMap<String, Weak<Flight>>>>,
+}
+
+impl InFlightRenders {
+    /// Join the render for the key if one is running. Otherwise start the render and share it with the requests that
+    /// join it before it finishes. Requests that join the render receive the response parts the render set on the server
+    /// context of the request that started it along with the initial frame. If the render set cookies, the page is
+    /// personal to the request that started it, so the requests that joined it render on their own instead
+    pub(crate) async fn render<S>(
+        &self,
+        key: String,
+        server_context: &DioxusServerContext,
+        render: impl Future<Output = Result<(RenderFreshness, S), IncrementalRendererError>>,
+    ) -> Result<
+        (
+            RenderFreshness,
+            BoxStream<'static, Result<String, IncrementalRendererError>>,
+        ),
+        IncrementalRendererError,
+    >
+    where
+        S: Stream<Item = Result<String, IncrementalRendererError>> + Send + 'static,
+    {
+        let (flight, started) = {
+            let mut renders = self.renders.lock();
+            match renders.get(&key).and_then(Weak::upgrade) {
+                Some(flight) => (flight, false),
+                None => {
+                    let flight = Arc::new(Flight {
+                        key: key.clone(),
+                        renders: self.clone(),
+                        state: Default::default(),
+                        notify: Notify::new(),
+                        driver: Default::default(),
+                    });
+                    renders.insert(key, Arc::downgrade(&flight));
+                    (flight, true)
+                }
+            }
+        };
+
+        if started {
+            match render.await {
+                Ok((freshness, stream)) => flight.drive(freshness, stream, server_context.clone()),
+                Err(err) => {
+                    flight.finish(Some(err.to_string()));
+                    return Err(err);
+                }
+            }
+        } else {
+            tracing::trace!("Joined the render that is running for {}", flight.key);
+            if !flight.shareable().await {
+                tracing::trace!(
+                    "The render for {} set cookies, rendering on our own",
+                    flight.key
+                );
+                drop(flight);
+                let (freshness, stream) = render.await?;
+                return Ok((freshness, stream.boxed()));
+            }
+        }
+
+        let freshness = flight.freshness().await?;
+        let joined = (!started).then(|| server_context.clone());
+        Ok((freshness, flight.subscribe(joined).boxed()))
+    }
+}
+
+/// A render that one or more requests are streaming
+struct Flight {
+    key: String,
+    renders: InFlightRenders,
+    state: Mutex<FlightState>,
+    notify: Notify,
+    driver: Mutex<Option<tokio::task::AbortHandle>>,
+}
+
+#[derive(Default)]
+struct FlightState {
+    freshness: Option<RenderFreshness>,
+    chunks: Vec<SharedChunk>,
+    /// The response parts the render set before it sent the initial frame
+    response: Option<DioxusServerContext>,
+    /// If the render set cookies, which makes its response personal to the request that started it
+    private: bool,
+    done: bool,
+}
+
+impl Flight {
+    /// Copy the chunks of the render into the flight in the background until the render finishes or every request that
+    /// shares it is dropped
+    fn drive<S>(
+        self: &Arc<Self>,
+        freshness: RenderFreshness,
+        stream: S,
+        leader: DioxusServerContext,
+    ) where
+        S: Stream<Item = Result<String, IncrementalRendererError>> + Send + 'static,
+    {
+        self.state.lock().freshness = Some(freshness);
+        self.notify.notify_waiters();
+
+        let flight = Arc::downgrade(self);
+        let driver = tokio::spawn(async move {
+            let mut stream = std::pin::pin!(stream);
+            while let Some(chunk) = stream.next().await {
+                let Some(flight) = flight.upgrade() else {
+                    return;
+                };
+                let mut state = flight.state.lock();
+                // The initial render is done once the first chunk arrives. Snapshot the response parts before the handler
+                // of the leading request takes them
+                let first_chunk = state.chunks.is_empty();
+                if first_chunk {
+                    let response = DioxusServerContext::default();
+                    response.copy_response_from(&leader);
+                    state.response = Some(response);
+                    state.private = leader
+                        .response_parts()
+                        .headers
+                        .contains_key(http::header::SET_COOKIE);
+                }
+                state.chunks.push(chunk.map_err(|err| err.to_string()));
+                let private = state.private;
+                drop(state);
+                // Requests that arrive later start a render of their own instead of joining a personal one
+                if first_chunk && private {
+                    flight.forget();
+                }
+                flight.notify.notify_waiters();
+            }
+            if let Some(flight) = flight.upgrade() {
+                flight.finish(None);
+            }
+        });
+        *self.driver.lock() = Some(driver.abort_handle());
+    }
+
+    /// Mark the render as finished so new requests start a new render
+    fn finish(&self, error: Option<String>) {
+        {
+            let mut state = self.state.lock();
+            state.chunks.extend(error.map(Err));
+            state.done = true;
+        }
+        self.notify.notify_waiters();
+        self.forget();
+    }
+
+    /// Remove the flight from the running renders so new requests start a new render
+    fn forget(&self) {
+        let mut renders = self.renders.renders.lock();
+        if renders
+            .get(&self.key)
+            .is_some_and(|flight| std::ptr::eq(flight.as_ptr(), self))
+        {
+            renders.remove(&self.key);
+        }
+    }
+
+    /// Wait until the render sent its initial frame and check if requests that joined it can share its response. Renders
+    /// that fail before the initial frame share the error
+    async fn shareable(&self) -> bool {
+        loop {
+            let notified = self.notify.notified();
+            {
+                let state = self.state.lock();
+                if state.response.is_some() {
+                    return !state.private;
+                }
+                if state.done {
+                    return true;
+                }
+            }
+            notified.await;
+        }
+    }
+
+    /// Wait until the render started
+    async fn freshness(&self) -> Result<RenderFreshness, IncrementalRendererError> {
+        loop {
+            // Register for the notification before checking the state so a change in between isn't missed
+            let notified = self.notify.notified();
+            {
+                let state = self.state.lock();
+                if let Some(freshness) = state.freshness {
+                    return Ok(freshness);
+                }
+                if state.done {
+                    let error = match state.chunks.last() {
+                        Some(Err(error)) => error.clone(),
+                        _ => "The shared render failed to start".to_string(),
+                    };
+                    return Err(IncrementalRendererError::Other(error.into()));
+                }
+            }
+            notified.await;
+        }
+    }
+
+    /// The chunk at the index. Returns `Some(None)` once the render is finished and `None` if the chunk isn't ready yet.
+    /// The response parts of the render are copied into the server context of a joined request with the first chunk
+    fn chunk(
+        &self,
+        index: usize,
+        joined: Option<&DioxusServerContext>,
+    ) -> Option<Option<Result<String, IncrementalRendererError>>> {
+        let state = self.state.lock();
+        if let (0, Some(joined), Some(response)) = (index, joined, &state.response) {
+            joined.copy_response_from(response);
+        }
+        match state.chunks.get(index) {
+            Some(chunk) => Some(Some(
+                chunk
+                    .clone()
+                    .map_err(|err| IncrementalRendererError::Other(err.into())),
+            )),
+            None if state.done => Some(None),
+            None => None,
+        }
+    }
+
+    /// Stream every chunk of the render from the start. Pass the server context of a request that joined the render to
+    /// copy the response parts of the render into it
+    fn subscribe(
+        self: Arc<Self>,
+        joined: Option<DioxusServerContext>,
+    ) -> impl Stream<Item = Result<String, IncrementalRendererError>> {
+        futures_util::stream::unfold((self, joined, 0), |(flight, joined, index)| async move {
+            loop {
+                let notified = flight.notify.notified();
+                if let Some(chunk) = flight.chunk(index, joined.as_ref()) {
+                    drop(notified);
+                    return chunk.map(|chunk| (chunk, (flight, joined, index + 1)));
+                }
+                notified.await;
+            }
+        })
+    }
+}
+
+impl Drop for Flight {
+    fn drop(&mut self) {
+        // Every request that shared the render is gone, so stop rendering
+        if let Some(driver) = self.driver.get_mut().take() {
+            driver.abort();
+        }
+        let mut renders = self.renders.renders.lock();
+        if renders
+            .get(&self.key)
+            .is_some_and(|flight| flight.strong_count() == 0)
+        {
+            renders.remove(&self.key);
+        }
+    }
+}
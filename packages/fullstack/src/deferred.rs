@@ -0,0 +1,202 @@
+//! Defer hydrating parts of the page until the user needs them
+
+use dioxus_lib::prelude::*;
+use std::{cell::Cell, rc::Rc};
+
+/// When the client hydrates the children of a [`DeferHydration`] boundary
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HydrateOn {
+    /// Hydrate the children once the boundary scrolls into view
+    #[default]
+    Visible,
+    /// Hydrate the children once the user hovers over, focuses or touches the boundary. The event that starts
+    /// hydration is not replayed, so this fits content the user looks at before they act on it
+    Interaction,
+}
+
+impl HydrateOn {
+    fn as_str(self) -> &'static str {
+        match self {
+            HydrateOn::Visible => "visible",
+            HydrateOn::Interaction => "interaction",
+        }
+    }
+}
+
+/// The props for [`DeferHydration`]
+#[derive(Clone, Props, PartialEq)]
+pub struct DeferHydrationProps {
+    /// When the client hydrates the children. (defaults to [`HydrateOn::Visible`])
+    #[props(default)]
+    on: HydrateOn,
+    /// The children the server renders into the page and the client hydrates later
+    children: Element,
+}
+
+/// Render the children on the server, but wait to hydrate them on the client until they scroll into view or the user
+/// interacts with them. The client doesn't run the components of the children until then, which cuts the work it
+/// does when the page loads.
+///
+/// The server renders the children into a `div` without hydration markers and doesn't send the data of their
+/// server futures to the client. Once the boundary hydrates, the client renders the children from scratch in place of
+/// the server html, so their server futures run again on the client. Boundaries inside the children hydrate with
+/// them. Outside of hydration, for example after navigating on the client, the children render right away.
+///
+/// # Example
+///
+/// ```rust, no_run
+/// # use dioxus_lib::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// fn app() -> Element {
+///     rsx! {
+///         main { "The content above the fold" }
+///         // The client doesn't run the footer until the user scrolls down to it
+///         DeferHydration { on: HydrateOn::Visible, Footer {} }
+///     }
+/// }
+///
+/// #[component]
+/// fn Footer() -> Element {
+///     rsx! { footer { "A lot of links" } }
+/// }
+/// ```
+#[component]
+pub fn DeferHydration(props: DeferHydrationProps) -> Element {
+    // The content of another boundary is rendered from scratch on the client, so there is nothing to defer
+    let nested = use_hook(|| try_consume_context::<DeferredContent>().is_some());
+    if nested {
+        return props.children;
+    }
+
+    let id = use_hook(next_boundary_id);
+    let mut hydrated = use_signal(|| !hydrating());
+    use_hook(|| {
+        if !*hydrated.peek() {
+            spawn(async move {
+                // Wait until the bootstrap script the server sent down sees the boundary scroll into view or the
+                // user interact with it
+                let mut eval = dioxus_lib::document::eval(WAIT_FOR_TRIGGER);
+                if eval.send(id).is_ok() {
+                    _ = eval.recv::<bool>().await;
+                }
+                hydrated.set(true);
+            });
+        }
+    });
+
+    let on = props.on.as_str();
+    if hydrated() {
+        rsx! {
+            div { "data-dx-defer": "{id}", "data-dx-hydrate-on": on,
+                DeferredChildren { children: props.children }
+            }
+        }
+    } else {
+        // The html the server rendered stays in the div until the client renders the children in its place
+        rsx! {
+            div { "data-dx-defer": "{id}", "data-dx-hydrate-on": on }
+        }
+    }
+}
+
+/// Marks the scope that renders the children of a [`DeferHydration`] boundary
+#[derive(Clone, Copy)]
+pub(crate) struct DeferredContent;
+
+#[component]
+fn DeferredChildren(children: Element) -> Element {
+    use_hook(|| provide_context(DeferredContent));
+    children
+}
+
+/// Hands out the ids of the deferred boundaries in the order they are first rendered. The server and the client render
+/// the page in the same order, so each boundary gets the same id on both.
+#[derive(Clone, Default)]
+pub(crate) struct DeferredBoundaries(Rc<Cell<usize>>);
+
+fn next_boundary_id() -> usize {
+    let boundaries = ScopeId::ROOT
+        .has_context::<DeferredBoundaries>()
+        .unwrap_or_else(|| ScopeId::ROOT.provide_context(DeferredBoundaries::default()));
+    let id = boundaries.0.get();
+    boundaries.0.set(id + 1);
+    id
+}
+
+/// Check if the components are rendering for the first time to hydrate the html from the server
+fn hydrating() -> bool {
+    #[cfg(all(not(feature = "server"), feature = "web"))]
+    {
+        dioxus_web::is_hydrating()
+    }
+    #[cfg(not(all(not(feature = "server"), feature = "web")))]
+    {
+        false
+    }
+}
+
+/// Resolves once the boundary with the id it receives should hydrate. Without the bootstrap script or the boundary,
+/// there is nothing to wait for
+const WAIT_FOR_TRIGGER: &str = r#"
+const id = await dioxus.recv();
+const boundary = document.querySelector(`[data-dx-defer="${id}"]`);
+if (boundary && window.dx_watch_deferred) {
+  window.dx_watch_deferred(boundary, () => dioxus.send(true));
+} else {
+  dioxus.send(true);
+}
+"#;
+
+/// Watches the deferred boundaries on the page from before the wasm loads, so a boundary that scrolled into view or was
+/// interacted with in the meantime hydrates as soon as the client starts
+#[cfg(feature = "server")]
+pub(crate) const DEFERRED_HYDRATION_BOOTSTRAP: &str = r#"(() => {
+  const ready = new WeakSet();
+  const waiting = new WeakMap();
+  const hydrate = (boundary) => {
+    if (ready.has(boundary)) return;
+    ready.add(boundary);
+    waiting.get(boundary)?.();
+    waiting.delete(boundary);
+  };
+  const observer = "IntersectionObserver" in window ? new IntersectionObserver((entries) => {
+    for (const entry of entries) {
+      if (entry.isIntersecting) {
+        observer.unobserve(entry.target);
+        hydrate(entry.target);
+      }
+    }
+  }) : null;
+  const watch = (boundary) => {
+    if (boundary.dxWatched) return;
+    boundary.dxWatched = true;
+    const on = boundary.getAttribute("data-dx-hydrate-on");
+    if (on === "visible" && observer) {
+      observer.observe(boundary);
+    } else if (on === "interaction") {
+      for (const event of ["pointerover", "focusin", "touchstart"]) {
+        boundary.addEventListener(event, () => hydrate(boundary), { once: true, passive: true });
+      }
+    } else {
+      hydrate(boundary);
+    }
+  };
+  window.dx_watch_deferred = (boundary, onReady) => {
+    watch(boundary);
+    if (ready.has(boundary)) onReady();
+    else waiting.set(boundary, onReady);
+  };
+  document.querySelectorAll("[data-dx-hydrate-on]").forEach(watch);
+})();"#;
+
+/// Check if a scope renders the children of a [`DeferHydration`] boundary
+#[cfg(feature = "server")]
+pub(crate) fn is_deferred_content(virtual_dom: &VirtualDom, scope: ScopeId) -> bool {
+    virtual_dom.in_runtime(|| scope.has_context::<DeferredContent>().is_some())
+}
+
+/// Check if the page has any deferred boundaries that the bootstrap script needs to watch
+#[cfg(feature = "server")]
+pub(crate) fn has_deferred_boundaries(virtual_dom: &VirtualDom) -> bool {
+    virtual_dom.in_runtime(|| ScopeId::ROOT.has_context::<DeferredBoundaries>().is_some())
+}
@@ -0,0 +1,69 @@
+//! Authorization guards that run before a server function
+
+use crate::prelude::DioxusServerContext;
+use http::StatusCode;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+
+/// A guard that decides if a request may call a server function. It returns the status code to reject the request with
+/// if the request is not allowed
+pub type GuardFn = fn(&DioxusServerContext) -> Result<(), StatusCode>;
+
+/// A guard registered for a server function with `#[server(guard = "my_guard")]`.
+///
+/// The server macro registers these for you. The guards run against the [`DioxusServerContext`] of the request before
+/// the server function runs, so they can read the request headers and any context you provide to the server functions.
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// use http::StatusCode;
+///
+/// fn require_admin(context: &DioxusServerContext) -> Result<(), StatusCode> {
+///     let is_admin = context
+///         .request_parts()
+///         .headers
+///         .get("cookie")
+///         .and_then(|cookie| cookie.to_str().ok())
+///         .is_some_and(|cookie| cookie.contains("role=admin"));
+///     is_admin.then_some(()).ok_or(StatusCode::FORBIDDEN)
+/// }
+///
+/// #[server(guard = "require_admin")]
+/// async fn delete_user(id: u32) -> Result<(), ServerFnError> {
+///     // Only admins get here
+///     Ok(())
+/// }
+/// ```
+pub struct ServerFnGuard {
+    path: &'static str,
+    guard: GuardFn,
+}
+
+impl ServerFnGuard {
+    /// Create a guard for the server function mounted at the path
+    pub const fn new(path: &'static str, guard: GuardFn) -> Self {
+        Self { path, guard }
+    }
+}
+
+server_fn::inventory::collect!(ServerFnGuard);
+
+/// Every guard registered with the server macro by the path of the server function it guards
+static GUARDS: Lazy<HashMap<&'static str, Vec<GuardFn>>> = Lazy::new(|| {
+    let mut guards: HashMap<_, Vec<_>> = HashMap::new();
+    for guard in server_fn::inventory::iter::<ServerFnGuard> {
+        guards.entry(guard.path).or_default().push(guard.guard);
+    }
+    guards
+});
+
+/// Run every guard registered for the server function at the path. Returns the status of the first guard that rejects the request
+pub(crate) fn check_guards(path: &str, context: &DioxusServerContext) -> Result<(), StatusCode> {
+    GUARDS
+        .get(path)
+        .into_iter()
+        .flatten()
+        .try_for_each(|guard| guard(context))
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The feature flags a page is rendered with. The server decides the flags for every request with
+/// [`RenderHandleState::with_feature_flags`](crate::prelude::RenderHandleState::with_feature_flags), and components read
+/// them with [`use_flags`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeatureFlags {
+    enabled: BTreeSet<String>,
+    variants: BTreeMap<String, String>,
+}
+
+impl FeatureFlags {
+    /// Create a new set of feature flags with every flag disabled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable a flag
+    pub fn enable(mut self, flag: impl Into<String>) -> Self {
+        self.enabled.insert(flag.into());
+        self
+    }
+
+    /// Pick the variant of an experiment
+    pub fn variant(mut self, experiment: impl Into<String>, variant: impl Into<String>) -> Self {
+        self.variants.insert(experiment.into(), variant.into());
+        self
+    }
+
+    /// Check if a flag is enabled
+    pub fn is_enabled(&self, flag: &str) -> bool {
+        self.enabled.contains(flag)
+    }
+
+    /// Get the variant of an experiment, or `None` if no variant was picked
+    pub fn variant_of(&self, experiment: &str) -> Option<&str> {
+        self.variants.get(experiment).map(String::as_str)
+    }
+}
+
+/// Get the [`FeatureFlags`] the page was server rendered with. The flags are sent to the client with the rest of the
+/// hydration data, so the first render on the client sees the same flags as the server and the page doesn't flicker.
+///
+/// Returns flags with every flag disabled if the server doesn't decide any flags or the page was not server rendered.
+///
+/// # Example
+/// ```rust
+/// use dioxus_lib::prelude::*;
+/// use dioxus_fullstack::prelude::*;
+///
+/// fn app() -> Element {
+///     let flags = use_flags();
+///     let checkout = match flags.variant_of("checkout") {
+///         Some("one-page") => "One page checkout",
+///         _ => "Classic checkout",
+///     };
+///
+///     rsx! {
+///         if flags.is_enabled("new-header") {
+///             h1 { "Welcome back" }
+///         }
+///         "{checkout}"
+///     }
+/// }
+/// ```
+pub fn use_flags() -> FeatureFlags {
+    crate::prelude::use_server_cached(|| {
+        #[cfg(feature = "server")]
+        {
+            dioxus_lib::prelude::try_consume_context::<FeatureFlags>().unwrap_or_default()
+        }
+        #[cfg(not(feature = "server"))]
+        {
+            FeatureFlags::default()
+        }
+    })
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+/// The locale a page is rendered in, like `fr` or `en-US`. The server picks it from the `Accept-Language` header of the
+/// request and the locales you support with [`ServeConfigBuilder::supported_locales`](crate::prelude::ServeConfigBuilder::supported_locales).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Locale(pub String);
+
+impl Locale {
+    /// Get the language tag of the locale
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Get the [`Locale`] the page was server rendered in. The locale is sent to the client with the rest of the hydration
+/// data, so the first render on the client uses the same locale as the server.
+///
+/// Returns `None` if no supported locales are configured or the page was not server rendered.
+///
+/// # Example
+/// ```rust
+/// use dioxus_lib::prelude::*;
+/// use dioxus_fullstack::prelude::*;
+///
+/// fn app() -> Element {
+///     let greeting = match use_locale().as_ref().map(Locale::as_str) {
+///         Some("fr") => "Bonjour le monde",
+///         _ => "Hello world",
+///     };
+///
+///     rsx! { "{greeting}" }
+/// }
+/// ```
+pub fn use_locale() -> Option<Locale> {
+    crate::prelude::use_server_cached(|| {
+        #[cfg(feature = "server")]
+        {
+            dioxus_lib::prelude::try_consume_context::<Locale>()
+        }
+        #[cfg(not(feature = "server"))]
+        {
+            None
+        }
+    })
+}
+
+/// Pick the supported locale that best matches an `Accept-Language` header. Ranges are tried from the highest quality
+/// to the lowest. A range matches a supported locale with the same tag, a more general tag (`fr-CH` matches `fr`) or
+/// the same primary language (`fr` matches `fr-FR`).
+#[cfg(feature = "server")]
+pub(crate) fn negotiate_locale<'a>(
+    accept_language: &str,
+    supported: &'a [String],
+) -> Option<&'a str> {
+    let mut ranges: Vec<(&str, f32)> = accept_language
+        .split(',')
+        .filter_map(|range| {
+            let mut params = range.split(';');
+            let tag = params.next()?.trim();
+            let quality = params
+                .filter_map(|param| param.split_once('='))
+                .find(|(name, _)| name.trim().eq_ignore_ascii_case("q"))
+                .map(|(_, quality)| quality.trim().parse::<f32>().unwrap_or(0.0))
+                .unwrap_or(1.0);
+            (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+        })
+        .collect();
+    // The sort is stable, so ranges with the same quality keep the order of the header
+    ranges.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+    let find = |tag: &str| {
+        supported
+            .iter()
+            .find(|locale| locale.eq_ignore_ascii_case(tag))
+    };
+    for (range, _) in ranges {
+        if range == "*" {
+            return supported.first().map(String::as_str);
+        }
+        // Drop subtags from the end of the range until it matches
+        let mut tag = range;
+        loop {
+            if let Some(locale) = find(tag) {
+                return Some(locale);
+            }
+            match tag.rsplit_once('-') {
+                Some((rest, _)) => tag = rest,
+                None => break,
+            }
+        }
+        // Fall back to a more specific locale with the same language
+        if let Some(locale) = supported.iter().find(|locale| {
+            locale
+                .split('-')
+                .next()
+                .is_some_and(|language| language.eq_ignore_ascii_case(tag))
+        }) {
+            return Some(locale);
+        }
+    }
+    None
+}
@@ -1,2 +1,7 @@
+pub mod feature_flags;
+pub mod locale;
 pub mod server_cached;
 pub mod server_future;
+
+#[cfg(feature = "server")]
+pub mod server_context;
@@ -0,0 +1,32 @@
+use crate::prelude::DioxusServerContext;
+use dioxus_lib::prelude::{try_consume_context, use_hook};
+
+/// Get the [`DioxusServerContext`] of the request that is being rendered. This lets components read the headers and
+/// cookies of the request while they are server rendered instead of only inside server functions.
+///
+/// Returns `None` if the component isn't rendered by [`render_handler`](crate::prelude::render_handler) or another
+/// renderer that provides a server context. The hook is only available with the `server` feature, so gate any code
+/// that uses it behind the same feature.
+///
+/// # Example
+/// ```rust
+/// use dioxus_lib::prelude::*;
+/// use dioxus_fullstack::prelude::*;
+///
+/// fn app() -> Element {
+///     let context = use_server_context();
+///     let locale = context
+///         .as_ref()
+///         .and_then(|context| {
+///             let parts = context.request_parts();
+///             let language = parts.headers.get("accept-language")?.to_str().ok()?;
+///             Some(language.split(',').next().unwrap_or_default().trim().to_string())
+///         })
+///         .unwrap_or_else(|| "en".to_string());
+///
+///     rsx! { "Your locale is {locale}" }
+/// }
+/// ```
+pub fn use_server_context() -> Option<DioxusServerContext> {
+    use_hook(try_consume_context::<DioxusServerContext>)
+}
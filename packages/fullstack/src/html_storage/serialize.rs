@@ -43,6 +43,12 @@ impl super::HTMLData {
     }
 
     fn take_from_scope(&mut self, vdom: &VirtualDom, scope: ScopeId) {
+        // The client renders deferred content from scratch, so it never reads the data of that content
+        #[cfg(feature = "server")]
+        if crate::deferred::is_deferred_content(vdom, scope) {
+            return;
+        }
+
         vdom.in_runtime(|| {
             scope.in_runtime(|| {
                 // Grab any serializable server context from this scope
@@ -14,10 +14,22 @@ pub mod server;
 
 mod hooks;
 
+mod deferred;
+
+mod sse;
+
+mod ndjson;
+
+mod binary;
+
+mod negotiate;
+
 pub mod document;
 #[cfg(feature = "server")]
 mod render;
 #[cfg(feature = "server")]
+mod render_diff;
+#[cfg(feature = "server")]
 mod streaming;
 
 #[cfg(feature = "server")]
@@ -28,11 +40,79 @@ pub use serve_config::*;
 #[cfg(feature = "server")]
 mod server_context;
 
+#[cfg(feature = "warp")]
+#[cfg_attr(docsrs, doc(cfg(feature = "warp")))]
+pub mod warp;
+
+#[cfg(feature = "server")]
+mod client_hints;
+
+#[cfg(feature = "server")]
+mod backpressure;
+
+#[cfg(feature = "server")]
+mod coalesce;
+
+#[cfg(feature = "server")]
+mod guard;
+
+#[cfg(any(
+    target_arch = "wasm32",
+    feature = "server",
+    feature = "desktop",
+    feature = "mobile"
+))]
+mod retry;
+
+#[cfg(any(
+    target_arch = "wasm32",
+    feature = "server",
+    feature = "desktop",
+    feature = "mobile"
+))]
+mod mock;
+
 /// A prelude of commonly used items in dioxus-fullstack.
 pub mod prelude {
     use crate::hooks;
     pub use hooks::{server_cached::use_server_cached, server_future::use_server_future};
 
+    pub use hooks::locale::{use_locale, Locale};
+
+    pub use hooks::feature_flags::{use_flags, FeatureFlags};
+
+    #[cfg(feature = "server")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "server")))]
+    pub use hooks::server_context::use_server_context;
+
+    pub use crate::sse::{Sse, SseStream};
+
+    pub use crate::ndjson::{Ndjson, NdjsonStream};
+
+    pub use crate::binary::{Binary, BinaryResponse};
+
+    pub use crate::negotiate::{HtmlFragment, HtmlOrJson, Negotiated};
+
+    pub use crate::deferred::{DeferHydration, DeferHydrationProps, HydrateOn};
+
+    #[cfg(any(
+        target_arch = "wasm32",
+        feature = "server",
+        feature = "desktop",
+        feature = "mobile"
+    ))]
+    pub use crate::retry::{
+        retry_policy, set_retry_policy, RetryClient, RetryPolicy, RetryRequest,
+    };
+
+    #[cfg(any(
+        target_arch = "wasm32",
+        feature = "server",
+        feature = "desktop",
+        feature = "mobile"
+    ))]
+    pub use crate::mock::{MockClient, MockRequest, MockResponse, MockServerFns};
+
     #[cfg(feature = "axum")]
     #[cfg_attr(docsrs, doc(cfg(feature = "axum")))]
     pub use crate::server::*;
@@ -43,17 +123,42 @@ pub mod prelude {
 
     #[cfg(feature = "server")]
     #[cfg_attr(docsrs, doc(cfg(feature = "server")))]
-    pub use crate::serve_config::{ServeConfig, ServeConfigBuilder};
+    pub use crate::render_diff::{diff_html, diff_route_renders, RenderDiff};
+
+    #[cfg(feature = "server")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "server")))]
+    pub use crate::serve_config::{
+        AcceptMatching, OverloadPolicy, RenderMode, RenderOverflow, ServeConfig,
+        ServeConfigBuilder, StreamFlushStrategy,
+    };
 
     #[cfg(all(feature = "server", feature = "axum"))]
     #[cfg_attr(docsrs, doc(cfg(all(feature = "server", feature = "axum"))))]
     pub use crate::server_context::Axum;
 
+    #[cfg(feature = "server")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "server")))]
+    pub use crate::guard::{GuardFn, ServerFnGuard};
+
     #[cfg(feature = "server")]
     #[cfg_attr(docsrs, doc(cfg(feature = "server")))]
     pub use crate::server_context::{
         extract, server_context, with_server_context, DioxusServerContext, FromContext,
-        FromServerContext, ProvideServerContext,
+        FromServerContext, InvalidQuery, ProvideServerContext,
+    };
+
+    #[cfg(feature = "server")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "server")))]
+    pub use crate::client_hints::ClientHints;
+
+    #[cfg(feature = "server")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "server")))]
+    pub use crate::coalesce::default_coalescing_key;
+
+    #[cfg(feature = "server")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "server")))]
+    pub use crate::backpressure::{
+        backpressure_channel, BackpressureReceiver, BackpressureSender, ClientDisconnected,
     };
 
     #[cfg(feature = "server")]
@@ -61,5 +166,14 @@ pub mod prelude {
     pub use dioxus_isrg::{IncrementalRenderer, IncrementalRendererConfig};
 
     pub use dioxus_server_macro::*;
+    pub use server_fn::codec::{MultipartData, MultipartFormData};
+
+    #[cfg(feature = "cbor")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cbor")))]
+    pub use server_fn::codec::Cbor;
+
+    #[cfg(feature = "msgpack")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "msgpack")))]
+    pub use server_fn::codec::MsgPack;
     pub use server_fn::{self, ServerFn as _, ServerFnError};
 }
@@ -0,0 +1,294 @@
+//! Canned server function responses for component tests
+
+use crate::retry::DefaultClient;
+use bytes::Bytes;
+use futures_util::Stream;
+use serde::Serialize;
+use server_fn::client::Client;
+use server_fn::error::ServerFnErrorSerde;
+use server_fn::request::ClientReq;
+use server_fn::response::ClientRes;
+use server_fn::ServerFnError;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// Canned responses for server functions that use the [`MockClient`]. Provide them as a context to the
+/// [`VirtualDom`](dioxus_lib::prelude::VirtualDom) you test and every call to a mocked server function from a component
+/// gets the canned response instead of reaching the server.
+///
+/// Only client builds (web, desktop and mobile) send server function calls through a client. With the `server` feature,
+/// calling a server function runs its body directly, so run component tests against a client build.
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// #[server(endpoint = "user_name", client = MockClient)]
+/// async fn user_name() -> Result<String, ServerFnError> {
+///     Ok("Alice".to_string())
+/// }
+///
+/// fn app() -> Element {
+///     let name = use_resource(user_name);
+///     rsx! { "{name:?}" }
+/// }
+///
+/// # async fn test() {
+/// let mut dom = VirtualDom::new(app);
+/// dom.provide_root_context(MockServerFns::new().respond_json("/api/user_name", &"Bob"));
+/// dom.rebuild_in_place();
+/// // The resource resolves with the canned response without a running server
+/// dom.wait_for_work().await;
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct MockServerFns {
+    responses: Arc<HashMap<String, CannedResponse>>,
+}
+
+#[derive(Clone)]
+struct CannedResponse {
+    status: u16,
+    body: Bytes,
+}
+
+impl MockServerFns {
+    /// Create a new set of canned responses without any responses
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Respond to calls to the server function at the path with a raw body and status code
+    pub fn respond(mut self, path: impl Into<String>, status: u16, body: impl Into<Bytes>) -> Self {
+        Arc::make_mut(&mut self.responses).insert(
+            path.into(),
+            CannedResponse {
+                status,
+                body: body.into(),
+            },
+        );
+        self
+    }
+
+    /// Respond to calls to the server function at the path with a value encoded as JSON, the default encoding of server functions
+    pub fn respond_json(self, path: impl Into<String>, value: &impl Serialize) -> Self {
+        let body = serde_json::to_vec(value).expect("failed to serialize the canned response");
+        self.respond(path, 200, body)
+    }
+
+    /// Make calls to the server function at the path fail with the error
+    pub fn respond_error<CustErr>(
+        self,
+        path: impl Into<String>,
+        error: ServerFnError<CustErr>,
+    ) -> Self
+    where
+        ServerFnError<CustErr>: ServerFnErrorSerde,
+    {
+        let body = error.ser().unwrap_or_default();
+        self.respond(path, 500, body)
+    }
+
+    /// Get the canned response of the component that is running for the path
+    fn current(path: &str) -> Option<CannedResponse> {
+        dioxus_lib::prelude::try_consume_context::<Self>()?
+            .responses
+            .get(path)
+            .cloned()
+    }
+}
+
+/// A server function [`Client`] that answers calls with the [`MockServerFns`] provided to the component that makes the
+/// call. Calls to server functions without a canned response are sent with the wrapped client, so it is safe to leave
+/// the client on your server functions outside of tests.
+pub struct MockClient<C = DefaultClient>(PhantomData<C>);
+
+impl<CustErr, C> Client<CustErr> for MockClient<C>
+where
+    C: Client<CustErr>,
+    CustErr: Send,
+{
+    type Request = MockRequest<C::Request>;
+    type Response = MockResponse<C::Response>;
+
+    async fn send(req: Self::Request) -> Result<Self::Response, ServerFnError<CustErr>> {
+        match req {
+            MockRequest::Canned(response) => Ok(MockResponse::Canned(response)),
+            MockRequest::Sent(request) => C::send(request).await.map(MockResponse::Sent),
+        }
+    }
+}
+
+/// A request sent by the [`MockClient`]
+pub enum MockRequest<R> {
+    /// A request with a canned response
+    #[doc(hidden)]
+    Canned(CannedResponseBody),
+    /// A request that is sent with the wrapped client
+    #[doc(hidden)]
+    Sent(R),
+}
+
+/// The canned response of a [`MockRequest`]
+#[doc(hidden)]
+pub struct CannedResponseBody {
+    status: u16,
+    body: Bytes,
+}
+
+impl<R> MockRequest<R> {
+    fn new<CustErr>(
+        path: &str,
+        send: impl FnOnce() -> Result<R, ServerFnError<CustErr>>,
+    ) -> Result<Self, ServerFnError<CustErr>> {
+        // The path may include the query of a GET request
+        let path = path.split('?').next().unwrap_or_default();
+        match MockServerFns::current(path) {
+            Some(CannedResponse { status, body }) => {
+                Ok(Self::Canned(CannedResponseBody { status, body }))
+            }
+            None => send().map(Self::Sent),
+        }
+    }
+}
+
+impl<CustErr, R: ClientReq<CustErr>> ClientReq<CustErr> for MockRequest<R> {
+    type FormData = R::FormData;
+
+    fn try_new_get(
+        path: &str,
+        content_type: &str,
+        accepts: &str,
+        query: &str,
+    ) -> Result<Self, ServerFnError<CustErr>> {
+        Self::new(path, || R::try_new_get(path, content_type, accepts, query))
+    }
+
+    fn try_new_post(
+        path: &str,
+        content_type: &str,
+        accepts: &str,
+        body: String,
+    ) -> Result<Self, ServerFnError<CustErr>> {
+        Self::new(path, || R::try_new_post(path, content_type, accepts, body))
+    }
+
+    fn try_new_post_bytes(
+        path: &str,
+        content_type: &str,
+        accepts: &str,
+        body: Bytes,
+    ) -> Result<Self, ServerFnError<CustErr>> {
+        Self::new(path, || {
+            R::try_new_post_bytes(path, content_type, accepts, body)
+        })
+    }
+
+    fn try_new_post_form_data(
+        path: &str,
+        accepts: &str,
+        content_type: &str,
+        body: Self::FormData,
+    ) -> Result<Self, ServerFnError<CustErr>> {
+        Self::new(path, || {
+            R::try_new_post_form_data(path, accepts, content_type, body)
+        })
+    }
+
+    fn try_new_multipart(
+        path: &str,
+        accepts: &str,
+        body: Self::FormData,
+    ) -> Result<Self, ServerFnError<CustErr>> {
+        Self::new(path, || R::try_new_multipart(path, accepts, body))
+    }
+
+    fn try_new_streaming(
+        path: &str,
+        accepts: &str,
+        content_type: &str,
+        body: impl Stream<Item = Bytes> + Send + 'static,
+    ) -> Result<Self, ServerFnError<CustErr>> {
+        Self::new(path, || {
+            R::try_new_streaming(path, accepts, content_type, body)
+        })
+    }
+}
+
+/// A response received by the [`MockClient`]
+pub enum MockResponse<R> {
+    /// A canned response
+    #[doc(hidden)]
+    Canned(CannedResponseBody),
+    /// A response from the server
+    #[doc(hidden)]
+    Sent(R),
+}
+
+impl<CustErr, R> ClientRes<CustErr> for MockResponse<R>
+where
+    R: ClientRes<CustErr> + Send,
+    CustErr: Send,
+{
+    async fn try_into_string(self) -> Result<String, ServerFnError<CustErr>> {
+        match self {
+            Self::Canned(canned) => String::from_utf8(canned.body.to_vec())
+                .map_err(|err| ServerFnError::Deserialization(err.to_string())),
+            Self::Sent(response) => response.try_into_string().await,
+        }
+    }
+
+    async fn try_into_bytes(self) -> Result<Bytes, ServerFnError<CustErr>> {
+        match self {
+            Self::Canned(canned) => Ok(canned.body),
+            Self::Sent(response) => response.try_into_bytes().await,
+        }
+    }
+
+    fn try_into_stream(
+        self,
+    ) -> Result<
+        impl Stream<Item = Result<Bytes, ServerFnError>> + Send + Sync + 'static,
+        ServerFnError<CustErr>,
+    > {
+        use futures_util::future::Either;
+
+        match self {
+            Self::Canned(canned) => Ok(Either::Left(futures_util::stream::iter([Ok(canned.body)]))),
+            Self::Sent(response) => response.try_into_stream().map(Either::Right),
+        }
+    }
+
+    fn status(&self) -> u16 {
+        match self {
+            Self::Canned(canned) => canned.status,
+            Self::Sent(response) => response.status(),
+        }
+    }
+
+    fn status_text(&self) -> String {
+        match self {
+            Self::Canned(canned) => http::StatusCode::from_u16(canned.status)
+                .ok()
+                .and_then(|status| status.canonical_reason())
+                .unwrap_or_default()
+                .to_string(),
+            Self::Sent(response) => response.status_text(),
+        }
+    }
+
+    fn location(&self) -> String {
+        match self {
+            Self::Canned(_) => String::new(),
+            Self::Sent(response) => response.location(),
+        }
+    }
+
+    fn has_redirect(&self) -> bool {
+        match self {
+            Self::Canned(_) => false,
+            Self::Sent(response) => response.has_redirect(),
+        }
+    }
+}
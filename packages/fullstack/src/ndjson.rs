@@ -0,0 +1,194 @@
+//! Server functions that respond with a stream of newline delimited JSON
+
+use futures_util::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+#[cfg(feature = "axum")]
+use serde::Serialize;
+#[cfg(feature = "axum")]
+use server_fn::codec::IntoRes;
+use server_fn::codec::{Encoding, FromRes};
+use server_fn::error::{NoCustomError, ServerFnErrorSerde};
+use server_fn::response::ClientRes;
+use server_fn::ServerFnError;
+use std::fmt::Display;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::task::{Context, Poll};
+
+/// An output encoding for server functions that stream [newline delimited JSON](https://github.com/ndjson/ndjson-spec)
+/// to the client. Use it with an [`NdjsonStream`] output.
+///
+/// Each item of the stream is serialized to one line of JSON and sent as soon as it is ready, so the client can render
+/// rows while the rest of the list is still loading. The response is only read as fast as the client reads it, so a slow
+/// client slows down the stream instead of making the server buffer the items. Errors are sent as a
+/// `{"$error": "..."}` line without ending the stream.
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// # use futures_util::StreamExt;
+/// #[server(output = Ndjson)]
+/// async fn users() -> Result<NdjsonStream<String>, ServerFnError> {
+///     let users = futures_util::stream::iter(["Alice", "Bob"]).map(str::to_string);
+///     Ok(NdjsonStream::new(users))
+/// }
+///
+/// fn app() -> Element {
+///     let mut names = use_signal(Vec::new);
+///     use_future(move || async move {
+///         let mut stream = users().await?.into_inner();
+///         while let Some(Ok(user)) = stream.next().await {
+///             names.write().push(user);
+///         }
+///         Ok::<_, ServerFnError>(())
+///     });
+///     rsx! {
+///         for user in names.iter() {
+///             p { "{user}" }
+///         }
+///     }
+/// }
+/// ```
+pub struct Ndjson;
+
+impl Encoding for Ndjson {
+    const CONTENT_TYPE: &'static str = "application/x-ndjson";
+    const METHOD: http::Method = http::Method::POST;
+}
+
+/// A stream of items a server function sends to the client with the [`Ndjson`] encoding
+pub struct NdjsonStream<T, CustErr = NoCustomError>(
+    Pin<Box<dyn Stream<Item = Result<T, ServerFnError<CustErr>>> + Send>>,
+);
+
+impl<T: 'static, CustErr: 'static> NdjsonStream<T, CustErr> {
+    /// Create a new stream from a stream of items
+    pub fn new(stream: impl Stream<Item = T> + Send + 'static) -> Self {
+        Self(Box::pin(stream.map(Ok)))
+    }
+
+    /// Create a new stream from a stream of items that may fail. Errors are sent to the client without ending the stream
+    pub fn try_new(
+        stream: impl Stream<Item = Result<T, ServerFnError<CustErr>>> + Send + 'static,
+    ) -> Self {
+        Self(Box::pin(stream))
+    }
+
+    /// Get the stream of items
+    pub fn into_inner(self) -> impl Stream<Item = Result<T, ServerFnError<CustErr>>> + Send {
+        self.0
+    }
+}
+
+impl<T, CustErr> Stream for NdjsonStream<T, CustErr> {
+    type Item = Result<T, ServerFnError<CustErr>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0.poll_next_unpin(cx)
+    }
+}
+
+/// The key of the object errors are sent as
+#[cfg(feature = "axum")]
+const ERROR_KEY: &str = "$error";
+
+#[cfg(feature = "axum")]
+impl<T, CustErr> IntoRes<Ndjson, http::Response<axum::body::Body>, CustErr>
+    for NdjsonStream<T, CustErr>
+where
+    T: Serialize + Send + 'static,
+    CustErr: FromStr + Display + Send + 'static,
+{
+    async fn into_res(self) -> Result<http::Response<axum::body::Body>, ServerFnError<CustErr>> {
+        // The response body is polled after the server function returns, so keep providing the server context of the
+        // request while the stream runs
+        let server_context = crate::prelude::server_context();
+        let mut stream = self.0;
+        let lines = futures_util::stream::poll_fn(move |cx| {
+            crate::prelude::with_server_context(server_context.clone(), || {
+                stream.poll_next_unpin(cx)
+            })
+        })
+        .map(|item| {
+            let mut line = match item {
+                Ok(item) => serde_json::to_vec(&item)?,
+                Err(err) => serde_json::to_vec(&serde_json::json!({
+                    ERROR_KEY: err.ser().unwrap_or_default()
+                }))?,
+            };
+            line.push(b'\n');
+            Ok::<_, serde_json::Error>(bytes::Bytes::from(line))
+        });
+
+        http::Response::builder()
+            .header(http::header::CONTENT_TYPE, Ndjson::CONTENT_TYPE)
+            .body(axum::body::Body::from_stream(lines))
+            .map_err(|err| ServerFnError::Response(err.to_string()))
+    }
+}
+
+impl<T, CustErr, Response> FromRes<Ndjson, Response, CustErr> for NdjsonStream<T, CustErr>
+where
+    T: DeserializeOwned + Send + 'static,
+    CustErr: FromStr + Display + Send + 'static,
+    Response: ClientRes<CustErr> + Send,
+{
+    async fn from_res(res: Response) -> Result<Self, ServerFnError<CustErr>> {
+        let body = res.try_into_stream()?;
+        let items = futures_util::stream::unfold(
+            (Box::pin(body), Vec::new()),
+            |(mut body, mut buffer)| async move {
+                loop {
+                    // Chunks may split a line or a character, so only decode complete lines
+                    if let Some(end) = buffer.iter().position(|byte| *byte == b'\n') {
+                        let line = buffer.drain(..=end).collect::<Vec<_>>();
+                        match parse_line::<T, CustErr>(&line) {
+                            Some(item) => return Some((item, (body, buffer))),
+                            None => continue,
+                        }
+                    }
+                    match body.next().await {
+                        Some(Ok(chunk)) => buffer.extend_from_slice(&chunk),
+                        Some(Err(err)) => {
+                            return Some((
+                                Err(ServerFnError::Response(err.to_string())),
+                                (body, buffer),
+                            ))
+                        }
+                        // The last line may not end with a newline
+                        None if !buffer.is_empty() => {
+                            let line = std::mem::take(&mut buffer);
+                            let item = parse_line::<T, CustErr>(&line)?;
+                            return Some((item, (body, buffer)));
+                        }
+                        None => return None,
+                    }
+                }
+            },
+        );
+        Ok(Self(Box::pin(items)))
+    }
+}
+
+/// Parse one line. Returns `None` for blank lines
+fn parse_line<T: DeserializeOwned, CustErr: FromStr + Display>(
+    line: &[u8],
+) -> Option<Result<T, ServerFnError<CustErr>>> {
+    let line = std::str::from_utf8(line).map(str::trim).unwrap_or_default();
+    if line.is_empty() {
+        return None;
+    }
+
+    #[derive(serde::Deserialize)]
+    struct ErrorLine {
+        #[serde(rename = "$error")]
+        error: String,
+    }
+    if line.starts_with(r#"{"$error":"#) {
+        if let Ok(ErrorLine { error }) = serde_json::from_str(line) {
+            return Some(Err(ServerFnError::de(&error)));
+        }
+    }
+    Some(serde_json::from_str(line).map_err(|err| ServerFnError::Deserialization(err.to_string())))
+}
@@ -0,0 +1,132 @@
+//! Server functions that respond with JSON or HTML depending on the Accept header
+
+use serde::de::DeserializeOwned;
+#[cfg(feature = "axum")]
+use serde::Serialize;
+#[cfg(feature = "axum")]
+use server_fn::codec::IntoRes;
+use server_fn::codec::{Encoding, FromRes};
+use server_fn::response::ClientRes;
+use server_fn::ServerFnError;
+#[cfg(feature = "axum")]
+use std::{
+    fmt::{Debug, Display},
+    str::FromStr,
+};
+
+/// An output encoding for server functions that back both an API client and a plain HTML form. Use it with an
+/// [`HtmlOrJson`] output.
+///
+/// The server looks at the `Accept` header of the request. If the request prefers `text/html`, the output is rendered
+/// with [`HtmlFragment::to_html`] and sent as `text/html`. Otherwise, including when there is no `Accept` header, the
+/// output is sent as JSON. Server function clients always ask for JSON.
+///
+/// Form posts that receive an HTML response are not redirected to the `Referer`, so the form gets the fragment back.
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// pub enum Saved {
+///     Todo { id: u32, title: String },
+///     Rejected { reason: String },
+/// }
+///
+/// impl HtmlFragment for Saved {
+///     fn to_html(&self) -> String {
+///         match self {
+///             Saved::Todo { id, title } => format!("<li id=\"todo-{id}\">{title}</li>"),
+///             Saved::Rejected { reason } => format!("<p class=\"error\">{reason}</p>"),
+///         }
+///     }
+/// }
+///
+/// #[server(output = Negotiated)]
+/// async fn save_todo(title: String) -> Result<HtmlOrJson<Saved>, ServerFnError> {
+///     Ok(HtmlOrJson(Saved::Todo { id: 1, title }))
+/// }
+/// ```
+pub struct Negotiated;
+
+impl Encoding for Negotiated {
+    const CONTENT_TYPE: &'static str = "application/json";
+    const METHOD: http::Method = http::Method::POST;
+}
+
+/// The output of a [`Negotiated`] server function
+pub struct HtmlOrJson<T>(pub T);
+
+impl<T> HtmlOrJson<T> {
+    /// Get the output
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// An output of a [`Negotiated`] server function that can be rendered as an HTML fragment
+pub trait HtmlFragment {
+    /// Render the output as HTML. The HTML is sent to the client as is, so escape any user input it contains
+    fn to_html(&self) -> String;
+}
+
+/// Check if the request prefers an HTML response over JSON. Whichever of the two types is listed first wins
+#[cfg(feature = "axum")]
+fn prefers_html(accept: &str) -> bool {
+    let position = |mime: &str| {
+        accept
+            .split(',')
+            .position(|entry| entry.trim().starts_with(mime))
+    };
+    match (position("text/html"), position("application/json")) {
+        (Some(html), Some(json)) => html < json,
+        (html, _) => html.is_some(),
+    }
+}
+
+/// Marks a response a [`Negotiated`] server function answered with HTML. Only those responses answer a form post
+/// themselves instead of redirecting to the `Referer`
+#[cfg(feature = "axum")]
+#[derive(Clone, Copy)]
+pub(crate) struct NegotiatedHtml;
+
+#[cfg(feature = "axum")]
+impl<T, CustErr> IntoRes<Negotiated, http::Response<axum::body::Body>, CustErr> for HtmlOrJson<T>
+where
+    T: HtmlFragment + Serialize + Send,
+    CustErr: FromStr + Display + Debug + Send + Sync + 'static,
+{
+    async fn into_res(self) -> Result<http::Response<axum::body::Body>, ServerFnError<CustErr>> {
+        use server_fn::response::Res;
+
+        let accept = crate::prelude::server_context()
+            .request_parts()
+            .headers
+            .get(http::header::ACCEPT)
+            .and_then(|accept| accept.to_str().ok())
+            .map(prefers_html);
+        if accept == Some(true) {
+            let mut res =
+                http::Response::try_from_string("text/html; charset=utf-8", self.0.to_html())?;
+            res.extensions_mut().insert(NegotiatedHtml);
+            return Ok(res);
+        }
+
+        let json = serde_json::to_string(&self.0)
+            .map_err(|err| ServerFnError::Serialization(err.to_string()))?;
+        http::Response::try_from_string(Negotiated::CONTENT_TYPE, json)
+    }
+}
+
+impl<T, CustErr, Response> FromRes<Negotiated, Response, CustErr> for HtmlOrJson<T>
+where
+    T: DeserializeOwned + Send,
+    Response: ClientRes<CustErr> + Send,
+{
+    async fn from_res(res: Response) -> Result<Self, ServerFnError<CustErr>> {
+        let json = res.try_into_string().await?;
+        serde_json::from_str(&json)
+            .map(Self)
+            .map_err(|err| ServerFnError::Deserialization(err.to_string()))
+    }
+}
@@ -1,6 +1,7 @@
 //! A shared pool of renderers for efficient server side rendering.
 use crate::document::ServerDocument;
-use crate::streaming::{Mount, StreamingRenderer};
+use crate::serve_config::IndexHtml;
+use crate::streaming::{flush_chunks, Mount, StreamingRenderer};
 use dioxus_interpreter_js::INITIALIZE_STREAMING_JS;
 use dioxus_isrg::{CachedRender, RenderFreshness};
 use dioxus_lib::document::Document;
@@ -20,6 +21,10 @@ use dioxus_lib::prelude::*;
 struct PendingSuspenseBoundary {
     mount: Mount,
     children: Vec<ScopeId>,
+    /// The name of the component that contains the suspense boundary. This is used to label the timing of the boundary
+    name: &'static str,
+    /// When the placeholder for the boundary was rendered
+    started: std::time::Instant,
 }
 
 /// Spawn a task in the background. If wasm is enabled, this will use the single threaded tokio runtime
@@ -49,28 +54,22 @@ where
 
 struct SsrRendererPool {
     renderers: RwLock<Vec<Renderer>>,
-    incremental_cache: Option<RwLock<dioxus_isrg::IncrementalRenderer>>,
 }
 
 impl SsrRendererPool {
-    fn new(
-        initial_size: usize,
-        incremental: Option<dioxus_isrg::IncrementalRendererConfig>,
-    ) -> Self {
+    fn new(initial_size: usize) -> Self {
         let renderers = RwLock::new((0..initial_size).map(|_| pre_renderer()).collect());
-        Self {
-            renderers,
-            incremental_cache: incremental.map(|cache| RwLock::new(cache.build())),
-        }
+        Self { renderers }
     }
 
-    /// Look for a cached route in the incremental cache and send it into the render channel if it exists
+    /// Look for a cached route in the incremental cache of the config and send it into the render channel if it exists
     fn check_cached_route(
         &self,
+        cfg: &ServeConfig,
         route: &str,
         render_into: &mut Sender<Result<String, dioxus_isrg::IncrementalRendererError>>,
     ) -> Option<RenderFreshness> {
-        if let Some(incremental) = &self.incremental_cache {
+        if let Some(incremental) = cfg.incremental_cache() {
             if let Ok(mut incremental) = incremental.write() {
                 match incremental.get(route) {
                     Ok(Some(cached_render)) => {
@@ -98,62 +97,52 @@ impl SsrRendererPool {
 
     /// Render a virtual dom into a stream. This method will return immediately and continue streaming the result in the background
     /// The streaming is canceled when the stream the function returns is dropped
-    async fn render_to(
+    async fn render_to<F>(
         self: Arc<Self>,
         cfg: &ServeConfig,
         route: String,
-        virtual_dom_factory: impl FnOnce() -> VirtualDom + Send + Sync + 'static,
+        virtual_dom_factory: impl FnOnce() -> F + Send + 'static,
         server_context: &DioxusServerContext,
-    ) -> Result<
-        (
-            RenderFreshness,
-            impl Stream<Item = Result<String, dioxus_isrg::IncrementalRendererError>>,
-        ),
-        dioxus_isrg::IncrementalRendererError,
-    > {
-        struct ReceiverWithDrop {
-            receiver: futures_channel::mpsc::Receiver<
-                Result<String, dioxus_isrg::IncrementalRendererError>,
-            >,
-            cancel_task: Option<tokio::task::JoinHandle<()>>,
-        }
-
-        impl Stream for ReceiverWithDrop {
-            type Item = Result<String, dioxus_isrg::IncrementalRendererError>;
-
-            fn poll_next(
-                mut self: std::pin::Pin<&mut Self>,
-                cx: &mut std::task::Context<'_>,
-            ) -> std::task::Poll<Option<Self::Item>> {
-                self.receiver.poll_next_unpin(cx)
-            }
-        }
-
-        // When we drop the stream, we need to cancel the task that is feeding values to the stream
-        impl Drop for ReceiverWithDrop {
-            fn drop(&mut self) {
-                if let Some(cancel_task) = self.cancel_task.take() {
-                    cancel_task.abort();
-                }
-            }
-        }
-
+        use_incremental_cache: bool,
+    ) -> Result<(RenderFreshness, ReceiverWithDrop), dioxus_isrg::IncrementalRendererError>
+    where
+        F: Future<Output = Result<VirtualDom, dioxus_isrg::IncrementalRendererError>> + 'static,
+    {
         let (mut into, rx) = futures_channel::mpsc::channel::<
             Result<String, dioxus_isrg::IncrementalRendererError>,
         >(1000);
 
+        let csp_nonce = server_context.csp_nonce();
+
+        // Only full page navigations should read from or write to the incremental cache. Pages with a nonce, a template
+        // built from the request or feature flags are unique to the request, so they can't be cached either
+        let request_index = server_context.get::<RequestIndex>();
+        let cacheable = use_incremental_cache
+            && csp_nonce.is_none()
+            && request_index.is_none()
+            && server_context.get::<FeatureFlags>().is_none()
+            && is_cacheable_navigation(&server_context.request_parts());
+
         // before we even spawn anything, we can check synchronously if we have the route cached
-        if let Some(freshness) = self.check_cached_route(&route, &mut into) {
-            return Ok((
-                freshness,
-                ReceiverWithDrop {
-                    receiver: rx,
-                    cancel_task: None,
-                },
-            ));
+        if cacheable {
+            if let Some(freshness) = self.check_cached_route(cfg, &route, &mut into) {
+                return Ok((
+                    freshness,
+                    ReceiverWithDrop {
+                        receiver: rx,
+                        cancel_task: None,
+                        _cancel_render: None,
+                    },
+                ));
+            }
         }
 
-        let wrapper = FullstackHTMLTemplate { cfg: cfg.clone() };
+        let wrapper = FullstackHTMLTemplate {
+            cfg: cfg.clone(),
+            csp_nonce: csp_nonce.clone(),
+            head: server_context.get::<RequestHead>().map(|head| head.0),
+            index: request_index.map(|index| index.0),
+        };
 
         let server_context = server_context.clone();
         let mut renderer = self
@@ -164,20 +153,79 @@ impl SsrRendererPool {
             .unwrap_or_else(pre_renderer);
 
         let myself = self.clone();
-
-        let join_handle = spawn_platform(move || async move {
-            let mut virtual_dom = virtual_dom_factory();
+        let catch_panics = cfg.catch_render_panics.then(|| into.clone());
+
+        let render = move || async move {
+            // The factory may be async, so we need to provide the server context while it runs
+            let mut virtual_dom = match ProvideServerContext::new(
+                virtual_dom_factory(),
+                server_context.clone(),
+            )
+            .await
+            {
+                Ok(virtual_dom) => VirtualDomWithDropHook {
+                    virtual_dom,
+                    on_drop: wrapper.cfg.on_virtual_dom_drop.clone(),
+                    server_context: server_context.clone(),
+                },
+                Err(err) => {
+                    _ = into.start_send(Err(err));
+                    return;
+                }
+            };
             let document = std::rc::Rc::new(crate::document::server::ServerDocument::default());
             virtual_dom.provide_root_context(document.clone());
             virtual_dom.provide_root_context(Rc::new(
                 dioxus_history::MemoryHistory::with_initial_path(&route),
             ) as Rc<dyn dioxus_history::History>);
             virtual_dom.provide_root_context(document.clone() as std::rc::Rc<dyn Document>);
+            // Provide the client hints before the first render so the initial html can be tailored to the device
+            virtual_dom.provide_root_context(server_context.client_hints());
+            // Let components read the request while they render with `use_server_context`
+            virtual_dom.provide_root_context(server_context.clone());
+            if let Some(locale) = server_context.get::<Locale>() {
+                virtual_dom.provide_root_context(locale);
+            }
+            if let Some(feature_flags) = server_context.get::<FeatureFlags>() {
+                virtual_dom.provide_root_context(feature_flags);
+            }
 
             // poll the future, which may call server_context()
             tracing::info!("Rebuilding vdom");
             with_server_context(server_context.clone(), || virtual_dom.rebuild_in_place());
 
+            // Components run inside of a catch_unwind in dioxus core, so their panics are thrown to the root error
+            // boundary instead of unwinding the render. Report those panics like a panic that unwound the render
+            if wrapper.cfg.catch_render_panics {
+                if let Some(message) = root_panic(&virtual_dom) {
+                    report_render_panic(&mut into, &message);
+                    return;
+                }
+            }
+
+            // Static pages are sent in one piece without any of the scripts streaming and hydration need
+            if server_context.is_static() || wrapper.cfg.is_static_route(&route) {
+                ProvideServerContext::new(virtual_dom.wait_for_suspense(), server_context.clone())
+                    .await;
+                renderer.pre_render = false;
+                renderer.reset_hydration();
+                let mut page = String::new();
+                let body = renderer.render(&virtual_dom);
+                if let Err(err) = wrapper.render_static_page(&mut page, &virtual_dom, body) {
+                    _ = into.start_send(Err(err));
+                    return;
+                }
+                if let Some(incremental) = wrapper.cfg.incremental_cache().filter(|_| cacheable) {
+                    if let Ok(mut incremental) = incremental.write() {
+                        let _ = incremental.cache(route, page.clone());
+                    }
+                }
+                _ = into.start_send(Ok(page));
+                renderer.pre_render = true;
+                myself.renderers.write().unwrap().push(renderer);
+                return;
+            }
+
             let mut pre_body = String::new();
 
             if let Err(err) = wrapper.render_head(&mut pre_body, &virtual_dom) {
@@ -185,7 +233,7 @@ impl SsrRendererPool {
                 return;
             }
 
-            let stream = Arc::new(StreamingRenderer::new(pre_body, into));
+            let stream = Arc::new(StreamingRenderer::new(pre_body, into).with_csp_nonce(csp_nonce));
             let scope_to_mount_mapping = Arc::new(RwLock::new(HashMap::new()));
 
             renderer.pre_render = true;
@@ -196,8 +244,17 @@ impl SsrRendererPool {
                 // The stack starts with the root scope because the root is a suspense boundary
                 let pending_suspense_boundaries_stack = RwLock::new(vec![]);
                 renderer.set_render_components(move |renderer, to, vdom, scope| {
-                    let is_suspense_boundary =
-                        SuspenseContext::downcast_suspense_boundary_from_scope(
+                    // The client renders the children of deferred boundaries from scratch once they hydrate, so they
+                    // are rendered without hydration markers
+                    if crate::deferred::is_deferred_content(vdom, scope) {
+                        let pre_render = std::mem::replace(&mut renderer.pre_render, false);
+                        let out = renderer.render_scope(to, vdom, scope);
+                        renderer.pre_render = pre_render;
+                        return out;
+                    }
+                    // Suspense boundaries inside deferred content are rendered as they are, the client never hydrates them
+                    let is_suspense_boundary = renderer.pre_render
+                        && SuspenseContext::downcast_suspense_boundary_from_scope(
                             &vdom.runtime(),
                             scope,
                         )
@@ -229,6 +286,8 @@ impl SsrRendererPool {
                             PendingSuspenseBoundary {
                                 mount,
                                 children: vec![],
+                                name: suspense_boundary_name(vdom, scope),
+                                started: std::time::Instant::now(),
                             },
                         );
                         // Add the scope to the list of children of the parent suspense boundary
@@ -290,6 +349,13 @@ impl SsrRendererPool {
                     };
                     // If the suspense boundary was immediately removed, it may not have a mount. We can just skip resolving it
                     if let Some(pending_suspense_boundary) = pending_suspense_boundary {
+                        tracing::debug!(
+                            boundary = pending_suspense_boundary.name,
+                            mount = %pending_suspense_boundary.mount,
+                            route = %route,
+                            duration_ms = pending_suspense_boundary.started.elapsed().as_secs_f64() * 1000.,
+                            "Resolved suspense boundary"
+                        );
                         let mut resolved_chunk = String::new();
                         // After we replace the placeholder in the dom with javascript, we need to send down the resolved data so that the client can hydrate the node
                         let render_suspense = |into: &mut String| {
@@ -335,7 +401,7 @@ impl SsrRendererPool {
             }
 
             // If incremental rendering is enabled, add the new render to the cache without the streaming bits
-            if let Some(incremental) = &self.incremental_cache {
+            if let Some(incremental) = wrapper.cfg.incremental_cache().filter(|_| cacheable) {
                 let mut cached_render = String::new();
                 if let Err(err) = wrapper.render_head(&mut cached_render, &virtual_dom) {
                     throw_error!(err);
@@ -358,6 +424,15 @@ impl SsrRendererPool {
 
             renderer.reset_render_components();
             myself.renderers.write().unwrap().push(renderer);
+        };
+        // Stop rendering as soon as the client disconnects and the stream is dropped. The virtual dom and any server
+        // functions its suspense boundaries are waiting for are dropped with the render
+        let cancel_render = tokio_util::sync::CancellationToken::new();
+        let render_cancelled = cancel_render.clone();
+        let join_handle = spawn_platform(move || async move {
+            let render = std::pin::pin!(catch_render_panics(catch_panics, render()));
+            let cancelled = std::pin::pin!(render_cancelled.cancelled());
+            futures_util::future::select(render, cancelled).await;
         });
 
         Ok((
@@ -365,11 +440,162 @@ impl SsrRendererPool {
             ReceiverWithDrop {
                 receiver: rx,
                 cancel_task: Some(join_handle),
+                _cancel_render: Some(cancel_render.drop_guard()),
             },
         ))
     }
 }
 
+/// The stream of a render. The render is canceled when the stream is dropped
+struct ReceiverWithDrop {
+    receiver:
+        futures_channel::mpsc::Receiver<Result<String, dioxus_isrg::IncrementalRendererError>>,
+    cancel_task: Option<tokio::task::JoinHandle<()>>,
+    // Aborting the handle of a pinned task only stops the task that waits for it, so the render itself also
+    // listens for the token
+    _cancel_render: Option<tokio_util::sync::DropGuard>,
+}
+
+impl Stream for ReceiverWithDrop {
+    type Item = Result<String, dioxus_isrg::IncrementalRendererError>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        self.receiver.poll_next_unpin(cx)
+    }
+}
+
+// When we drop the stream, we need to cancel the task that is feeding values to the stream
+impl Drop for ReceiverWithDrop {
+    fn drop(&mut self) {
+        if let Some(cancel_task) = self.cancel_task.take() {
+            cancel_task.abort();
+        }
+    }
+}
+
+/// Run the render task. If a sender is passed, a panic while rendering is caught and sent to the response stream as an
+/// error instead of unwinding the task
+async fn catch_render_panics(
+    panic_into: Option<Sender<Result<String, dioxus_isrg::IncrementalRendererError>>>,
+    render: impl Future<Output = ()>,
+) {
+    use futures_util::FutureExt;
+
+    let Some(mut panic_into) = panic_into else {
+        return render.await;
+    };
+    // The render task owns everything it touches except the renderer pool, and a renderer that panicked is never
+    // returned to the pool, so no broken state is observed after the panic
+    if let Err(panic) = std::panic::AssertUnwindSafe(render).catch_unwind().await {
+        report_render_panic(&mut panic_into, &panic_message(&*panic));
+    }
+}
+
+/// Send a panic to the response stream as an error
+fn report_render_panic(
+    panic_into: &mut Sender<Result<String, dioxus_isrg::IncrementalRendererError>>,
+    message: &str,
+) {
+    tracing::error!("A component panicked while rendering: {message}");
+    _ = panic_into.start_send(Err(dioxus_isrg::IncrementalRendererError::Other(
+        format!("A component panicked while rendering: {message}").into(),
+    )));
+}
+
+/// The message of a panic payload
+fn panic_message(panic: &dyn std::any::Any) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// The message of the first panic that reached the root error boundary of the virtual dom
+fn root_panic(virtual_dom: &VirtualDom) -> Option<String> {
+    let errors = virtual_dom.in_runtime(|| ScopeId::APP.consume_context::<ErrorContext>())?;
+    let errors = errors.errors();
+    errors.iter().find_map(|error| {
+        let panic = error.downcast::<dioxus_lib::prelude::dioxus_core::CapturedPanic>()?;
+        Some(panic_message(&*panic.error))
+    })
+}
+
+/// A [`VirtualDom`] that runs the drop hook from the [`ServeConfig`] when it is dropped. The render task may be aborted
+/// at any await point, so the hook runs from the destructor instead of the end of the task
+struct VirtualDomWithDropHook {
+    virtual_dom: VirtualDom,
+    on_drop: Option<crate::serve_config::VirtualDomDropHook>,
+    server_context: DioxusServerContext,
+}
+
+impl std::ops::Deref for VirtualDomWithDropHook {
+    type Target = VirtualDom;
+
+    fn deref(&self) -> &Self::Target {
+        &self.virtual_dom
+    }
+}
+
+impl std::ops::DerefMut for VirtualDomWithDropHook {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.virtual_dom
+    }
+}
+
+impl Drop for VirtualDomWithDropHook {
+    fn drop(&mut self) {
+        if let Some(on_drop) = self.on_drop.take() {
+            with_server_context(self.server_context.clone(), || on_drop(&self.virtual_dom));
+        }
+    }
+}
+
+/// Check if a request is a top level GET navigation that may be served from and stored in the incremental cache.
+///
+/// Speculative prefetches (marked with `Sec-Purpose: prefetch` or the legacy `Purpose: prefetch` header) and
+/// subresource requests (a `Sec-Fetch-Dest` other than `document`) bypass the cache so they don't fill it with
+/// navigations the user never committed to.
+fn is_cacheable_navigation(parts: &http::request::Parts) -> bool {
+    if parts.method != http::Method::GET {
+        return false;
+    }
+
+    let header_contains = |name: &str, value: &str| {
+        parts
+            .headers
+            .get(name)
+            .and_then(|header| header.to_str().ok())
+            .map(|header| header.to_ascii_lowercase().contains(value))
+            .unwrap_or(false)
+    };
+    if header_contains("sec-purpose", "prefetch") || header_contains("purpose", "prefetch") {
+        return false;
+    }
+
+    match parts
+        .headers
+        .get("sec-fetch-dest")
+        .and_then(|header| header.to_str().ok())
+    {
+        Some(destination) => destination.eq_ignore_ascii_case("document"),
+        None => true,
+    }
+}
+
+/// Get the name of the component that renders a suspense boundary. The boundary itself is always a `SuspenseBoundary`
+/// component, so the parent component is a more useful label when reporting how long the boundary took to resolve.
+fn suspense_boundary_name(virtual_dom: &VirtualDom, suspense_scope: ScopeId) -> &'static str {
+    virtual_dom
+        .in_runtime(|| suspense_scope.parent_scope())
+        .and_then(|parent| virtual_dom.get_scope(parent))
+        .map(|scope| scope.name())
+        .unwrap_or("SuspenseBoundary")
+}
+
 /// Start capturing errors at a suspense boundary. If the parent suspense boundary is frozen, we need to capture the errors in the suspense boundary
 /// and send them to the client to continue bubbling up
 fn start_capturing_errors(suspense_scope: ScopeId) {
@@ -388,19 +614,41 @@ fn serialize_server_data(virtual_dom: &VirtualDom, scope: ScopeId) -> String {
 }
 
 /// State used in server side rendering. This utilizes a pool of [`dioxus_ssr::Renderer`]s to cache static templates between renders.
+///
+/// The renderers don't depend on the [`ServeConfig`] they render with, so one [`SSRState`] can be shared between applications
+/// with different configs. The incremental cache belongs to each [`ServeConfig`], so sharing an [`SSRState`] never mixes
+/// up the cached pages of different configs.
 #[derive(Clone)]
 pub struct SSRState {
     // We keep a pool of renderers to avoid re-creating them on every request. They are boxed to make them very cheap to move
     renderers: Arc<SsrRendererPool>,
 }
 
-impl SSRState {
-    /// Create a new [`SSRState`].
-    pub fn new(cfg: &ServeConfig) -> Self {
+impl Default for SSRState {
+    fn default() -> Self {
         Self {
-            renderers: Arc::new(SsrRendererPool::new(4, cfg.incremental.clone())),
+            renderers: Arc::new(SsrRendererPool::new(4)),
         }
     }
+}
+
+impl SSRState {
+    /// Create a new [`SSRState`]. The state is not tied to the config, so it can be shared with [`RenderHandleState`](crate::prelude::RenderHandleState)s that use any config.
+    pub fn new(_cfg: &ServeConfig) -> Self {
+        Self::default()
+    }
+
+    /// The number of templates the idle renderers in the pool have cached. Renderers that are rendering a page right now
+    /// are not counted
+    pub fn cached_templates(&self) -> usize {
+        self.renderers
+            .renderers
+            .read()
+            .unwrap()
+            .iter()
+            .map(|renderer| renderer.cached_templates())
+            .sum()
+    }
 
     /// Render the application to HTML.
     pub async fn render<'a>(
@@ -416,23 +664,251 @@ impl SSRState {
         ),
         dioxus_isrg::IncrementalRendererError,
     > {
-        self.renderers
-            .clone()
-            .render_to(cfg, route, virtual_dom_factory, server_context)
+        self.render_with_async_factory(
+            route,
+            cfg,
+            move || std::future::ready(Ok(virtual_dom_factory())),
+            server_context,
+        )
+        .await
+    }
+
+    /// Render the application to HTML with an async [`VirtualDom`] factory. The factory runs inside the server context for
+    /// the request, so it can make request-scoped decisions with [`server_context()`]. If the factory fails, the returned
+    /// stream will yield the error.
+    pub async fn render_with_async_factory<'a, F>(
+        &'a self,
+        route: String,
+        cfg: &'a ServeConfig,
+        virtual_dom_factory: impl FnOnce() -> F + Send + 'static,
+        server_context: &'a DioxusServerContext,
+    ) -> Result<
+        (
+            RenderFreshness,
+            impl Stream<Item = Result<String, dioxus_isrg::IncrementalRendererError>>,
+        ),
+        dioxus_isrg::IncrementalRendererError,
+    >
+    where
+        F: Future<Output = Result<VirtualDom, dioxus_isrg::IncrementalRendererError>> + 'static,
+    {
+        self.render_with_cache(route, cfg, virtual_dom_factory, server_context, true)
             .await
     }
+
+    /// Render the application like [`SSRState::render_with_async_factory`]. If `use_incremental_cache` is false, the
+    /// page is never read from or written to the incremental cache
+    pub(crate) async fn render_with_cache<'a, F>(
+        &'a self,
+        route: String,
+        cfg: &'a ServeConfig,
+        virtual_dom_factory: impl FnOnce() -> F + Send + 'static,
+        server_context: &'a DioxusServerContext,
+        use_incremental_cache: bool,
+    ) -> Result<
+        (
+            RenderFreshness,
+            impl Stream<Item = Result<String, dioxus_isrg::IncrementalRendererError>>,
+        ),
+        dioxus_isrg::IncrementalRendererError,
+    >
+    where
+        F: Future<Output = Result<VirtualDom, dioxus_isrg::IncrementalRendererError>> + 'static,
+    {
+        let render = self.renderers.clone().render_to(
+            cfg,
+            route,
+            virtual_dom_factory,
+            server_context,
+            use_incremental_cache,
+        );
+        let (freshness, stream) = match coalescing_key(cfg, server_context) {
+            Some(key) => {
+                let (freshness, stream) = cfg
+                    .in_flight_renders
+                    .render(key, server_context, render)
+                    .await?;
+                (freshness, futures_util::future::Either::Left(stream))
+            }
+            None => {
+                let (freshness, stream) = render.await?;
+                (freshness, futures_util::future::Either::Right(stream))
+            }
+        };
+        Ok((freshness, flush_chunks(cfg.stream_flush_strategy, stream)))
+    }
+}
+
+/// The key to share the render of a request by if the config coalesces renders
+fn coalescing_key(cfg: &ServeConfig, server_context: &DioxusServerContext) -> Option<String> {
+    let coalesce_key = cfg.coalesce_key.as_ref()?;
+    // Pages with a nonce or a template built from the request are unique to every response
+    if server_context.csp_nonce().is_some() || server_context.get::<RequestIndex>().is_some() {
+        return None;
+    }
+    let mut key = coalesce_key(&server_context.request_parts())?;
+    // The extra head html is built from the request, so only requests with the same head can share a render
+    if let Some(head) = server_context.get::<RequestHead>() {
+        key.push('\n');
+        key.push_str(&head.0);
+    }
+    // Likewise, only requests with the same feature flags can share a render
+    if let Some(feature_flags) = server_context.get::<FeatureFlags>() {
+        key.push('\n');
+        key.push_str(&serde_json::to_string(&feature_flags).ok()?);
+    }
+    Some(key)
 }
 
 /// The template that wraps the body of the HTML for a fullstack page. This template contains the data needed to hydrate server functions that were run on the server.
 pub struct FullstackHTMLTemplate {
     cfg: ServeConfig,
+    csp_nonce: Option<Arc<str>>,
+    head: Option<Arc<str>>,
+    index: Option<Arc<IndexHtml>>,
 }
 
+/// Extra html for the head of the page that is being rendered. Stored in the [`DioxusServerContext`] of the request
+#[derive(Clone)]
+pub(crate) struct RequestHead(pub(crate) Arc<str>);
+
+/// The index.html template built for the page that is being rendered. Stored in the [`DioxusServerContext`] of the request
+#[derive(Clone)]
+pub(crate) struct RequestIndex(pub(crate) Arc<IndexHtml>);
+
 impl FullstackHTMLTemplate {
     /// Create a new [`FullstackHTMLTemplate`].
     pub fn new(cfg: &ServeConfig) -> Self {
-        Self { cfg: cfg.clone() }
+        Self {
+            cfg: cfg.clone(),
+            csp_nonce: None,
+            head: None,
+            index: None,
+        }
+    }
+
+    /// Add extra html to the end of the head of every page the template renders, like `<meta>` tags. Any tag in the
+    /// html that would close the head or open the body is escaped so it stays inside of the head
+    pub fn with_head(mut self, head: impl Into<String>) -> Self {
+        self.head = Some(Arc::from(escape_head(&head.into())));
+        self
+    }
+
+    /// Add a content security policy nonce to every inline script the template renders
+    pub fn with_csp_nonce(mut self, nonce: impl Into<Arc<str>>) -> Self {
+        self.csp_nonce = Some(nonce.into());
+        self
+    }
+
+    /// Render with an index.html template that was built for the request instead of the template from the config
+    pub(crate) fn with_index(mut self, index: Arc<IndexHtml>) -> Self {
+        self.index = Some(index);
+        self
+    }
+
+    /// The index.html template the page is rendered with
+    fn index(&self) -> &IndexHtml {
+        self.index.as_deref().unwrap_or(&self.cfg.index)
+    }
+
+    /// Write a segment of the index.html template, adding the nonce to any scripts inside of it
+    fn write_segment<R: std::fmt::Write>(
+        &self,
+        to: &mut R,
+        segment: &str,
+    ) -> Result<(), dioxus_isrg::IncrementalRendererError> {
+        match &self.csp_nonce {
+            Some(nonce) => to.write_str(&add_nonce_to_scripts(segment, nonce))?,
+            None => to.write_str(segment)?,
+        }
+        Ok(())
+    }
+}
+
+/// The `nonce` attribute for an inline script. Renders nothing if there is no nonce
+pub(crate) struct NonceAttribute<'a>(pub(crate) Option<&'a str>);
+
+impl std::fmt::Display for NonceAttribute<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(nonce) => write!(f, r#" nonce="{nonce}""#),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Escape any tag in html for the head that would close the head or start the body of the page
+pub(crate) fn escape_head(html: &str) -> String {
+    const BREAKOUT_TAGS: [&str; 4] = ["</head", "<body", "</html", "<html"];
+
+    let lowercase = html.to_ascii_lowercase();
+    let mut output = String::with_capacity(html.len());
+    let mut last = 0;
+    for (index, _) in lowercase.match_indices('<') {
+        if BREAKOUT_TAGS.iter().any(|tag| {
+            lowercase[index..].strip_prefix(tag).is_some_and(|rest| {
+                rest.starts_with(|c: char| c == '>' || c == '/' || c.is_ascii_whitespace())
+                    || rest.is_empty()
+            })
+        }) {
+            output.push_str(&html[last..index]);
+            output.push_str("&lt;");
+            last = index + 1;
+        }
     }
+    output.push_str(&html[last..]);
+    output
+}
+
+/// Remove the scripts and the preloads for scripts and wasm from a segment of the index.html
+fn strip_client_scripts(html: &str) -> String {
+    let lowercase = html.to_ascii_lowercase();
+    let mut output = String::with_capacity(html.len());
+    let mut last = 0;
+    let mut search = 0;
+    while let Some(start) = lowercase[search..].find('<').map(|index| search + index) {
+        let rest = &lowercase[start..];
+        let end = if rest.starts_with("<script") {
+            rest.find("</script>")
+                .map(|close| start + close + "</script>".len())
+        } else if rest.starts_with("<link") {
+            let tag_end = rest.find('>').map_or(rest.len(), |end| end + 1);
+            let tag = &rest[..tag_end];
+            (tag.contains("modulepreload") || tag.contains(".wasm")).then_some(start + tag_end)
+        } else {
+            None
+        };
+        match end {
+            Some(end) => {
+                output.push_str(&html[last..start]);
+                last = end;
+                search = end;
+            }
+            None => search = start + 1,
+        }
+    }
+    output.push_str(&html[last..]);
+    output
+}
+
+/// Add a nonce to every opening script tag in some html that doesn't already have one
+fn add_nonce_to_scripts(html: &str, nonce: &str) -> String {
+    const SCRIPT_TAG: &str = "<script";
+
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(index) = rest.find(SCRIPT_TAG) {
+        let (before, after) = rest.split_at(index + SCRIPT_TAG.len());
+        output.push_str(before);
+        let is_script_tag = after.starts_with(|c: char| c == '>' || c.is_ascii_whitespace());
+        let tag_end = after.find('>').unwrap_or(after.len());
+        if is_script_tag && !after[..tag_end].contains("nonce=") {
+            output.push_str(&NonceAttribute(Some(nonce)).to_string());
+        }
+        rest = after;
+    }
+    output.push_str(rest);
+    output
 }
 
 impl FullstackHTMLTemplate {
@@ -442,7 +918,7 @@ impl FullstackHTMLTemplate {
         to: &mut R,
         virtual_dom: &VirtualDom,
     ) -> Result<(), dioxus_isrg::IncrementalRendererError> {
-        let ServeConfig { index, .. } = &self.cfg;
+        let index = self.index();
 
         let title = {
             let document: Option<std::rc::Rc<ServerDocument>> =
@@ -451,23 +927,34 @@ impl FullstackHTMLTemplate {
             document.and_then(|document| document.title())
         };
 
-        to.write_str(&index.head_before_title)?;
+        self.write_segment(to, &index.head_before_title)?;
         if let Some(title) = title {
             to.write_str(&title)?;
         } else {
             to.write_str(&index.title)?;
         }
-        to.write_str(&index.head_after_title)?;
+        self.write_segment(to, &index.head_after_title)?;
+
+        // The critical styles come before any head elements from components so the first paint is already styled
+        self.render_critical_css(to)?;
 
         let document: Option<std::rc::Rc<ServerDocument>> =
             virtual_dom.in_runtime(|| ScopeId::ROOT.consume_context());
         if let Some(document) = document {
-            // Collect any head content from the document provider and inject that into the head
-            document.render(to)?;
+            // Collect any head content from the document provider and inject that into the head. Scripts from components
+            // need the nonce too if there is a content security policy
+            let mut head = String::new();
+            document.render(&mut head)?;
+            self.write_segment(to, &head)?;
 
             // Enable a warning when inserting contents into the head during streaming
             document.start_streaming();
         }
+        self.render_robots_meta(to, virtual_dom)?;
+
+        if let Some(head) = &self.head {
+            self.write_segment(to, head)?;
+        }
 
         self.render_before_body(to)?;
 
@@ -479,11 +966,47 @@ impl FullstackHTMLTemplate {
         &self,
         to: &mut R,
     ) -> Result<(), dioxus_isrg::IncrementalRendererError> {
-        let ServeConfig { index, .. } = &self.cfg;
+        let index = self.index();
+
+        self.render_root_id(to)?;
+        self.write_segment(to, &index.close_head)?;
+
+        let nonce = NonceAttribute(self.csp_nonce.as_deref());
+        write!(to, "<script{nonce}>{INITIALIZE_STREAMING_JS}</script>")?;
+
+        Ok(())
+    }
+
+    /// Render the `<meta name="robots">` tag the page asked for with [`DioxusServerContext::set_robots_with_meta`]
+    fn render_robots_meta<R: std::fmt::Write>(
+        &self,
+        to: &mut R,
+        virtual_dom: &VirtualDom,
+    ) -> Result<(), dioxus_isrg::IncrementalRendererError> {
+        let server_context: Option<DioxusServerContext> =
+            virtual_dom.in_runtime(|| ScopeId::ROOT.consume_context());
+        if let Some(directive) = server_context.and_then(|context| context.robots_meta()) {
+            let directive = crate::serve_config::escape_attribute(&directive);
+            write!(to, r#"<meta name="robots" content="{directive}">"#)?;
+        }
 
-        to.write_str(&index.close_head)?;
+        Ok(())
+    }
 
-        write!(to, "<script>{INITIALIZE_STREAMING_JS}</script>")?;
+    /// Tell the client which element the application is rendered into, so it hydrates the same element even if its own
+    /// config names a different root
+    fn render_root_id<R: std::fmt::Write>(
+        &self,
+        to: &mut R,
+    ) -> Result<(), dioxus_isrg::IncrementalRendererError> {
+        let root_id = serde_json::to_string(&self.index().root_id)
+            .unwrap_or_default()
+            .replace("</", "<\\/");
+        let nonce = NonceAttribute(self.csp_nonce.as_deref());
+        write!(
+            to,
+            "<script{nonce}>window.dioxus_root_id={root_id};</script>"
+        )?;
 
         Ok(())
     }
@@ -494,16 +1017,44 @@ impl FullstackHTMLTemplate {
         to: &mut R,
         virtual_dom: &VirtualDom,
     ) -> Result<(), dioxus_isrg::IncrementalRendererError> {
-        let ServeConfig { index, .. } = &self.cfg;
+        let index = self.index();
 
         // Collect the initial server data from the root node. For most apps, no use_server_futures will be resolved initially, so this will be full on `None`s.
         // Sending down those Nones are still important to tell the client not to run the use_server_futures that are already running on the backend
         let resolved_data = serialize_server_data(virtual_dom, ScopeId::ROOT);
+        let nonce = NonceAttribute(self.csp_nonce.as_deref());
         write!(
             to,
-            r#"<script>window.initial_dioxus_hydration_data="{resolved_data}";</script>"#,
+            r#"<script{nonce}>window.initial_dioxus_hydration_data="{resolved_data}";</script>"#,
         )?;
-        to.write_str(&index.post_main)?;
+
+        // Start watching the deferred boundaries before the wasm loads
+        if crate::deferred::has_deferred_boundaries(virtual_dom) {
+            write!(
+                to,
+                "<script{nonce}>{}</script>",
+                crate::deferred::DEFERRED_HYDRATION_BOOTSTRAP
+            )?;
+        }
+
+        // In debug builds, send down the shape of the rendered tree so the client can point out where hydration diverges.
+        // The client doesn't hydrate deferred content, so it is left out
+        #[cfg(debug_assertions)]
+        {
+            let paths = dioxus_lib::prelude::dioxus_core::internal::hydration_node_paths_skipping(
+                virtual_dom,
+                |scope| crate::deferred::is_deferred_content(virtual_dom, scope.id()),
+            );
+            let paths = serde_json::to_string(&paths)
+                .unwrap_or_default()
+                .replace("</", "<\\/");
+            write!(
+                to,
+                r#"<script{nonce}>window.dioxus_hydration_paths={paths};</script>"#,
+            )?;
+        }
+        self.write_segment(to, &index.post_main)?;
+        self.render_main_stylesheet(to)?;
 
         Ok(())
     }
@@ -513,13 +1064,96 @@ impl FullstackHTMLTemplate {
         &self,
         to: &mut R,
     ) -> Result<(), dioxus_isrg::IncrementalRendererError> {
-        let ServeConfig { index, .. } = &self.cfg;
+        let index = self.index();
+
+        self.write_segment(to, &index.after_closing_body_tag)?;
+
+        Ok(())
+    }
+
+    /// Render the template without any content in the main element. The client will render the app from scratch instead of hydrating it.
+    pub fn render_shell<R: std::fmt::Write>(
+        &self,
+        to: &mut R,
+    ) -> Result<(), dioxus_isrg::IncrementalRendererError> {
+        let index = self.index();
+
+        self.write_segment(to, &index.head_before_title)?;
+        to.write_str(&index.title)?;
+        self.write_segment(to, &index.head_after_title)?;
+        self.render_critical_css(to)?;
+        self.render_root_id(to)?;
+        self.write_segment(to, &index.close_head)?;
+        self.write_segment(to, &index.post_main)?;
+        self.render_main_stylesheet(to)?;
+        self.render_after_body(to)?;
+
+        Ok(())
+    }
+
+    /// Render a complete page without hydration. Scripts from the index.html are left out, so the page doesn't load any
+    /// client side code
+    pub fn render_static_page<R: std::fmt::Write>(
+        &self,
+        to: &mut R,
+        virtual_dom: &VirtualDom,
+        body: impl std::fmt::Display,
+    ) -> Result<(), dioxus_isrg::IncrementalRendererError> {
+        let index = self.index();
+
+        let document: Option<std::rc::Rc<ServerDocument>> =
+            virtual_dom.in_runtime(|| ScopeId::ROOT.consume_context());
+        let title = document.as_ref().and_then(|document| document.title());
 
-        to.write_str(&index.after_closing_body_tag)?;
+        self.write_segment(to, &strip_client_scripts(&index.head_before_title))?;
+        to.write_str(title.as_deref().unwrap_or(&index.title))?;
+        self.write_segment(to, &strip_client_scripts(&index.head_after_title))?;
+        self.render_critical_css(to)?;
+        if let Some(document) = document {
+            let mut head = String::new();
+            document.render(&mut head)?;
+            self.write_segment(to, &head)?;
+        }
+        self.render_robots_meta(to, virtual_dom)?;
+        if let Some(head) = &self.head {
+            self.write_segment(to, head)?;
+        }
+        self.write_segment(to, &strip_client_scripts(&index.close_head))?;
+        write!(to, "{body}")?;
+        self.write_segment(to, &strip_client_scripts(&index.post_main))?;
+        self.render_main_stylesheet(to)?;
+        self.write_segment(to, &strip_client_scripts(&index.after_closing_body_tag))?;
 
         Ok(())
     }
 
+    /// Render the critical CSS and the preload for the main stylesheet into the head
+    fn render_critical_css<R: std::fmt::Write>(
+        &self,
+        to: &mut R,
+    ) -> Result<(), dioxus_isrg::IncrementalRendererError> {
+        if let Some(css) = &self.cfg.critical_css {
+            let nonce = NonceAttribute(self.csp_nonce.as_deref());
+            write!(to, "<style{nonce}>{css}</style>")?;
+        }
+        if let Some(stylesheet) = &self.cfg.main_stylesheet {
+            write!(to, r#"<link rel="preload" href="{stylesheet}" as="style">"#)?;
+        }
+        Ok(())
+    }
+
+    /// Apply the main stylesheet at the end of the body. A stylesheet in the body doesn't block painting the page above
+    /// it, so the main element is painted with just the critical CSS
+    fn render_main_stylesheet<R: std::fmt::Write>(
+        &self,
+        to: &mut R,
+    ) -> Result<(), dioxus_isrg::IncrementalRendererError> {
+        if let Some(stylesheet) = &self.cfg.main_stylesheet {
+            write!(to, r#"<link rel="stylesheet" href="{stylesheet}">"#)?;
+        }
+        Ok(())
+    }
+
     /// Wrap a body in the template
     pub fn wrap_body<R: std::fmt::Write>(
         &self,
@@ -0,0 +1,139 @@
+//! Render a route twice and find where the html differs to debug hydration mismatches
+
+use crate::prelude::*;
+use dioxus_lib::prelude::*;
+use std::rc::Rc;
+
+/// The first place two renders of the same route differ. Returned by [`diff_html`] and [`diff_route_renders`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RenderDiff {
+    /// The byte offset of the first difference
+    pub offset: usize,
+    /// The line of the first difference, starting at 1
+    pub line: usize,
+    /// The column of the first difference in characters, starting at 1
+    pub column: usize,
+    /// The html around the difference in the expected render
+    pub expected: String,
+    /// The html around the difference in the render that was checked
+    pub found: String,
+}
+
+impl std::fmt::Display for RenderDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "the renders differ at line {} column {}:",
+            self.line, self.column
+        )?;
+        writeln!(f, "  expected: {}", self.expected)?;
+        write!(f, "  found:    {}", self.found)
+    }
+}
+
+/// How many characters of context to show on each side of a difference
+const CONTEXT_CHARS: usize = 40;
+
+/// Find the first place two html strings differ. Returns `None` if they are identical.
+pub fn diff_html(expected: &str, found: &str) -> Option<RenderDiff> {
+    let offset = expected
+        .char_indices()
+        .zip(found.chars())
+        .find(|((_, expected), found)| expected != found)
+        .map(|((offset, _), _)| offset)
+        .or_else(|| {
+            // One render is a prefix of the other
+            (expected.len() != found.len()).then(|| expected.len().min(found.len()))
+        })?;
+
+    let before = &expected[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .map(|line| line.chars().count())
+        .unwrap_or_default()
+        + 1;
+
+    let context = |html: &str| {
+        let start = html[..offset]
+            .char_indices()
+            .rev()
+            .nth(CONTEXT_CHARS - 1)
+            .map(|(index, _)| index)
+            .unwrap_or_default();
+        html[start..].chars().take(CONTEXT_CHARS * 2).collect()
+    };
+
+    Some(RenderDiff {
+        offset,
+        line,
+        column,
+        expected: context(expected),
+        found: context(found),
+    })
+}
+
+/// Render a route with two [`VirtualDom`]s and find the first place the html differs. Returns `None` if the renders match.
+///
+/// Each [`VirtualDom`] is rendered the same way the server renders a page: with the route as the initial path of the history,
+/// inside a [`DioxusServerContext`] for the route, and with all suspense resolved. The html includes the hydration ids the
+/// client uses to hydrate the page, so any difference means the client would fail to hydrate a page rendered by the other.
+///
+/// Render the route once in the state the server would see and once in the state the client restores to find components
+/// that render differently on the server and the client.
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// #[derive(Clone, Copy)]
+/// struct DarkMode(bool);
+///
+/// fn app() -> Element {
+///     let dark_mode = try_consume_context::<DarkMode>().map(|mode| mode.0).unwrap_or_default();
+///     rsx! { div { class: if dark_mode { "dark" } else { "light" }, "Hello World" } }
+/// }
+///
+/// #[tokio::test]
+/// async fn theme_hydrates() {
+///     let diff = diff_route_renders(
+///         "/",
+///         || VirtualDom::new(app),
+///         || VirtualDom::new(app).with_root_context(DarkMode(true)),
+///     )
+///     .await;
+///     assert!(diff.is_none(), "{}", diff.unwrap());
+/// }
+/// ```
+pub async fn diff_route_renders(
+    route: &str,
+    expected: impl FnOnce() -> VirtualDom,
+    found: impl FnOnce() -> VirtualDom,
+) -> Option<RenderDiff> {
+    let expected = render_route(route, expected()).await;
+    let found = render_route(route, found()).await;
+    diff_html(&expected, &found)
+}
+
+/// Render a route to html with all suspense resolved
+async fn render_route(route: &str, mut virtual_dom: VirtualDom) -> String {
+    let mut parts = http::Request::new(()).into_parts().0;
+    if let Ok(uri) = route.parse() {
+        parts.uri = uri;
+    }
+    let server_context = DioxusServerContext::new(parts);
+
+    let document = Rc::new(crate::document::server::ServerDocument::default());
+    virtual_dom.provide_root_context(document.clone());
+    virtual_dom.provide_root_context(document as Rc<dyn dioxus_lib::document::Document>);
+    virtual_dom.provide_root_context(Rc::new(dioxus_history::MemoryHistory::with_initial_path(
+        route,
+    )) as Rc<dyn dioxus_history::History>);
+    virtual_dom.provide_root_context(server_context.client_hints());
+
+    with_server_context(server_context.clone(), || virtual_dom.rebuild_in_place());
+    ProvideServerContext::new(virtual_dom.wait_for_suspense(), server_context).await;
+
+    dioxus_ssr::pre_render(&virtual_dom)
+}
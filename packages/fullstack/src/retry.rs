@@ -0,0 +1,266 @@
+//! Automatic retries for idempotent server function calls on the client
+
+use bytes::Bytes;
+use futures_util::Stream;
+use server_fn::client::Client;
+use server_fn::request::ClientReq;
+use server_fn::response::ClientRes;
+use server_fn::ServerFnError;
+use std::marker::PhantomData;
+use std::sync::RwLock;
+use std::time::Duration;
+
+#[cfg(any(feature = "desktop", feature = "mobile"))]
+pub(crate) type DefaultClient = server_fn::client::reqwest::ReqwestClient;
+#[cfg(not(any(feature = "desktop", feature = "mobile")))]
+pub(crate) type DefaultClient = server_fn::client::browser::BrowserClient;
+
+static RETRY_POLICY: RwLock<RetryPolicy> = RwLock::new(RetryPolicy::new());
+
+/// Set the [`RetryPolicy`] every server function that uses the [`RetryClient`] follows
+pub fn set_retry_policy(policy: RetryPolicy) {
+    *RETRY_POLICY.write().unwrap_or_else(|err| err.into_inner()) = policy;
+}
+
+/// Get the [`RetryPolicy`] the [`RetryClient`] currently follows
+pub fn retry_policy() -> RetryPolicy {
+    *RETRY_POLICY.read().unwrap_or_else(|err| err.into_inner())
+}
+
+/// How the [`RetryClient`] retries a server function call that failed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RetryPolicy {
+    /// Create a new retry policy with the default settings
+    pub const fn new() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(2),
+        }
+    }
+
+    /// Set the maximum number of times a call is sent, including the first attempt. Set it to `1` to disable retries (defaults to 3)
+    pub const fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Set how long to wait before the first retry. The wait doubles after every failed retry (defaults to 100ms)
+    pub const fn initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// Set the longest time to wait between two attempts (defaults to 2s)
+    pub const fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// The time to wait after a failed attempt
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_backoff)
+    }
+}
+
+/// A server function [`Client`] that retries idempotent calls that fail because of the network or the server.
+///
+/// Only `GET` server functions are retried. Server functions send every other encoding as a `POST` request, which is not
+/// idempotent, so those calls are never retried automatically. A call is retried if the request fails to reach the server
+/// or the server responds with a `5xx` status. Calls the server rejects with a `4xx` status are never retried.
+///
+/// The client follows the global [`RetryPolicy`] you can change with [`set_retry_policy`]. It wraps the default client of
+/// the platform unless you pass another client as the type parameter.
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// use server_fn::codec::GetUrl;
+///
+/// #[server(input = GetUrl, client = RetryClient)]
+/// async fn latest_posts() -> Result<Vec<String>, ServerFnError> {
+///     Ok(vec!["Hello World".to_string()])
+/// }
+///
+/// fn main() {
+///     set_retry_policy(
+///         RetryPolicy::new()
+///             .max_attempts(5)
+///             .initial_backoff(std::time::Duration::from_millis(250)),
+///     );
+///     dioxus::launch(app);
+/// }
+///
+/// fn app() -> Element {
+///     let posts = use_resource(latest_posts);
+///     rsx! { "{posts:?}" }
+/// }
+/// ```
+pub struct RetryClient<C = DefaultClient>(PhantomData<C>);
+
+impl<CustErr, C> Client<CustErr> for RetryClient<C>
+where
+    C: Client<CustErr>,
+    CustErr: Send,
+{
+    type Request = RetryRequest<C::Request>;
+    type Response = C::Response;
+
+    async fn send(req: Self::Request) -> Result<Self::Response, ServerFnError<CustErr>> {
+        let RetryRequest { mut request, retry } = req;
+        let Some(retry) = retry else {
+            return C::send(request).await;
+        };
+
+        let policy = retry_policy();
+        let mut attempt = 1;
+        loop {
+            let result = C::send(request).await;
+            let retryable = match &result {
+                Ok(response) => response.status() >= 500,
+                Err(ServerFnError::Request(_)) => true,
+                Err(_) => false,
+            };
+            if !retryable || attempt >= policy.max_attempts {
+                return result;
+            }
+            drop(result);
+
+            let backoff = policy.backoff(attempt);
+            tracing::debug!(
+                "Retrying server function {} in {}ms after attempt {attempt} failed",
+                retry.path,
+                backoff.as_millis()
+            );
+            sleep(backoff).await;
+            attempt += 1;
+            request = retry.build::<CustErr, C::Request>()?;
+        }
+    }
+}
+
+/// A request sent by the [`RetryClient`]. Idempotent requests remember how they were built so they can be sent again.
+pub struct RetryRequest<R> {
+    request: R,
+    retry: Option<GetRequest>,
+}
+
+/// Everything needed to build a `GET` request again
+struct GetRequest {
+    path: String,
+    content_type: String,
+    accepts: String,
+    query: String,
+}
+
+impl GetRequest {
+    fn build<CustErr, R: ClientReq<CustErr>>(&self) -> Result<R, ServerFnError<CustErr>> {
+        R::try_new_get(&self.path, &self.content_type, &self.accepts, &self.query)
+    }
+}
+
+impl<R> RetryRequest<R> {
+    fn once(request: R) -> Self {
+        Self {
+            request,
+            retry: None,
+        }
+    }
+}
+
+impl<CustErr, R: ClientReq<CustErr>> ClientReq<CustErr> for RetryRequest<R> {
+    type FormData = R::FormData;
+
+    fn try_new_get(
+        path: &str,
+        content_type: &str,
+        accepts: &str,
+        query: &str,
+    ) -> Result<Self, ServerFnError<CustErr>> {
+        let retry = GetRequest {
+            path: path.to_string(),
+            content_type: content_type.to_string(),
+            accepts: accepts.to_string(),
+            query: query.to_string(),
+        };
+        Ok(Self {
+            request: retry.build::<CustErr, R>()?,
+            retry: Some(retry),
+        })
+    }
+
+    fn try_new_post(
+        path: &str,
+        content_type: &str,
+        accepts: &str,
+        body: String,
+    ) -> Result<Self, ServerFnError<CustErr>> {
+        R::try_new_post(path, content_type, accepts, body).map(Self::once)
+    }
+
+    fn try_new_post_bytes(
+        path: &str,
+        content_type: &str,
+        accepts: &str,
+        body: Bytes,
+    ) -> Result<Self, ServerFnError<CustErr>> {
+        R::try_new_post_bytes(path, content_type, accepts, body).map(Self::once)
+    }
+
+    fn try_new_post_form_data(
+        path: &str,
+        accepts: &str,
+        content_type: &str,
+        body: Self::FormData,
+    ) -> Result<Self, ServerFnError<CustErr>> {
+        R::try_new_post_form_data(path, accepts, content_type, body).map(Self::once)
+    }
+
+    fn try_new_multipart(
+        path: &str,
+        accepts: &str,
+        body: Self::FormData,
+    ) -> Result<Self, ServerFnError<CustErr>> {
+        R::try_new_multipart(path, accepts, body).map(Self::once)
+    }
+
+    fn try_new_streaming(
+        path: &str,
+        accepts: &str,
+        content_type: &str,
+        body: impl Stream<Item = Bytes> + Send + 'static,
+    ) -> Result<Self, ServerFnError<CustErr>> {
+        R::try_new_streaming(path, accepts, content_type, body).map(Self::once)
+    }
+}
+
+/// Wait before the next attempt
+async fn sleep(duration: Duration) {
+    #[cfg(target_arch = "wasm32")]
+    {
+        // The timer callback only holds the sender, so the future stays `Send` like the rest of the client
+        let (sender, receiver) = futures_channel::oneshot::channel();
+        gloo_timers::callback::Timeout::new(duration.as_millis() as u32, move || {
+            _ = sender.send(());
+        })
+        .forget();
+        _ = receiver.await;
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    tokio::time::sleep(duration).await;
+}
@@ -4,16 +4,263 @@
 use std::fs::File;
 use std::io::Read;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 
 use dioxus_lib::prelude::dioxus_core::LaunchConfig;
 
+use crate::coalesce::{default_coalescing_key, CoalesceKeyFn};
+
 /// A ServeConfig is used to configure how to serve a Dioxus application. It contains information about how to serve static assets, and what content to render with [`dioxus-ssr`].
 #[derive(Clone, Default)]
 pub struct ServeConfigBuilder {
-    pub(crate) root_id: Option<&'static str>,
+    pub(crate) root_id: Option<String>,
     pub(crate) index_html: Option<String>,
     pub(crate) index_path: Option<PathBuf>,
     pub(crate) incremental: Option<dioxus_isrg::IncrementalRendererConfig>,
+    pub(crate) render_mode: Option<RenderModeFn>,
+    pub(crate) overload_policy: Option<OverloadPolicy>,
+    pub(crate) render_limit: Option<(usize, RenderOverflow)>,
+    pub(crate) critical_css: Option<CriticalCss>,
+    pub(crate) main_stylesheet: Option<String>,
+    pub(crate) strict_csp: bool,
+    pub(crate) csp_policy: Option<String>,
+    pub(crate) request_timeouts: RouteTimeouts,
+    pub(crate) trust_forwarded_headers: bool,
+    pub(crate) on_virtual_dom_drop: Option<VirtualDomDropHook>,
+    pub(crate) https_redirect: bool,
+    pub(crate) production: Option<bool>,
+    pub(crate) build_id: Option<String>,
+    pub(crate) accept_matching: AcceptMatching,
+    pub(crate) stream_flush_strategy: StreamFlushStrategy,
+    pub(crate) supported_locales: Vec<String>,
+    pub(crate) default_locale: Option<String>,
+    pub(crate) catch_render_panics: bool,
+    pub(crate) early_hints: Vec<String>,
+    pub(crate) render_missing_assets: bool,
+    pub(crate) coalesce_key: Option<CoalesceKeyFn>,
+    pub(crate) static_routes: Option<StaticRouteFn>,
+    pub(crate) preload_links: Vec<PreloadLinks>,
+    pub(crate) index_template: Option<IndexTemplateFn>,
+}
+
+/// A function that builds the index.html template for a request
+pub(crate) type IndexTemplateFn =
+    Arc<dyn Fn(&crate::prelude::DioxusServerContext) -> String + Send + Sync>;
+
+/// A function that runs right before the [`VirtualDom`](dioxus_lib::prelude::VirtualDom) of a request is dropped
+pub(crate) type VirtualDomDropHook = Arc<dyn Fn(&dioxus_lib::prelude::VirtualDom) + Send + Sync>;
+
+/// A function that decides if the page at a path is rendered without hydration
+pub(crate) type StaticRouteFn = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// Assets that are preloaded with a `Link` header on the pages a predicate matches
+#[derive(Clone)]
+pub(crate) struct PreloadLinks {
+    routes: Option<StaticRouteFn>,
+    assets: Vec<String>,
+}
+
+/// A function that decides how a request should be rendered
+pub(crate) type RenderModeFn = Arc<dyn Fn(&http::request::Parts) -> RenderMode + Send + Sync>;
+
+/// How the server should render a request for an html page.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RenderMode {
+    /// Render the whole component tree on the server and hydrate it on the client. (default)
+    #[default]
+    Full,
+    /// Skip rendering the component tree and only send down the index.html shell with an empty root element.
+    /// The client will render the app from scratch once the wasm bundle loads.
+    ShellOnly,
+}
+
+/// How the server decides if a request accepts an html page from its `Accept` header. Requests without an `Accept`
+/// header are always rendered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AcceptMatching {
+    /// Render the page if the `Accept` header contains `text/html` anywhere. (default)
+    #[default]
+    Lenient,
+    /// Parse the media ranges in the `Accept` header and only render the page if the most specific range that matches
+    /// `text/html` (`text/html`, `text/*` or `*/*`) has a quality above zero. `text/html;q=0` is rejected.
+    Strict,
+}
+
+impl AcceptMatching {
+    /// Check if an `Accept` header accepts html
+    pub(crate) fn accepts_html(&self, accept: &str) -> bool {
+        match self {
+            AcceptMatching::Lenient => accept.to_ascii_lowercase().contains("text/html"),
+            AcceptMatching::Strict => {
+                // The quality of the most specific matching range. Higher specificity wins
+                let mut best: Option<(u8, f32)> = None;
+                for range in accept.split(',') {
+                    let mut params = range.split(';');
+                    let media_type = params
+                        .next()
+                        .unwrap_or_default()
+                        .trim()
+                        .to_ascii_lowercase();
+                    let specificity = match media_type.as_str() {
+                        "text/html" => 2,
+                        "text/*" => 1,
+                        "*/*" => 0,
+                        _ => continue,
+                    };
+                    let quality = params
+                        .filter_map(|param| param.split_once('='))
+                        .find(|(name, _)| name.trim().eq_ignore_ascii_case("q"))
+                        .map(|(_, quality)| quality.trim().parse::<f32>().unwrap_or(0.0))
+                        .unwrap_or(1.0);
+                    if best.map_or(true, |(best, _)| specificity > best) {
+                        best = Some((specificity, quality));
+                    }
+                }
+                best.is_some_and(|(_, quality)| quality > 0.0)
+            }
+        }
+    }
+}
+
+/// When the chunks of a streaming render are sent to the client. Every resolved suspense boundary produces a new chunk.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum StreamFlushStrategy {
+    /// Send every chunk as soon as it is rendered. (default)
+    #[default]
+    EveryBoundary,
+    /// Wait up to the duration after a chunk is rendered for more chunks and send them together. This reduces the number
+    /// of writes for pages with many small suspense boundaries at the cost of some latency.
+    Coalesce(Duration),
+    /// Buffer the whole page and send it once every suspense boundary is resolved.
+    Never,
+}
+
+/// Thresholds for the tokio runtime that the server is running on. If any of the thresholds are exceeded, the runtime is
+/// considered overloaded and requests are rendered with [`RenderMode::ShellOnly`] until the load goes down.
+///
+/// Shedding the server render keeps latency bounded during traffic spikes by moving the rendering work to the clients.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OverloadPolicy {
+    /// The maximum number of tasks waiting in the global queue of the runtime before it is considered overloaded
+    pub max_global_queue_depth: Option<usize>,
+    /// The maximum number of tasks alive in the runtime before it is considered overloaded
+    pub max_alive_tasks: Option<usize>,
+}
+
+impl OverloadPolicy {
+    /// Check if the current tokio runtime exceeds any of the thresholds
+    pub(crate) fn is_overloaded(&self) -> bool {
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            return false;
+        };
+        let metrics = handle.metrics();
+
+        let queue_overloaded = self
+            .max_global_queue_depth
+            .is_some_and(|max| metrics.global_queue_depth() > max);
+        let tasks_overloaded = self
+            .max_alive_tasks
+            .is_some_and(|max| metrics.num_alive_tasks() > max);
+
+        queue_overloaded || tasks_overloaded
+    }
+}
+
+/// What happens to a page request that arrives while [`ServeConfigBuilder::max_concurrent_renders`] pages are already
+/// rendering
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RenderOverflow {
+    /// Wait up to the duration for another render to finish before responding with `503 Service Unavailable`
+    Queue(Duration),
+    /// Respond with `503 Service Unavailable` right away
+    Shed,
+}
+
+/// The renders a config allows at once. Clones of the config share the same permits
+#[derive(Clone)]
+pub(crate) struct RenderLimit {
+    permits: Arc<tokio::sync::Semaphore>,
+    overflow: RenderOverflow,
+}
+
+impl RenderLimit {
+    fn new(max_renders: usize, overflow: RenderOverflow) -> Self {
+        Self {
+            permits: Arc::new(tokio::sync::Semaphore::new(max_renders)),
+            overflow,
+        }
+    }
+
+    /// Wait for a permit to render a page. Returns `None` if the request should be rejected
+    pub(crate) async fn acquire(&self) -> Option<tokio::sync::OwnedSemaphorePermit> {
+        match self.overflow {
+            RenderOverflow::Shed => self.permits.clone().try_acquire_owned().ok(),
+            RenderOverflow::Queue(timeout) => {
+                tokio::time::timeout(timeout, self.permits.clone().acquire_owned())
+                    .await
+                    .ok()?
+                    .ok()
+            }
+        }
+    }
+
+    /// The number of seconds a rejected client should wait before it tries again
+    pub(crate) fn retry_after(&self) -> u64 {
+        match self.overflow {
+            RenderOverflow::Shed => 1,
+            RenderOverflow::Queue(timeout) => timeout.as_secs().max(1),
+        }
+    }
+}
+
+/// The critical CSS to inline into the head of every page
+#[derive(Clone)]
+pub(crate) enum CriticalCss {
+    Inline(String),
+    File(PathBuf),
+}
+
+impl CriticalCss {
+    /// Load the CSS and escape it so it can't close the `<style>` tag it is inlined into
+    fn load(self) -> Option<Arc<str>> {
+        let css = match self {
+            Self::Inline(css) => css,
+            Self::File(path) => match std::fs::read_to_string(&path) {
+                Ok(css) => css,
+                Err(err) => {
+                    tracing::error!("Failed to read the critical CSS from {path:?}: {err}");
+                    return None;
+                }
+            },
+        };
+        Some(Arc::from(escape_style(&css)))
+    }
+}
+
+/// Escape every `</style` in some CSS. `\/` is an escaped `/` in CSS, so the stylesheet means the same thing
+fn escape_style(css: &str) -> String {
+    const CLOSE_TAG: &str = "</style";
+
+    let lowercase = css.to_ascii_lowercase();
+    let mut output = String::with_capacity(css.len());
+    let mut last = 0;
+    for (index, _) in lowercase.match_indices(CLOSE_TAG) {
+        output.push_str(&css[last..index]);
+        output.push_str("<\\/");
+        last = index + 2;
+    }
+    output.push_str(&css[last..]);
+    output
+}
+
+/// Escape a value so it can be placed inside of a double quoted html attribute
+pub(crate) fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
 }
 
 impl LaunchConfig for ServeConfigBuilder {}
@@ -26,6 +273,30 @@ impl ServeConfigBuilder {
             index_html: None,
             index_path: None,
             incremental: None,
+            render_mode: None,
+            overload_policy: None,
+            render_limit: None,
+            critical_css: None,
+            main_stylesheet: None,
+            strict_csp: false,
+            trust_forwarded_headers: false,
+            on_virtual_dom_drop: None,
+            https_redirect: false,
+            production: None,
+            build_id: None,
+            accept_matching: AcceptMatching::default(),
+            stream_flush_strategy: StreamFlushStrategy::default(),
+            supported_locales: Vec::new(),
+            default_locale: None,
+            catch_render_panics: false,
+            early_hints: Vec::new(),
+            render_missing_assets: false,
+            coalesce_key: None,
+            static_routes: None,
+            preload_links: Vec::new(),
+            index_template: None,
+            csp_policy: None,
+            request_timeouts: RouteTimeouts::default(),
         }
     }
 
@@ -59,6 +330,47 @@ impl ServeConfigBuilder {
         self
     }
 
+    /// Build the index.html template for every rendered page from the request, instead of using one template for every
+    /// page. This lets you change the branding of the page per tenant without rebuilding the application. (defaults to the
+    /// index.html file)
+    ///
+    /// The template must contain the element with the [root id](ServeConfigBuilder::root_id) and a closing `</head>` and
+    /// `</body>` tag. Pages whose template is missing one of them fail to render with a `500 Internal Server Error`.
+    /// Pages rendered with a template from the request are never stored in the incremental cache or shared between requests.
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// # fn app() -> Element { todo!() }
+    /// use dioxus::prelude::*;
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// LaunchBuilder::new()
+    ///     .with_cfg(server_only! {
+    ///         ServeConfigBuilder::default().index_html_with(|server_context| {
+    ///             let host = server_context
+    ///                 .request_parts()
+    ///                 .headers
+    ///                 .get(http::header::HOST)
+    ///                 .and_then(|host| host.to_str().ok())
+    ///                 .unwrap_or_default()
+    ///                 .to_string();
+    ///             let theme = if host.starts_with("acme.") { "acme" } else { "default" };
+    ///             format!(
+    ///                 r#"<!DOCTYPE html><html><head><link rel="stylesheet" href="/themes/{theme}.css"></head><body><div id="main"></div></body></html>"#
+    ///             )
+    ///         })
+    ///     })
+    ///     .launch(app);
+    /// ```
+    pub fn index_html_with(
+        mut self,
+        template: impl Fn(&crate::prelude::DioxusServerContext) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.index_template = Some(Arc::new(template));
+        self
+    }
+
     /// Set the path of the index.html file to be served. (defaults to {assets_path}/index.html)
     pub fn index_path(mut self, index_path: PathBuf) -> Self {
         self.index_path = Some(index_path);
@@ -90,14 +402,10 @@ impl ServeConfigBuilder {
     ///
     /// let mut cfg = dioxus::fullstack::Config::new();
     ///
-    /// // Only set the server config if the server feature is enabled
+    /// // Only set the server config if the server feature is enabled. Server rendered pages tell the web client which
+    /// // element to hydrate, so the web config doesn't need the root id
     /// server_only! {
-    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().root_id("app"));
-    /// }
-    ///
-    /// // You also need to set the root id in your web config
-    /// web! {
-    ///     cfg = cfg.with_web_config(dioxus::web::Config::default().rootname("app"));
+    ///     cfg = cfg.with_server_cfg(ServeConfigBuilder::default().root_id("my-custom-root"));
     /// }
     ///
     /// // And desktop config
@@ -110,8 +418,646 @@ impl ServeConfigBuilder {
     ///     .with_cfg(cfg)
     ///     .launch(app);
     /// ```
-    pub fn root_id(mut self, root_id: &'static str) -> Self {
-        self.root_id = Some(root_id);
+    pub fn root_id(self, root_id: &'static str) -> Self {
+        self.with_root_id(root_id)
+    }
+
+    /// Set the id of the root element in the index.html file to place the prerendered content into. Unlike
+    /// [`ServeConfigBuilder::root_id`], the id doesn't need to live for the whole program, so it can come from an
+    /// environment variable or a config file. (defaults to main)
+    ///
+    /// Every server rendered page tells the web client the id, so the client hydrates the same element the server rendered
+    /// into even if the root name in the web config is different.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # fn app() -> Element { todo!() }
+    /// use dioxus::prelude::*;
+    ///
+    /// LaunchBuilder::new()
+    ///     .with_cfg(server_only! {
+    ///         let root_id = std::env::var("APP_ROOT_ID").unwrap_or_else(|_| "main".to_string());
+    ///         ServeConfigBuilder::default().with_root_id(&root_id)
+    ///     })
+    ///     .launch(app);
+    /// ```
+    pub fn with_root_id(mut self, root_id: &str) -> Self {
+        self.root_id = Some(root_id.to_string());
+        self
+    }
+
+    /// Choose how each request should be rendered based on the parts of the request. (defaults to [`RenderMode::Full`] for every request)
+    ///
+    /// Rendering the full component tree is great for crawlers that need the final html, but interactive users may get a faster
+    /// first load with a [`RenderMode::ShellOnly`] page that skips the expensive server render.
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// # fn app() -> Element { todo!() }
+    /// use dioxus::prelude::*;
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// LaunchBuilder::new()
+    ///     .with_cfg(server_only! {
+    ///         ServeConfigBuilder::default().render_mode_for(|parts| {
+    ///             let user_agent = parts
+    ///                 .headers
+    ///                 .get(http::header::USER_AGENT)
+    ///                 .and_then(|agent| agent.to_str().ok())
+    ///                 .unwrap_or_default()
+    ///                 .to_ascii_lowercase();
+    ///             if user_agent.contains("bot") || user_agent.contains("crawler") {
+    ///                 RenderMode::Full
+    ///             } else {
+    ///                 RenderMode::ShellOnly
+    ///             }
+    ///         })
+    ///     })
+    ///     .launch(app);
+    /// ```
+    pub fn render_mode_for(
+        mut self,
+        render_mode: impl Fn(&http::request::Parts) -> RenderMode + Send + Sync + 'static,
+    ) -> Self {
+        self.render_mode = Some(Arc::new(render_mode));
+        self
+    }
+
+    /// Degrade to [`RenderMode::ShellOnly`] while the tokio runtime is overloaded. (defaults to always rendering with the configured [`RenderMode`])
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// # fn app() -> Element { todo!() }
+    /// use dioxus::prelude::*;
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// LaunchBuilder::new()
+    ///     .with_cfg(server_only! {
+    ///         ServeConfigBuilder::default().shed_load_when(OverloadPolicy {
+    ///             max_global_queue_depth: Some(256),
+    ///             ..Default::default()
+    ///         })
+    ///     })
+    ///     .launch(app);
+    /// ```
+    pub fn shed_load_when(mut self, policy: OverloadPolicy) -> Self {
+        self.overload_policy = Some(policy);
+        self
+    }
+
+    /// Render at most `max_renders` pages at once. Page requests over the limit wait or are rejected with
+    /// `503 Service Unavailable` and a `Retry-After` header depending on the [`RenderOverflow`]. (defaults to no limit)
+    ///
+    /// Server rendering is CPU bound, so rendering an unbounded number of pages at once under load slows every render
+    /// down and grows memory with each page in flight. A render holds its permit until the whole page is streamed.
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// # fn app() -> Element { todo!() }
+    /// use dioxus::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// LaunchBuilder::new()
+    ///     .with_cfg(server_only! {
+    ///         ServeConfigBuilder::default()
+    ///             .max_concurrent_renders(64, RenderOverflow::Queue(Duration::from_secs(2)))
+    ///     })
+    ///     .launch(app);
+    /// ```
+    pub fn max_concurrent_renders(mut self, max_renders: usize, overflow: RenderOverflow) -> Self {
+        self.render_limit = Some((max_renders, overflow));
+        self
+    }
+
+    /// Inline CSS into a `<style>` tag in the head of every server rendered page, before any html from your components.
+    /// (defaults to no critical CSS)
+    ///
+    /// Inline the styles the first paint needs, so the page doesn't flash unstyled content while the full stylesheet
+    /// loads. Pair it with [`ServeConfigBuilder::main_stylesheet`] to stop the full stylesheet from blocking the first
+    /// paint. Any `</style` in the CSS is escaped.
+    pub fn critical_css(mut self, css: impl Into<String>) -> Self {
+        self.critical_css = Some(CriticalCss::Inline(css.into()));
+        self
+    }
+
+    /// Inline the CSS in the file at the path into the head of every server rendered page. The file is read once when the
+    /// config is built. See [`ServeConfigBuilder::critical_css`].
+    pub fn critical_css_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.critical_css = Some(CriticalCss::File(path.into()));
+        self
+    }
+
+    /// Load the stylesheet at the url without blocking the first paint of the page. (defaults to no stylesheet)
+    ///
+    /// The head of the page preloads the stylesheet and the stylesheet is applied after the main element, so the browser
+    /// paints the page with the [critical CSS](ServeConfigBuilder::critical_css) while the full stylesheet downloads.
+    /// Don't also link the stylesheet in your index.html or from your components, or it will block the first paint again.
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// # fn app() -> Element { todo!() }
+    /// use dioxus::prelude::*;
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// LaunchBuilder::new()
+    ///     .with_cfg(server_only! {
+    ///         ServeConfigBuilder::default()
+    ///             .critical_css("body { margin: 0; font-family: sans-serif; }")
+    ///             .main_stylesheet("/assets/main.css")
+    ///     })
+    ///     .launch(app);
+    /// ```
+    pub fn main_stylesheet(mut self, url: impl Into<String>) -> Self {
+        self.main_stylesheet = Some(url.into());
+        self
+    }
+
+    /// Send a strict nonce based `Content-Security-Policy` with every server rendered page. (defaults to false)
+    ///
+    /// A new random nonce is generated for every request and added to every inline script in the page, including the
+    /// script that loads the wasm bundle and the scripts that hydrate streamed suspense boundaries. The policy only allows
+    /// scripts with that nonce and the scripts they load:
+    ///
+    /// ```text
+    /// script-src 'nonce-{nonce}' 'strict-dynamic' 'wasm-unsafe-eval'; object-src 'none'; base-uri 'self'
+    /// ```
+    ///
+    /// Pages rendered with a nonce are never read from or written to the incremental cache because the nonce must be
+    /// unique to every response.
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// # fn app() -> Element { todo!() }
+    /// use dioxus::prelude::*;
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// LaunchBuilder::new()
+    ///     .with_cfg(server_only! {
+    ///         ServeConfigBuilder::default().strict_csp(true)
+    ///     })
+    ///     .launch(app);
+    /// ```
+    pub fn strict_csp(mut self, strict_csp: bool) -> Self {
+        self.strict_csp = strict_csp;
+        self
+    }
+
+    /// Send a custom nonce based `Content-Security-Policy` with every server rendered page. Every `{nonce}` in the policy is
+    /// replaced with a new random nonce for each request. (defaults to no policy)
+    ///
+    /// Like [`ServeConfigBuilder::strict_csp`], the nonce is added to every inline script the server renders, including
+    /// scripts components add to the head with `document::Script`. Components and server functions can read the nonce
+    /// for the current request with [`DioxusServerContext::csp_nonce`](crate::prelude::DioxusServerContext::csp_nonce).
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// # fn app() -> Element { todo!() }
+    /// use dioxus::prelude::*;
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// LaunchBuilder::new()
+    ///     .with_cfg(server_only! {
+    ///         ServeConfigBuilder::default().content_security_policy(
+    ///             "default-src 'self'; script-src 'nonce-{nonce}' 'strict-dynamic' 'wasm-unsafe-eval'; object-src 'none'",
+    ///         )
+    ///     })
+    ///     .launch(app);
+    /// ```
+    pub fn content_security_policy(mut self, policy: impl Into<String>) -> Self {
+        self.csp_policy = Some(policy.into());
+        self
+    }
+
+    /// Respond with `504 Gateway Timeout` if a request takes longer than the timeout to respond. (defaults to no timeout)
+    ///
+    /// The timeout applies to server functions, static assets and server rendered pages that don't match a more specific
+    /// pattern set with [`ServeConfigBuilder::request_timeout_for`]. For server rendered pages, the timeout only covers the
+    /// time until the initial frame of the page is sent.
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeouts.default = Some(timeout);
+        self
+    }
+
+    /// Set the timeout for requests with a path that matches a route pattern. (defaults to the [`ServeConfigBuilder::request_timeout`])
+    ///
+    /// A pattern either matches a path exactly or, if it ends with `/*`, matches every path under it. If several patterns
+    /// match a path, the longest pattern wins.
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// # fn app() -> Element { todo!() }
+    /// use dioxus::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// LaunchBuilder::new()
+    ///     .with_cfg(server_only! {
+    ///         ServeConfigBuilder::default()
+    ///             // Give exports two minutes, but keep the api responsive
+    ///             .request_timeout_for("/export/*", Duration::from_secs(120))
+    ///             .request_timeout_for("/api/*", Duration::from_secs(5))
+    ///     })
+    ///     .launch(app);
+    /// ```
+    pub fn request_timeout_for(mut self, pattern: impl Into<String>, timeout: Duration) -> Self {
+        self.request_timeouts.routes.push((pattern.into(), timeout));
+        self
+    }
+
+    /// Trust the `Forwarded`, `X-Forwarded-Proto` and `X-Forwarded-Host` headers when building the origin of a request for
+    /// [`DioxusServerContext::origin`](crate::prelude::DioxusServerContext::origin), and the `X-Forwarded-For` header
+    /// when reading the IP of the client for [`DioxusServerContext::client_ip`](crate::prelude::DioxusServerContext::client_ip)
+    /// while the page renders. (defaults to false)
+    ///
+    /// Behind a reverse proxy, the request the server receives only has the path the client asked for, so the origin is
+    /// read from the headers the proxy adds. Anyone can send these headers, so only enable this if every request passes
+    /// through a proxy that overwrites them. Otherwise a client can make the server render absolute URLs like canonical
+    /// links that point to any host, which can poison shared caches.
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// # fn app() -> Element { todo!() }
+    /// use dioxus::prelude::*;
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// LaunchBuilder::new()
+    ///     .with_cfg(server_only! {
+    ///         ServeConfigBuilder::default().trust_forwarded_headers(true)
+    ///     })
+    ///     .launch(app);
+    /// ```
+    pub fn trust_forwarded_headers(mut self, trust_forwarded_headers: bool) -> Self {
+        self.trust_forwarded_headers = trust_forwarded_headers;
+        self
+    }
+
+    /// Run a callback right before the [`VirtualDom`](dioxus_lib::prelude::VirtualDom) of each server rendered page is
+    /// dropped. (defaults to no callback)
+    ///
+    /// The callback runs after the page has finished rendering, or when the render is cancelled because the client
+    /// disconnected. It runs inside the server context of the request, so you can read the request with
+    /// [`server_context`](crate::prelude::server_context) to record metrics or clean up per request resources.
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// # fn app() -> Element { todo!() }
+    /// use dioxus::prelude::*;
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// LaunchBuilder::new()
+    ///     .with_cfg(server_only! {
+    ///         ServeConfigBuilder::default().on_virtual_dom_drop(|dom| {
+    ///             let path = server_context().request_parts().uri.path().to_string();
+    ///             tracing::info!(path, suspended = dom.suspended_tasks_remaining(), "Dropped a rendered page");
+    ///         })
+    ///     })
+    ///     .launch(app);
+    /// ```
+    pub fn on_virtual_dom_drop(
+        mut self,
+        on_drop: impl Fn(&dioxus_lib::prelude::VirtualDom) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_virtual_dom_drop = Some(Arc::new(on_drop));
+        self
+    }
+
+    /// Permanently redirect requests sent over plain HTTP to HTTPS when the server runs in [production](ServeConfigBuilder::production).
+    /// (defaults to false)
+    ///
+    /// The scheme the client used is read from the `X-Forwarded-Proto` header the proxy that terminates TLS adds. Requests
+    /// without the header are never redirected, so servers that terminate TLS themselves are not affected. The redirect keeps
+    /// the host and path of the request and uses `308 Permanent Redirect` so the method and body are preserved.
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// # fn app() -> Element { todo!() }
+    /// use dioxus::prelude::*;
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// LaunchBuilder::new()
+    ///     .with_cfg(server_only! {
+    ///         ServeConfigBuilder::default()
+    ///             .redirect_to_https(true)
+    ///             // Only enforce HTTPS when deployed so http://localhost keeps working during development
+    ///             .production(std::env::var("APP_ENV").is_ok_and(|env| env == "production"))
+    ///     })
+    ///     .launch(app);
+    /// ```
+    pub fn redirect_to_https(mut self, redirect_to_https: bool) -> Self {
+        self.https_redirect = redirect_to_https;
+        self
+    }
+
+    /// Set if the server is running in production. Settings that would break local development like
+    /// [`ServeConfigBuilder::redirect_to_https`] only take effect in production. (defaults to true in release builds)
+    pub fn production(mut self, production: bool) -> Self {
+        self.production = Some(production);
+        self
+    }
+
+    /// Add a `?v=<build_id>` query to the URLs of local assets the index.html links to so clients fetch fresh copies after
+    /// each deploy. (defaults to no build id)
+    ///
+    /// Only `src` and `href` attributes that point to a file on the same origin are rewritten. Assets with a content hash
+    /// in their file name (like `main-a1b2c3d4.css`) and URLs that already have a query are left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// # fn app() -> Element { todo!() }
+    /// use dioxus::prelude::*;
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// LaunchBuilder::new()
+    ///     .with_cfg(server_only! {
+    ///         let mut cfg = ServeConfigBuilder::default();
+    ///         // Use the commit the deploy was built from
+    ///         if let Ok(build_id) = std::env::var("GIT_SHA") {
+    ///             cfg = cfg.build_id(build_id);
+    ///         }
+    ///         cfg
+    ///     })
+    ///     .launch(app);
+    /// ```
+    pub fn build_id(mut self, build_id: impl Into<String>) -> Self {
+        self.build_id = Some(build_id.into());
+        self
+    }
+
+    /// Set how strictly the `Accept` header of a request is matched before the page is rendered. Requests that don't accept
+    /// html are rejected with `406 Not Acceptable`. (defaults to [`AcceptMatching::Lenient`])
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// # fn app() -> Element { todo!() }
+    /// use dioxus::prelude::*;
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// LaunchBuilder::new()
+    ///     .with_cfg(server_only! {
+    ///         ServeConfigBuilder::default().accept_matching(AcceptMatching::Strict)
+    ///     })
+    ///     .launch(app);
+    /// ```
+    pub fn accept_matching(mut self, accept_matching: AcceptMatching) -> Self {
+        self.accept_matching = accept_matching;
+        self
+    }
+
+    /// Set when the chunks of a streaming render are flushed to the client. (defaults to [`StreamFlushStrategy::EveryBoundary`])
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// # fn app() -> Element { todo!() }
+    /// use dioxus::prelude::*;
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// LaunchBuilder::new()
+    ///     .with_cfg(server_only! {
+    ///         // Send the suspense boundaries that resolve within 10ms of each other together
+    ///         ServeConfigBuilder::default().stream_flush_strategy(StreamFlushStrategy::Coalesce(
+    ///             std::time::Duration::from_millis(10),
+    ///         ))
+    ///     })
+    ///     .launch(app);
+    /// ```
+    pub fn stream_flush_strategy(mut self, stream_flush_strategy: StreamFlushStrategy) -> Self {
+        self.stream_flush_strategy = stream_flush_strategy;
+        self
+    }
+
+    /// Set the locales the application is translated into, like `["en", "fr", "pt-BR"]`. (defaults to no locales)
+    ///
+    /// Every page picks the supported locale that best matches the `Accept-Language` header of the request. Components
+    /// read it with [`use_locale`](crate::prelude::use_locale), and the response is sent with a matching
+    /// `Content-Language` header. If nothing matches, the [default locale](ServeConfigBuilder::default_locale) is used.
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// # fn app() -> Element { todo!() }
+    /// use dioxus::prelude::*;
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// LaunchBuilder::new()
+    ///     .with_cfg(server_only! {
+    ///         ServeConfigBuilder::default()
+    ///             .supported_locales(["en", "fr", "pt-BR"])
+    ///             .default_locale("en")
+    ///     })
+    ///     .launch(app);
+    /// ```
+    pub fn supported_locales(
+        mut self,
+        locales: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.supported_locales = locales.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the locale pages are rendered in when the `Accept-Language` header doesn't match any of the
+    /// [supported locales](ServeConfigBuilder::supported_locales). (defaults to the first supported locale)
+    pub fn default_locale(mut self, locale: impl Into<String>) -> Self {
+        self.default_locale = Some(locale.into());
+        self
+    }
+
+    /// Catch panics while a page renders and respond with `500 Internal Server Error` instead of dropping the
+    /// connection. The response uses the [error component](crate::prelude::RenderHandleState::with_error_component) if
+    /// you set one. (defaults to false)
+    ///
+    /// Only panics before the first chunk of the page is sent can change the response. A panic while a suspense boundary
+    /// resolves later in the stream ends the stream with an error.
+    ///
+    /// The render is treated as [`UnwindSafe`](std::panic::UnwindSafe). Each render owns its [`VirtualDom`](dioxus_lib::prelude::VirtualDom),
+    /// but any shared state the components touch, like a `Mutex` or `RefCell` in a context provider or a global, may be
+    /// left half updated by the panic and will be seen by the following requests. When this is disabled, panics propagate
+    /// like they normally do.
+    pub fn catch_render_panics(mut self, catch_render_panics: bool) -> Self {
+        self.catch_render_panics = catch_render_panics;
+        self
+    }
+
+    /// Set the urls of critical assets like the wasm bundle and the main stylesheet that the browser should start
+    /// downloading before the page arrives. (defaults to no assets)
+    ///
+    /// Every rendered page is sent with a `Link: <url>; rel=preload` header for each asset. The kind of asset is picked
+    /// from the extension of the url. Axum can't send a `103 Early Hints` response itself, but CDNs and proxies that
+    /// support Early Hints, like Cloudflare, turn the `Link` headers into a `103` response for the following requests.
+    /// Clients and proxies that don't support Early Hints still see the headers on the final response.
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// # fn app() -> Element { todo!() }
+    /// use dioxus::prelude::*;
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// LaunchBuilder::new()
+    ///     .with_cfg(server_only! {
+    ///         ServeConfigBuilder::default().early_hints(["/wasm/app_bg.wasm", "/assets/main.css"])
+    ///     })
+    ///     .launch(app);
+    /// ```
+    pub fn early_hints(mut self, assets: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.early_hints = assets.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Preload assets like fonts and hero images with a `Link: <url>; rel=preload` header on every rendered page. Each call
+    /// adds to the assets that are preloaded. (defaults to no assets)
+    ///
+    /// Unlike [`ServeConfigBuilder::early_hints`], these links are meant for the final response, for CDNs that turn preload
+    /// links into a server push. Links the components add to the response headers of their
+    /// [`DioxusServerContext`](crate::prelude::DioxusServerContext) are merged into the same header, and every link is
+    /// only sent once.
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// # fn app() -> Element { todo!() }
+    /// use dioxus::prelude::*;
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// LaunchBuilder::new()
+    ///     .with_cfg(server_only! {
+    ///         ServeConfigBuilder::default().preload_links(["/assets/inter.woff2"])
+    ///     })
+    ///     .launch(app);
+    /// ```
+    pub fn preload_links(mut self, assets: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.preload_links.push(PreloadLinks {
+            routes: None,
+            assets: assets.into_iter().map(Into::into).collect(),
+        });
+        self
+    }
+
+    /// Preload assets with a `Link` header only on the pages at the paths the predicate returns true for. See
+    /// [`ServeConfigBuilder::preload_links`].
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// # fn app() -> Element { todo!() }
+    /// use dioxus::prelude::*;
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// LaunchBuilder::new()
+    ///     .with_cfg(server_only! {
+    ///         ServeConfigBuilder::default()
+    ///             .preload_links(["/assets/inter.woff2"])
+    ///             // Only the home page shows the hero image
+    ///             .preload_links_for(|path| path == "/", ["/assets/hero.avif"])
+    ///     })
+    ///     .launch(app);
+    /// ```
+    pub fn preload_links_for(
+        mut self,
+        routes: impl Fn(&str) -> bool + Send + Sync + 'static,
+        assets: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.preload_links.push(PreloadLinks {
+            routes: Some(Arc::new(routes)),
+            assets: assets.into_iter().map(Into::into).collect(),
+        });
+        self
+    }
+
+    /// Render the page of your application with a `404 Not Found` status when a request that accepts html asks for an
+    /// asset that doesn't exist. (defaults to false)
+    ///
+    /// This helps when the html and the hashed assets were deployed out of sync and a user follows a stale link to an
+    /// asset. Requests that don't accept html, like the browser loading a script or stylesheet, still get a bare 404.
+    /// See [`StaticAssetsConfig::render_missing_assets`](crate::prelude::StaticAssetsConfig::render_missing_assets) to
+    /// use this with your own router.
+    pub fn render_missing_assets(mut self, render_missing_assets: bool) -> Self {
+        self.render_missing_assets = render_missing_assets;
+        self
+    }
+
+    /// Share one render between identical page requests that arrive while the page is still rendering. (defaults to false)
+    ///
+    /// When many clients request the same uncached page at once, only the first request renders it and the others stream
+    /// the same html as it is rendered. Requests are matched by [`default_coalescing_key`](crate::prelude::default_coalescing_key),
+    /// which skips requests with cookies or credentials. Use [`ServeConfigBuilder::coalesce_renders_by`] if your pages
+    /// depend on other parts of the request.
+    ///
+    /// The requests that join a render don't run your components, so headers the components set on their
+    /// [`DioxusServerContext`](crate::prelude::DioxusServerContext) are only sent to the request that started the render.
+    /// Requests with a CSP nonce are never coalesced.
+    pub fn coalesce_renders(mut self, coalesce_renders: bool) -> Self {
+        self.coalesce_key =
+            coalesce_renders.then(|| Arc::new(default_coalescing_key) as CoalesceKeyFn);
+        self
+    }
+
+    /// Share one render between page requests that arrive while the page is still rendering and have the same key. Requests
+    /// the function returns `None` for are always rendered on their own. See [`ServeConfigBuilder::coalesce_renders`].
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// # fn app() -> Element { todo!() }
+    /// use dioxus::prelude::*;
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// LaunchBuilder::new()
+    ///     .with_cfg(server_only! {
+    ///         ServeConfigBuilder::default().coalesce_renders_by(|parts| {
+    ///             // The pages under /blog are the same for every user
+    ///             let path = parts.uri.path();
+    ///             path.starts_with("/blog").then(|| path.to_string())
+    ///         })
+    ///     })
+    ///     .launch(app);
+    /// ```
+    pub fn coalesce_renders_by(
+        mut self,
+        key: impl Fn(&http::request::Parts) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.coalesce_key = Some(Arc::new(key));
+        self
+    }
+
+    /// Render the pages at the paths the predicate returns true for as static html without hydration. (defaults to no static pages)
+    ///
+    /// Static pages have no hydration ids, no hydration data and none of the scripts from the index.html, so they ship
+    /// no client side code at all. The page waits for every suspense boundary to resolve before it is sent, because
+    /// streamed suspense boundaries need a script to be swapped in. Components can also render their page as static with
+    /// [`DioxusServerContext::set_static`](crate::prelude::DioxusServerContext::set_static).
+    ///
+    /// # Example
+    ///
+    /// ```rust, no_run
+    /// # fn app() -> Element { todo!() }
+    /// use dioxus::prelude::*;
+    ///
+    /// // Only set the server config if the server feature is enabled
+    /// LaunchBuilder::new()
+    ///     .with_cfg(server_only! {
+    ///         ServeConfigBuilder::default().static_routes(|path| path == "/terms" || path == "/privacy")
+    ///     })
+    ///     .launch(app);
+    /// ```
+    pub fn static_routes(
+        mut self,
+        is_static: impl Fn(&str) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.static_routes = Some(Arc::new(is_static));
         self
     }
 
@@ -125,18 +1071,58 @@ impl ServeConfigBuilder {
             .map(PathBuf::from)
             .unwrap_or_else(|| public_path.join("index.html"));
 
-        let root_id = self.root_id.unwrap_or("main");
+        let root_id = self.root_id.unwrap_or_else(|| "main".to_string());
 
         let index_html = match self.index_html {
             Some(index) => index,
+            // Pages are rendered with the template from the request, so the index.html is only needed if it exists
+            None if self.index_template.is_some() => load_index_path(index_path)
+                .unwrap_or_else(|_| {
+                    format!(
+                        r#"<!DOCTYPE html><html><head></head><body><div id="{root_id}"></div></body></html>"#
+                    )
+                }),
             None => load_index_path(index_path)?,
         };
 
-        let index = load_index_html(index_html, root_id);
+        let index = load_index_html(index_html, &root_id, self.build_id.as_deref())
+            .map_err(|reason| UnableToLoadIndex(IndexError::Invalid(reason)))?;
+        let index_template = self.index_template.map(|render| IndexTemplate {
+            render,
+            root_id,
+            build_id: self.build_id.clone(),
+        });
 
         Ok(ServeConfig {
             index,
             incremental: self.incremental,
+            incremental_cache: Default::default(),
+            render_mode: self.render_mode,
+            overload_policy: self.overload_policy,
+            render_limit: self
+                .render_limit
+                .map(|(max_renders, overflow)| RenderLimit::new(max_renders, overflow)),
+            critical_css: self.critical_css.and_then(CriticalCss::load),
+            main_stylesheet: self.main_stylesheet.as_deref().map(escape_attribute),
+            strict_csp: self.strict_csp,
+            csp_policy: self.csp_policy,
+            request_timeouts: self.request_timeouts,
+            trust_forwarded_headers: self.trust_forwarded_headers,
+            on_virtual_dom_drop: self.on_virtual_dom_drop,
+            https_redirect: self.https_redirect,
+            production: self.production.unwrap_or(!cfg!(debug_assertions)),
+            accept_matching: self.accept_matching,
+            stream_flush_strategy: self.stream_flush_strategy,
+            supported_locales: self.supported_locales,
+            default_locale: self.default_locale,
+            catch_render_panics: self.catch_render_panics,
+            early_hints: self.early_hints,
+            render_missing_assets: self.render_missing_assets,
+            coalesce_key: self.coalesce_key,
+            in_flight_renders: Default::default(),
+            static_routes: self.static_routes,
+            preload_links: self.preload_links,
+            index_template,
         })
     }
 }
@@ -161,18 +1147,27 @@ pub(crate) fn public_path() -> PathBuf {
 
 /// An error that can occur when loading the index.html file
 #[derive(Debug)]
-pub struct UnableToLoadIndex(PathBuf);
+pub struct UnableToLoadIndex(IndexError);
+
+#[derive(Debug)]
+enum IndexError {
+    NotFound(PathBuf),
+    Invalid(String),
+}
 
 impl std::fmt::Display for UnableToLoadIndex {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Failed to find index.html. Make sure the index_path is set correctly and the WASM application has been built. Tried to open file at path: {:?}", self.0)
+        match &self.0 {
+            IndexError::NotFound(path) => write!(f, "Failed to find index.html. Make sure the index_path is set correctly and the WASM application has been built. Tried to open file at path: {path:?}"),
+            IndexError::Invalid(reason) => write!(f, "The index.html template is invalid: {reason}"),
+        }
     }
 }
 
 impl std::error::Error for UnableToLoadIndex {}
 
 fn load_index_path(path: PathBuf) -> Result<String, UnableToLoadIndex> {
-    let mut file = File::open(&path).map_err(|_| UnableToLoadIndex(path))?;
+    let mut file = File::open(&path).map_err(|_| UnableToLoadIndex(IndexError::NotFound(path)))?;
 
     let mut contents = String::new();
     file.read_to_string(&mut contents)
@@ -180,27 +1175,79 @@ fn load_index_path(path: PathBuf) -> Result<String, UnableToLoadIndex> {
     Ok(contents)
 }
 
-fn load_index_html(contents: String, root_id: &'static str) -> IndexHtml {
-    let (pre_main, post_main) = contents.split_once(&format!("id=\"{root_id}\"")).unwrap_or_else(|| panic!("Failed to find id=\"{root_id}\" in index.html. The id is used to inject the application into the page."));
+/// Append `?v=<build_id>` to the local asset URLs in `src` and `href` attributes that don't have a content hash
+fn add_build_id_to_asset_urls(html: &str, build_id: &str) -> String {
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = ["src=\"", "href=\""]
+        .iter()
+        .filter_map(|attribute| rest.find(attribute).map(|index| index + attribute.len()))
+        .min()
+    {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find('"') else {
+            break;
+        };
+        let url = &rest[..end];
+        output.push_str(url);
+        if needs_build_id(url) {
+            output.push_str("?v=");
+            output.push_str(build_id);
+        }
+        rest = &rest[end..];
+    }
+    output.push_str(rest);
+    output
+}
 
-    let post_main = post_main.split_once('>').unwrap_or_else(|| {
-        panic!("Failed to find closing > after id=\"{root_id}\" in index.html.")
-    });
+/// Check if a URL points to a local file without a content hash or query
+fn needs_build_id(url: &str) -> bool {
+    let is_local = url.starts_with('/') && !url.starts_with("//");
+    if !is_local || url.contains(['?', '#']) {
+        return false;
+    }
+    let file_name = url.rsplit('/').next().unwrap_or_default();
+    let Some((stem, _extension)) = file_name.rsplit_once('.') else {
+        return false;
+    };
+    // Bundlers add the hash as the last segment of the file stem, like `main-a1b2c3d4` or `style.dxh1a2b3c`
+    let hash = stem.rsplit(['-', '.', '_']).next().unwrap_or_default();
+    let is_hashed =
+        hash.len() >= 8 && (hash.starts_with("dxh") || hash.chars().all(|c| c.is_ascii_hexdigit()));
+    !is_hashed
+}
+
+/// Split the index.html template into the segments the application is rendered between
+fn load_index_html(
+    contents: String,
+    root_id: &str,
+    build_id: Option<&str>,
+) -> Result<IndexHtml, String> {
+    let contents = match build_id {
+        Some(build_id) => add_build_id_to_asset_urls(&contents, build_id),
+        None => contents,
+    };
+
+    let (pre_main, post_main) = contents.split_once(&format!("id=\"{root_id}\"")).ok_or_else(|| format!("Failed to find id=\"{root_id}\" in index.html. The id is used to inject the application into the page."))?;
+
+    let post_main = post_main
+        .split_once('>')
+        .ok_or_else(|| format!("Failed to find closing > after id=\"{root_id}\" in index.html."))?;
 
     let (pre_main, post_main) = (
         pre_main.to_string() + &format!("id=\"{root_id}\"") + post_main.0 + ">",
         post_main.1.to_string(),
     );
 
-    let (head, close_head) = pre_main.split_once("</head>").unwrap_or_else(|| {
-        panic!("Failed to find closing </head> tag after id=\"{root_id}\" in index.html.")
-    });
+    let (head, close_head) = pre_main.split_once("</head>").ok_or_else(|| {
+        format!("Failed to find closing </head> tag before id=\"{root_id}\" in index.html.")
+    })?;
     let (head, close_head) = (head.to_string(), "</head>".to_string() + close_head);
 
-    let (post_main, after_closing_body_tag) =
-        post_main.split_once("</body>").unwrap_or_else(|| {
-            panic!("Failed to find closing </body> tag after id=\"{root_id}\" in index.html.")
-        });
+    let (post_main, after_closing_body_tag) = post_main.split_once("</body>").ok_or_else(|| {
+        format!("Failed to find closing </body> tag after id=\"{root_id}\" in index.html.")
+    })?;
 
     // Strip out the head if it exists
     let mut head_before_title = String::new();
@@ -209,24 +1256,26 @@ fn load_index_html(contents: String, root_id: &'static str) -> IndexHtml {
     if let Some((new_head_before_title, new_title)) = head_after_title.split_once("<title>") {
         let (new_title, new_head_after_title) = new_title
             .split_once("</title>")
-            .expect("Failed to find closing </title> tag after <title> in index.html.");
+            .ok_or("Failed to find closing </title> tag after <title> in index.html.")?;
         title = format!("<title>{new_title}</title>");
         head_before_title = new_head_before_title.to_string();
         head_after_title = new_head_after_title.to_string();
     }
 
-    IndexHtml {
+    Ok(IndexHtml {
+        root_id: root_id.to_string(),
         head_before_title,
         head_after_title,
         title,
         close_head,
         post_main: post_main.to_string(),
         after_closing_body_tag: "</body>".to_string() + after_closing_body_tag,
-    }
+    })
 }
 
 #[derive(Clone)]
 pub(crate) struct IndexHtml {
+    pub(crate) root_id: String,
     pub(crate) head_before_title: String,
     pub(crate) head_after_title: String,
     pub(crate) title: String,
@@ -241,6 +1290,74 @@ pub(crate) struct IndexHtml {
 pub struct ServeConfig {
     pub(crate) index: IndexHtml,
     pub(crate) incremental: Option<dioxus_isrg::IncrementalRendererConfig>,
+    incremental_cache:
+        Arc<once_cell::sync::OnceCell<std::sync::RwLock<dioxus_isrg::IncrementalRenderer>>>,
+    pub(crate) render_mode: Option<RenderModeFn>,
+    pub(crate) overload_policy: Option<OverloadPolicy>,
+    pub(crate) render_limit: Option<RenderLimit>,
+    pub(crate) critical_css: Option<Arc<str>>,
+    pub(crate) main_stylesheet: Option<String>,
+    pub(crate) strict_csp: bool,
+    pub(crate) csp_policy: Option<String>,
+    pub(crate) request_timeouts: RouteTimeouts,
+    pub(crate) trust_forwarded_headers: bool,
+    pub(crate) on_virtual_dom_drop: Option<VirtualDomDropHook>,
+    pub(crate) https_redirect: bool,
+    pub(crate) production: bool,
+    pub(crate) accept_matching: AcceptMatching,
+    pub(crate) stream_flush_strategy: StreamFlushStrategy,
+    pub(crate) supported_locales: Vec<String>,
+    pub(crate) default_locale: Option<String>,
+    pub(crate) catch_render_panics: bool,
+    pub(crate) early_hints: Vec<String>,
+    pub(crate) render_missing_assets: bool,
+    pub(crate) coalesce_key: Option<CoalesceKeyFn>,
+    pub(crate) in_flight_renders: crate::coalesce::InFlightRenders,
+    pub(crate) static_routes: Option<StaticRouteFn>,
+    pub(crate) preload_links: Vec<PreloadLinks>,
+    pub(crate) index_template: Option<IndexTemplate>,
+}
+
+/// Builds the index.html template for a request
+#[derive(Clone)]
+pub(crate) struct IndexTemplate {
+    render: IndexTemplateFn,
+    root_id: String,
+    build_id: Option<String>,
+}
+
+/// Timeouts for requests that vary by route pattern
+#[derive(Clone, Debug, Default)]
+pub(crate) struct RouteTimeouts {
+    default: Option<Duration>,
+    routes: Vec<(String, Duration)>,
+}
+
+impl RouteTimeouts {
+    /// Check if any timeouts are configured
+    pub(crate) fn is_empty(&self) -> bool {
+        self.default.is_none() && self.routes.is_empty()
+    }
+
+    /// Get the timeout for a request path from the longest matching pattern or the default timeout
+    pub(crate) fn timeout_for(&self, path: &str) -> Option<Duration> {
+        self.routes
+            .iter()
+            .filter(|(pattern, _)| route_pattern_matches(pattern, path))
+            .max_by_key(|(pattern, _)| pattern.len())
+            .map(|(_, timeout)| *timeout)
+            .or(self.default)
+    }
+}
+
+/// Check if a route pattern matches a path. Patterns that end with `/*` match every path under the prefix
+fn route_pattern_matches(pattern: &str, path: &str) -> bool {
+    match pattern.strip_suffix("/*") {
+        Some(prefix) => path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/')),
+        None => pattern == path,
+    }
 }
 
 impl LaunchConfig for ServeConfig {}
@@ -255,4 +1372,168 @@ impl ServeConfig {
     pub fn builder() -> ServeConfigBuilder {
         ServeConfigBuilder::new()
     }
+
+    /// Get the incremental cache for this config if incremental rendering is enabled. The cache is built the first time
+    /// it is used and shared between every clone of the config.
+    pub(crate) fn incremental_cache(
+        &self,
+    ) -> Option<&std::sync::RwLock<dioxus_isrg::IncrementalRenderer>> {
+        let incremental = self.incremental.as_ref()?;
+        Some(
+            self.incremental_cache
+                .get_or_init(|| std::sync::RwLock::new(incremental.clone().build())),
+        )
+    }
+
+    /// Build the index.html template for a request. Returns `None` if every page uses the same template
+    pub(crate) fn request_index(
+        &self,
+        server_context: &crate::prelude::DioxusServerContext,
+    ) -> Option<Result<IndexHtml, UnableToLoadIndex>> {
+        let template = self.index_template.as_ref()?;
+        let html = (template.render)(server_context);
+        Some(
+            load_index_html(html, &template.root_id, template.build_id.as_deref())
+                .map_err(|reason| UnableToLoadIndex(IndexError::Invalid(reason))),
+        )
+    }
+
+    /// Check if the page at a route is rendered without hydration
+    pub(crate) fn is_static_route(&self, route: &str) -> bool {
+        let path = route.split(['?', '#']).next().unwrap_or_default();
+        self.static_routes
+            .as_ref()
+            .is_some_and(|is_static| is_static(path))
+    }
+
+    /// Get the [`RenderMode`] that should be used for a request
+    pub(crate) fn render_mode(&self, parts: &http::request::Parts) -> RenderMode {
+        if self
+            .overload_policy
+            .is_some_and(|policy| policy.is_overloaded())
+        {
+            tracing::debug!("The runtime is overloaded, skipping the server render");
+            return RenderMode::ShellOnly;
+        }
+
+        self.render_mode
+            .as_ref()
+            .map(|render_mode| render_mode(parts))
+            .unwrap_or_default()
+    }
+
+    /// Generate a new random nonce for a request if the strict content security policy is enabled
+    pub(crate) fn generate_csp_nonce(&self) -> Option<String> {
+        use base64::Engine;
+
+        (self.strict_csp || self.csp_policy.is_some()).then(|| {
+            base64::engine::general_purpose::STANDARD.encode(uuid::Uuid::new_v4().as_bytes())
+        })
+    }
+
+    /// Get the value of the `Content-Security-Policy` header for a response rendered with a nonce
+    pub(crate) fn content_security_policy(&self, nonce: &str) -> String {
+        if let Some(policy) = &self.csp_policy {
+            return policy.replace("{nonce}", nonce);
+        }
+        format!("script-src 'nonce-{nonce}' 'strict-dynamic' 'wasm-unsafe-eval'; object-src 'none'; base-uri 'self'")
+    }
+
+    /// Check if requests sent over plain HTTP should be redirected to HTTPS
+    pub(crate) fn redirects_to_https(&self) -> bool {
+        self.https_redirect && self.production
+    }
+
+    /// The `Link` entries that preload the critical assets and the preload links of the page at a route
+    pub(crate) fn preload_links(&self, route: &str) -> Vec<String> {
+        let path = route.split(['?', '#']).next().unwrap_or_default();
+        let preload_links = self
+            .preload_links
+            .iter()
+            .filter(|links| links.routes.as_ref().map_or(true, |routes| routes(path)))
+            .flat_map(|links| &links.assets);
+        self.early_hints
+            .iter()
+            .chain(preload_links)
+            .map(|url| {
+                let extension = url
+                    .split(['?', '#'])
+                    .next()
+                    .and_then(|path| path.rsplit_once('.'))
+                    .map(|(_, extension)| extension.to_ascii_lowercase());
+                // Fetches and fonts are requested in cors mode, so the preload needs to match or it is downloaded twice
+                let kind = match extension.as_deref() {
+                    Some("css") => "; as=style",
+                    Some("js" | "mjs") => "; as=script",
+                    Some("wasm") => "; as=fetch; crossorigin",
+                    Some("woff" | "woff2" | "ttf" | "otf") => "; as=font; crossorigin",
+                    Some("png" | "jpg" | "jpeg" | "gif" | "webp" | "avif" | "svg") => "; as=image",
+                    _ => "",
+                };
+                format!("<{url}>; rel=preload{kind}")
+            })
+            .collect()
+    }
+
+    /// Pick the locale a request is rendered in. Returns `None` if no locales are supported
+    pub(crate) fn locale(&self, parts: &http::request::Parts) -> Option<crate::prelude::Locale> {
+        if self.supported_locales.is_empty() {
+            return None;
+        }
+        let negotiated = parts
+            .headers
+            .get(http::header::ACCEPT_LANGUAGE)
+            .and_then(|accept_language| accept_language.to_str().ok())
+            .and_then(|accept_language| {
+                crate::hooks::locale::negotiate_locale(accept_language, &self.supported_locales)
+            });
+        let locale = negotiated
+            .or(self.default_locale.as_deref())
+            .or(self.supported_locales.first().map(String::as_str))?;
+        Some(crate::prelude::Locale(locale.to_string()))
+    }
+
+    /// Get the origin (scheme and host) the client sent a request to. Returns `None` if the request doesn't have a host
+    pub(crate) fn request_origin(&self, parts: &http::request::Parts) -> Option<String> {
+        let header = |name: &str| {
+            parts
+                .headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                // Proxies append to the header, so the first value is the one the client sent
+                .and_then(|value| value.split(',').next())
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        let mut scheme = None;
+        let mut host = None;
+        if self.trust_forwarded_headers {
+            // The standard `Forwarded` header takes priority over the `X-Forwarded-*` headers
+            for pair in header("forwarded")
+                .iter()
+                .flat_map(|value| value.split(';'))
+            {
+                let Some((key, value)) = pair.split_once('=') else {
+                    continue;
+                };
+                let value = value.trim().trim_matches('"').to_string();
+                match key.trim().to_ascii_lowercase().as_str() {
+                    "proto" => scheme = scheme.or(Some(value)),
+                    "host" => host = host.or(Some(value)),
+                    _ => {}
+                }
+            }
+            scheme = scheme.or_else(|| header("x-forwarded-proto"));
+            host = host.or_else(|| header("x-forwarded-host"));
+        }
+
+        let host = host
+            .or_else(|| parts.uri.authority().map(|authority| authority.to_string()))
+            .or_else(|| header("host"))?;
+        let scheme = scheme
+            .or_else(|| parts.uri.scheme_str().map(str::to_string))
+            .unwrap_or_else(|| "http".to_string());
+        Some(format!("{scheme}://{host}"))
+    }
 }
@@ -0,0 +1,114 @@
+//! Structured access logs for every route the router extension adds
+
+use axum::{body::Body, extract::Request, middleware::Next, response::Response};
+use http::{Method, StatusCode};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// The kind of route that handled a request
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RouteKind {
+    /// A server function
+    ServerFunction,
+    /// A server rendered page
+    Ssr,
+    /// A static asset from the public directory
+    StaticAsset,
+    /// Any other route
+    Other,
+}
+
+impl std::fmt::Display for RouteKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RouteKind::ServerFunction => write!(f, "server_fn"),
+            RouteKind::Ssr => write!(f, "ssr"),
+            RouteKind::StaticAsset => write!(f, "static"),
+            RouteKind::Other => write!(f, "other"),
+        }
+    }
+}
+
+/// A record of a request that was handled. Passed to the formatter of [`DioxusRouterExt::with_access_log`](crate::prelude::DioxusRouterExt::with_access_log).
+#[derive(Clone, Debug)]
+pub struct LogRecord {
+    /// The method of the request
+    pub method: Method,
+    /// The path and query of the request
+    pub path: String,
+    /// The status of the response
+    pub status: StatusCode,
+    /// The time from receiving the request until the response headers were ready. Streaming responses may keep sending
+    /// the body after this
+    pub latency: Duration,
+    /// The id of the request if the request or response has an `X-Request-Id` header
+    pub request_id: Option<String>,
+    /// The kind of route that handled the request
+    pub kind: RouteKind,
+}
+
+impl std::fmt::Display for LogRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}ms kind={} request_id={}",
+            self.method,
+            self.path,
+            self.status.as_u16(),
+            self.latency.as_millis(),
+            self.kind,
+            self.request_id.as_deref().unwrap_or("-")
+        )
+    }
+}
+
+/// Mark a response with the kind of route that created it
+pub(crate) fn mark_route_kind<B>(
+    kind: RouteKind,
+) -> impl Fn(http::Response<B>) -> http::Response<B> + Clone {
+    move |mut response| {
+        response.extensions_mut().insert(kind);
+        response
+    }
+}
+
+/// The middleware that logs every request
+pub(crate) async fn middleware(
+    formatter: Arc<dyn Fn(&LogRecord) -> String + Send + Sync>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let start = Instant::now();
+    let method = request.method().clone();
+    let path = request
+        .uri()
+        .path_and_query()
+        .map(|path| path.to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+    let request_id = read_request_id(request.headers());
+
+    let response = next.run(request).await;
+
+    let record = LogRecord {
+        method,
+        path,
+        status: response.status(),
+        latency: start.elapsed(),
+        request_id: read_request_id(response.headers()).or(request_id),
+        kind: response
+            .extensions()
+            .get::<RouteKind>()
+            .copied()
+            .unwrap_or(RouteKind::Other),
+    };
+    tracing::info!(target: "dioxus_fullstack::access_log", "{}", formatter(&record));
+
+    response
+}
+
+fn read_request_id(headers: &http::HeaderMap) -> Option<String> {
+    headers
+        .get(crate::server_context::REQUEST_ID_HEADER)
+        .and_then(|id| id.to_str().ok())
+        .map(str::to_string)
+}
@@ -0,0 +1,57 @@
+//! Background tasks that live as long as the router they were spawned from
+
+use super::ContextProviders;
+use futures_util::FutureExt;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::time::Duration;
+use tokio_util::sync::{CancellationToken, DropGuard};
+
+/// How long to wait before restarting a background task that panicked
+const RESTART_DELAY: Duration = Duration::from_secs(1);
+
+/// Cancels the background task when the last clone of the router that owns it is dropped
+pub(crate) struct BackgroundTaskGuard {
+    _cancel: DropGuard,
+}
+
+/// Spawn a task that is restarted if it panics until it finishes or the returned guard is dropped
+pub(crate) fn spawn_supervised<F, Fut>(
+    context_providers: ContextProviders,
+    task: F,
+) -> BackgroundTaskGuard
+where
+    F: Fn(ContextProviders) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let cancel = CancellationToken::new();
+    let cancelled = cancel.clone();
+    tokio::spawn(async move {
+        let supervise = async {
+            loop {
+                match AssertUnwindSafe(task(context_providers.clone()))
+                    .catch_unwind()
+                    .await
+                {
+                    Ok(()) => break,
+                    Err(_) => {
+                        tracing::error!(
+                            "A background task panicked. Restarting it in {RESTART_DELAY:?}"
+                        );
+                        tokio::time::sleep(RESTART_DELAY).await;
+                    }
+                }
+            }
+        };
+        let cancelled = cancelled.cancelled();
+        futures_util::pin_mut!(supervise, cancelled);
+        if let futures_util::future::Either::Right(_) =
+            futures_util::future::select(supervise, cancelled).await
+        {
+            tracing::debug!("Cancelled a background task because its router was dropped");
+        }
+    });
+    BackgroundTaskGuard {
+        _cancel: cancel.drop_guard(),
+    }
+}
@@ -0,0 +1,197 @@
+//! Compress static assets the bundler didn't precompress when they are requested
+
+use super::StaticAssetsConfig;
+use axum::body::Body;
+use futures_util::TryStreamExt;
+use http::header::*;
+use http::{response::Parts, HeaderValue, StatusCode};
+use std::path::{Component, Path, PathBuf};
+
+/// Compresses the identity variant of a static asset with brotli if there is no precompressed variant
+pub(crate) struct FallbackCompression {
+    root: PathBuf,
+    is_dir: bool,
+    cache_to_disk: bool,
+}
+
+impl FallbackCompression {
+    /// Create the fallback compression for the file or directory served at a route if the config enables it
+    pub(crate) fn new(config: &StaticAssetsConfig, path: &Path) -> Option<Self> {
+        config.compress_on_the_fly.then(|| Self {
+            root: path.to_path_buf(),
+            is_dir: path.is_dir(),
+            cache_to_disk: config.cache_compressed_assets,
+        })
+    }
+
+    /// Check if the response to a request should be compressed if it isn't already
+    pub(crate) fn wants_compression(
+        &self,
+        config: &StaticAssetsConfig,
+        request: &axum::extract::Request,
+    ) -> bool {
+        accepts_brotli(config, request)
+    }
+
+    /// Compress the body of a successful identity response. The response is returned unchanged if it is already
+    /// compressed or the content type doesn't benefit from compression
+    pub(crate) async fn compress(&self, request_path: &str, parts: &mut Parts, body: Body) -> Body {
+        let compressible = parts
+            .headers
+            .get(CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .is_some_and(is_compressible);
+        if parts.status != StatusCode::OK
+            || parts.headers.contains_key(CONTENT_ENCODING)
+            || !compressible
+        {
+            return body;
+        }
+
+        parts
+            .headers
+            .insert(CONTENT_ENCODING, HeaderValue::from_static("br"));
+        // The compressed body has different offsets, so it can't serve ranges
+        parts.headers.remove(ACCEPT_RANGES);
+
+        let file = self
+            .cache_to_disk
+            .then(|| self.file_for(request_path))
+            .flatten();
+        let Some(file) = file else {
+            parts.headers.remove(CONTENT_LENGTH);
+            return compress_stream(body);
+        };
+
+        // Compress the whole file so the next request can be served from the precompressed variant on disk
+        let identity = match axum::body::to_bytes(body, usize::MAX).await {
+            Ok(identity) => identity,
+            Err(err) => {
+                tracing::error!("Failed to read static asset {file:?} to compress it: {err}");
+                parts.status = StatusCode::INTERNAL_SERVER_ERROR;
+                parts.headers.remove(CONTENT_ENCODING);
+                parts.headers.remove(CONTENT_LENGTH);
+                return Body::empty();
+            }
+        };
+        let compressed = match compress_bytes(&identity).await {
+            Ok(compressed) => compressed,
+            Err(err) => {
+                tracing::error!("Failed to compress static asset {file:?}: {err}");
+                parts.headers.remove(CONTENT_ENCODING);
+                return Body::from(identity);
+            }
+        };
+        if let Err(err) = write_precompressed(&file, &compressed).await {
+            tracing::warn!("Failed to cache the compressed variant of {file:?}: {err}");
+        }
+        parts
+            .headers
+            .insert(CONTENT_LENGTH, HeaderValue::from(compressed.len()));
+        Body::from(compressed)
+    }
+
+    /// Find the file a request path points to. Returns `None` for paths that could escape the served directory
+    fn file_for(&self, request_path: &str) -> Option<PathBuf> {
+        if !self.is_dir {
+            return Some(self.root.clone());
+        }
+        let mut file = self.root.clone();
+        for segment in request_path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+        {
+            let segment = Path::new(segment);
+            match segment.components().next() {
+                Some(Component::Normal(_)) if segment.components().count() == 1 => {
+                    file.push(segment)
+                }
+                _ => return None,
+            }
+        }
+        file.is_file().then_some(file)
+    }
+}
+
+/// Check if the response to a request may be encoded with brotli
+pub(crate) fn accepts_brotli(
+    config: &StaticAssetsConfig,
+    request: &axum::extract::Request,
+) -> bool {
+    // Range requests are always served from the identity encoding
+    if request.headers().contains_key(RANGE) || !config.allows_compression(request) {
+        return false;
+    }
+    request
+        .headers()
+        .get_all(ACCEPT_ENCODING)
+        .iter()
+        .filter_map(|encoding| encoding.to_str().ok())
+        .flat_map(|encoding| encoding.split(','))
+        .any(|encoding| {
+            let mut params = encoding.split(';');
+            let name = params.next().unwrap_or_default().trim();
+            let rejected = params.any(|param| {
+                param
+                    .trim()
+                    .strip_prefix("q=")
+                    .and_then(|quality| quality.parse::<f32>().ok())
+                    .is_some_and(|quality| quality <= 0.0)
+            });
+            name.eq_ignore_ascii_case("br") && !rejected
+        })
+}
+
+/// Check if assets with a content type are worth compressing. Images, fonts and videos are already compressed
+fn is_compressible(content_type: &str) -> bool {
+    let content_type = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    content_type.starts_with("text/")
+        || matches!(
+            content_type.as_str(),
+            "application/javascript"
+                | "application/json"
+                | "application/wasm"
+                | "application/xml"
+                | "application/manifest+json"
+                | "image/svg+xml"
+        )
+}
+
+/// Compress a body with brotli as it streams to the client
+fn compress_stream(body: Body) -> Body {
+    let reader =
+        tokio_util::io::StreamReader::new(body.into_data_stream().map_err(std::io::Error::other));
+    let encoder = async_compression::tokio::bufread::BrotliEncoder::new(reader);
+    Body::from_stream(tokio_util::io::ReaderStream::new(encoder))
+}
+
+async fn compress_bytes(identity: &[u8]) -> std::io::Result<Vec<u8>> {
+    use tokio::io::AsyncReadExt;
+
+    let mut compressed = Vec::new();
+    async_compression::tokio::bufread::BrotliEncoder::new(identity)
+        .read_to_end(&mut compressed)
+        .await?;
+    Ok(compressed)
+}
+
+/// Write the precompressed variant next to the original file. The file is written to a temporary path first so
+/// concurrent requests never serve a partially written variant
+async fn write_precompressed(file: &Path, compressed: &[u8]) -> std::io::Result<()> {
+    let mut precompressed = file.as_os_str().to_owned();
+    precompressed.push(".br");
+    let mut temporary = precompressed.clone();
+    temporary.push(format!(".{}.tmp", uuid::Uuid::new_v4().simple()));
+
+    tokio::fs::write(&temporary, compressed).await?;
+    if let Err(err) = tokio::fs::rename(&temporary, &precompressed).await {
+        _ = tokio::fs::remove_file(&temporary).await;
+        return Err(err);
+    }
+    Ok(())
+}
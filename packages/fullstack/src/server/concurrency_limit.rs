@@ -0,0 +1,155 @@
+//! Limit the number of requests a single client can have in flight at once
+
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http::StatusCode;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+/// Limit the number of requests a single client IP can have in flight at once. Use it with [`DioxusRouterExt::limit_concurrency_per_client`](crate::prelude::DioxusRouterExt::limit_concurrency_per_client).
+///
+/// Requests over the limit are rejected with `429 Too Many Requests`. A request stays in flight until its response body
+/// has been completely sent, so a streaming SSR response counts against the limit until the last suspense boundary resolves.
+///
+/// The client IP is read from the connection, so the router must be served with
+/// [`into_make_service_with_connect_info::<SocketAddr>`](axum::Router::into_make_service_with_connect_info). Requests
+/// without connection info are never limited. If the connection comes from one of the trusted proxies, the client IP is
+/// read from the `X-Forwarded-For` header instead.
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus_lib::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// #[tokio::main]
+/// async fn main() {
+///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+///     let router = axum::Router::new()
+///         .serve_dioxus_application(ServeConfig::new().unwrap(), app)
+///         // Allow each client at most 16 requests at once. Our load balancer runs on 10.0.0.1
+///         .limit_concurrency_per_client(
+///             ClientConcurrencyLimit::new(16).trusted_proxy("10.0.0.1".parse().unwrap()),
+///         )
+///         .into_make_service_with_connect_info::<std::net::SocketAddr>();
+///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+///     axum::serve(listener, router).await.unwrap();
+/// }
+///
+/// fn app() -> Element {
+///     rsx! { "Hello World" }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct ClientConcurrencyLimit {
+    max_in_flight: usize,
+    trusted_proxies: Arc<Vec<IpAddr>>,
+    in_flight: Arc<Mutex<HashMap<IpAddr, usize>>>,
+}
+
+impl ClientConcurrencyLimit {
+    /// Create a new limit that allows each client IP to have at most `max_in_flight` requests in flight at once
+    pub fn new(max_in_flight: usize) -> Self {
+        Self {
+            max_in_flight,
+            trusted_proxies: Default::default(),
+            in_flight: Default::default(),
+        }
+    }
+
+    /// Trust the `X-Forwarded-For` header of requests from a proxy. (defaults to trusting no proxies)
+    pub fn trusted_proxy(mut self, proxy: IpAddr) -> Self {
+        Arc::make_mut(&mut self.trusted_proxies).push(proxy);
+        self
+    }
+
+    /// Get the IP of the client that sent a request
+    fn client_ip(&self, request: &Request) -> Option<IpAddr> {
+        let ConnectInfo(peer) = request.extensions().get::<ConnectInfo<SocketAddr>>()?;
+        let peer = peer.ip();
+        if !self.trusted_proxies.contains(&peer) {
+            return Some(peer);
+        }
+
+        // Each proxy appends the address it received the request from, so walk the header from the right until we
+        // reach an address that isn't one of our proxies
+        let forwarded_for = request
+            .headers()
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|header| header.to_str().ok())
+            .flat_map(|header| header.split(','))
+            .filter_map(|ip| ip.trim().parse::<IpAddr>().ok())
+            .collect::<Vec<_>>();
+        Some(
+            forwarded_for
+                .into_iter()
+                .rev()
+                .find(|ip| !self.trusted_proxies.contains(ip))
+                .unwrap_or(peer),
+        )
+    }
+
+    /// Reserve a slot for a request from the client. Returns `None` if the client already has too many requests in flight
+    fn acquire(&self, client: IpAddr) -> Option<InFlightGuard> {
+        let mut in_flight = self.in_flight.lock();
+        let count = in_flight.entry(client).or_default();
+        if *count >= self.max_in_flight {
+            return None;
+        }
+        *count += 1;
+        Some(InFlightGuard {
+            client,
+            in_flight: self.in_flight.clone(),
+        })
+    }
+
+    /// The middleware that enforces the limit
+    pub(crate) async fn middleware(self, request: Request, next: Next) -> Response {
+        let Some(client) = self.client_ip(&request) else {
+            return next.run(request).await;
+        };
+        let Some(guard) = self.acquire(client) else {
+            tracing::warn!(
+                "Rejected a request from {client} because it has too many requests in flight"
+            );
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                "Too many concurrent requests from this client",
+            )
+                .into_response();
+        };
+
+        let response = next.run(request).await;
+
+        // Hold the slot until the whole body has been sent
+        let (parts, body) = response.into_parts();
+        let body = futures_util::StreamExt::map(body.into_data_stream(), move |chunk| {
+            let _guard = &guard;
+            chunk
+        });
+        Response::from_parts(parts, Body::from_stream(body))
+    }
+}
+
+/// A slot for a request in flight. The slot is released when the guard is dropped
+struct InFlightGuard {
+    client: IpAddr,
+    in_flight: Arc<Mutex<HashMap<IpAddr, usize>>>,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let mut in_flight = self.in_flight.lock();
+        if let Some(count) = in_flight.get_mut(&self.client) {
+            *count -= 1;
+            if *count == 0 {
+                in_flight.remove(&self.client);
+            }
+        }
+    }
+}
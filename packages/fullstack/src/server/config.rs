@@ -0,0 +1,633 @@
+//! Configuration for how server functions and static assets are registered with the axum router
+
+use super::error_status::{custom_error_status, CustomErrorStatus, ErrorStatus};
+use super::{ContextProviders, DownloadCache, ShutdownHandle};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+/// The default limit for the size of server function request bodies
+const DEFAULT_BODY_LIMIT: usize = 2 * 1024 * 1024;
+
+/// The methods a `POST` request can be turned into with [`ServerFnConfig::method_override`]
+const OVERRIDABLE_METHODS: [http::Method; 4] = [
+    http::Method::GET,
+    http::Method::PUT,
+    http::Method::PATCH,
+    http::Method::DELETE,
+];
+
+/// The environment variable [`ServerFnConfig`] reads the prefix the client calls server functions at from if
+/// [`ServerFnConfig::expected_client_prefix`] is not set
+pub const CLIENT_SERVER_FN_PREFIX_ENV: &str = "DIOXUS_CLIENT_SERVER_FN_PREFIX";
+
+/// Configuration for how server functions are registered with [`DioxusRouterExt::register_server_functions_with_config`](crate::prelude::DioxusRouterExt::register_server_functions_with_config).
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus_lib::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// #[tokio::main]
+/// async fn main() {
+///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+///     let router = axum::Router::new()
+///         // Register server functions and keep the raw request body available in the server context
+///         .register_server_functions_with_config(ServerFnConfig::new().raw_request(true))
+///         .into_make_service();
+///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+///     axum::serve(listener, router).await.unwrap();
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct ServerFnConfig {
+    pub(crate) context_providers: ContextProviders,
+    pub(crate) raw_request: bool,
+    pub(crate) raw_body_paths: HashSet<String>,
+    pub(crate) prefix: Option<String>,
+    pub(crate) timeout: Option<std::time::Duration>,
+    pub(crate) only_paths_starting_with: Option<String>,
+    pub(crate) download_cache: Option<DownloadCache>,
+    pub(crate) body_limit: Option<usize>,
+    pub(crate) body_limit_overrides: HashMap<String, usize>,
+    pub(crate) deprecations: HashMap<String, Deprecation>,
+    pub(crate) expected_client_prefix: Option<String>,
+    pub(crate) shutdown: Option<ShutdownHandle>,
+    pub(crate) skip_referer_redirect: bool,
+    pub(crate) map_error_status: bool,
+    pub(crate) custom_error_statuses: Vec<CustomErrorStatus>,
+    pub(crate) method_override: Option<(http::HeaderName, Vec<http::Method>)>,
+    pub(crate) trust_forwarded_headers: bool,
+}
+
+impl ServerFnConfig {
+    /// Create a new [`ServerFnConfig`] with no additional context and the default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the context providers that will be inserted into the [`DioxusServerContext`](crate::prelude::DioxusServerContext) for every server function call
+    pub fn context_providers(mut self, context_providers: ContextProviders) -> Self {
+        self.context_providers = context_providers;
+        self
+    }
+
+    /// Keep the full request available to server functions through [`DioxusServerContext::request`](crate::prelude::DioxusServerContext::request). (defaults to false)
+    ///
+    /// The body of the request is consumed by the server function, so enabling this will buffer the entire body in memory
+    /// before the server function runs. Only enable this if your server functions need to read the headers and body together.
+    pub fn raw_request(mut self, raw_request: bool) -> Self {
+        self.raw_request = raw_request;
+        self
+    }
+
+    /// Buffer the body of requests to the server function with the given path so it can read the exact bytes it was sent
+    /// with [`DioxusServerContext::raw_body`](crate::prelude::DioxusServerContext::raw_body). The path is the path of the
+    /// server function without the route prefix, like `/api/stripe_webhook`.
+    ///
+    /// Unlike [`ServerFnConfig::raw_request`], only the server functions you list pay for buffering the body. The body is
+    /// still bound by the [body limit](ServerFnConfig::body_limit) of the server function.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// #[server(endpoint = "stripe_webhook")]
+    /// async fn stripe_webhook(event: serde_json::Value) -> Result<(), ServerFnError> {
+    ///     // Verify the signature over the exact bytes Stripe sent
+    ///     let body = server_context()
+    ///         .raw_body()
+    ///         .ok_or_else(|| ServerFnError::new("the body was not buffered"))?;
+    ///     println!("Verifying the signature over {} bytes", body.len());
+    ///     Ok(())
+    /// }
+    ///
+    /// let config = ServerFnConfig::new().raw_body_for("/api/stripe_webhook");
+    /// ```
+    pub fn raw_body_for(mut self, path: impl Into<String>) -> Self {
+        self.raw_body_paths.insert(normalize_route(&path.into()));
+        self
+    }
+
+    /// Send server function errors with a status code that matches the error instead of `500 Internal Server Error`. (defaults to false)
+    ///
+    /// Arguments that fail to parse are sent with `422 Unprocessable Entity`, bodies that fail to deserialize with
+    /// `400 Bad Request` and calls to unknown server functions with `404 Not Found`. Custom errors registered with
+    /// [`ServerFnConfig::error_status`] are sent with the status they pick. Other errors keep the `500` status. A status
+    /// the server function sets on the [`DioxusServerContext`](crate::prelude::DioxusServerContext) always wins.
+    ///
+    /// The body is the same serialized error either way, so the server function stubs on the client still return the
+    /// typed error.
+    pub fn map_error_status(mut self, map_error_status: bool) -> Self {
+        self.map_error_status = map_error_status;
+        self
+    }
+
+    /// Send server functions that fail with the custom error type with the status code the error picks. This enables
+    /// [`ServerFnConfig::map_error_status`]. See [`ErrorStatus`] for an example.
+    pub fn error_status<E: std::str::FromStr + ErrorStatus>(mut self) -> Self {
+        self.map_error_status = true;
+        self.custom_error_statuses.push(custom_error_status::<E>());
+        self
+    }
+
+    /// Mount every server function under a route prefix. (defaults to no prefix)
+    ///
+    /// Server functions are still looked up by their original path, so the prefix only changes the route they are served
+    /// from. The client needs to call the prefixed url, which you can configure with [`server_fn::client::set_server_url`].
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        let prefix = normalize_route(&prefix.into());
+        self.prefix = (!prefix.is_empty()).then_some(prefix);
+        self
+    }
+
+    /// Set the prefix the client calls server functions at. This is the path of the url the client passes to
+    /// [`server_fn::client::set_server_url`]. (defaults to the [`CLIENT_SERVER_FN_PREFIX_ENV`] environment variable or no check)
+    ///
+    /// When the server functions are registered, the prefix is compared with [`ServerFnConfig::prefix`] and an error is
+    /// logged if they differ, so the mismatch is caught at startup instead of making every server function call fail
+    /// with `400 Bad Request`.
+    pub fn expected_client_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.expected_client_prefix = Some(normalize_route(&prefix.into()));
+        self
+    }
+
+    /// Log an error if the prefix the client expects doesn't match the prefix the server functions are mounted at
+    pub(crate) fn check_client_prefix(&self) {
+        let expected = self.expected_client_prefix.clone().or_else(|| {
+            std::env::var(CLIENT_SERVER_FN_PREFIX_ENV)
+                .ok()
+                .map(|prefix| normalize_route(&prefix))
+        });
+        let Some(expected) = expected else {
+            return;
+        };
+        let registered = self.prefix.as_deref().unwrap_or_default();
+        if expected == registered {
+            return;
+        }
+        tracing::error!(
+            "The client calls server functions under the prefix {expected:?}, but the server registers them under the prefix {registered:?}. Every server function call from the client will fail with `400 Bad Request`. Set the same prefix with `ServerFnConfig::prefix` on the server and `server_fn::client::set_server_url` on the client."
+        );
+    }
+
+    /// Track the server function calls with a [`ShutdownHandle`] so the server can wait for them to finish before it
+    /// exits. Once the handle starts shutting down, new calls are rejected with `503 Service Unavailable`. (defaults to no handle)
+    pub fn shutdown_handle(mut self, shutdown: ShutdownHandle) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
+    /// Redirect plain form posts back to the page they came from. (defaults to true)
+    ///
+    /// When a request that accepts `text/html` calls a server function and the response doesn't set a `Location`, the
+    /// response is turned into a `302 Found` redirect to the `Referer`. Disable this if your forms expect the response of the
+    /// server function itself, like progressively enhanced forms that swap in an HTML fragment.
+    pub fn auto_referer_redirect(mut self, auto_referer_redirect: bool) -> Self {
+        self.skip_referer_redirect = !auto_referer_redirect;
+        self
+    }
+
+    /// Respond with `504 Gateway Timeout` if a server function takes longer than the timeout to respond. (defaults to no timeout)
+    ///
+    /// When the timeout expires, the server function future is dropped so it doesn't hold the connection open.
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Only register server functions whose path starts with the path segments of `path`. (defaults to registering every server function)
+    ///
+    /// This is a filter, it doesn't move the server functions to another route. Each server function is still served from
+    /// its own path behind [`ServerFnConfig::prefix`], because that is the path the client calls. Server functions from
+    /// every crate in your workspace are collected into one list, so the only way to tell them apart is their path. Declare
+    /// the server functions of each crate under a distinct route with `#[server(prefix = "/api/billing")]`, then register
+    /// each crate separately with this filter to give them their own context and configuration.
+    pub fn only_paths_starting_with(mut self, path: impl Into<String>) -> Self {
+        self.only_paths_starting_with = Some(normalize_route(&path.into()));
+        self
+    }
+
+    /// Cache the byte streams server functions respond with and serve range requests for them from the cache. (defaults to no cache)
+    ///
+    /// The request body is buffered to find the cached response, so this is best suited to server functions with small
+    /// arguments and large generated responses like exported archives. See [`DownloadCache`] for details.
+    pub fn download_cache(mut self, download_cache: DownloadCache) -> Self {
+        self.download_cache = Some(download_cache);
+        self
+    }
+
+    /// Respond with `413 Payload Too Large` if the body of a server function request is larger than the limit in bytes. (defaults to 2MB)
+    ///
+    /// The limit applies to every kind of request body, including multipart forms and streaming uploads. Bodies with a
+    /// `Content-Length` header are rejected before they are read, and other bodies are rejected as soon as the server
+    /// function reads past the limit.
+    pub fn body_limit(mut self, limit: usize) -> Self {
+        self.body_limit = Some(limit);
+        self
+    }
+
+    /// Override the body limit for the server function with the given path. The path is the path of the server function
+    /// without the route prefix, like `/api/upload_file`.
+    pub fn body_limit_for(mut self, path: impl Into<String>, limit: usize) -> Self {
+        self.body_limit_overrides
+            .insert(normalize_route(&path.into()), limit);
+        self
+    }
+
+    /// Trust the `X-Forwarded-For` header when reading the IP of the client with
+    /// [`DioxusServerContext::client_ip`](crate::prelude::DioxusServerContext::client_ip). (defaults to false)
+    ///
+    /// Anyone can send the header, so only enable this if every request passes through a proxy that overwrites it.
+    pub fn trust_forwarded_headers(mut self, trust_forwarded_headers: bool) -> Self {
+        self.trust_forwarded_headers = trust_forwarded_headers;
+        self
+    }
+
+    /// Let `POST` requests call server functions that use one of the methods by naming the method in the header, like
+    /// `X-HTTP-Method-Override: DELETE`. Use this for proxies and clients that can only send `GET` and `POST`. (defaults
+    /// to no override)
+    ///
+    /// Only `GET`, `PUT`, `PATCH` and `DELETE` can be overridden to, and only if they are in the methods. Other methods
+    /// are ignored. A `POST` request that names a method the server function doesn't use is rejected with
+    /// `405 Method Not Allowed`.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// use http::{HeaderName, Method};
+    ///
+    /// let config = ServerFnConfig::new().method_override(
+    ///     HeaderName::from_static("x-http-method-override"),
+    ///     [Method::PUT, Method::DELETE],
+    /// );
+    /// ```
+    pub fn method_override(
+        mut self,
+        header: http::HeaderName,
+        methods: impl IntoIterator<Item = http::Method>,
+    ) -> Self {
+        let methods = methods
+            .into_iter()
+            .filter(|method| {
+                let overridable = OVERRIDABLE_METHODS.contains(method);
+                if !overridable {
+                    tracing::warn!("Ignoring method override to {method}: only GET, PUT, PATCH and DELETE can be overridden to");
+                }
+                overridable
+            })
+            .collect();
+        self.method_override = Some((header, methods));
+        self
+    }
+
+    /// Get the header that can turn a `POST` request into a request with the method if the method can be overridden to
+    pub(crate) fn method_override_header(
+        &self,
+        method: &http::Method,
+    ) -> Option<&http::HeaderName> {
+        self.method_override
+            .as_ref()
+            .filter(|(_, methods)| methods.contains(method))
+            .map(|(header, _)| header)
+    }
+
+    /// Mark the server function with the given path as deprecated. Every response from the server function includes the
+    /// `Deprecation`, `Sunset` and `Link` headers the [`Deprecation`] describes so clients know to migrate. The path is the
+    /// path of the server function without the route prefix, like `/api/v1/get_user`.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// let sunset = SystemTime::now() + Duration::from_secs(90 * 24 * 60 * 60);
+    /// let config = ServerFnConfig::new().deprecate(
+    ///     "/api/v1/get_user",
+    ///     Deprecation::new()
+    ///         .since(SystemTime::now())
+    ///         .sunset(sunset)
+    ///         .link("https://example.com/docs/migrating-to-v2"),
+    /// );
+    /// ```
+    pub fn deprecate(mut self, path: impl Into<String>, deprecation: Deprecation) -> Self {
+        self.deprecations
+            .insert(normalize_route(&path.into()), deprecation);
+        self
+    }
+
+    /// Add the deprecation headers for the server function with the given path to a response
+    pub(crate) fn apply_deprecation(&self, path: &str, headers: &mut http::HeaderMap) {
+        if let Some(deprecation) = self.deprecations.get(path) {
+            deprecation.apply(headers);
+        }
+    }
+
+    /// Check if the body of requests to the server function with the given path is kept in the server context
+    pub(crate) fn keeps_raw_body(&self, path: &str) -> bool {
+        self.raw_request || self.raw_body_paths.contains(path)
+    }
+
+    /// Get the body limit for the server function with the given path
+    pub(crate) fn body_limit_for_path(&self, path: &str) -> usize {
+        self.body_limit_overrides
+            .get(path)
+            .copied()
+            .or(self.body_limit)
+            .unwrap_or(DEFAULT_BODY_LIMIT)
+    }
+
+    /// Check if a server function with the given path should be registered with this config
+    pub(crate) fn includes(&self, path: &str) -> bool {
+        match &self.only_paths_starting_with {
+            Some(parent) if !parent.is_empty() => path
+                .strip_prefix(parent.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/')),
+            _ => true,
+        }
+    }
+
+    /// Get the route a server function with the given path is mounted at
+    pub(crate) fn route_for(&self, path: &str) -> String {
+        match &self.prefix {
+            Some(prefix) => format!("{prefix}{path}"),
+            None => path.to_string(),
+        }
+    }
+
+    /// A hint about the configured prefix for the error returned when a server function is not found
+    pub(crate) fn prefix_hint(&self) -> String {
+        match &self.prefix {
+            Some(prefix) => format!("\nThe server functions for this route are registered under the prefix {prefix:?}. The client must call server functions at that prefix with `server_fn::client::set_server_url`."),
+            None => String::new(),
+        }
+    }
+}
+
+/// Describes how a server function is deprecated. See [`ServerFnConfig::deprecate`].
+#[derive(Clone, Debug, Default)]
+pub struct Deprecation {
+    since: Option<std::time::SystemTime>,
+    sunset: Option<std::time::SystemTime>,
+    link: Option<String>,
+}
+
+impl Deprecation {
+    /// Create a new deprecation without a date. The `Deprecation` header is set to `true`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set when the server function was deprecated. The `Deprecation` header is set to the date (defaults to `true`)
+    pub fn since(mut self, since: std::time::SystemTime) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    /// Set when the server function will stop responding. The date is sent in the `Sunset` header (defaults to no sunset)
+    pub fn sunset(mut self, sunset: std::time::SystemTime) -> Self {
+        self.sunset = Some(sunset);
+        self
+    }
+
+    /// Set a link to documentation about the deprecation, like a migration guide. The link is sent in a `Link` header with
+    /// the `deprecation` relation (defaults to no link)
+    pub fn link(mut self, link: impl Into<String>) -> Self {
+        self.link = Some(link.into());
+        self
+    }
+
+    fn apply(&self, headers: &mut http::HeaderMap) {
+        let deprecation = match self.since {
+            // Dates are sent as a structured field date, which is the number of seconds since the unix epoch
+            Some(since) => {
+                let seconds = since
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|since| since.as_secs())
+                    .unwrap_or_default();
+                format!("@{seconds}")
+            }
+            None => "true".to_string(),
+        };
+        if let Ok(value) = http::HeaderValue::try_from(deprecation) {
+            headers.insert(http::HeaderName::from_static("deprecation"), value);
+        }
+        if let Some(sunset) = self.sunset {
+            if let Ok(value) = http::HeaderValue::try_from(httpdate::fmt_http_date(sunset)) {
+                headers.insert(http::HeaderName::from_static("sunset"), value);
+            }
+        }
+        if let Some(link) = &self.link {
+            match http::HeaderValue::try_from(format!("<{link}>; rel=\"deprecation\"")) {
+                Ok(value) => {
+                    headers.append(http::header::LINK, value);
+                }
+                Err(_) => {
+                    tracing::warn!("The deprecation link {link:?} is not a valid header value")
+                }
+            }
+        }
+    }
+}
+
+/// Normalize a route segment to start with a slash and not end with one. An empty route stays empty.
+fn normalize_route(route: &str) -> String {
+    let route = route.trim_end_matches('/');
+    if route.is_empty() || route.starts_with('/') {
+        route.to_string()
+    } else {
+        format!("/{route}")
+    }
+}
+
+/// Configuration for how static assets are served with [`DioxusRouterExt::serve_static_assets_with_config`](crate::prelude::DioxusRouterExt::serve_static_assets_with_config).
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus_lib::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// #[tokio::main]
+/// async fn main() {
+///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+///     let router = axum::Router::new()
+///         // Serve static assets and explain what went wrong if the assets haven't been built
+///         .serve_static_assets_with_config(StaticAssetsConfig::new().missing_assets_diagnostic(true))
+///         .into_make_service();
+///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+///     axum::serve(listener, router).await.unwrap();
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct StaticAssetsConfig {
+    pub(crate) missing_assets_diagnostic: bool,
+    pub(crate) min_compression_version: Option<http::Version>,
+    pub(crate) compression_filter: Option<CompressionFilter>,
+    pub(crate) compress_on_the_fly: bool,
+    pub(crate) cache_compressed_assets: bool,
+    pub(crate) render_missing_assets: Option<super::RenderHandleState>,
+    pub(crate) mime_resolver: Option<MimeResolver>,
+}
+
+/// A function that decides if a client may receive compressed assets
+pub(crate) type CompressionFilter = Arc<dyn Fn(&axum::extract::Request) -> bool + Send + Sync>;
+
+/// A function that picks the content type of a static asset from its path
+pub(crate) type MimeResolver = Arc<dyn Fn(&Path) -> Option<&'static str> + Send + Sync>;
+
+impl StaticAssetsConfig {
+    /// Create a new [`StaticAssetsConfig`] with the default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Respond to unmatched requests with a diagnostic message if the public directory is missing or empty. (defaults to false)
+    ///
+    /// Without this, requests for assets that were never built return a bare 404. The diagnostic is registered as the router
+    /// fallback, so this is mainly useful during development.
+    pub fn missing_assets_diagnostic(mut self, missing_assets_diagnostic: bool) -> Self {
+        self.missing_assets_diagnostic = missing_assets_diagnostic;
+        self
+    }
+
+    /// Only serve compressed assets to clients that connect with at least this HTTP version. (defaults to any version)
+    ///
+    /// Older clients are always served the uncompressed asset, even if they send an `Accept-Encoding` header. This is
+    /// useful to work around legacy clients that advertise encodings they can't actually decode.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_fullstack::prelude::*;
+    /// // Only serve brotli compressed assets over HTTP/2 and HTTP/3
+    /// let config = StaticAssetsConfig::new().min_compression_version(http::Version::HTTP_2);
+    /// ```
+    pub fn min_compression_version(mut self, version: http::Version) -> Self {
+        self.min_compression_version = Some(version);
+        self
+    }
+
+    /// Only serve compressed assets to clients the filter returns true for. (defaults to every client)
+    ///
+    /// The filter is checked along with [`StaticAssetsConfig::min_compression_version`], so a client must pass both to
+    /// receive compressed assets.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_fullstack::prelude::*;
+    /// // Don't compress assets for a legacy client that mishandles brotli
+    /// let config = StaticAssetsConfig::new().compress_if(|request| {
+    ///     let user_agent = request
+    ///         .headers()
+    ///         .get(http::header::USER_AGENT)
+    ///         .and_then(|agent| agent.to_str().ok())
+    ///         .unwrap_or_default();
+    ///     !user_agent.contains("LegacyClient/1.")
+    /// });
+    /// ```
+    pub fn compress_if(
+        mut self,
+        filter: impl Fn(&axum::extract::Request) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.compression_filter = Some(Arc::new(filter));
+        self
+    }
+
+    /// Compress assets with brotli when they are requested if the bundler didn't create a precompressed variant. (defaults to false)
+    ///
+    /// Only text based assets like html, css, javascript, json, svg and wasm are compressed. Assets that are compressed on
+    /// the fly can't serve range requests, so media players always receive the uncompressed file.
+    pub fn compress_on_the_fly(mut self, compress_on_the_fly: bool) -> Self {
+        self.compress_on_the_fly = compress_on_the_fly;
+        self
+    }
+
+    /// Write assets that are [compressed on the fly](StaticAssetsConfig::compress_on_the_fly) to a `.br` file next to the
+    /// original file, so later requests are served from disk without compressing the asset again. (defaults to false)
+    ///
+    /// The public directory must be writable. If the compressed variant can't be written, the asset is still served
+    /// compressed and the error is logged.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_fullstack::prelude::*;
+    /// let config = StaticAssetsConfig::new()
+    ///     .compress_on_the_fly(true)
+    ///     .cache_compressed_assets(true);
+    /// ```
+    pub fn cache_compressed_assets(mut self, cache_compressed_assets: bool) -> Self {
+        self.cache_compressed_assets = cache_compressed_assets;
+        self
+    }
+
+    /// Render the page with the [`RenderHandleState`](super::RenderHandleState) when a request that accepts html asks for
+    /// a file that doesn't exist in an asset directory. (defaults to a bare 404)
+    ///
+    /// A missing hashed asset usually means the html and the assets were deployed out of sync. With this, a user who
+    /// follows a stale link gets the page of your application with a `404 Not Found` status instead of an empty
+    /// response. Requests that don't accept html, like the browser loading a script or stylesheet, still get a bare 404.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// # fn app() -> Element { todo!() }
+    /// let state = RenderHandleState::new(ServeConfig::new().unwrap(), app);
+    /// let config = StaticAssetsConfig::new().render_missing_assets(state);
+    /// ```
+    pub fn render_missing_assets(mut self, state: super::RenderHandleState) -> Self {
+        self.render_missing_assets = Some(state);
+        self
+    }
+
+    /// Pick the `Content-Type` of static assets with a resolver. If the resolver returns `None` for a path, the content
+    /// type is guessed from the extension of the file. (defaults to guessing every content type)
+    ///
+    /// WebAssembly modules are always served as `application/wasm` unless the resolver picks a different type, because
+    /// browsers refuse to compile a module while it streams in with any other type.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_fullstack::prelude::*;
+    /// let config = StaticAssetsConfig::new().mime_type(|path| {
+    ///     match path.extension()?.to_str()? {
+    ///         "glb" => Some("model/gltf-binary"),
+    ///         _ => None,
+    ///     }
+    /// });
+    /// ```
+    pub fn mime_type(
+        mut self,
+        resolver: impl Fn(&Path) -> Option<&'static str> + Send + Sync + 'static,
+    ) -> Self {
+        self.mime_resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// Get the content type of a static asset if it shouldn't be guessed from the extension
+    pub(crate) fn content_type(&self, path: &Path) -> Option<&'static str> {
+        if let Some(content_type) = self
+            .mime_resolver
+            .as_ref()
+            .and_then(|resolve| resolve(path))
+        {
+            return Some(content_type);
+        }
+        match path.extension()?.to_str()? {
+            "wasm" => Some("application/wasm"),
+            "webmanifest" => Some("application/manifest+json"),
+            _ => None,
+        }
+    }
+
+    /// Check if a client may receive compressed assets
+    pub(crate) fn allows_compression(&self, request: &axum::extract::Request) -> bool {
+        let version_allowed = self
+            .min_compression_version
+            .map_or(true, |min_version| request.version() >= min_version);
+        let filter_allowed = self
+            .compression_filter
+            .as_ref()
+            .map_or(true, |filter| filter(request));
+        version_allowed && filter_allowed
+    }
+}
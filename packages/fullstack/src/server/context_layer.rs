@@ -0,0 +1,135 @@
+//! Provide the [`DioxusServerContext`] to axum handlers that aren't server functions
+
+use super::{apply_response_metadata, ContextProviders};
+use crate::prelude::*;
+use axum::body::Body;
+use futures_util::future::BoxFuture;
+use http::{header::LOCATION, Request, Response, StatusCode};
+use std::task::{Context, Poll};
+
+/// A [`Layer`](tower_layer::Layer) that creates a [`DioxusServerContext`] for every request, just like the server functions
+/// get. Handlers behind the layer can read the context with the [`DioxusServerContext`] extractor or [`server_context`],
+/// and share helpers for cookies and sessions with your server functions.
+///
+/// Headers, the status and redirects the handler sets on the context are applied to its response.
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus_lib::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// async fn profile(server_context: DioxusServerContext) -> String {
+///     let parts = server_context.request_parts();
+///     format!("Hello from {}", parts.uri.path())
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+///     let router = axum::Router::new()
+///         .route("/profile", axum::routing::get(profile))
+///         .layer(DioxusServerContextLayer::new())
+///         .into_make_service();
+///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+///     axum::serve(listener, router).await.unwrap();
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct DioxusServerContextLayer {
+    context_providers: ContextProviders,
+}
+
+impl DioxusServerContextLayer {
+    /// Create a new [`DioxusServerContextLayer`] without any additional context
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the context providers that will be inserted into the [`DioxusServerContext`] of every request
+    pub fn context_providers(mut self, context_providers: ContextProviders) -> Self {
+        self.context_providers = context_providers;
+        self
+    }
+}
+
+impl<S> tower_layer::Layer<S> for DioxusServerContextLayer {
+    type Service = DioxusServerContextService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        DioxusServerContextService {
+            inner,
+            context_providers: self.context_providers.clone(),
+        }
+    }
+}
+
+/// The service created by [`DioxusServerContextLayer`]
+#[derive(Clone)]
+pub struct DioxusServerContextService<S> {
+    inner: S,
+    context_providers: ContextProviders,
+}
+
+impl<S, B> tower::Service<Request<Body>> for DioxusServerContextService<S>
+where
+    S: tower::Service<Request<Body>, Response = Response<B>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    B: Send + 'static,
+{
+    type Response = Response<B>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let (mut parts, body) = request.into_parts();
+        let server_context = DioxusServerContext::new(parts.clone());
+        for index in 0..self.context_providers.len() {
+            let context_providers = self.context_providers.clone();
+            server_context.insert_boxed_factory(Box::new(move || context_providers[index]()));
+        }
+        parts.extensions.insert(server_context.clone());
+        let request = Request::from_parts(parts, body);
+
+        // The inner service was driven to readiness, so call it and leave a fresh clone in its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        Box::pin(async move {
+            let mut response =
+                ProvideServerContext::new(inner.call(request), server_context.clone()).await?;
+
+            let mut response_parts = server_context.response_parts_mut();
+            response
+                .headers_mut()
+                .extend(response_parts.headers.drain());
+            server_context.apply_vary(response.headers_mut());
+            apply_response_metadata(&mut response_parts, &mut response);
+            if let Some((location, status)) = server_context.redirect_location() {
+                *response.status_mut() = status;
+                response.headers_mut().insert(LOCATION, location);
+            }
+            Ok(response)
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: Send + Sync> axum::extract::FromRequestParts<S> for DioxusServerContext {
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(
+        parts: &mut http::request::Parts,
+        _: &S,
+    ) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<DioxusServerContext>()
+            .cloned()
+            .ok_or((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "The DioxusServerContext extractor requires the DioxusServerContextLayer",
+            ))
+    }
+}
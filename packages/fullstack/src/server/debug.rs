@@ -0,0 +1,73 @@
+//! A route that describes how the server is configured to help debug misconfigured applications
+
+use super::{registered_server_functions, RenderHandleState};
+use axum::{response::Response, routing::get, Router};
+use http::{header::*, HeaderValue};
+
+/// The path of the debug route
+const DEBUG_PATH: &str = "/__dioxus/debug";
+
+/// Add the debug route for the state to the router, unless the config is for production
+pub(crate) fn route<S>(router: Router<S>, state: &RenderHandleState) -> Router<S>
+where
+    S: Send + Sync + Clone + 'static,
+{
+    if state.config.production {
+        tracing::error!(
+            "The dioxus debug endpoint was requested for a production config and will not be served. It exposes the \
+             server configuration to anyone who can reach {DEBUG_PATH}"
+        );
+        return router;
+    }
+    tracing::warn!(
+        "The dioxus debug endpoint is serving the server configuration at {DEBUG_PATH}. Never enable it on a server \
+         that is reachable by untrusted clients"
+    );
+
+    let state = state.clone();
+    router.route(
+        DEBUG_PATH,
+        get(move || {
+            let summary = summary(&state);
+            async move {
+                let mut response = Response::new(summary.to_string().into());
+                let headers = response.headers_mut();
+                headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+                headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+                response
+            }
+        }),
+    )
+}
+
+/// Describe the effective config, the server functions and the state of the renderer
+fn summary(state: &RenderHandleState) -> serde_json::Value {
+    let config = &state.config;
+    let server_functions = registered_server_functions()
+        .into_iter()
+        .map(|(path, method)| serde_json::json!({ "path": path, "method": method.as_str() }))
+        .collect::<Vec<_>>();
+
+    serde_json::json!({
+        "config": {
+            "root_id": config.index.root_id,
+            "production": config.production,
+            "incremental": config.incremental.is_some(),
+            "stream_flush_strategy": format!("{:?}", config.stream_flush_strategy),
+            "render_limit": config.render_limit.is_some(),
+            "overload_policy": config.overload_policy.is_some(),
+            "strict_csp": config.strict_csp,
+            "trust_forwarded_headers": config.trust_forwarded_headers,
+            "https_redirect": config.https_redirect,
+            "catch_render_panics": config.catch_render_panics,
+            "render_missing_assets": config.render_missing_assets,
+            "supported_locales": config.supported_locales,
+            "default_locale": config.default_locale,
+            "static_routes": config.static_routes.is_some(),
+            "index_template": config.index_template.is_some(),
+        },
+        "server_functions": server_functions,
+        "static_assets": crate::public_path(),
+        "ssr_state_initialized": state.ssr_state.get().is_some(),
+    })
+}
@@ -0,0 +1,310 @@
+//! A cache for large binary server function responses that supports resuming downloads with range requests
+
+use axum::body::Body;
+use bytes::Bytes;
+use futures_util::StreamExt;
+use http::header::*;
+use http::{HeaderMap, Response, StatusCode};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// An in memory cache for the byte streams server functions return. Use it with [`ServerFnConfig::download_cache`](crate::prelude::ServerFnConfig::download_cache).
+///
+/// When a server function responds with `application/octet-stream` (like a server function that returns a
+/// `ByteStream`), the whole response is stored in the cache. Requests with the same arguments are answered from the
+/// cache without running the server function again, and requests with a `Range` header get a `206 Partial Content`
+/// response so clients can resume an interrupted download.
+///
+/// Cached responses are keyed by the path, query and body of the request along with the `Cookie` and `Authorization`
+/// headers. Only cache server functions whose response depends on nothing else.
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus_lib::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// #[tokio::main]
+/// async fn main() {
+///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+///     let router = axum::Router::new()
+///         // Keep up to 512MB of generated exports in memory for an hour
+///         .register_server_functions_with_config(
+///             ServerFnConfig::new()
+///                 .only_paths_starting_with("/api/export")
+///                 .download_cache(
+///                     DownloadCache::new(512 * 1024 * 1024)
+///                         .time_to_live(std::time::Duration::from_secs(60 * 60)),
+///                 ),
+///         )
+///         .into_make_service();
+///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+///     axum::serve(listener, router).await.unwrap();
+/// }
+/// ```
+#[derive(Clone)]
+pub struct DownloadCache {
+    entries: Arc<Mutex<HashMap<u64, CachedDownload>>>,
+    max_bytes: usize,
+    time_to_live: Option<Duration>,
+}
+
+#[derive(Clone)]
+struct CachedDownload {
+    body: Bytes,
+    content_type: Option<HeaderValue>,
+    etag: HeaderValue,
+    created: Instant,
+    last_used: Instant,
+}
+
+/// The request headers that decide which part of a cached download is sent. Header values share their bytes with the
+/// request, so copying them out is cheaper than keeping the whole header map around while the server function runs.
+#[derive(Clone, Default)]
+pub(crate) struct RangeHeaders {
+    range: Option<HeaderValue>,
+    if_range: Option<HeaderValue>,
+}
+
+impl RangeHeaders {
+    pub(crate) fn of(headers: &HeaderMap) -> Self {
+        Self {
+            range: headers.get(RANGE).cloned(),
+            if_range: headers.get(IF_RANGE).cloned(),
+        }
+    }
+}
+
+impl DownloadCache {
+    /// Create a new cache that holds up to `max_bytes` of response bodies. Responses larger than the cache are streamed
+    /// to the client without being cached. When the cache is full, the least recently used responses are evicted.
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            entries: Default::default(),
+            max_bytes,
+            time_to_live: None,
+        }
+    }
+
+    /// Evict cached responses after they have been in the cache for a duration. (defaults to keeping responses until they are evicted to make room)
+    pub fn time_to_live(mut self, time_to_live: Duration) -> Self {
+        self.time_to_live = Some(time_to_live);
+        self
+    }
+
+    /// Get the key a request is cached under
+    pub(crate) fn key(&self, parts: &http::request::Parts, body: &[u8]) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        parts.uri.path().hash(&mut hasher);
+        parts.uri.query().hash(&mut hasher);
+        for header in [COOKIE, AUTHORIZATION] {
+            parts
+                .headers
+                .get(header)
+                .map(|value| value.as_bytes())
+                .hash(&mut hasher);
+        }
+        body.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Respond to a request from the cache if the response for the key is cached
+    pub(crate) fn respond(&self, key: u64, request: &RangeHeaders) -> Option<Response<Body>> {
+        let mut entries = self.entries.lock();
+        let expired = entries.get(&key).map(|entry| self.is_expired(entry))?;
+        if expired {
+            entries.remove(&key);
+            return None;
+        }
+        let entry = entries.get_mut(&key)?;
+        entry.last_used = Instant::now();
+        let entry = entry.clone();
+        drop(entries);
+
+        Some(entry.respond(request))
+    }
+
+    /// Store a response in the cache if it is a successful byte stream that fits in the cache. The response is returned
+    /// with support for range requests if it was cached or unchanged otherwise.
+    pub(crate) async fn store(
+        &self,
+        key: u64,
+        request: &RangeHeaders,
+        response: Response<Body>,
+    ) -> Response<Body> {
+        let is_byte_stream = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .is_some_and(|content_type| content_type.starts_with("application/octet-stream"));
+        if !response.status().is_success() || !is_byte_stream {
+            return response;
+        }
+
+        let (mut parts, body) = response.into_parts();
+
+        // Buffer the body until it is too large to cache. If it grows past the limit, stream the rest of the body after
+        // the chunks we already read
+        let mut stream = body.into_data_stream();
+        let mut chunks = Vec::new();
+        let mut size = 0;
+        while let Some(chunk) = stream.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(err) => {
+                    tracing::error!(
+                        "Failed to read the server function response to cache it: {err}"
+                    );
+                    return Response::builder()
+                        .status(StatusCode::INTERNAL_SERVER_ERROR)
+                        .body(Body::from(format!("Failed to read response body: {err}")))
+                        .expect("could not build Response");
+                }
+            };
+            size += chunk.len();
+            chunks.push(chunk);
+            if size > self.max_bytes {
+                let buffered = futures_util::stream::iter(chunks.into_iter().map(Ok));
+                return Response::from_parts(parts, Body::from_stream(buffered.chain(stream)));
+            }
+        }
+
+        let mut body = bytes::BytesMut::with_capacity(size);
+        for chunk in chunks {
+            body.extend_from_slice(&chunk);
+        }
+        let body = body.freeze();
+
+        let now = Instant::now();
+        let entry = CachedDownload {
+            etag: HeaderValue::from_str(&format!("\"{key:x}-{:x}\"", body.len()))
+                .expect("the etag is always a valid header"),
+            content_type: parts.headers.remove(CONTENT_TYPE),
+            body,
+            created: now,
+            last_used: now,
+        };
+
+        self.insert(key, entry.clone());
+
+        // Keep any other headers the server function set on the response
+        let mut response = entry.respond(request);
+        let range_headers: Vec<HeaderName> = response.headers().keys().cloned().collect();
+        for (name, value) in parts.headers.iter() {
+            if !range_headers.contains(name) && name != CONTENT_LENGTH {
+                response.headers_mut().append(name, value.clone());
+            }
+        }
+        response
+    }
+
+    fn insert(&self, key: u64, entry: CachedDownload) {
+        let mut entries = self.entries.lock();
+        entries.retain(|_, entry| !self.is_expired(entry));
+        entries.remove(&key);
+
+        let mut size: usize = entries.values().map(|entry| entry.body.len()).sum();
+        while size + entry.body.len() > self.max_bytes {
+            let Some(least_recently_used) = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| *key)
+            else {
+                break;
+            };
+            if let Some(evicted) = entries.remove(&least_recently_used) {
+                size -= evicted.body.len();
+            }
+        }
+
+        entries.insert(key, entry);
+    }
+
+    fn is_expired(&self, entry: &CachedDownload) -> bool {
+        self.time_to_live
+            .is_some_and(|time_to_live| entry.created.elapsed() > time_to_live)
+    }
+}
+
+impl CachedDownload {
+    /// Respond with the whole body or the part of it the request asked for
+    fn respond(&self, request: &RangeHeaders) -> Response<Body> {
+        let len = self.body.len();
+        let mut response = Response::builder()
+            .header(ACCEPT_RANGES, "bytes")
+            .header(ETAG, self.etag.clone());
+        if let Some(content_type) = &self.content_type {
+            response = response.header(CONTENT_TYPE, content_type.clone());
+        }
+
+        // Only honor the range if the client is resuming the same version of the download
+        let if_range_matches = request
+            .if_range
+            .as_ref()
+            .map_or(true, |if_range| *if_range == self.etag);
+        let range = request
+            .range
+            .as_ref()
+            .filter(|_| if_range_matches)
+            .and_then(|range| range.to_str().ok());
+
+        let response = match range.and_then(|range| parse_range(range, len)) {
+            Some(ByteRange::Satisfiable(start, end)) => response
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(CONTENT_RANGE, format!("bytes {start}-{end}/{len}"))
+                .body(Body::from(self.body.slice(start..=end))),
+            Some(ByteRange::Unsatisfiable) => response
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header(CONTENT_RANGE, format!("bytes */{len}"))
+                .body(Body::empty()),
+            None => response
+                .status(StatusCode::OK)
+                .body(Body::from(self.body.clone())),
+        };
+        response.expect("could not build Response")
+    }
+}
+
+/// A range of bytes a client requested
+enum ByteRange {
+    /// An inclusive start and end inside the body
+    Satisfiable(usize, usize),
+    /// A range that doesn't overlap the body
+    Unsatisfiable,
+}
+
+/// Parse a single `bytes` range. Returns `None` if the header should be ignored and the whole body sent instead
+fn parse_range(range: &str, len: usize) -> Option<ByteRange> {
+    let range = range.trim().strip_prefix("bytes=")?;
+    // Multiple ranges are not supported
+    if range.contains(',') {
+        return None;
+    }
+    let (start, end) = range.split_once('-')?;
+    let last = len.checked_sub(1);
+    let (start, end) = match (start.trim(), end.trim()) {
+        // The last `suffix` bytes of the body
+        ("", suffix) => {
+            let suffix: usize = suffix.parse().ok()?;
+            match last {
+                Some(last) if suffix > 0 => (len.saturating_sub(suffix), last),
+                _ => return Some(ByteRange::Unsatisfiable),
+            }
+        }
+        (start, "") => (start.parse().ok()?, last.unwrap_or_default()),
+        (start, end) => {
+            let start: usize = start.parse().ok()?;
+            let end: usize = end.parse().ok()?;
+            if end < start {
+                return None;
+            }
+            (start, end.min(last.unwrap_or_default()))
+        }
+    };
+    Some(if start < len {
+        ByteRange::Satisfiable(start, end)
+    } else {
+        ByteRange::Unsatisfiable
+    })
+}
@@ -0,0 +1,162 @@
+//! Serve static assets that are embedded in the server binary instead of read from the public directory
+
+use super::{access_log, compression, etag_matches, RouteKind, StaticAssetsConfig};
+use axum::{body::Body, extract::Request, response::Response, routing::get, Router};
+use bytes::Bytes;
+use http::header::*;
+use http::{HeaderValue, StatusCode};
+use std::borrow::Cow;
+use std::sync::Arc;
+
+/// A set of static assets embedded in the server binary. Serve them with
+/// [`DioxusRouterExt::serve_static_assets_embedded`](crate::prelude::DioxusRouterExt::serve_static_assets_embedded).
+///
+/// Paths are relative to the root of the assets and use `/` as the separator, just like the paths of files in the public
+/// directory. Implement this for the embedded directory of a crate like `rust-embed` or `include_dir`, or use a static
+/// list of `(path, contents)` pairs.
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus_fullstack::prelude::*;
+/// use std::borrow::Cow;
+///
+/// struct Assets(std::collections::HashMap<String, Vec<u8>>);
+///
+/// impl EmbeddedAssets for Assets {
+///     fn paths(&self) -> Vec<Cow<'static, str>> {
+///         self.0.keys().cloned().map(Cow::Owned).collect()
+///     }
+///
+///     fn get(&self, path: &str) -> Option<Cow<'static, [u8]>> {
+///         self.0.get(path).cloned().map(Cow::Owned)
+///     }
+/// }
+/// ```
+pub trait EmbeddedAssets: Send + Sync + 'static {
+    /// The path of every embedded file, including files in nested directories
+    fn paths(&self) -> Vec<Cow<'static, str>>;
+
+    /// The contents of the embedded file at a path, or `None` if there is no file at the path
+    fn get(&self, path: &str) -> Option<Cow<'static, [u8]>>;
+}
+
+impl EmbeddedAssets for &'static [(&'static str, &'static [u8])] {
+    fn paths(&self) -> Vec<Cow<'static, str>> {
+        self.iter().map(|(path, _)| Cow::Borrowed(*path)).collect()
+    }
+
+    fn get(&self, path: &str) -> Option<Cow<'static, [u8]>> {
+        self.iter()
+            .find(|(embedded, _)| *embedded == path)
+            .map(|(_, contents)| Cow::Borrowed(*contents))
+    }
+}
+
+/// The body of an embedded asset in one encoding
+struct Variant {
+    body: Bytes,
+    etag: String,
+}
+
+impl Variant {
+    fn new(contents: Cow<'static, [u8]>, encoding: Option<&str>) -> Self {
+        use std::hash::{Hash, Hasher};
+
+        let body = match contents {
+            Cow::Borrowed(contents) => Bytes::from_static(contents),
+            Cow::Owned(contents) => Bytes::from(contents),
+        };
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        body.hash(&mut hasher);
+        let encoding = encoding
+            .map(|encoding| format!("-{encoding}"))
+            .unwrap_or_default();
+        let etag = format!("\"{:x}-{}{encoding}\"", hasher.finish(), body.len());
+        Self { body, etag }
+    }
+}
+
+/// An embedded asset with its precompressed variant if there is one
+struct EmbeddedAsset {
+    content_type: HeaderValue,
+    identity: Variant,
+    brotli: Option<Variant>,
+}
+
+impl EmbeddedAsset {
+    fn respond(&self, config: &StaticAssetsConfig, request: &Request) -> Response {
+        let brotli = self
+            .brotli
+            .as_ref()
+            .filter(|_| compression::accepts_brotli(config, request));
+        let variant = brotli.unwrap_or(&self.identity);
+
+        let mut response = if etag_matches(request.headers().get(IF_NONE_MATCH), &variant.etag) {
+            let mut response = Response::new(Body::empty());
+            *response.status_mut() = StatusCode::NOT_MODIFIED;
+            response
+        } else {
+            let mut response = Response::new(Body::from(variant.body.clone()));
+            let headers = response.headers_mut();
+            headers.insert(CONTENT_TYPE, self.content_type.clone());
+            headers.insert(CONTENT_LENGTH, HeaderValue::from(variant.body.len()));
+            if brotli.is_some() {
+                headers.insert(CONTENT_ENCODING, HeaderValue::from_static("br"));
+            }
+            response
+        };
+        let headers = response.headers_mut();
+        headers.insert(
+            ETAG,
+            HeaderValue::from_str(&variant.etag).expect("the etag is always a valid header"),
+        );
+        headers.insert(VARY, HeaderValue::from_static("accept-encoding"));
+        access_log::mark_route_kind(RouteKind::StaticAsset)(response)
+    }
+}
+
+/// Add a route for every embedded asset except the root `index.html`
+pub(crate) fn serve_embedded_assets<S>(
+    mut router: Router<S>,
+    embedded: impl EmbeddedAssets,
+    config: StaticAssetsConfig,
+) -> Router<S>
+where
+    S: Send + Sync + Clone + 'static,
+{
+    for path in embedded.paths() {
+        let path = path.trim_start_matches('/');
+        if path.is_empty() || path == "index.html" {
+            continue;
+        }
+        let Some(identity) = embedded.get(path) else {
+            continue;
+        };
+        let content_type = config
+            .content_type(std::path::Path::new(path))
+            .or_else(|| mime_guess::from_path(path).first_raw())
+            .unwrap_or("application/octet-stream");
+        let asset = Arc::new(EmbeddedAsset {
+            content_type: HeaderValue::from_static(content_type),
+            identity: Variant::new(identity, None),
+            brotli: embedded
+                .get(&format!("{path}.br"))
+                .map(|brotli| Variant::new(brotli, Some("br"))),
+        });
+        let handler = {
+            let config = config.clone();
+            move |request: Request| {
+                let asset = asset.clone();
+                let config = config.clone();
+                async move { asset.respond(&config, &request) }
+            }
+        };
+
+        router = router.route(&format!("/{path}"), get(handler.clone()));
+        // Directories serve their index file like the public directory does
+        if let Some(directory) = path.strip_suffix("/index.html") {
+            router = router.route(&format!("/{directory}/"), get(handler));
+        }
+    }
+    router
+}
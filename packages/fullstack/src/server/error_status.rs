@@ -0,0 +1,110 @@
+//! Send server function errors with a status code that matches what went wrong
+
+use axum::body::{Body, HttpBody};
+use http::{Response, StatusCode};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Serialized server function errors are short messages, so larger bodies are never read
+const MAX_ERROR_BODY: u64 = 64 * 1024;
+
+/// A custom server function error that knows the status code it should be sent with. Register the error type with
+/// [`ServerFnConfig::error_status`](crate::prelude::ServerFnConfig::error_status) to send server functions that fail
+/// with it with the status code.
+///
+/// The client still receives the same serialized error, so the server function stub returns the typed error no matter
+/// what status it was sent with.
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus_lib::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// use http::StatusCode;
+///
+/// #[derive(Debug, Clone)]
+/// enum AppError {
+///     NotFound,
+///     Unauthorized,
+/// }
+///
+/// impl std::fmt::Display for AppError {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         write!(f, "{self:?}")
+///     }
+/// }
+///
+/// impl std::str::FromStr for AppError {
+///     type Err = ();
+///
+///     fn from_str(s: &str) -> Result<Self, Self::Err> {
+///         match s {
+///             "NotFound" => Ok(Self::NotFound),
+///             "Unauthorized" => Ok(Self::Unauthorized),
+///             _ => Err(()),
+///         }
+///     }
+/// }
+///
+/// impl ErrorStatus for AppError {
+///     fn status(&self) -> StatusCode {
+///         match self {
+///             Self::NotFound => StatusCode::NOT_FOUND,
+///             Self::Unauthorized => StatusCode::UNAUTHORIZED,
+///         }
+///     }
+/// }
+///
+/// let config = ServerFnConfig::new().error_status::<AppError>();
+/// ```
+pub trait ErrorStatus {
+    /// The status code to send the error with
+    fn status(&self) -> StatusCode;
+}
+
+/// Parse a serialized custom error and get its status code
+pub(crate) type CustomErrorStatus = Arc<dyn Fn(&str) -> Option<StatusCode> + Send + Sync>;
+
+/// Create a [`CustomErrorStatus`] for a custom error type
+pub(crate) fn custom_error_status<E: FromStr + ErrorStatus>() -> CustomErrorStatus {
+    Arc::new(|error| error.parse::<E>().ok().map(|error| error.status()))
+}
+
+/// The status code for a serialized [`ServerFnError`](server_fn::ServerFnError). Errors are serialized as
+/// `{variant}|{message}`
+fn status_for_error(error: &str, custom: &[CustomErrorStatus]) -> Option<StatusCode> {
+    let (variant, message) = error.split_once('|')?;
+    match variant {
+        "WrappedServerFn" => custom.iter().find_map(|status| status(message)),
+        "Args" | "MissingArg" => Some(StatusCode::UNPROCESSABLE_ENTITY),
+        "Deserialization" => Some(StatusCode::BAD_REQUEST),
+        "Registration" => Some(StatusCode::NOT_FOUND),
+        _ => None,
+    }
+}
+
+/// Replace the `500 Internal Server Error` status of a server function error response with the status that matches the
+/// error
+pub(crate) async fn map_error_status(
+    response: Response<Body>,
+    custom: &[CustomErrorStatus],
+) -> Response<Body> {
+    let (mut parts, body) = response.into_parts();
+    // Only read bodies we know are small, so a streaming response is never consumed here
+    if body
+        .size_hint()
+        .exact()
+        .map_or(true, |len| len > MAX_ERROR_BODY)
+    {
+        return Response::from_parts(parts, body);
+    }
+    let Ok(bytes) = axum::body::to_bytes(body, MAX_ERROR_BODY as usize).await else {
+        return Response::from_parts(parts, Body::empty());
+    };
+    if let Some(status) = std::str::from_utf8(&bytes)
+        .ok()
+        .and_then(|error| status_for_error(error, custom))
+    {
+        parts.status = status;
+    }
+    Response::from_parts(parts, Body::from(bytes))
+}
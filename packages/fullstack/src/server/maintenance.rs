@@ -0,0 +1,128 @@
+//! Take the whole application offline for maintenance without restarting the server
+
+use axum::{body::Body, extract::Request, middleware::Next, response::Response};
+use http::header::{CACHE_CONTROL, CONTENT_TYPE};
+use http::{Method, StatusCode};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+const DEFAULT_PAGE: &str = "<!DOCTYPE html><html><head><title>Down for maintenance</title></head><body><h1>Down for maintenance</h1><p>We'll be back shortly.</p></body></html>";
+
+/// A switch that puts the application into maintenance mode. Use it with
+/// [`DioxusRouterExt::with_maintenance_switch`](crate::prelude::DioxusRouterExt::with_maintenance_switch).
+///
+/// While the flag is set, every request to the routes added before the switch is answered with
+/// `503 Service Unavailable` and the maintenance page, including server rendered pages and server functions. Flip the
+/// flag at any time to enter or leave maintenance mode.
+///
+/// Files in the public directory are still served so the maintenance page can load its own styles and images. Turn
+/// that off with [`MaintenanceSwitch::serve_static_assets`].
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus_lib::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use std::sync::Arc;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let maintenance = Arc::new(AtomicBool::new(false));
+///
+///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+///     let router = axum::Router::new()
+///         .serve_dioxus_application(ServeConfig::new().unwrap(), app)
+///         .with_maintenance_switch(
+///             MaintenanceSwitch::new(maintenance.clone())
+///                 .page("<h1>We are upgrading the servers</h1>"),
+///         )
+///         // The admin route is added after the switch so it stays available during maintenance
+///         .route(
+///             "/admin/maintenance",
+///             axum::routing::post(move |enabled: String| async move {
+///                 maintenance.store(enabled == "on", Ordering::Relaxed);
+///             }),
+///         )
+///         .into_make_service();
+///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+///     axum::serve(listener, router).await.unwrap();
+/// }
+///
+/// fn app() -> Element {
+///     rsx! { "Hello World" }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct MaintenanceSwitch {
+    enabled: Arc<AtomicBool>,
+    page: Arc<str>,
+    serve_static_assets: bool,
+}
+
+impl MaintenanceSwitch {
+    /// Create a new switch that enables maintenance mode while the flag is set
+    pub fn new(enabled: Arc<AtomicBool>) -> Self {
+        Self {
+            enabled,
+            page: Arc::from(DEFAULT_PAGE),
+            serve_static_assets: true,
+        }
+    }
+
+    /// Set the html that is sent while the application is in maintenance mode. (defaults to a short notice)
+    pub fn page(mut self, html: impl Into<String>) -> Self {
+        self.page = Arc::from(html.into());
+        self
+    }
+
+    /// Keep serving files from the public directory while the application is in maintenance mode. (defaults to true)
+    pub fn serve_static_assets(mut self, serve_static_assets: bool) -> Self {
+        self.serve_static_assets = serve_static_assets;
+        self
+    }
+
+    /// Check if the application is in maintenance mode
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Check if a request is for a file in the public directory. The index.html is rendered by the application, so it
+    /// doesn't count
+    fn is_static_asset(&self, request: &Request) -> bool {
+        if !matches!(*request.method(), Method::GET | Method::HEAD) {
+            return false;
+        }
+        let path = request.uri().path().trim_start_matches('/');
+        if path.is_empty()
+            || path.ends_with("index.html")
+            || path.split('/').any(|segment| segment == "..")
+        {
+            return false;
+        }
+        crate::public_path().join(path).is_file()
+    }
+
+    /// The middleware that answers requests with the maintenance page while the switch is on
+    pub(crate) async fn middleware(self, request: Request, next: Next) -> Response {
+        if !self.is_enabled() || (self.serve_static_assets && self.is_static_asset(&request)) {
+            return next.run(request).await;
+        }
+
+        tracing::debug!(
+            "Rejected a request to {} because the application is in maintenance mode",
+            request.uri().path()
+        );
+        Response::builder()
+            .status(StatusCode::SERVICE_UNAVAILABLE)
+            .header(CONTENT_TYPE, "text/html; charset=utf-8")
+            .header(CACHE_CONTROL, "no-store")
+            .body(Body::from(self.page.to_string()))
+            .expect("could not build Response")
+    }
+}
+
+impl From<Arc<AtomicBool>> for MaintenanceSwitch {
+    fn from(enabled: Arc<AtomicBool>) -> Self {
+        Self::new(enabled)
+    }
+}
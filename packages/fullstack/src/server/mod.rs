@@ -54,6 +54,51 @@
 
 pub mod launch;
 
+mod config;
+pub use config::*;
+
+mod download_cache;
+pub use download_cache::DownloadCache;
+
+mod concurrency_limit;
+pub use concurrency_limit::ClientConcurrencyLimit;
+
+mod maintenance;
+pub use maintenance::MaintenanceSwitch;
+
+mod background;
+
+mod access_log;
+mod compression;
+pub use access_log::{LogRecord, RouteKind};
+
+mod shutdown;
+pub use shutdown::ShutdownHandle;
+
+mod error_status;
+pub use error_status::ErrorStatus;
+
+mod state_builder;
+pub use state_builder::{NoRoot, RenderHandleStateBuilder, WithRoot};
+
+mod embedded;
+pub use embedded::EmbeddedAssets;
+
+mod context_layer;
+pub use context_layer::{DioxusServerContextLayer, DioxusServerContextService};
+
+#[cfg(feature = "debug-endpoint")]
+mod debug;
+
+#[cfg(feature = "metrics")]
+mod request_metrics;
+
+mod registered_routes;
+use registered_routes::RegisteredRoutes;
+
+mod tus;
+pub use tus::{CompletedUpload, DirectoryUploadStore, ResumableUploads, UploadInfo, UploadStore};
+
 #[allow(unused)]
 pub(crate) type ContextProviders =
     Arc<Vec<Box<dyn Fn() -> Box<dyn std::any::Any> + Send + Sync + 'static>>>;
@@ -65,7 +110,7 @@ use axum::{
     http::{Request, Response, StatusCode},
     response::IntoResponse,
 };
-use dioxus_lib::prelude::{Element, VirtualDom};
+use dioxus_lib::prelude::{Element, ErrorContext, ScopeId, VirtualDom};
 use http::header::*;
 
 use std::sync::Arc;
@@ -98,64 +143,2092 @@ pub trait DioxusRouterExt<S> {
         self.register_server_functions_with_context(Default::default())
     }
 
-    /// Registers server functions with some additional context to insert into the [`DioxusServerContext`] for that handler.
+    /// Registers server functions with some additional context to insert into the [`DioxusServerContext`] for that handler.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// # use std::sync::Arc;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         // Register server functions routes with the default handler
+    ///         .register_server_functions_with_context(Arc::new(vec![Box::new(|| Box::new(1234567890u32))]))
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    /// ```
+    fn register_server_functions_with_context(self, context_providers: ContextProviders) -> Self
+    where
+        Self: Sized,
+    {
+        self.register_server_functions_with_config(
+            ServerFnConfig::new().context_providers(context_providers),
+        )
+    }
+
+    /// Registers server functions with a [`ServerFnConfig`] that controls how the server functions are handled.
+    ///
+    /// Registering the server functions on a router that already has them is harmless. Server functions whose route
+    /// is already taken are skipped with an error log naming the route instead of panicking, so you can compose routers
+    /// that each register the server functions.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         // Register server functions routes and keep the raw request around for server functions that need it
+    ///         .register_server_functions_with_config(ServerFnConfig::new().raw_request(true))
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    /// ```
+    fn register_server_functions_with_config(self, config: ServerFnConfig) -> Self;
+
+    /// Registers a single server function with a [`ServerFnConfig`], even if the automatic collection of server functions
+    /// missed it. The server function is the struct the `#[server]` macro generates, like `GetUser` for `get_user`.
+    ///
+    /// Server functions are collected automatically when the binary is linked. The linker may drop the server functions of
+    /// a crate in your workspace if nothing in the server binary references the crate, or if the server functions are
+    /// behind a feature that is only enabled for the client. Calls to those server functions fail with
+    /// `400 Bad Request` and a "No server function found" message. Register each missing server function with this, like
+    /// `register_explicit` on the client. [`ServerFnConfig::only_paths_starting_with`] is ignored, but the prefix is used.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// // Usually this lives in another crate of the workspace
+    /// #[server]
+    /// async fn get_user(id: u32) -> Result<String, ServerFnError> {
+    ///     Ok(format!("user {id}"))
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         // Make sure get_user is registered even if the linker dropped it from the automatic collection
+    ///         .register_server_function::<GetUser>(ServerFnConfig::new())
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    /// ```
+    fn register_server_function<T>(self, config: ServerFnConfig) -> Self
+    where
+        T: server_fn::ServerFn<ServerRequest = Request<Body>, ServerResponse = Response<Body>>
+            + 'static;
+
+    /// Registers server functions that respond with `504 Gateway Timeout` if they take longer than the timeout, with some additional context to insert into the [`DioxusServerContext`] for that handler.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         // Register server functions routes that give up after 30 seconds
+    ///         .register_server_functions_with_timeout(std::time::Duration::from_secs(30), Default::default())
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    /// ```
+    fn register_server_functions_with_timeout(
+        self,
+        timeout: std::time::Duration,
+        context_providers: ContextProviders,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        self.register_server_functions_with_config(
+            ServerFnConfig::new()
+                .timeout(timeout)
+                .context_providers(context_providers),
+        )
+    }
+
+    /// Registers only the server functions whose path starts with `path`, with some additional context to insert into the [`DioxusServerContext`] for that handler.
+    ///
+    /// This filters the server functions, it doesn't mount them under `path`. Each server function is still served from the path
+    /// it was declared with, because that is the path the client calls. To keep the server functions of each crate in your
+    /// workspace apart, declare them under a distinct route with `#[server(prefix = "...")]` and register each crate separately
+    /// with this. See [`ServerFnConfig::only_paths_starting_with`].
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// # use std::sync::Arc;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         // Server functions declared with `#[server(prefix = "/api/billing")]`
+    ///         .register_server_functions_starting_with("/api/billing", Arc::new(vec![Box::new(|| Box::new(1234567890u32))]))
+    ///         // Server functions declared with `#[server(prefix = "/api/auth")]`
+    ///         .register_server_functions_starting_with("/api/auth", Default::default())
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    /// ```
+    fn register_server_functions_starting_with(
+        self,
+        path: &str,
+        context_providers: ContextProviders,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        self.register_server_functions_with_config(
+            ServerFnConfig::new()
+                .only_paths_starting_with(path)
+                .context_providers(context_providers),
+        )
+    }
+
+    /// Registers server functions under a route prefix with some additional context to insert into the [`DioxusServerContext`] for that handler.
+    ///
+    /// This is useful if you host multiple Dioxus applications behind one server and their server function paths collide. The
+    /// client still calls server functions at their original path, so you need to point the client at the prefixed
+    /// url with [`server_fn::client::set_server_url`].
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         // Register server functions routes under the /tenant-a prefix
+    ///         .register_server_functions_with_prefix("/tenant-a", Default::default())
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    /// ```
+    fn register_server_functions_with_prefix(
+        self,
+        prefix: &str,
+        context_providers: ContextProviders,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        self.register_server_functions_with_config(
+            ServerFnConfig::new()
+                .prefix(prefix)
+                .context_providers(context_providers),
+        )
+    }
+
+    /// Registers server functions that respond with `413 Payload Too Large` if the request body is larger than the limit,
+    /// with some additional context to insert into the [`DioxusServerContext`] for that handler.
+    ///
+    /// The limit applies to every kind of request body, including multipart forms and streaming uploads. Use
+    /// [`ServerFnConfig::body_limit_for`] with [`DioxusRouterExt::register_server_functions_with_config`] to give
+    /// individual server functions a different limit.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         // Register server functions routes that accept at most 64KB request bodies
+    ///         .register_server_functions_with_body_limit(64 * 1024, Default::default())
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    /// ```
+    fn register_server_functions_with_body_limit(
+        self,
+        limit: usize,
+        context_providers: ContextProviders,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        self.register_server_functions_with_config(
+            ServerFnConfig::new()
+                .body_limit(limit)
+                .context_providers(context_providers),
+        )
+    }
+
+    /// Registers server functions with some additional context and returns a [`ShutdownHandle`] that tracks the server
+    /// function calls that are running. Use the handle to drain server functions before the server exits.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let (router, shutdown) = axum::Router::new()
+    ///         .register_server_functions_with_shutdown(Default::default());
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router.into_make_service())
+    ///         .with_graceful_shutdown(async move {
+    ///             tokio::signal::ctrl_c().await.unwrap();
+    ///             shutdown.shutdown();
+    ///             shutdown.wait_for_idle(std::time::Duration::from_secs(30)).await;
+    ///         })
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    fn register_server_functions_with_shutdown(
+        self,
+        context_providers: ContextProviders,
+    ) -> (Self, ShutdownHandle)
+    where
+        Self: Sized,
+    {
+        let shutdown = ShutdownHandle::new();
+        let router = self.register_server_functions_with_config(
+            ServerFnConfig::new()
+                .shutdown_handle(shutdown.clone())
+                .context_providers(context_providers),
+        );
+        (router, shutdown)
+    }
+
+    /// Serves the static WASM for your Dioxus application (except the generated index.html).
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # #![allow(non_snake_case)]
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         // Server side render the application, serve static assets, and register server functions
+    ///         .serve_static_assets()
+    ///         // Server render the application
+    ///         // ...
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    /// ```
+    fn serve_static_assets(self) -> Self
+    where
+        Self: Sized,
+    {
+        self.serve_static_assets_with_config(StaticAssetsConfig::new())
+    }
+
+    /// Serves the static assets for your Dioxus application with a resolver that picks the `Content-Type` of each file.
+    /// Files the resolver returns `None` for get the content type guessed from their extension. See
+    /// [`StaticAssetsConfig::mime_type`].
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         .serve_static_assets_with_mime(|path| match path.extension()?.to_str()? {
+    ///             "ktx2" => Some("image/ktx2"),
+    ///             _ => None,
+    ///         })
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    /// ```
+    fn serve_static_assets_with_mime(
+        self,
+        resolver: impl Fn(&std::path::Path) -> Option<&'static str> + Send + Sync + 'static,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        self.serve_static_assets_with_config(StaticAssetsConfig::new().mime_type(resolver))
+    }
+
+    /// Serves the static WASM for your Dioxus application (except the generated index.html) with a [`StaticAssetsConfig`].
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # #![allow(non_snake_case)]
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         // Serve static assets and explain what went wrong if the assets haven't been built
+    ///         .serve_static_assets_with_config(StaticAssetsConfig::new().missing_assets_diagnostic(true))
+    ///         // Server render the application
+    ///         // ...
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    /// ```
+    fn serve_static_assets_with_config(self, config: StaticAssetsConfig) -> Self;
+
+    /// Serves the static assets for your Dioxus application and serves an index file for every other `GET` request that
+    /// accepts html. This lets a client side router handle every route of a web application that isn't server rendered.
+    ///
+    /// The index path is relative to the public directory. Requests that don't accept html like missing images still get
+    /// a `404 Not Found`. Routes you add to the router like server functions are matched before the fallback.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         // Register server functions before the fallback
+    ///         .register_server_functions()
+    ///         // Serve static assets and let the client route everything else
+    ///         .serve_static_assets_with_spa_fallback("index.html")
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    /// ```
+    fn serve_static_assets_with_spa_fallback(self, index: &str) -> Self;
+
+    /// Serves the static assets for your Dioxus application from files embedded in the server binary instead of the public
+    /// directory. This lets you distribute the server as a single binary without any asset files next to it.
+    ///
+    /// Assets are served at the same routes as [`DioxusRouterExt::serve_static_assets`], and the `index.html` file at the
+    /// root of the embedded assets is skipped. If an embedded file has a `.br` variant, it is served to clients that
+    /// accept brotli.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// static ASSETS: &[(&str, &[u8])] = &[
+    ///     ("assets/main.css", b"body { margin: 0; }"),
+    ///     ("assets/main.css.br", b"..."),
+    /// ];
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         // Serve the embedded assets instead of the public directory
+    ///         .serve_static_assets_embedded(ASSETS)
+    ///         // Server render the application
+    ///         // ...
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    /// ```
+    fn serve_static_assets_embedded(self, embedded: impl EmbeddedAssets) -> Self;
+
+    /// Serves the Dioxus application. This will serve a complete server side rendered application.
+    /// This will serve static assets, server render the application, register server functions, and integrate with hot reloading.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # #![allow(non_snake_case)]
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         // Server side render the application, serve static assets, and register server functions
+    ///         .serve_dioxus_application(ServeConfig::new().unwrap(), app)
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    ///
+    /// fn app() -> Element {
+    ///     rsx! { "Hello World" }
+    /// }
+    /// ```
+    fn serve_dioxus_application<Cfg, Error>(self, cfg: Cfg, app: fn() -> Element) -> Self
+    where
+        Cfg: TryInto<ServeConfig, Error = Error>,
+        Error: std::error::Error,
+        Self: Sized;
+
+    /// Serves the Dioxus application with an async [`VirtualDom`] factory. This will serve a complete server side rendered application.
+    /// This will serve static assets, server render the application, register server functions, and integrate with hot reloading.
+    ///
+    /// The factory runs for every request inside the [`DioxusServerContext`] of the request, so it can use [`server_context()`] to make
+    /// request-scoped decisions. If the factory returns an error, the server responds with a `500 Internal Server Error`.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # #![allow(non_snake_case)]
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         // Server side render the application, serve static assets, and register server functions
+    ///         .serve_dioxus_application_with_async_factory(ServeConfig::new().unwrap(), || {
+    ///             Box::pin(async move {
+    ///                 // Load some request-scoped configuration before building the root
+    ///                 let headers = server_context().request_parts().headers.clone();
+    ///                 let dark_mode = headers.contains_key("x-dark-mode");
+    ///                 Ok(VirtualDom::new(app).with_root_context(dark_mode))
+    ///             })
+    ///         })
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    ///
+    /// fn app() -> Element {
+    ///     rsx! { "Hello World" }
+    /// }
+    /// ```
+    fn serve_dioxus_application_with_async_factory<Cfg, Error>(
+        self,
+        cfg: Cfg,
+        build_virtual_dom: impl Fn() -> VirtualDomFuture + Send + Sync + 'static,
+    ) -> Self
+    where
+        Cfg: TryInto<ServeConfig, Error = Error>,
+        Error: std::error::Error,
+        Self: Sized;
+
+    /// Serves the Dioxus application like [`DioxusRouterExt::serve_dioxus_application`], but hands every request the
+    /// application doesn't render to your fallback instead of responding with `406 Not Acceptable`.
+    ///
+    /// The application renders `GET` and `HEAD` requests that accept `text/html`. Every other request that doesn't match
+    /// a route, like a `POST` or a request for json to an unknown API path, is sent to the fallback.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # #![allow(non_snake_case)]
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         .serve_dioxus_application_with_fallback(ServeConfig::new().unwrap(), app, |_request| async {
+    ///             (
+    ///                 http::StatusCode::NOT_FOUND,
+    ///                 axum::Json(serde_json::json!({ "error": "not found" })),
+    ///             )
+    ///         })
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    ///
+    /// fn app() -> Element {
+    ///     rsx! { "Hello World" }
+    /// }
+    /// ```
+    fn serve_dioxus_application_with_fallback<Cfg, Error, F, Fut, R>(
+        self,
+        cfg: Cfg,
+        app: fn() -> Element,
+        fallback: F,
+    ) -> Self
+    where
+        Cfg: TryInto<ServeConfig, Error = Error>,
+        Error: std::error::Error,
+        F: Fn(Request<Body>) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = R> + Send + 'static,
+        R: IntoResponse,
+        Self: Sized;
+
+    /// Respond with `429 Too Many Requests` if a single client has too many requests in flight at once. The limit applies to
+    /// every route registered before this call. See [`ClientConcurrencyLimit`] for how clients are identified.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         .serve_dioxus_application(ServeConfig::new().unwrap(), app)
+    ///         // Allow each client at most 16 requests at once
+    ///         .limit_concurrency_per_client(ClientConcurrencyLimit::new(16))
+    ///         .into_make_service_with_connect_info::<std::net::SocketAddr>();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    ///
+    /// fn app() -> Element {
+    ///     rsx! { "Hello World" }
+    /// }
+    /// ```
+    fn limit_concurrency_per_client(self, limit: ClientConcurrencyLimit) -> Self;
+
+    /// Respond with `503 Service Unavailable` and a maintenance page while the flag of the switch is set. The switch applies
+    /// to every route registered before this call, including server functions and server rendered pages. Files in the
+    /// public directory stay available by default. See [`MaintenanceSwitch`] for the options.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// use std::sync::atomic::AtomicBool;
+    /// use std::sync::Arc;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     // Set to true while deploying
+    ///     let maintenance = Arc::new(AtomicBool::new(false));
+    ///
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         .serve_dioxus_application(ServeConfig::new().unwrap(), app)
+    ///         .with_maintenance_switch(maintenance)
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    ///
+    /// fn app() -> Element {
+    ///     rsx! { "Hello World" }
+    /// }
+    /// ```
+    fn with_maintenance_switch(self, switch: impl Into<MaintenanceSwitch>) -> Self;
+
+    /// Start a background task that runs as long as the router. The task receives the context providers, so it can build
+    /// the same context as your server functions, for example to share a database pool for a periodic cleanup job.
+    ///
+    /// The task is spawned immediately and restarted if it panics. It is cancelled once the router and every service
+    /// created from it are dropped, which happens when the server shuts down.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         .serve_dioxus_application(ServeConfig::new().unwrap(), app)
+    ///         .spawn_background(Default::default(), |_context_providers| async move {
+    ///             let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+    ///             loop {
+    ///                 interval.tick().await;
+    ///                 tracing::info!("Refreshing the cache");
+    ///             }
+    ///         })
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    ///
+    /// fn app() -> Element {
+    ///     rsx! { "Hello World" }
+    /// }
+    /// ```
+    fn spawn_background<F, Fut>(self, context_providers: ContextProviders, task: F) -> Self
+    where
+        F: Fn(ContextProviders) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = ()> + Send + 'static;
+
+    /// Accept resumable uploads with the [tus protocol](https://tus.io/protocols/resumable-upload) at the path. Clients
+    /// create uploads with a `POST` to the path and send their chunks to the upload url the server responds with. Once
+    /// every byte of a file arrived, the completion callback of the [`ResumableUploads`] runs inside the server context
+    /// like a server function.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let uploads = ResumableUploads::new(
+    ///         DirectoryUploadStore::new("uploads"),
+    ///         |upload: CompletedUpload| async move {
+    ///             tracing::info!("Received {} bytes", upload.length);
+    ///             Ok(())
+    ///         },
+    ///     );
+    ///
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         .with_resumable_uploads("/api/uploads", uploads)
+    ///         .serve_dioxus_application(ServeConfig::new().unwrap(), app)
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    ///
+    /// fn app() -> Element {
+    ///     rsx! { "Hello World" }
+    /// }
+    /// ```
+    fn with_resumable_uploads(self, path: &str, uploads: ResumableUploads) -> Self;
+
+    /// Serve a json summary of the effective [`ServeConfig`], the registered server functions, the static asset directory
+    /// and whether the [`SSRState`] was initialized at `/__dioxus/debug`. Use it to find out why server side rendering
+    /// doesn't behave the way you expect.
+    ///
+    /// The summary exposes how your server is configured, so the route is only available with the `debug-endpoint`
+    /// feature, logs a warning when it is added and is never served with a production config.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let state = RenderHandleState::new(ServeConfig::new().unwrap(), app);
+    ///
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         .with_debug_endpoint(&state)
+    ///         .register_server_functions()
+    ///         .fallback(axum::routing::get(render_handler).with_state(state))
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    ///
+    /// fn app() -> Element {
+    ///     rsx! { "Hello World" }
+    /// }
+    /// ```
+    #[cfg(feature = "debug-endpoint")]
+    fn with_debug_endpoint(self, state: &RenderHandleState) -> Self;
+
+    /// Record the number and latency of requests to the routes added before this call with the [`metrics`](::metrics)
+    /// crate. Install a recorder like `metrics-exporter-prometheus` to collect them.
+    ///
+    /// Every request increments the `dioxus_fullstack_requests_total` counter and records its latency in seconds in the
+    /// `dioxus_fullstack_request_duration_seconds` histogram. Both are labeled with:
+    /// - `kind`: the [`RouteKind`] that handled the request
+    /// - `route`: the route pattern, which is the path of the server function for server functions, or `*` for pages
+    ///   rendered by the fallback
+    /// - `method` and `status`
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         .serve_dioxus_application(ServeConfig::new().unwrap(), app)
+    ///         .with_metrics()
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    ///
+    /// fn app() -> Element {
+    ///     rsx! { "Hello World" }
+    /// }
+    /// ```
+    #[cfg(feature = "metrics")]
+    fn with_metrics(self) -> Self;
+
+    /// Serve the metrics in the Prometheus text format at the path. The render function produces the body of every
+    /// response, like `PrometheusHandle::render` from `metrics-exporter-prometheus`. Add the endpoint after
+    /// [`DioxusRouterExt::with_metrics`] so scrapes are not counted as requests.
+    ///
+    /// # Example
+    /// ```rust, ignore
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let prometheus = metrics_exporter_prometheus::PrometheusBuilder::new()
+    ///         .install_recorder()
+    ///         .unwrap();
+    ///
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         .serve_dioxus_application(ServeConfig::new().unwrap(), app)
+    ///         .with_metrics()
+    ///         .with_metrics_endpoint("/metrics", move || prometheus.render())
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    ///
+    /// fn app() -> Element {
+    ///     rsx! { "Hello World" }
+    /// }
+    /// ```
+    #[cfg(feature = "metrics")]
+    fn with_metrics_endpoint(
+        self,
+        path: &str,
+        render: impl Fn() -> String + Send + Sync + 'static,
+    ) -> Self;
+
+    /// Registers liveness and readiness routes that respond with `200 OK` to any `GET` request without rendering the application.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         .with_health_routes("/healthz", "/readyz")
+    ///         .serve_dioxus_application(ServeConfig::new().unwrap(), app)
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    ///
+    /// fn app() -> Element {
+    ///     rsx! { "Hello World" }
+    /// }
+    /// ```
+    fn with_health_routes(self, live: &str, ready: &str) -> Self
+    where
+        Self: Sized,
+    {
+        self.with_health_routes_and_readiness(live, ready, || true)
+    }
+
+    /// Registers liveness and readiness routes that don't render the application. The liveness route always responds with
+    /// `200 OK`. The readiness route responds with `200 OK` if the readiness check returns true and
+    /// `503 Service Unavailable` otherwise.
+    ///
+    /// The readiness check runs on every request to the readiness route, so it should be fast. If checking your dependencies is
+    /// slow, check them in a background task and read the result in the readiness check.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     // Set to true once the database connection pool is ready
+    ///     let database_ready = Arc::new(AtomicBool::new(false));
+    ///
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         .with_health_routes_and_readiness("/healthz", "/readyz", move || {
+    ///             database_ready.load(Ordering::Relaxed)
+    ///         })
+    ///         .serve_dioxus_application(ServeConfig::new().unwrap(), app)
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    ///
+    /// fn app() -> Element {
+    ///     rsx! { "Hello World" }
+    /// }
+    /// ```
+    fn with_health_routes_and_readiness(
+        self,
+        live: &str,
+        ready: &str,
+        is_ready: impl Fn() -> bool + Send + Sync + 'static,
+    ) -> Self;
+
+    /// Log every request to the routes added before this call with the [`tracing`] target `dioxus_fullstack::access_log`.
+    /// The formatter turns each [`LogRecord`] into the line that is logged. [`LogRecord`] implements [`Display`](std::fmt::Display)
+    /// if you don't need a custom format.
+    ///
+    /// Each record includes the [`RouteKind`] that handled the request, so you can tell server functions, server rendered
+    /// pages and static assets apart without classifying the routes yourself.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         .serve_dioxus_application(ServeConfig::new().unwrap(), app)
+    ///         .with_access_log(|record| {
+    ///             format!(
+    ///                 r#"{{"method":"{}","path":"{}","status":{},"latency_ms":{},"kind":"{}"}}"#,
+    ///                 record.method,
+    ///                 record.path,
+    ///                 record.status.as_u16(),
+    ///                 record.latency.as_millis(),
+    ///                 record.kind,
+    ///             )
+    ///         })
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    ///
+    /// fn app() -> Element {
+    ///     rsx! { "Hello World" }
+    /// }
+    /// ```
+    fn with_access_log(
+        self,
+        formatter: impl Fn(&LogRecord) -> String + Send + Sync + 'static,
+    ) -> Self;
+}
+
+impl<S> DioxusRouterExt<S> for Router<S>
+where
+    S: Send + Sync + Clone + 'static,
+{
+    fn register_server_functions_with_config(mut self, config: ServerFnConfig) -> Self {
+        config.check_client_prefix();
+        let config = Arc::new(config);
+
+        let mut registered = RegisteredRoutes::of(&self);
+        for (path, method) in server_fn::axum::server_fn_paths() {
+            if !config.includes(path) {
+                continue;
+            }
+            self = route_server_function(self, path, method, &config, &mut registered);
+        }
+
+        self
+    }
+
+    fn register_server_function<T>(self, config: ServerFnConfig) -> Self
+    where
+        T: server_fn::ServerFn<ServerRequest = Request<Body>, ServerResponse = Response<Body>>
+            + 'static,
+    {
+        use server_fn::codec::Encoding;
+
+        // Add the server function to the registry the handler looks server functions up in
+        server_fn::axum::register_explicit::<T>();
+        config.check_client_prefix();
+        let mut registered = RegisteredRoutes::of(&self);
+        route_server_function(
+            self,
+            T::PATH,
+            <T::InputEncoding as Encoding>::METHOD,
+            &Arc::new(config),
+            &mut registered,
+        )
+    }
+
+    fn serve_static_assets_with_config(mut self, config: StaticAssetsConfig) -> Self {
+        use tower::ServiceExt;
+        use tower_http::services::{ServeDir, ServeFile};
+
+        let public_path = crate::public_path();
+
+        if !public_path.exists() {
+            if config.missing_assets_diagnostic {
+                return self.fallback(missing_assets_handler(public_path));
+            }
+            return self;
+        }
+
+        // Serve all files in public folder except index.html
+        let dir = std::fs::read_dir(&public_path).unwrap_or_else(|e| {
+            panic!(
+                "Couldn't read public directory at {:?}: {}",
+                &public_path, e
+            )
+        });
+        let entries = dir.flatten().collect::<Vec<_>>();
+
+        if entries.is_empty() && config.missing_assets_diagnostic {
+            return self.fallback(missing_assets_handler(public_path));
+        }
+
+        for entry in entries {
+            let path = entry.path();
+            if path.ends_with("index.html") {
+                continue;
+            }
+            let route = path
+                .strip_prefix(&public_path)
+                .unwrap()
+                .iter()
+                .map(|segment| {
+                    segment.to_str().unwrap_or_else(|| {
+                        panic!("Failed to convert path segment {:?} to string", segment)
+                    })
+                })
+                .collect::<Vec<_>>()
+                .join("/");
+            let route = format!("/{}", route);
+            let negotiate_encoding = {
+                let config = config.clone();
+                move |request| {
+                    identity_encoding_for_excluded_clients(
+                        &config,
+                        identity_encoding_for_ranges(request),
+                    )
+                }
+            };
+            let fallback_compression =
+                compression::FallbackCompression::new(&config, &path).map(Arc::new);
+            let compress_for = {
+                let config = config.clone();
+                move |request: &Request<Body>| {
+                    fallback_compression
+                        .clone()
+                        .filter(|compression| compression.wants_compression(&config, request))
+                }
+            };
+            if path.is_dir() {
+                let serve = ServeDir::new(&path)
+                    .precompressed_br()
+                    .map_request(negotiate_encoding);
+                let render_missing_assets = config.render_missing_assets.clone();
+                let config = config.clone();
+                self = self.nest_service(
+                    &route,
+                    tower::service_fn(move |request: Request<Body>| {
+                        let compression = compress_for(&request);
+                        let content_type = config
+                            .content_type(&path.join(request.uri().path().trim_start_matches('/')));
+                        // Keep a copy of the request to render the page if the asset is missing
+                        let render_missing = render_missing_assets
+                            .clone()
+                            .filter(|_| accepts_html_page(&request))
+                            .map(|state| (state, page_request(&request)));
+                        let response = encoding_aware_etag(serve.clone(), request, compression);
+                        async move {
+                            let response = response.await?;
+                            match render_missing {
+                                Some((state, request))
+                                    if response.status() == StatusCode::NOT_FOUND =>
+                                {
+                                    let mut page =
+                                        render_handler(State(state), request).await.into_response();
+                                    *page.status_mut() = StatusCode::NOT_FOUND;
+                                    Ok(page)
+                                }
+                                _ => Ok(with_content_type(response, content_type)),
+                            }
+                        }
+                    })
+                    .map_response(access_log::mark_route_kind(RouteKind::StaticAsset)),
+                );
+            } else {
+                let content_type = config.content_type(&path);
+                let serve = ServeFile::new(path)
+                    .precompressed_br()
+                    .map_request(negotiate_encoding);
+                self = self.nest_service(
+                    &route,
+                    tower::service_fn(move |request| {
+                        let compression = compress_for(&request);
+                        encoding_aware_etag(serve.clone(), request, compression)
+                    })
+                    .map_response(move |response| with_content_type(response, content_type))
+                    .map_response(access_log::mark_route_kind(RouteKind::StaticAsset)),
+                );
+            }
+        }
+
+        self
+    }
+
+    fn serve_static_assets_embedded(self, embedded: impl EmbeddedAssets) -> Self {
+        embedded::serve_embedded_assets(self, embedded, StaticAssetsConfig::new())
+    }
+
+    fn serve_static_assets_with_spa_fallback(self, index: &str) -> Self {
+        use tower::ServiceExt;
+
+        let serve_index = tower_http::services::ServeFile::new(
+            crate::public_path().join(index.trim_start_matches('/')),
+        );
+        self.serve_static_assets()
+            .fallback_service(tower::service_fn(move |request: Request<Body>| {
+                let serve_index = serve_index.clone();
+                async move {
+                    let accepts_html = request
+                        .headers()
+                        .get(ACCEPT)
+                        .and_then(|accept| accept.to_str().ok())
+                        .is_some_and(|accept| AcceptMatching::Lenient.accepts_html(accept));
+                    if request.method() != http::Method::GET || !accepts_html {
+                        return Ok(StatusCode::NOT_FOUND.into_response());
+                    }
+                    let response = serve_index.oneshot(request).await?;
+                    Ok::<_, std::convert::Infallible>(access_log::mark_route_kind(
+                        RouteKind::StaticAsset,
+                    )(
+                        response.into_response()
+                    ))
+                }
+            }))
+    }
+
+    fn serve_dioxus_application<Cfg, Error>(self, cfg: Cfg, app: fn() -> Element) -> Self
+    where
+        Cfg: TryInto<ServeConfig, Error = Error>,
+        Error: std::error::Error,
+    {
+        serve_dioxus_application_with_state(
+            self,
+            cfg.try_into(),
+            |cfg| RenderHandleState::new(cfg, app),
+            None,
+        )
+    }
+
+    fn serve_dioxus_application_with_fallback<Cfg, Error, F, Fut, R>(
+        self,
+        cfg: Cfg,
+        app: fn() -> Element,
+        fallback: F,
+    ) -> Self
+    where
+        Cfg: TryInto<ServeConfig, Error = Error>,
+        Error: std::error::Error,
+        F: Fn(Request<Body>) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = R> + Send + 'static,
+        R: IntoResponse,
+    {
+        let fallback: Fallback = Arc::new(move |request| {
+            let response = fallback(request);
+            Box::pin(async move { response.await.into_response() })
+        });
+        serve_dioxus_application_with_state(
+            self,
+            cfg.try_into(),
+            |cfg| RenderHandleState::new(cfg, app),
+            Some(fallback),
+        )
+    }
+
+    fn serve_dioxus_application_with_async_factory<Cfg, Error>(
+        self,
+        cfg: Cfg,
+        build_virtual_dom: impl Fn() -> VirtualDomFuture + Send + Sync + 'static,
+    ) -> Self
+    where
+        Cfg: TryInto<ServeConfig, Error = Error>,
+        Error: std::error::Error,
+    {
+        serve_dioxus_application_with_state(
+            self,
+            cfg.try_into(),
+            |cfg| RenderHandleState::new_with_async_virtual_dom_factory(cfg, build_virtual_dom),
+            None,
+        )
+    }
+
+    fn with_health_routes_and_readiness(
+        self,
+        live: &str,
+        ready: &str,
+        is_ready: impl Fn() -> bool + Send + Sync + 'static,
+    ) -> Self {
+        let is_ready = Arc::new(is_ready);
+        self.route(live, get(|| async { StatusCode::OK })).route(
+            ready,
+            get(move || {
+                let is_ready = is_ready.clone();
+                async move {
+                    if is_ready() {
+                        StatusCode::OK
+                    } else {
+                        StatusCode::SERVICE_UNAVAILABLE
+                    }
+                }
+            }),
+        )
+    }
+
+    fn with_access_log(
+        self,
+        formatter: impl Fn(&LogRecord) -> String + Send + Sync + 'static,
+    ) -> Self {
+        let formatter: Arc<dyn Fn(&LogRecord) -> String + Send + Sync> = Arc::new(formatter);
+        self.layer(axum::middleware::from_fn(
+            move |request: Request<Body>, next: axum::middleware::Next| {
+                access_log::middleware(formatter.clone(), request, next)
+            },
+        ))
+    }
+
+    fn limit_concurrency_per_client(self, limit: ClientConcurrencyLimit) -> Self {
+        self.layer(axum::middleware::from_fn(
+            move |request: Request<Body>, next: axum::middleware::Next| {
+                limit.clone().middleware(request, next)
+            },
+        ))
+    }
+
+    fn spawn_background<F, Fut>(self, context_providers: ContextProviders, task: F) -> Self
+    where
+        F: Fn(ContextProviders) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        // Every route keeps the guard alive, so the task is cancelled once the last clone of the router is dropped
+        let guard = Arc::new(background::spawn_supervised(context_providers, task));
+        self.layer(axum::Extension(guard))
+    }
+
+    fn with_resumable_uploads(self, path: &str, uploads: ResumableUploads) -> Self {
+        uploads.route(self, path)
+    }
+
+    #[cfg(feature = "debug-endpoint")]
+    fn with_debug_endpoint(self, state: &RenderHandleState) -> Self {
+        debug::route(self, state)
+    }
+
+    #[cfg(feature = "metrics")]
+    fn with_metrics(self) -> Self {
+        self.layer(axum::middleware::from_fn(request_metrics::middleware))
+    }
+
+    #[cfg(feature = "metrics")]
+    fn with_metrics_endpoint(
+        self,
+        path: &str,
+        render: impl Fn() -> String + Send + Sync + 'static,
+    ) -> Self {
+        let render = Arc::new(render);
+        self.route(
+            path,
+            get(move || {
+                let body = render();
+                async move { ([(CONTENT_TYPE, "text/plain; version=0.0.4")], body) }
+            }),
+        )
+    }
+
+    fn with_maintenance_switch(self, switch: impl Into<MaintenanceSwitch>) -> Self {
+        let switch = switch.into();
+        self.layer(axum::middleware::from_fn(
+            move |request: Request<Body>, next: axum::middleware::Next| {
+                switch.clone().middleware(request, next)
+            },
+        ))
+    }
+}
+
+/// A fallback for the requests the application doesn't render
+type Fallback = Arc<
+    dyn Fn(Request<Body>) -> futures_util::future::BoxFuture<'static, Response<Body>> + Send + Sync,
+>;
+
+/// Serve static assets, register server functions and render the application with the [`RenderHandleState`] created from
+/// the config. Requests the application doesn't render are sent to the fallback if there is one
+fn serve_dioxus_application_with_state<S, Error>(
+    router: Router<S>,
+    cfg: Result<ServeConfig, Error>,
+    state: impl FnOnce(ServeConfig) -> RenderHandleState,
+    fallback: Option<Fallback>,
+) -> Router<S>
+where
+    S: Send + Sync + Clone + 'static,
+    Error: std::error::Error,
+{
+    let state = match cfg {
+        Ok(cfg) => Some(state(cfg)),
+        Err(err) => {
+            tracing::trace!("Failed to create render handler. This is expected if you are only using fullstack for desktop/mobile server functions: {}", err);
+            None
+        }
+    };
+
+    // Add server functions and render index.html. In debug builds, explain what went wrong if the assets were never built
+    let server = router
+        .serve_static_assets_with_config(static_assets_config(
+            state.as_ref(),
+            cfg!(debug_assertions),
+        ))
+        .register_server_functions();
+
+    let Some(state) = state else {
+        return match fallback {
+            Some(fallback) => server.fallback(move |request: Request<Body>| fallback(request)),
+            None => server,
+        };
+    };
+    // Build the renderers before the first request arrives
+    state.ssr_state();
+    let cfg = state.config.clone();
+    let server = match fallback {
+        Some(fallback) => server.fallback(move |request: Request<Body>| {
+            let state = state.clone();
+            let fallback = fallback.clone();
+            async move {
+                let renders = matches!(*request.method(), http::Method::GET | http::Method::HEAD)
+                    && accepts_html(&state.config, request.headers());
+                if renders {
+                    render_handler(State(state), request).await.into_response()
+                } else {
+                    fallback(request).await
+                }
+            }
+        }),
+        None => server.fallback(get(render_handler).with_state(state)),
+    };
+    with_serve_config_layers(server, &cfg)
+}
+
+/// Add the route for a server function to the router
+fn route_server_function<S>(
+    router: Router<S>,
+    path: &'static str,
+    method: http::Method,
+    config: &Arc<ServerFnConfig>,
+    registered: &mut RegisteredRoutes,
+) -> Router<S>
+where
+    S: Send + Sync + Clone + 'static,
+{
+    use http::method::Method;
+
+    let route = config.route_for(path);
+    // Axum panics if the route is already registered for the method, so skip it instead
+    if !registered.insert(method.clone(), &route) {
+        tracing::error!(
+            "Skipped registering server function {method} {route}: the route is already registered. Make sure you only register server functions once per router."
+        );
+        return router;
+    }
+    tracing::trace!("Registering server function: {} {}", method, route);
+    let context_providers = config.context_providers.clone();
+    let override_header = config
+        .method_override_header(&method)
+        .cloned()
+        .filter(|_| {
+            let free = registered.insert(Method::POST, &route);
+            if !free {
+                tracing::error!(
+                    "Skipped the method override for server function {method} {route}: POST {route} is already registered."
+                );
+            }
+            free
+        });
+    let config = config.clone();
+    let handler = move |req| {
+        let response = handle_server_fns_inner(
+            path,
+            config.clone(),
+            move |server_context| {
+                for index in 0..context_providers.len() {
+                    let context_providers = context_providers.clone();
+                    server_context
+                        .insert_boxed_factory(Box::new(move || context_providers[index]()));
+                }
+            },
+            req,
+        );
+        async move {
+            access_log::mark_route_kind(RouteKind::ServerFunction)(response.await.into_response())
+        }
+    };
+    // Let POST requests that name the method in the override header call the server function
+    let method_override = override_header.map(|header| {
+        let handler = handler.clone();
+        let method = method.clone();
+        move |mut req: Request<Body>| {
+            let overridden = req
+                .headers()
+                .get(&header)
+                .and_then(|value| value.to_str().ok())
+                .is_some_and(|value| value.trim().eq_ignore_ascii_case(method.as_str()));
+            let response = overridden.then(|| {
+                *req.method_mut() = method.clone();
+                handler(req)
+            });
+            async move {
+                match response {
+                    Some(response) => response.await,
+                    None => StatusCode::METHOD_NOT_ALLOWED.into_response(),
+                }
+            }
+        }
+    });
+    let method_router = match method {
+        Method::GET => get(handler),
+        Method::POST => post(handler),
+        Method::PUT => put(handler),
+        Method::PATCH => patch(handler),
+        Method::DELETE => delete(handler),
+        _ => unimplemented!("Unsupported server function method: {}", method),
+    };
+    let method_router = match method_override {
+        Some(method_override) => method_router.post(method_override),
+        None => method_router,
+    };
+    router.route(&route, method_router)
+}
+
+/// Get the path and method of every server function that [`DioxusRouterExt::register_server_functions`] registers,
+/// sorted by path. The paths include the prefix of each server function.
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus_lib::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// #[server]
+/// async fn get_user(id: u32) -> Result<String, ServerFnError> {
+///     Ok(format!("User {id}"))
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     for (path, method) in registered_server_functions() {
+///         println!("Serving server function {method} {path}");
+///     }
+/// }
+/// ```
+pub fn registered_server_functions() -> Vec<(String, http::Method)> {
+    let mut server_functions = server_fn::axum::server_fn_paths()
+        .map(|(path, method)| (path.to_string(), method))
+        .collect::<Vec<_>>();
+    server_functions.sort_by(|(a, _), (b, _)| a.cmp(b));
+    server_functions
+}
+
+/// The static assets config for a router that renders the application with the state
+fn static_assets_config(
+    state: Option<&RenderHandleState>,
+    missing_assets_diagnostic: bool,
+) -> StaticAssetsConfig {
+    let config = StaticAssetsConfig::new().missing_assets_diagnostic(missing_assets_diagnostic);
+    match state.filter(|state| state.config.render_missing_assets) {
+        Some(state) => config.render_missing_assets(state.clone()),
+        None => config,
+    }
+}
+
+/// Create a standalone router with the server functions, static assets and server side rendered pages of a Dioxus
+/// application that you can [`merge`](Router::merge) or [`nest`](Router::nest) into an existing router.
+///
+/// Unlike [`DioxusRouterExt::serve_dioxus_application`], the pages are rendered from normal routes instead of the fallback,
+/// so the fallback and middleware of your router are left alone. Routes in your router still win over the rendered
+/// pages for the paths they match.
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus_lib::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// #[tokio::main]
+/// async fn main() {
+///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+///     let router = axum::Router::new()
+///         .route("/api/health", axum::routing::get(|| async { "ok" }))
+///         // Merge the Dioxus application into the existing router
+///         .merge(dioxus_routes(ServeConfig::new().unwrap(), app))
+///         // The fallback of your router is kept
+///         .fallback(|| async { (http::StatusCode::NOT_FOUND, "Not found") })
+///         .into_make_service();
+///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+///     axum::serve(listener, router).await.unwrap();
+/// }
+///
+/// fn app() -> Element {
+///     rsx! { "Hello World" }
+/// }
+/// ```
+pub fn dioxus_routes<S, Cfg, Error>(cfg: Cfg, app: fn() -> Element) -> Router<S>
+where
+    S: Send + Sync + Clone + 'static,
+    Cfg: TryInto<ServeConfig, Error = Error>,
+    Error: std::error::Error,
+{
+    let state = match cfg.try_into() {
+        Ok(cfg) => Some(RenderHandleState::new(cfg, app)),
+        Err(err) => {
+            tracing::trace!("Failed to create render handler. This is expected if you are only using fullstack for desktop/mobile server functions: {}", err);
+            None
+        }
+    };
+
+    let router = Router::new()
+        .serve_static_assets_with_config(static_assets_config(state.as_ref(), false))
+        .register_server_functions();
+
+    let Some(state) = state else {
+        return router;
+    };
+    // Build the renderers before the first request arrives
+    state.ssr_state();
+    let cfg = state.config.clone();
+    let render = get(render_handler).with_state(state);
+    let router = router.route("/", render.clone()).route("/*path", render);
+    with_serve_config_layers(router, &cfg)
+}
+
+/// Add the middleware the [`ServeConfig`] asks for to the routes of the router
+fn with_serve_config_layers<S>(mut server: Router<S>, cfg: &ServeConfig) -> Router<S>
+where
+    S: Send + Sync + Clone + 'static,
+{
+    let timeouts = Arc::new(cfg.request_timeouts.clone());
+    if !timeouts.is_empty() {
+        server = server.layer(axum::middleware::from_fn(
+            move |request: Request<Body>, next: axum::middleware::Next| {
+                route_timeout(timeouts.clone(), request, next)
+            },
+        ));
+    }
+    // Redirect before doing any other work for the request
+    if cfg.redirects_to_https() {
+        let cfg = Arc::new(cfg.clone());
+        server = server.layer(axum::middleware::from_fn(
+            move |request: Request<Body>, next: axum::middleware::Next| {
+                redirect_to_https(cfg.clone(), request, next)
+            },
+        ));
+    }
+    server
+}
+
+/// Check if a request is a page navigation that accepts html
+fn accepts_html_page(request: &Request<Body>) -> bool {
+    let accepts_html = request
+        .headers()
+        .get(ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| AcceptMatching::Lenient.accepts_html(accept));
+    accepts_html
+        && (request.method() == http::Method::GET || request.method() == http::Method::HEAD)
+}
+
+/// Copy a request that was nested under an asset directory so it can be rendered as a page. Nested services only see the
+/// end of the path, so the page is rendered for the original uri
+fn page_request(request: &Request<Body>) -> Request<Body> {
+    let uri = request
+        .extensions()
+        .get::<axum::extract::OriginalUri>()
+        .map(|uri| uri.0.clone())
+        .unwrap_or_else(|| request.uri().clone());
+    let mut page = Request::new(Body::empty());
+    *page.method_mut() = request.method().clone();
+    *page.uri_mut() = uri;
+    *page.version_mut() = request.version();
+    *page.headers_mut() = request.headers().clone();
+    page
+}
+
+/// Respond with `504 Gateway Timeout` if a request takes longer than the timeout for its route
+async fn route_timeout(
+    timeouts: Arc<crate::serve_config::RouteTimeouts>,
+    request: Request<Body>,
+    next: axum::middleware::Next,
+) -> Response<Body> {
+    let path = request.uri().path().to_string();
+    let Some(timeout) = timeouts.timeout_for(&path) else {
+        return next.run(request).await;
+    };
+    match tokio::time::timeout(timeout, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => {
+            tracing::warn!("Request to {path} timed out after {timeout:?}");
+            Response::builder()
+                .status(StatusCode::GATEWAY_TIMEOUT)
+                .body(Body::from(format!(
+                    "Request to {path} timed out after {timeout:?}"
+                )))
+                .expect("could not build Response")
+        }
+    }
+}
+
+/// Permanently redirect requests the client sent over plain HTTP to the same URL over HTTPS
+async fn redirect_to_https(
+    cfg: Arc<ServeConfig>,
+    request: Request<Body>,
+    next: axum::middleware::Next,
+) -> Response<Body> {
+    // The proxy in front of the server terminates TLS, so it tells us which scheme the client used
+    let forwarded_proto = request
+        .headers()
+        .get("x-forwarded-proto")
+        .and_then(|proto| proto.to_str().ok())
+        .and_then(|proto| proto.split(',').next())
+        .map(|proto| proto.trim().to_ascii_lowercase());
+    let scheme = forwarded_proto.or_else(|| request.uri().scheme_str().map(str::to_string));
+    if scheme.as_deref() != Some("http") {
+        return next.run(request).await;
+    }
+
+    let (parts, _) = request.into_parts();
+    let Some(origin) = cfg.request_origin(&parts) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    let host = origin
+        .split_once("://")
+        .map_or(origin.as_str(), |(_, host)| host);
+    let path = parts
+        .uri
+        .path_and_query()
+        .map(|path| path.as_str())
+        .unwrap_or("/");
+    Response::builder()
+        .status(StatusCode::PERMANENT_REDIRECT)
+        .header(LOCATION, format!("https://{host}{path}"))
+        .body(Body::empty())
+        .expect("could not build Response")
+}
+
+/// A handler that explains that the static assets for the application were never built
+fn missing_assets_handler(
+    public_path: std::path::PathBuf,
+) -> impl FnOnce() -> std::future::Ready<(StatusCode, String)> + Clone + Send + Sync + 'static {
+    move || {
+        std::future::ready((
+            StatusCode::NOT_FOUND,
+            format!(
+                "No static assets were found in the public directory at {public_path:?}. \
+                Make sure the client has been bundled (for example with `dx serve` or `dx bundle`) \
+                before starting the server."
+            ),
+        ))
+    }
+}
+
+/// Override the content type tower-http guessed for a static asset that was served successfully
+fn with_content_type(
+    mut response: Response<Body>,
+    content_type: Option<&'static str>,
+) -> Response<Body> {
+    if let Some(content_type) = content_type.filter(|_| response.status().is_success()) {
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+    }
+    response
+}
+
+/// Byte ranges are resolved against the file that ends up being served, and a precompressed variant has completely
+/// different offsets than the original file. Media players seeking through a file expect ranges of the identity
+/// encoding, so we drop the `Accept-Encoding` header for range requests to make [`tower_http::services::ServeDir`]
+/// serve a `206 Partial Content` response from the uncompressed file. Non-range requests still negotiate the
+/// precompressed variant, and the `Accept-Ranges: bytes` header is emitted by tower-http for both.
+fn identity_encoding_for_ranges(mut request: Request<Body>) -> Request<Body> {
+    if request.headers().contains_key(RANGE) {
+        request.headers_mut().remove(ACCEPT_ENCODING);
+    }
+    request
+}
+
+/// Drop the `Accept-Encoding` header for clients the [`StaticAssetsConfig`] doesn't allow to receive compressed assets, so
+/// they are always served the uncompressed file
+fn identity_encoding_for_excluded_clients(
+    config: &StaticAssetsConfig,
+    mut request: Request<Body>,
+) -> Request<Body> {
+    if !config.allows_compression(&request) {
+        request.headers_mut().remove(ACCEPT_ENCODING);
+    }
+    request
+}
+
+/// Add an `ETag` that is unique to the encoding of a static asset and `Vary: Accept-Encoding` to the response.
+///
+/// [`tower_http::services::ServeDir`] only validates requests with `Last-Modified`, which is the same for the brotli and
+/// identity variants of a file. A cache that stores one variant could revalidate it while asking for the other, and get a
+/// `304 Not Modified` for a body in the wrong encoding. The `ETag` includes the content encoding, so `If-None-Match` is
+/// evaluated here instead and only matches the variant the client actually has.
+///
+/// If the client accepts brotli but the file has no precompressed variant, the fallback compression compresses the
+/// identity variant before the `ETag` is computed.
+async fn encoding_aware_etag<S>(
+    service: S,
+    mut request: Request<Body>,
+    fallback_compression: Option<Arc<compression::FallbackCompression>>,
+) -> Result<Response<Body>, std::convert::Infallible>
+where
+    S: tower::Service<
+        Request<Body>,
+        Response = Response<tower_http::services::fs::ServeFileSystemResponseBody>,
+        Error = std::convert::Infallible,
+    >,
+{
+    use tower::ServiceExt;
+
+    // If-None-Match takes precedence over If-Modified-Since, so don't let the file service answer with a 304 based on the
+    // modification time alone
+    let if_none_match = request.headers_mut().remove(IF_NONE_MATCH);
+    if if_none_match.is_some() {
+        request.headers_mut().remove(IF_MODIFIED_SINCE);
+    }
+
+    let request_path = request.uri().path().to_string();
+    let (mut parts, body) = service.oneshot(request).await?.into_parts();
+    let mut body = Body::new(body);
+    if let Some(compression) = fallback_compression {
+        body = compression.compress(&request_path, &mut parts, body).await;
+    }
+
+    let varies_by_encoding = parts
+        .headers
+        .get_all(VARY)
+        .iter()
+        .filter_map(|vary| vary.to_str().ok())
+        .flat_map(|vary| vary.split(','))
+        .any(|vary| vary.trim().eq_ignore_ascii_case("accept-encoding"));
+    if !varies_by_encoding {
+        parts
+            .headers
+            .append(VARY, HeaderValue::from_static("accept-encoding"));
+    }
+
+    if parts.status != StatusCode::OK {
+        return Ok(Response::from_parts(parts, body));
+    }
+    let Some(etag) = static_asset_etag(&parts.headers) else {
+        return Ok(Response::from_parts(parts, body));
+    };
+
+    let not_modified = etag_matches(if_none_match.as_ref(), &etag);
+    parts.headers.insert(
+        ETAG,
+        HeaderValue::from_str(&etag).expect("the etag is always a valid header"),
+    );
+
+    if not_modified {
+        parts.status = StatusCode::NOT_MODIFIED;
+        for header in [
+            CONTENT_LENGTH,
+            CONTENT_TYPE,
+            CONTENT_ENCODING,
+            ACCEPT_RANGES,
+        ] {
+            parts.headers.remove(header);
+        }
+        return Ok(Response::from_parts(parts, Body::empty()));
+    }
+    Ok(Response::from_parts(parts, body))
+}
+
+/// Check if an `If-None-Match` header matches the `ETag` of a static asset
+fn etag_matches(if_none_match: Option<&HeaderValue>, etag: &str) -> bool {
+    if_none_match
+        .and_then(|if_none_match| if_none_match.to_str().ok())
+        .is_some_and(|if_none_match| {
+            if_none_match.split(',').any(|tag| {
+                let tag = tag.trim();
+                tag == "*" || tag.trim_start_matches("W/") == etag.trim_start_matches("W/")
+            })
+        })
+}
+
+/// Build a weak `ETag` for a static asset from its modification time, length and content encoding
+fn static_asset_etag(headers: &HeaderMap) -> Option<String> {
+    use std::hash::{Hash, Hasher};
+
+    let last_modified = headers.get(LAST_MODIFIED)?;
+    let length = headers.get(CONTENT_LENGTH)?.to_str().ok()?;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    last_modified.as_bytes().hash(&mut hasher);
+    let encoding = headers
+        .get(CONTENT_ENCODING)
+        .and_then(|encoding| encoding.to_str().ok())
+        .map(|encoding| format!("-{encoding}"))
+        .unwrap_or_default();
+    Some(format!("W/\"{:x}-{length}{encoding}\"", hasher.finish()))
+}
+
+fn apply_request_parts_to_response<B>(
+    headers: hyper::header::HeaderMap,
+    response: &mut axum::response::Response<B>,
+) {
+    let mut_headers = response.headers_mut();
+    for (key, value) in headers.iter() {
+        mut_headers.insert(key, value.clone());
+    }
+}
+
+/// Apply the status, version and extensions set in the [`DioxusServerContext`] to the response. The status and version are
+/// only applied if they were changed from the default, so the response keeps the status the handler chose otherwise.
+fn apply_response_metadata<B>(
+    parts: &mut http::response::Parts,
+    response: &mut axum::response::Response<B>,
+) {
+    if parts.status != StatusCode::OK {
+        *response.status_mut() = parts.status;
+    }
+    if parts.version != http::Version::default() {
+        *response.version_mut() = parts.version;
+    }
+    response
+        .extensions_mut()
+        .extend(std::mem::take(&mut parts.extensions));
+}
+
+/// A future that builds the [`VirtualDom`] for a request
+pub type VirtualDomFuture = std::pin::Pin<
+    Box<
+        dyn std::future::Future<
+            Output = Result<VirtualDom, Box<dyn std::error::Error + Send + Sync>>,
+        >,
+    >,
+>;
+
+/// The different ways a [`RenderHandleState`] can build the [`VirtualDom`] for a request
+#[derive(Clone)]
+enum VirtualDomFactory {
+    Sync(Arc<dyn Fn() -> VirtualDom + Send + Sync>),
+    Routed(Arc<dyn Fn(&str) -> VirtualDom + Send + Sync>),
+    Async(Arc<dyn Fn() -> VirtualDomFuture + Send + Sync>),
+}
+
+impl VirtualDomFactory {
+    /// Start building the [`VirtualDom`] for the url of a request. This must be called on the thread the [`VirtualDom`]
+    /// will be rendered on
+    fn build(
+        &self,
+        url: &str,
+    ) -> std::pin::Pin<
+        Box<
+            dyn std::future::Future<
+                Output = Result<VirtualDom, dioxus_isrg::IncrementalRendererError>,
+            >,
+        >,
+    > {
+        match self {
+            Self::Sync(build_virtual_dom) => {
+                let virtual_dom = build_virtual_dom();
+                Box::pin(std::future::ready(Ok(virtual_dom)))
+            }
+            Self::Routed(build_virtual_dom) => {
+                let virtual_dom = build_virtual_dom(url);
+                Box::pin(std::future::ready(Ok(virtual_dom)))
+            }
+            Self::Async(build_virtual_dom) => {
+                let virtual_dom = build_virtual_dom();
+                Box::pin(async move {
+                    virtual_dom
+                        .await
+                        .map_err(dioxus_isrg::IncrementalRendererError::Other)
+                })
+            }
+        }
+    }
+}
+
+/// State used by [`render_handler`] to render a dioxus component with axum
+#[derive(Clone)]
+pub struct RenderHandleState {
+    config: ServeConfig,
+    build_virtual_dom: VirtualDomFactory,
+    ssr_state: Arc<once_cell::sync::OnceCell<SSRState>>,
+    on_ssr_state_init: Option<SsrStateInitHook>,
+    head_builder: Option<HeadBuilder>,
+    feature_flags: Option<FeatureFlagsBuilder>,
+    error_component: Option<fn() -> Element>,
+}
+
+/// A function that builds extra html for the head of a page from the request
+type HeadBuilder = Arc<dyn Fn(&DioxusServerContext) -> String + Send + Sync>;
+
+/// A function that decides the feature flags of a page from the request
+type FeatureFlagsBuilder = Arc<dyn Fn(&DioxusServerContext) -> FeatureFlags + Send + Sync>;
+
+/// A callback that runs once the [`SSRState`] of a [`RenderHandleState`] is initialized
+type SsrStateInitHook = Arc<dyn Fn(&SSRState, std::time::Duration) + Send + Sync>;
+
+impl RenderHandleState {
+    /// Create a new [`RenderHandleState`]
+    pub fn new(config: ServeConfig, root: fn() -> Element) -> Self {
+        Self {
+            config,
+            build_virtual_dom: VirtualDomFactory::Sync(Arc::new(move || VirtualDom::new(root))),
+            ssr_state: Default::default(),
+            on_ssr_state_init: None,
+            head_builder: None,
+            feature_flags: None,
+            error_component: None,
+        }
+    }
+
+    /// Create a new [`RenderHandleState`] with a custom [`VirtualDom`] factory. This method can be used to pass context into the root component of your application.
+    pub fn new_with_virtual_dom_factory(
+        config: ServeConfig,
+        build_virtual_dom: impl Fn() -> VirtualDom + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            config,
+            build_virtual_dom: VirtualDomFactory::Sync(Arc::new(build_virtual_dom)),
+            ssr_state: Default::default(),
+            on_ssr_state_init: None,
+            head_builder: None,
+            feature_flags: None,
+            error_component: None,
+        }
+    }
+
+    /// Create a new [`RenderHandleState`] with a [`VirtualDom`] factory that receives the path and query of the request.
+    /// Use this for routers that read their initial location from context, so the root can be built already knowing its
+    /// route before the first render.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// #[derive(Clone)]
+    /// struct InitialRoute(String);
+    ///
+    /// fn app() -> Element {
+    ///     let InitialRoute(route) = use_context();
+    ///     rsx! { "Rendering {route}" }
+    /// }
+    ///
+    /// let state = RenderHandleState::new_with_url_aware_virtual_dom_factory(
+    ///     ServeConfig::new().unwrap(),
+    ///     |url| VirtualDom::new(app).with_root_context(InitialRoute(url.to_string())),
+    /// );
+    /// ```
+    pub fn new_with_url_aware_virtual_dom_factory(
+        config: ServeConfig,
+        build_virtual_dom: impl Fn(&str) -> VirtualDom + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            config,
+            build_virtual_dom: VirtualDomFactory::Routed(Arc::new(build_virtual_dom)),
+            ssr_state: Default::default(),
+            on_ssr_state_init: None,
+            head_builder: None,
+            feature_flags: None,
+            error_component: None,
+        }
+    }
+
+    /// Create a new [`RenderHandleState`] with an async [`VirtualDom`] factory. The factory runs inside the [`DioxusServerContext`]
+    /// of each request, so it can load request-scoped data before the root component is built.
+    pub fn new_with_async_virtual_dom_factory(
+        config: ServeConfig,
+        build_virtual_dom: impl Fn() -> VirtualDomFuture + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            config,
+            build_virtual_dom: VirtualDomFactory::Async(Arc::new(build_virtual_dom)),
+            ssr_state: Default::default(),
+            on_ssr_state_init: None,
+            head_builder: None,
+            feature_flags: None,
+            error_component: None,
+        }
+    }
+
+    /// Set the [`ServeConfig`] for this [`RenderHandleState`]
+    pub fn with_config(mut self, config: ServeConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Set the [`SSRState`] for this [`RenderHandleState`]. Sharing a [`SSRState`] between multiple [`RenderHandleState`]s is more efficient than creating a new [`SSRState`] for each [`RenderHandleState`].
+    ///
+    /// The [`SSRState`] is independent of the [`ServeConfig`], so the states that share it can each use a different config.
+    /// Each config keeps its own incremental cache.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// # fn public_app() -> Element { todo!() }
+    /// # fn admin_app() -> Element { todo!() }
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let public_cfg = ServeConfig::new().unwrap();
+    ///     let admin_cfg = ServeConfig::builder()
+    ///         .index_path("admin/index.html".into())
+    ///         .strict_csp(true)
+    ///         .build()
+    ///         .unwrap();
+    ///
+    ///     // Both applications render with the same pool of renderers
+    ///     let ssr_state = SSRState::new(&public_cfg);
+    ///     let admin = RenderHandleState::new(admin_cfg, admin_app).with_ssr_state(ssr_state.clone());
+    ///     let public = RenderHandleState::new(public_cfg, public_app).with_ssr_state(ssr_state);
+    ///
+    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let router = axum::Router::new()
+    ///         .nest(
+    ///             "/admin",
+    ///             axum::Router::new().fallback(axum::routing::get(render_handler).with_state(admin)),
+    ///         )
+    ///         .fallback(axum::routing::get(render_handler).with_state(public))
+    ///         .into_make_service();
+    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    ///     axum::serve(listener, router).await.unwrap();
+    /// }
+    /// ```
+    pub fn with_ssr_state(mut self, ssr_state: SSRState) -> Self {
+        self.ssr_state = Arc::new(once_cell::sync::OnceCell::from(ssr_state));
+        self
+    }
+
+    /// Run a callback the first time the [`SSRState`] is initialized. The callback receives the new state and how long it
+    /// took to build, and runs exactly once for this state and all of its clones.
+    ///
+    /// The callback doesn't run if the state was set with [`RenderHandleState::with_ssr_state`].
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// fn app() -> Element {
+    ///     rsx! { "Hello World" }
+    /// }
+    ///
+    /// let state = RenderHandleState::new(ServeConfig::new().unwrap(), app).on_ssr_state_init(
+    ///     |_, duration| tracing::info!("SSR ready in {}ms", duration.as_millis()),
+    /// );
+    /// ```
+    pub fn on_ssr_state_init(
+        mut self,
+        on_init: impl Fn(&SSRState, std::time::Duration) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_ssr_state_init = Some(Arc::new(on_init));
+        self
+    }
+
+    /// Add extra html to the `<head>` of every server rendered page. The builder runs for each request before the page
+    /// renders, so the html can depend on the url or headers of the request, like a canonical link or Open Graph tags.
+    ///
+    /// The html is inserted after the head elements the components render. Any tag that would close the head or open the
+    /// body is escaped so the html stays inside of the head. If incremental rendering is enabled, the html of the first
+    /// request for a route is cached with the rest of the page, so it should only depend on the url.
+    ///
+    /// # Example
+    /// ```rust, no_run
+    /// # use dioxus_lib::prelude::*;
+    /// # use dioxus_fullstack::prelude::*;
+    /// fn app() -> Element {
+    ///     rsx! { "Hello World" }
+    /// }
+    ///
+    /// let state = RenderHandleState::new(ServeConfig::new().unwrap(), app).with_head_builder(|context| {
+    ///     let path = context.request_parts().uri.path().to_string();
+    ///     format!(
+    ///         r#"<link rel="canonical" href="https://example.com{path}"><meta property="og:url" content="https://example.com{path}">"#
+    ///     )
+    /// });
+    /// ```
+    pub fn with_head_builder(
+        mut self,
+        head_builder: impl Fn(&DioxusServerContext) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.head_builder = Some(Arc::new(head_builder));
+        self
+    }
+
+    /// Decide the [`FeatureFlags`] of every server rendered page from the request, for example from a cookie that
+    /// assigns the user to an experiment. The flags are provided to the [`VirtualDom`] before the first render and sent to
+    /// the client with the hydration data, so [`use_flags`] returns the same flags on the server and the client.
+    ///
+    /// Pages rendered with feature flags are never served from the incremental cache, and only requests with the same flags
+    /// share a coalesced render.
     ///
     /// # Example
     /// ```rust, no_run
     /// # use dioxus_lib::prelude::*;
     /// # use dioxus_fullstack::prelude::*;
-    /// # use std::sync::Arc;
-    /// #[tokio::main]
-    /// async fn main() {
-    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
-    ///     let router = axum::Router::new()
-    ///         // Register server functions routes with the default handler
-    ///         .register_server_functions_with_context(Arc::new(vec![Box::new(|| Box::new(1234567890u32))]))
-    ///         .into_make_service();
-    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    ///     axum::serve(listener, router).await.unwrap();
+    /// fn app() -> Element {
+    ///     let flags = use_flags();
+    ///     rsx! {
+    ///         if flags.is_enabled("new-header") {
+    ///             h1 { "Welcome back" }
+    ///         } else {
+    ///             h1 { "Welcome" }
+    ///         }
+    ///     }
     /// }
+    ///
+    /// let state = RenderHandleState::new(ServeConfig::new().unwrap(), app).with_feature_flags(|context| {
+    ///     let beta = context
+    ///         .request_parts()
+    ///         .headers
+    ///         .get(http::header::COOKIE)
+    ///         .and_then(|cookie| cookie.to_str().ok())
+    ///         .is_some_and(|cookie| cookie.contains("beta=1"));
+    ///     let flags = FeatureFlags::new();
+    ///     if beta {
+    ///         flags.enable("new-header")
+    ///     } else {
+    ///         flags
+    ///     }
+    /// });
     /// ```
-    fn register_server_functions_with_context(self, context_providers: ContextProviders) -> Self;
+    pub fn with_feature_flags(
+        mut self,
+        feature_flags: impl Fn(&DioxusServerContext) -> FeatureFlags + Send + Sync + 'static,
+    ) -> Self {
+        self.feature_flags = Some(Arc::new(feature_flags));
+        self
+    }
 
-    /// Serves the static WASM for your Dioxus application (except the generated index.html).
+    /// Set a component to render when the server fails to render the page. The component is rendered to static html without
+    /// hydration and returned with a `500 Internal Server Error` status.
+    ///
+    /// If the error component also fails to render, a plain text error is returned instead.
     ///
     /// # Example
     /// ```rust, no_run
-    /// # #![allow(non_snake_case)]
     /// # use dioxus_lib::prelude::*;
     /// # use dioxus_fullstack::prelude::*;
-    /// #[tokio::main]
-    /// async fn main() {
-    ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
-    ///     let router = axum::Router::new()
-    ///         // Server side render the application, serve static assets, and register server functions
-    ///         .serve_static_assets()
-    ///         // Server render the application
-    ///         // ...
-    ///         .into_make_service();
-    ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    ///     axum::serve(listener, router).await.unwrap();
+    /// fn app() -> Element {
+    ///     rsx! { "Hello World" }
+    /// }
+    ///
+    /// fn error_page() -> Element {
+    ///     rsx! {
+    ///         h1 { "Something went wrong" }
+    ///         p { "Please try again later." }
+    ///     }
     /// }
+    ///
+    /// let state = RenderHandleState::new(ServeConfig::new().unwrap(), app)
+    ///     .with_error_component(error_page);
     /// ```
-    fn serve_static_assets(self) -> Self
-    where
-        Self: Sized;
+    pub fn with_error_component(mut self, error_component: fn() -> Element) -> Self {
+        self.error_component = Some(error_component);
+        self
+    }
 
-    /// Serves the Dioxus application. This will serve a complete server side rendered application.
-    /// This will serve static assets, server render the application, register server functions, and integrate with hot reloading.
+    /// Initialize the [`SSRState`] and incremental cache now instead of during the first request.
+    ///
+    /// Clones of the [`RenderHandleState`] share the initialized state, so this can be called before or after passing the
+    /// state to the router.
+    /// Use [`RenderHandleState::prewarm_route`] to also fill the renderer's template cache.
     ///
     /// # Example
     /// ```rust, no_run
-    /// # #![allow(non_snake_case)]
     /// # use dioxus_lib::prelude::*;
     /// # use dioxus_fullstack::prelude::*;
     /// #[tokio::main]
     /// async fn main() {
     ///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+    ///     let state = RenderHandleState::new(ServeConfig::new().unwrap(), app);
+    ///     state.prewarm();
     ///     let router = axum::Router::new()
-    ///         // Server side render the application, serve static assets, and register server functions
-    ///         .serve_dioxus_application(ServeConfig::new().unwrap(), app)
+    ///         .fallback(axum::routing::get(render_handler).with_state(state))
     ///         .into_make_service();
     ///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
     ///     axum::serve(listener, router).await.unwrap();
@@ -165,180 +2238,105 @@ pub trait DioxusRouterExt<S> {
     ///     rsx! { "Hello World" }
     /// }
     /// ```
-    fn serve_dioxus_application<Cfg, Error>(self, cfg: Cfg, app: fn() -> Element) -> Self
-    where
-        Cfg: TryInto<ServeConfig, Error = Error>,
-        Error: std::error::Error,
-        Self: Sized;
-}
-
-impl<S> DioxusRouterExt<S> for Router<S>
-where
-    S: Send + Sync + Clone + 'static,
-{
-    fn register_server_functions_with_context(
-        mut self,
-        context_providers: ContextProviders,
-    ) -> Self {
-        use http::method::Method;
-
-        for (path, method) in server_fn::axum::server_fn_paths() {
-            tracing::trace!("Registering server function: {} {}", method, path);
-            let context_providers = context_providers.clone();
-            let handler = move |req| {
-                handle_server_fns_inner(
-                    path,
-                    move |server_context| {
-                        for index in 0..context_providers.len() {
-                            let context_providers = context_providers.clone();
-                            server_context
-                                .insert_boxed_factory(Box::new(move || context_providers[index]()));
-                        }
-                    },
-                    req,
-                )
-            };
-            self = match method {
-                Method::GET => self.route(path, get(handler)),
-                Method::POST => self.route(path, post(handler)),
-                Method::PUT => self.route(path, put(handler)),
-                _ => unimplemented!("Unsupported server function method: {}", method),
-            };
-        }
-
-        self
+    pub fn prewarm(&self) {
+        self.ssr_state();
+        self.config.incremental_cache();
     }
 
-    fn serve_static_assets(mut self) -> Self {
-        use tower_http::services::{ServeDir, ServeFile};
+    /// Initialize the state like [`RenderHandleState::prewarm`] and render a route once, throwing away the result. The
+    /// render fills the template cache of the renderer, so the first real request doesn't pay for parsing the templates.
+    ///
+    /// The route is rendered like a real request, so any server futures it runs are executed. The render is never stored in
+    /// the incremental cache.
+    pub async fn prewarm_route(
+        &self,
+        route: &str,
+    ) -> Result<(), dioxus_isrg::IncrementalRendererError> {
+        use futures_util::StreamExt;
 
-        let public_path = crate::public_path();
+        self.prewarm();
 
-        if !public_path.exists() {
-            return self;
-        }
+        let mut parts = Request::new(()).into_parts().0;
+        parts.uri = route
+            .parse()
+            .map_err(|err| dioxus_isrg::IncrementalRendererError::Other(Box::new(err)))?;
+        let server_context = DioxusServerContext::new(parts);
 
-        // Serve all files in public folder except index.html
-        let dir = std::fs::read_dir(&public_path).unwrap_or_else(|e| {
-            panic!(
-                "Couldn't read public directory at {:?}: {}",
-                &public_path, e
+        let build_virtual_dom = self.build_virtual_dom.clone();
+        let url = route.to_string();
+        let (_, stream) = self
+            .ssr_state()
+            .render_with_cache(
+                route.to_string(),
+                &self.config,
+                move || build_virtual_dom.build(&url),
+                &server_context,
+                false,
             )
-        });
-
-        for entry in dir.flatten() {
-            let path = entry.path();
-            if path.ends_with("index.html") {
-                continue;
-            }
-            let route = path
-                .strip_prefix(&public_path)
-                .unwrap()
-                .iter()
-                .map(|segment| {
-                    segment.to_str().unwrap_or_else(|| {
-                        panic!("Failed to convert path segment {:?} to string", segment)
-                    })
-                })
-                .collect::<Vec<_>>()
-                .join("/");
-            let route = format!("/{}", route);
-            if path.is_dir() {
-                self = self.nest_service(&route, ServeDir::new(path).precompressed_br());
-            } else {
-                self = self.nest_service(&route, ServeFile::new(path).precompressed_br());
-            }
+            .await?;
+        let mut stream = std::pin::pin!(stream);
+        while let Some(chunk) = stream.next().await {
+            chunk?;
         }
-
-        self
+        Ok(())
     }
 
-    fn serve_dioxus_application<Cfg, Error>(self, cfg: Cfg, app: fn() -> Element) -> Self
-    where
-        Cfg: TryInto<ServeConfig, Error = Error>,
-        Error: std::error::Error,
-    {
-        // Add server functions and render index.html
-        let server = self.serve_static_assets().register_server_functions();
-
-        match cfg.try_into() {
-            Ok(cfg) => {
-                let ssr_state = SSRState::new(&cfg);
-                server.fallback(
-                    get(render_handler)
-                        .with_state(RenderHandleState::new(cfg, app).with_ssr_state(ssr_state)),
-                )
-            }
-            Err(err) => {
-                tracing::trace!("Failed to create render handler. This is expected if you are only using fullstack for desktop/mobile server functions: {}", err);
-                server
+    fn ssr_state(&self) -> &SSRState {
+        self.ssr_state.get_or_init(|| {
+            let start = std::time::Instant::now();
+            let ssr_state = SSRState::new(&self.config);
+            if let Some(on_init) = &self.on_ssr_state_init {
+                on_init(&ssr_state, start.elapsed());
             }
-        }
-    }
-}
-
-fn apply_request_parts_to_response<B>(
-    headers: hyper::header::HeaderMap,
-    response: &mut axum::response::Response<B>,
-) {
-    let mut_headers = response.headers_mut();
-    for (key, value) in headers.iter() {
-        mut_headers.insert(key, value.clone());
+            ssr_state
+        })
     }
-}
 
-/// State used by [`render_handler`] to render a dioxus component with axum
-#[derive(Clone)]
-pub struct RenderHandleState {
-    config: ServeConfig,
-    build_virtual_dom: Arc<dyn Fn() -> VirtualDom + Send + Sync>,
-    ssr_state: once_cell::sync::OnceCell<SSRState>,
-}
+    /// Create the response for a page that failed to render
+    fn render_error(&self, e: dioxus_isrg::IncrementalRendererError) -> Response<Body> {
+        tracing::error!("Failed to render page: {}", e);
 
-impl RenderHandleState {
-    /// Create a new [`RenderHandleState`]
-    pub fn new(config: ServeConfig, root: fn() -> Element) -> Self {
-        Self {
-            config,
-            build_virtual_dom: Arc::new(move || VirtualDom::new(root)),
-            ssr_state: Default::default(),
+        if let Some(html) = self.error_component.and_then(render_error_component) {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                axum::response::Html(html),
+            )
+                .into_response();
         }
-    }
 
-    /// Create a new [`RenderHandleState`] with a custom [`VirtualDom`] factory. This method can be used to pass context into the root component of your application.
-    pub fn new_with_virtual_dom_factory(
-        config: ServeConfig,
-        build_virtual_dom: impl Fn() -> VirtualDom + Send + Sync + 'static,
-    ) -> Self {
-        Self {
-            config,
-            build_virtual_dom: Arc::new(build_virtual_dom),
-            ssr_state: Default::default(),
-        }
+        report_err(e)
     }
+}
 
-    /// Set the [`ServeConfig`] for this [`RenderHandleState`]
-    pub fn with_config(mut self, config: ServeConfig) -> Self {
-        self.config = config;
-        self
-    }
+/// Render the error component to static html. Returns `None` if the error component panics or throws an error while
+/// rendering
+fn render_error_component(error_component: fn() -> Element) -> Option<String> {
+    let body = std::panic::catch_unwind(move || {
+        let mut virtual_dom = VirtualDom::new(error_component);
+        virtual_dom.rebuild_in_place();
+        // Components run inside of a catch_unwind in dioxus core, so their panics and errors are thrown to the root
+        // error boundary instead of unwinding the render
+        let failed = virtual_dom
+            .in_runtime(|| ScopeId::APP.consume_context::<ErrorContext>())
+            .is_some_and(|errors| !errors.errors().is_empty());
+        (!failed).then(|| dioxus_ssr::render(&virtual_dom))
+    });
 
-    /// Set the [`SSRState`] for this [`RenderHandleState`]. Sharing a [`SSRState`] between multiple [`RenderHandleState`]s is more efficient than creating a new [`SSRState`] for each [`RenderHandleState`].
-    pub fn with_ssr_state(mut self, ssr_state: SSRState) -> Self {
-        self.ssr_state = once_cell::sync::OnceCell::new();
-        if self.ssr_state.set(ssr_state).is_err() {
-            panic!("SSRState already set");
+    match body {
+        Ok(Some(body)) => Some(format!("<!DOCTYPE html><html><body>{body}</body></html>")),
+        _ => {
+            tracing::error!("The error component failed to render the error page");
+            None
         }
-        self
-    }
-
-    fn ssr_state(&self) -> &SSRState {
-        self.ssr_state.get_or_init(|| SSRState::new(&self.config))
     }
 }
 
 /// SSR renderer handler for Axum with added context injection.
 ///
+/// `HEAD` requests get the status and headers of the page without a body. Pages that are sent in one piece include their
+/// `Content-Length`. Streamed pages stop rendering after the initial frame, so monitoring tools that only check the
+/// status don't wait for every suspense boundary to resolve.
+///
 /// # Example
 /// ```rust,no_run
 /// #![allow(non_snake_case)]
@@ -373,45 +2371,328 @@ pub async fn render_handler(
     State(state): State<RenderHandleState>,
     request: Request<Body>,
 ) -> impl IntoResponse {
-    // Only respond to requests for HTML
-    if let Some(mime) = request.headers().get("Accept") {
-        let mime = mime.to_str().map(|mime| mime.to_ascii_lowercase());
-        match mime {
-            Ok(accepts) if accepts.contains("text/html") => {}
-            _ => return Err(StatusCode::NOT_ACCEPTABLE),
+    let head = request.method() == http::Method::HEAD;
+    let mut response = render_page(state, request).await.into_response();
+    if head {
+        // Send the length the body of a GET request would have if it is known, but never the body itself
+        if let Some(length) = axum::body::HttpBody::size_hint(response.body()).exact() {
+            response
+                .headers_mut()
+                .entry(CONTENT_LENGTH)
+                .or_insert_with(|| HeaderValue::from(length));
         }
+        *response.body_mut() = Body::empty();
     }
+    access_log::mark_route_kind(RouteKind::Ssr)(response)
+}
 
+/// Render the page for a request
+async fn render_page(
+    state: RenderHandleState,
+    request: Request<Body>,
+) -> Result<Response<Body>, StatusCode> {
     let cfg = &state.config;
+
+    // Only respond to requests for HTML
+    if !accepts_html(cfg, request.headers()) {
+        return Err(StatusCode::NOT_ACCEPTABLE);
+    }
     let ssr_state = state.ssr_state();
     let build_virtual_dom = state.build_virtual_dom.clone();
 
     let (parts, _) = request.into_parts();
+    let csp_nonce = cfg.generate_csp_nonce();
+    // HEAD requests get the headers of the page without the body
+    let head = parts.method == http::Method::HEAD;
+
+    // Skip rendering the component tree entirely if this request only needs the html shell
+    if cfg.render_mode(&parts) == RenderMode::ShellOnly {
+        let mut template = FullstackHTMLTemplate::new(cfg);
+        if let Some(nonce) = &csp_nonce {
+            template = template.with_csp_nonce(nonce.as_str());
+        }
+        match cfg.request_index(&DioxusServerContext::new(parts.clone())) {
+            Some(Ok(index)) => template = template.with_index(Arc::new(index)),
+            Some(Err(err)) => return Ok(state.render_error(index_error(err))),
+            None => {}
+        }
+        let mut shell = String::new();
+        return match template.render_shell(&mut shell) {
+            Ok(()) => {
+                let mut response = axum::response::Html(shell).into_response();
+                let links = cfg.preload_links(parts.uri.path());
+                set_link_header(response.headers_mut(), links);
+                if let Some(policy) = csp_nonce.and_then(|nonce| {
+                    HeaderValue::from_str(&cfg.content_security_policy(&nonce)).ok()
+                }) {
+                    response
+                        .headers_mut()
+                        .insert(http::header::CONTENT_SECURITY_POLICY, policy);
+                }
+                Ok(response)
+            }
+            Err(e) => Ok(report_err(e).into_response()),
+        };
+    }
+
     let url = parts
         .uri
         .path_and_query()
         .ok_or(StatusCode::BAD_REQUEST)?
         .to_string();
+
+    // Wait for a free render slot before doing any rendering work
+    let render_permit = match &cfg.render_limit {
+        Some(limit) => match limit.acquire().await {
+            Some(permit) => Some(permit),
+            None => return Ok(too_many_renders(&url, limit.retry_after())),
+        },
+        None => None,
+    };
+
     let parts: Arc<parking_lot::RwLock<http::request::Parts>> =
         Arc::new(parking_lot::RwLock::new(parts));
     let server_context = DioxusServerContext::from_shared_parts(parts.clone());
+    server_context.set_origin(cfg.request_origin(&parts.read()));
+    server_context.set_trust_forwarded_for(cfg.trust_forwarded_headers);
+    let locale = cfg.locale(&parts.read());
+    if let Some(locale) = locale {
+        // The page is rendered for the negotiated locale
+        server_context.add_vary("accept-language");
+        server_context.set_locale(locale.as_str());
+        server_context.insert(locale);
+    }
+    if let Some(nonce) = &csp_nonce {
+        server_context.set_csp_nonce(nonce, &cfg.content_security_policy(nonce));
+    }
+    if let Some(head_builder) = &state.head_builder {
+        let head = crate::render::escape_head(&head_builder(&server_context));
+        server_context.insert(crate::render::RequestHead(Arc::from(head)));
+    }
+    if let Some(feature_flags) = &state.feature_flags {
+        server_context.insert(feature_flags(&server_context));
+    }
+    match cfg.request_index(&server_context) {
+        Some(Ok(index)) => server_context.insert(crate::render::RequestIndex(Arc::new(index))),
+        Some(Err(err)) => return Ok(state.render_error(index_error(err))),
+        None => {}
+    }
 
+    let preload_links = cfg.preload_links(&url);
+    let route = url.clone();
+    let render_start = std::time::Instant::now();
     match ssr_state
-        .render(url, cfg, move || build_virtual_dom(), &server_context)
+        .render_with_async_factory(
+            url,
+            cfg,
+            move || build_virtual_dom.build(&route),
+            &server_context,
+        )
         .await
     {
         Ok((freshness, rx)) => {
-            let mut response = axum::response::Html::from(Body::from_stream(rx)).into_response();
+            use futures_util::StreamExt;
+
+            // Wait for the initial frame of the page before we start responding. If the initial render fails, we can still
+            // respond with an error page instead of cutting off the stream
+            let mut rx = Box::pin(rx);
+            let initial_frame = match rx.next().await {
+                Some(Ok(initial_frame)) => initial_frame,
+                Some(Err(e)) => return Ok(state.render_error(e)),
+                None => String::new(),
+            };
+            // Streamed suspense chunks are rendered after the response starts, so only the initial frame is timed
+            let render_duration = render_start.elapsed();
+            server_context.set_render_duration(render_duration);
+
+            // Buffered and static pages arrive in one piece, so they are sent with a known length instead of being streamed
+            let complete = cfg.stream_flush_strategy == StreamFlushStrategy::Never
+                || server_context.is_static()
+                || cfg.is_static_route(parts.read().uri.path());
+            let mut response = if complete {
+                let content_length = HeaderValue::from(initial_frame.len());
+                let mut response = axum::response::Html(initial_frame).into_response();
+                response
+                    .headers_mut()
+                    .insert(CONTENT_LENGTH, content_length);
+                response
+            } else if head {
+                // The length of a streamed page is only known once every suspense boundary resolved, so drop the rest of
+                // the render and release the render slot instead of rendering chunks nobody reads
+                drop(rx);
+                drop(render_permit);
+                axum::response::Html(Body::empty()).into_response()
+            } else {
+                let body = futures_util::stream::once(async move { Ok(initial_frame) })
+                    .chain(rx)
+                    // Hold the render slot until the whole page has been sent
+                    .map(move |chunk| {
+                        let _render_permit = &render_permit;
+                        chunk
+                    });
+                axum::response::Html::from(Body::from_stream(body)).into_response()
+            };
             freshness.write(response.headers_mut());
+            if let Some(locale) = server_context
+                .locale()
+                .and_then(|locale| HeaderValue::from_str(&locale).ok())
+            {
+                response.headers_mut().insert(CONTENT_LANGUAGE, locale);
+            }
             let headers = server_context.response_parts().headers.clone();
+            let context_links = headers
+                .get_all(LINK)
+                .iter()
+                .filter_map(|link| link.to_str().ok())
+                .map(str::to_string)
+                .collect::<Vec<_>>();
             apply_request_parts_to_response(headers, &mut response);
+            set_link_header(
+                response.headers_mut(),
+                preload_links.into_iter().chain(context_links),
+            );
+            server_context.apply_vary(response.headers_mut());
+            apply_response_metadata(&mut server_context.response_parts_mut(), &mut response);
+            if let Some((location, status)) = server_context.redirect_location() {
+                *response.status_mut() = status;
+                response.headers_mut().insert(LOCATION, location);
+            }
+            response
+                .headers_mut()
+                .append(SERVER_TIMING, server_timing(render_duration));
             Ok(response)
         }
-        Err(e) => {
-            tracing::error!("Failed to render page: {}", e);
-            Ok(report_err(e).into_response())
+        Err(e) => Ok(state.render_error(e)),
+    }
+}
+
+/// Check if a request accepts the HTML the application renders. Requests without an `Accept` header accept anything
+fn accepts_html(cfg: &ServeConfig, headers: &HeaderMap) -> bool {
+    match headers.get(ACCEPT) {
+        Some(accept) => accept
+            .to_str()
+            .is_ok_and(|accept| cfg.accept_matching.accepts_html(accept)),
+        None => true,
+    }
+}
+
+/// Wrap an invalid index.html template built for a request in a render error
+fn index_error(err: crate::UnableToLoadIndex) -> dioxus_isrg::IncrementalRendererError {
+    dioxus_isrg::IncrementalRendererError::Other(Box::new(err))
+}
+
+/// Replace the `Link` header of a response with the links, sending every link only once. Each link may contain several
+/// comma separated entries
+fn set_link_header(headers: &mut HeaderMap, links: impl IntoIterator<Item = String>) {
+    let mut entries: Vec<String> = Vec::new();
+    for link in links {
+        let mut link_entries: Vec<String> = Vec::new();
+        for part in link.split(',') {
+            match link_entries.last_mut() {
+                // Commas inside of the url or a quoted parameter don't start a new entry
+                Some(entry) if !part.trim_start().starts_with('<') => {
+                    entry.push(',');
+                    entry.push_str(part);
+                }
+                _ => link_entries.push(part.trim().to_string()),
+            }
         }
+        entries.extend(link_entries);
     }
+
+    // Links are the same if they only differ in whitespace, quotes or the case of the parameters
+    let link_key = |entry: &str| {
+        let (url, params) = entry.split_once('>').unwrap_or((entry, ""));
+        let params = params
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '"')
+            .collect::<String>()
+            .to_ascii_lowercase();
+        format!("{url}>{params}")
+    };
+    let mut seen = std::collections::HashSet::new();
+    entries.retain(|entry| !entry.is_empty() && seen.insert(link_key(entry)));
+
+    headers.remove(LINK);
+    if entries.is_empty() {
+        return;
+    }
+    if let Ok(links) = HeaderValue::from_str(&entries.join(", ")) {
+        headers.insert(LINK, links);
+    }
+}
+
+/// The `Server-Timing` header. It isn't part of [`http::header`] yet
+const SERVER_TIMING: HeaderName = HeaderName::from_static("server-timing");
+
+/// Build a `Server-Timing` entry for the server render of a page in milliseconds
+fn server_timing(render_duration: std::time::Duration) -> HeaderValue {
+    let millis = render_duration.as_secs_f64() * 1000.0;
+    HeaderValue::from_str(&format!("ssr;dur={millis:.1}"))
+        .expect("the server timing is always a valid header")
+}
+
+/// Count the bytes read from a request body and fail the body once it grows past the limit
+fn limit_body(body: Body, limit: usize, exceeded: Arc<std::sync::atomic::AtomicBool>) -> Body {
+    use futures_util::StreamExt;
+
+    let mut read = 0;
+    Body::from_stream(body.into_data_stream().map(move |chunk| {
+        let chunk = chunk?;
+        read += chunk.len();
+        if read > limit {
+            exceeded.store(true, std::sync::atomic::Ordering::Relaxed);
+            return Err(axum::Error::new(format!(
+                "the request body is larger than the limit of {limit} bytes"
+            )));
+        }
+        Ok(chunk)
+    }))
+}
+
+/// The response for a server function request that arrives while the server is shutting down
+fn shutting_down(path: &str) -> Response<Body> {
+    tracing::debug!(
+        "Rejected a request to server function {path} because the server is shutting down"
+    );
+    Response::builder()
+        .status(StatusCode::SERVICE_UNAVAILABLE)
+        .header(CONNECTION, "close")
+        .body(Body::from("The server is shutting down"))
+        .expect("could not build Response")
+}
+
+/// The response for a page request that arrives while the config is rendering as many pages as it allows
+fn too_many_renders(url: &str, retry_after: u64) -> Response<Body> {
+    tracing::warn!("Rejected a request for {url} because too many pages are rendering");
+    Response::builder()
+        .status(StatusCode::SERVICE_UNAVAILABLE)
+        .header(RETRY_AFTER, retry_after)
+        .body(Body::from("The server is rendering too many pages"))
+        .expect("could not build Response")
+}
+
+/// The response for a server function request that a guard rejected
+fn rejected_by_guard(path: &str, status: StatusCode) -> Response<Body> {
+    tracing::debug!("A guard rejected a request to server function {path} with status {status}");
+    Response::builder()
+        .status(status)
+        .body(Body::from(
+            status.canonical_reason().unwrap_or("Forbidden").to_string(),
+        ))
+        .expect("could not build Response")
+}
+
+fn payload_too_large(path: &str, limit: usize) -> Response<Body> {
+    tracing::warn!(
+        "Rejected a request to server function {path} with a body larger than {limit} bytes"
+    );
+    Response::builder()
+        .status(StatusCode::PAYLOAD_TOO_LARGE)
+        .body(Body::from(format!(
+            "The request body for server function {path} is larger than the limit of {limit} bytes"
+        )))
+        .expect("could not build Response")
 }
 
 fn report_err<E: std::fmt::Display>(e: E) -> Response<axum::body::Body> {
@@ -422,8 +2703,9 @@ fn report_err<E: std::fmt::Display>(e: E) -> Response<axum::body::Body> {
 }
 
 /// A handler for Dioxus server functions. This will run the server function and return the result.
-async fn handle_server_fns_inner(
+pub(crate) async fn handle_server_fns_inner(
     path: &str,
+    config: Arc<ServerFnConfig>,
     additional_context: impl Fn(&DioxusServerContext) + 'static + Clone + Send,
     req: Request<Body>,
 ) -> impl IntoResponse {
@@ -432,30 +2714,143 @@ async fn handle_server_fns_inner(
     let path_string = path.to_string();
 
     let future = move || async move {
+        // Keep the call tracked until the server function responds so shutdown waits for it
+        let _active_call = match &config.shutdown {
+            Some(shutdown) => match shutdown.track() {
+                Some(active_call) => Some(active_call),
+                None => return shutting_down(&path_string),
+            },
+            None => None,
+        };
         let (parts, body) = req.into_parts();
-        let req = Request::from_parts(parts.clone(), body);
+
+        // Reject bodies that are too large up front if the client told us how large the body is. Otherwise count the
+        // bytes as the server function reads them
+        let body_limit = config.body_limit_for_path(&path_string);
+        let content_length = parts
+            .headers
+            .get(CONTENT_LENGTH)
+            .and_then(|len| len.to_str().ok())
+            .and_then(|len| len.parse::<usize>().ok());
+        if content_length.is_some_and(|len| len > body_limit) {
+            return payload_too_large(&path_string, body_limit);
+        }
+        let body_limit_exceeded = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let body = limit_body(body, body_limit, body_limit_exceeded.clone());
+
+        // If the server function needs access to the raw request, buffer the body so it can be read
+        // from the server context after the server function consumes the body. The download cache
+        // also needs the body to find the cached response for the arguments
+        let keeps_raw_body = config.keeps_raw_body(&path_string);
+        let (body, raw_body) = if keeps_raw_body || config.download_cache.is_some() {
+            match axum::body::to_bytes(body, usize::MAX).await {
+                Ok(bytes) => (Body::from(bytes.clone()), Some(bytes)),
+                Err(_) if body_limit_exceeded.load(std::sync::atomic::Ordering::Relaxed) => {
+                    return payload_too_large(&path_string, body_limit);
+                }
+                Err(err) => {
+                    return Response::builder()
+                        .status(StatusCode::BAD_REQUEST)
+                        .body(Body::from(format!("Failed to read request body: {err}")))
+                        .expect("could not build Response");
+                }
+            }
+        } else {
+            (body, None)
+        };
+        let download_key = config
+            .download_cache
+            .as_ref()
+            .map(|cache| cache.key(&parts, raw_body.as_deref().unwrap_or_default()));
 
         if let Some(mut service) =
             server_fn::axum::get_server_fn_service(&path_string)
         {
-            let server_context = DioxusServerContext::new(parts);
-            additional_context(&server_context);
+            // The download cache needs the range headers after the parts move into the request
+            let range_headers =
+                download_key.map(|_| download_cache::RangeHeaders::of(&parts.headers));
 
             // store Accepts and Referrer in case we need them for redirect (below)
-            let accepts_html = req
-                .headers()
+            let accepts_html = parts
+                .headers
                 .get(ACCEPT)
                 .and_then(|v| v.to_str().ok())
                 .map(|v| v.contains("text/html"))
                 .unwrap_or(false);
-            let referrer = req.headers().get(REFERER).cloned();
+            let referrer = parts.headers.get(REFERER).cloned();
+
+            // The server function consumes its request, but the server context has to keep the parts until the
+            // response is sent. That is the only copy of the parts we make
+            let server_context = DioxusServerContext::new(parts.clone());
+            let req = Request::from_parts(parts, body);
+            if let Some(raw_body) = raw_body.filter(|_| keeps_raw_body) {
+                server_context.set_raw_body(raw_body);
+            }
+            server_context.set_trust_forwarded_for(config.trust_forwarded_headers);
+            additional_context(&server_context);
+
+            // Check the guards declared with `#[server(guard = "...")]` before the server function runs
+            if let Err(status) = crate::guard::check_guards(&path_string, &server_context) {
+                return rejected_by_guard(&path_string, status);
+            }
+
+            // Answer the request from the download cache without running the server function if we can. The guards
+            // run first, so a cached response never reaches a caller they reject
+            if let (Some(cache), Some(key), Some(range_headers)) =
+                (&config.download_cache, download_key, &range_headers)
+            {
+                if let Some(mut res) = cache.respond(key, range_headers) {
+                    config.apply_deprecation(&path_string, res.headers_mut());
+                    return res;
+                }
+            }
 
             // actually run the server fn (which may use the server context)
-            let mut res = ProvideServerContext::new(service.run(req), server_context.clone()).await;
+            let run = ProvideServerContext::new(service.run(req), server_context.clone());
+            let mut res = match config.timeout {
+                // If the server function takes too long, drop it and respond with a 504. The server context is only set
+                // while the future is polled, so dropping it here doesn't leak the context into other requests
+                Some(timeout) => match tokio::time::timeout(timeout, run).await {
+                    Ok(res) => res,
+                    Err(_) => {
+                        tracing::warn!("Server function {path_string} timed out after {timeout:?}");
+                        return Response::builder()
+                            .status(StatusCode::GATEWAY_TIMEOUT)
+                            .body(Body::from(format!(
+                                "Server function {path_string} timed out after {timeout:?}"
+                            )))
+                            .expect("could not build Response");
+                    }
+                },
+                None => run.await,
+            };
+
+            // If the body grew past the limit while the server function was reading it, the server function failed to
+            // decode the body. Replace whatever error it returned with a 413
+            if body_limit_exceeded.load(std::sync::atomic::Ordering::Relaxed) {
+                return payload_too_large(&path_string, body_limit);
+            }
+
+            // Send errors with a status that matches what went wrong instead of a generic 500
+            if config.map_error_status && res.status() == StatusCode::INTERNAL_SERVER_ERROR {
+                res = error_status::map_error_status(res, &config.custom_error_statuses).await;
+            }
+
+            if let (Some(cache), Some(key), Some(range_headers)) =
+                (&config.download_cache, download_key, &range_headers)
+            {
+                res = cache.store(key, range_headers, res).await;
+            }
 
             // it it accepts text/html (i.e., is a plain form post) and doesn't already have a
-            // Location set, then redirect to Referer
-            if accepts_html {
+            // Location set, then redirect to Referer. Negotiated server functions that answered with HTML
+            // answer the form themselves
+            let responds_with_html = res
+                .extensions()
+                .get::<crate::negotiate::NegotiatedHtml>()
+                .is_some();
+            let redirect = server_context.redirect_location();
+            if accepts_html && !config.skip_referer_redirect && !responds_with_html && redirect.is_none() {
                 if let Some(referrer) = referrer {
                     let has_location = res.headers().get(LOCATION).is_some();
                     if !has_location {
@@ -468,6 +2863,23 @@ async fn handle_server_fns_inner(
             // apply the response parts from the server context to the response
             let mut res_options = server_context.response_parts_mut();
             res.headers_mut().extend(res_options.headers.drain());
+            server_context.apply_vary(res.headers_mut());
+            apply_response_metadata(&mut res_options, &mut res);
+            config.apply_deprecation(&path_string, res.headers_mut());
+
+            // Explicit redirects win over the Referer redirect. Client side calls keep their response so the client can
+            // decide how to navigate
+            if let Some((location, status)) = redirect {
+                if accepts_html {
+                    *res.status_mut() = status;
+                } else {
+                    res.headers_mut().insert(
+                        HeaderName::from_static(server_fn::redirect::REDIRECT_HEADER),
+                        HeaderValue::from_static(""),
+                    );
+                }
+                res.headers_mut().insert(LOCATION, location);
+            }
 
             Ok(res)
         } else {
@@ -476,13 +2888,15 @@ async fn handle_server_fns_inner(
                     #[cfg(target_family = "wasm")]
                     {
                         Body::from(format!(
-                            "No server function found for path: {path_string}\nYou may need to explicitly register the server function with `register_explicit`, rebuild your wasm binary to update a server function link or make sure the prefix your server and client use for server functions match.",
+                            "No server function found for path: {path_string}\nYou may need to explicitly register the server function with `register_explicit`, rebuild your wasm binary to update a server function link or make sure the prefix your server and client use for server functions match.{}",
+                            config.prefix_hint()
                         ))
                     }
                     #[cfg(not(target_family = "wasm"))]
                     {
                         Body::from(format!(
-                            "No server function found for path: {path_string}\nYou may need to rebuild your wasm binary to update a server function link or make sure the prefix your server and client use for server functions match.",
+                            "No server function found for path: {path_string}\nYou may need to rebuild your wasm binary to update a server function link or make sure the prefix your server and client use for server functions match.{}",
+                            config.prefix_hint()
                         ))
                     }
                 }
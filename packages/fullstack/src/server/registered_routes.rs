@@ -0,0 +1,114 @@
+//! Track the routes a router already has, so registering a server function twice doesn't make axum panic
+
+use axum::Router;
+use http::Method;
+use std::collections::{HashMap, HashSet};
+
+/// The methods server functions can be registered with
+const SERVER_FN_METHODS: [Method; 5] = [
+    Method::GET,
+    Method::POST,
+    Method::PUT,
+    Method::PATCH,
+    Method::DELETE,
+];
+
+/// The `(method, route)` pairs that are already registered on a router
+#[derive(Default)]
+pub(crate) struct RegisteredRoutes(HashSet<(Method, String)>);
+
+impl RegisteredRoutes {
+    /// Collect the routes that are already registered on the router.
+    ///
+    /// Axum doesn't expose the routes of a router, but it lists the path and method handlers of every route in the
+    /// `Debug` output of the router. If the format ever changes, no routes are found and registering a duplicate
+    /// route panics in axum like it did before.
+    pub(crate) fn of<S>(router: &Router<S>) -> Self {
+        let debug = format!("{router:?}");
+        let Some(path_router) = debug
+            .strip_prefix("Router { path_router: PathRouter { routes: {")
+            .and_then(|rest| rest.split_once(", fallback_router: "))
+            .map(|(path_router, _)| path_router)
+        else {
+            return Self::default();
+        };
+        let Some((routes, paths)) = path_router.split_once("node: Node { paths: {") else {
+            return Self::default();
+        };
+
+        let paths = parse_paths(paths);
+        let mut registered = HashSet::new();
+        for (id, endpoint) in split_route_ids(routes) {
+            let Some(path) = paths.get(id) else {
+                continue;
+            };
+            match endpoint.strip_prefix("MethodRouter(MethodRouter { ") {
+                // Only the methods that have a handler are taken
+                Some(fields) => {
+                    for method in SERVER_FN_METHODS {
+                        let field = format!("{}: ", method.as_str().to_ascii_lowercase());
+                        let handled = fields.split(", ").any(|entry| {
+                            entry
+                                .strip_prefix(&field)
+                                .is_some_and(|handler| handler != "None")
+                        });
+                        if handled {
+                            registered.insert((method, path.clone()));
+                        }
+                    }
+                }
+                // A service route handles every method
+                None => registered.extend(
+                    SERVER_FN_METHODS
+                        .into_iter()
+                        .map(|method| (method, path.clone())),
+                ),
+            }
+        }
+        Self(registered)
+    }
+
+    /// Mark the route as registered. Returns `false` if it was already registered.
+    pub(crate) fn insert(&mut self, method: Method, route: &str) -> bool {
+        self.0.insert((method, route.to_string()))
+    }
+}
+
+/// Split `RouteId(1): <value>, RouteId(2): <value>` into the id and the value of each entry
+fn split_route_ids(map: &str) -> impl Iterator<Item = (&str, &str)> {
+    map.split("RouteId(").skip(1).filter_map(|entry| {
+        let (id, value) = entry.split_once("): ")?;
+        Some((id, value.trim_end_matches([',', ' ', '}'])))
+    })
+}
+
+/// Parse the `RouteId(1): "/path"` entries of the paths of a router node
+fn parse_paths(paths: &str) -> HashMap<&str, String> {
+    let mut parsed = HashMap::new();
+    let mut rest = paths;
+    while let Some((id, after)) = rest
+        .strip_prefix("RouteId(")
+        .and_then(|entry| entry.split_once("): \""))
+    {
+        // Read the debug escaped path up to the closing quote
+        let mut path = String::new();
+        let mut chars = after.char_indices();
+        let mut end = None;
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '\\' => path.extend(chars.next().map(|(_, escaped)| escaped)),
+                '"' => {
+                    end = Some(index);
+                    break;
+                }
+                c => path.push(c),
+            }
+        }
+        let Some(end) = end else {
+            break;
+        };
+        parsed.insert(id, path);
+        rest = after[end + 1..].trim_start_matches(", ");
+    }
+    parsed
+}
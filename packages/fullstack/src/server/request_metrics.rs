@@ -0,0 +1,49 @@
+//! Request counts and latency histograms for every route the router extension adds, recorded with the [`metrics`] crate
+
+use super::RouteKind;
+use axum::{
+    body::Body,
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use std::time::Instant;
+
+/// The name of the counter with the number of handled requests
+const REQUESTS_TOTAL: &str = "dioxus_fullstack_requests_total";
+
+/// The name of the histogram with the latency of handled requests in seconds
+const REQUEST_DURATION_SECONDS: &str = "dioxus_fullstack_request_duration_seconds";
+
+/// The middleware that records metrics for every request
+pub(crate) async fn middleware(request: Request<Body>, next: Next) -> Response {
+    let start = Instant::now();
+    let method = request.method().to_string();
+    // Label the request with the route pattern instead of the path so the number of labels stays bounded. Server
+    // functions are registered at their own path, so they are labeled with the path of the server function. Pages that
+    // the fallback renders don't have a pattern
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "*".to_string());
+
+    let response = next.run(request).await;
+
+    let kind = response
+        .extensions()
+        .get::<RouteKind>()
+        .copied()
+        .unwrap_or(RouteKind::Other)
+        .to_string();
+    let labels = [
+        ("kind", kind),
+        ("route", route),
+        ("method", method),
+        ("status", response.status().as_u16().to_string()),
+    ];
+    metrics::counter!(REQUESTS_TOTAL, &labels).increment(1);
+    metrics::histogram!(REQUEST_DURATION_SECONDS, &labels).record(start.elapsed().as_secs_f64());
+
+    response
+}
@@ -0,0 +1,110 @@
+//! Drain in-flight server functions before the server shuts down
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Tracks the server functions that are running so the server can wait for them to finish before it exits. Create one
+/// with [`DioxusRouterExt::register_server_functions_with_shutdown`](crate::prelude::DioxusRouterExt::register_server_functions_with_shutdown)
+/// or pass one to [`ServerFnConfig::shutdown_handle`](crate::prelude::ServerFnConfig::shutdown_handle).
+///
+/// Once [`ShutdownHandle::shutdown`] is called, new server function calls are rejected with `503 Service Unavailable`
+/// while the calls that already started keep running. A call is tracked until the server function returns its response,
+/// so a streaming response may still be sending its body when the call is no longer tracked.
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus_lib::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// #[tokio::main]
+/// async fn main() {
+///     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+///     let (router, shutdown) = axum::Router::new()
+///         .register_server_functions_with_shutdown(Default::default());
+///     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+///     axum::serve(listener, router.into_make_service())
+///         .with_graceful_shutdown(async move {
+///             tokio::signal::ctrl_c().await.unwrap();
+///             // Stop accepting server function calls and give the running calls 30 seconds to finish
+///             shutdown.shutdown();
+///             if !shutdown.wait_for_idle(std::time::Duration::from_secs(30)).await {
+///                 tracing::warn!("{} server functions were still running at shutdown", shutdown.active());
+///             }
+///         })
+///         .await
+///         .unwrap();
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct ShutdownHandle {
+    inner: Arc<ShutdownState>,
+}
+
+#[derive(Default)]
+struct ShutdownState {
+    active: AtomicUsize,
+    shutting_down: AtomicBool,
+    idle: Notify,
+}
+
+impl ShutdownHandle {
+    /// Create a new handle that is not shutting down
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start shutting down. Server function calls that start after this are rejected with `503 Service Unavailable`
+    pub fn shutdown(&self) {
+        self.inner.shutting_down.store(true, Ordering::SeqCst);
+    }
+
+    /// Check if [`ShutdownHandle::shutdown`] was called
+    pub fn is_shutting_down(&self) -> bool {
+        self.inner.shutting_down.load(Ordering::SeqCst)
+    }
+
+    /// The number of server function calls that are running
+    pub fn active(&self) -> usize {
+        self.inner.active.load(Ordering::SeqCst)
+    }
+
+    /// Wait until no server functions are running or the deadline passes. Returns `true` if every server function
+    /// finished in time.
+    pub async fn wait_for_idle(&self, deadline: Duration) -> bool {
+        let wait = async {
+            loop {
+                // Register for the notification before checking the count so a call that finishes in between isn't missed
+                let idle = self.inner.idle.notified();
+                if self.active() == 0 {
+                    return;
+                }
+                idle.await;
+            }
+        };
+        tokio::time::timeout(deadline, wait).await.is_ok()
+    }
+
+    /// Start tracking a server function call. Returns `None` if the server is shutting down
+    pub(crate) fn track(&self) -> Option<ActiveCall> {
+        self.inner.active.fetch_add(1, Ordering::SeqCst);
+        let call = ActiveCall {
+            inner: self.inner.clone(),
+        };
+        // Check after counting the call so a shutdown that waits for idle either sees this call or the call sees the shutdown
+        (!self.is_shutting_down()).then_some(call)
+    }
+}
+
+/// A server function call that is running. The call stops being tracked when this is dropped
+pub(crate) struct ActiveCall {
+    inner: Arc<ShutdownState>,
+}
+
+impl Drop for ActiveCall {
+    fn drop(&mut self) {
+        if self.inner.active.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.inner.idle.notify_waiters();
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! A builder that makes the valid ways to configure a [`RenderHandleState`] explicit
+
+use super::{
+    FeatureFlagsBuilder, HeadBuilder, RenderHandleState, SsrStateInitHook, VirtualDomFactory,
+    VirtualDomFuture,
+};
+use crate::prelude::*;
+use dioxus_lib::prelude::{Element, VirtualDom};
+use std::sync::Arc;
+
+/// The root of a [`RenderHandleStateBuilder`] that doesn't know how to build the [`VirtualDom`] yet. Set the root with
+/// [`RenderHandleStateBuilder::root`] or one of the factory methods
+pub struct NoRoot;
+
+/// The root of a [`RenderHandleStateBuilder`] that knows how to build the [`VirtualDom`] for a request
+pub struct WithRoot(VirtualDomFactory);
+
+/// A builder for a [`RenderHandleState`]. Create one with [`RenderHandleState::builder`].
+///
+/// The builder can only be built once it knows how to build the [`VirtualDom`] for a request, and every setting can be
+/// set more than once, with the last value winning.
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus_lib::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// # fn public_app() -> Element { todo!() }
+/// # fn admin_app() -> Element { todo!() }
+/// # fn error_page() -> Element { todo!() }
+/// let public = RenderHandleState::builder(ServeConfig::new().unwrap())
+///     .root(public_app)
+///     .error_component(error_page)
+///     .build();
+///
+/// // Render the admin application with the same pool of renderers
+/// let admin = RenderHandleState::builder(ServeConfig::new().unwrap())
+///     .root(admin_app)
+///     .share_ssr_state_with(&public)
+///     .build();
+/// ```
+pub struct RenderHandleStateBuilder<Root = NoRoot> {
+    config: ServeConfig,
+    root: Root,
+    ssr_state: Option<Arc<once_cell::sync::OnceCell<SSRState>>>,
+    on_ssr_state_init: Option<SsrStateInitHook>,
+    head_builder: Option<HeadBuilder>,
+    feature_flags: Option<FeatureFlagsBuilder>,
+    error_component: Option<fn() -> Element>,
+}
+
+impl RenderHandleState {
+    /// Create a [`RenderHandleStateBuilder`] that renders with the config
+    pub fn builder(config: ServeConfig) -> RenderHandleStateBuilder {
+        RenderHandleStateBuilder {
+            config,
+            root: NoRoot,
+            ssr_state: None,
+            on_ssr_state_init: None,
+            head_builder: None,
+            feature_flags: None,
+            error_component: None,
+        }
+    }
+}
+
+impl<Root> RenderHandleStateBuilder<Root> {
+    /// Render the root component for every request
+    pub fn root(self, root: fn() -> Element) -> RenderHandleStateBuilder<WithRoot> {
+        self.with_factory(VirtualDomFactory::Sync(Arc::new(move || {
+            VirtualDom::new(root)
+        })))
+    }
+
+    /// Build the [`VirtualDom`] for every request with a factory. See [`RenderHandleState::new_with_virtual_dom_factory`]
+    pub fn virtual_dom_factory(
+        self,
+        build_virtual_dom: impl Fn() -> VirtualDom + Send + Sync + 'static,
+    ) -> RenderHandleStateBuilder<WithRoot> {
+        self.with_factory(VirtualDomFactory::Sync(Arc::new(build_virtual_dom)))
+    }
+
+    /// Build the [`VirtualDom`] for every request with a factory that receives the url of the request. See
+    /// [`RenderHandleState::new_with_url_aware_virtual_dom_factory`]
+    pub fn url_aware_virtual_dom_factory(
+        self,
+        build_virtual_dom: impl Fn(&str) -> VirtualDom + Send + Sync + 'static,
+    ) -> RenderHandleStateBuilder<WithRoot> {
+        self.with_factory(VirtualDomFactory::Routed(Arc::new(build_virtual_dom)))
+    }
+
+    /// Build the [`VirtualDom`] for every request with an async factory. See
+    /// [`RenderHandleState::new_with_async_virtual_dom_factory`]
+    pub fn async_virtual_dom_factory(
+        self,
+        build_virtual_dom: impl Fn() -> VirtualDomFuture + Send + Sync + 'static,
+    ) -> RenderHandleStateBuilder<WithRoot> {
+        self.with_factory(VirtualDomFactory::Async(Arc::new(build_virtual_dom)))
+    }
+
+    /// Render with the [`SSRState`]. Sharing a [`SSRState`] between multiple [`RenderHandleState`]s is more efficient
+    /// than creating a new one for each of them. (defaults to a new [`SSRState`] that is created on the first request)
+    pub fn ssr_state(mut self, ssr_state: SSRState) -> Self {
+        self.ssr_state = Some(Arc::new(once_cell::sync::OnceCell::from(ssr_state)));
+        self
+    }
+
+    /// Render with the same [`SSRState`] as another [`RenderHandleState`], even if that state hasn't created its
+    /// [`SSRState`] yet. Whichever state renders first creates the [`SSRState`] for both.
+    pub fn share_ssr_state_with(mut self, other: &RenderHandleState) -> Self {
+        self.ssr_state = Some(other.ssr_state.clone());
+        self
+    }
+
+    /// Run a callback the first time the [`SSRState`] is initialized. See [`RenderHandleState::on_ssr_state_init`]
+    pub fn on_ssr_state_init(
+        mut self,
+        on_init: impl Fn(&SSRState, std::time::Duration) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_ssr_state_init = Some(Arc::new(on_init));
+        self
+    }
+
+    /// Add extra html to the `<head>` of every server rendered page. See [`RenderHandleState::with_head_builder`]
+    pub fn head_builder(
+        mut self,
+        head_builder: impl Fn(&DioxusServerContext) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.head_builder = Some(Arc::new(head_builder));
+        self
+    }
+
+    /// Decide the feature flags of every server rendered page from the request. See
+    /// [`RenderHandleState::with_feature_flags`]
+    pub fn feature_flags(
+        mut self,
+        feature_flags: impl Fn(&DioxusServerContext) -> FeatureFlags + Send + Sync + 'static,
+    ) -> Self {
+        self.feature_flags = Some(Arc::new(feature_flags));
+        self
+    }
+
+    /// Set a component to render when the server fails to render the page. See [`RenderHandleState::with_error_component`]
+    pub fn error_component(mut self, error_component: fn() -> Element) -> Self {
+        self.error_component = Some(error_component);
+        self
+    }
+
+    fn with_factory(self, factory: VirtualDomFactory) -> RenderHandleStateBuilder<WithRoot> {
+        RenderHandleStateBuilder {
+            config: self.config,
+            root: WithRoot(factory),
+            ssr_state: self.ssr_state,
+            on_ssr_state_init: self.on_ssr_state_init,
+            head_builder: self.head_builder,
+            feature_flags: self.feature_flags,
+            error_component: self.error_component,
+        }
+    }
+}
+
+impl RenderHandleStateBuilder<WithRoot> {
+    /// Build the [`RenderHandleState`]
+    pub fn build(self) -> RenderHandleState {
+        RenderHandleState {
+            config: self.config,
+            build_virtual_dom: self.root.0,
+            ssr_state: self.ssr_state.unwrap_or_default(),
+            on_ssr_state_init: self.on_ssr_state_init,
+            head_builder: self.head_builder,
+            feature_flags: self.feature_flags,
+            error_component: self.error_component,
+        }
+    }
+}
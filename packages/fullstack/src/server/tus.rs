@@ -0,0 +1,592 @@
+//! Resumable uploads with the [tus protocol](https://tus.io/protocols/resumable-upload)
+
+use super::{apply_response_metadata, ContextProviders};
+use crate::prelude::*;
+use axum::{
+    body::Body,
+    extract::{OriginalUri, Path},
+    response::Response,
+    routing::{options, post},
+    Router,
+};
+use base64::Engine;
+use bytes::Bytes;
+use futures_util::{future::BoxFuture, StreamExt};
+use http::{header::*, HeaderName, HeaderValue, Request, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const TUS_VERSION: &str = "1.0.0";
+const OFFSET_CONTENT_TYPE: &str = "application/offset+octet-stream";
+
+const TUS_RESUMABLE: HeaderName = HeaderName::from_static("tus-resumable");
+const TUS_VERSION_HEADER: HeaderName = HeaderName::from_static("tus-version");
+const TUS_EXTENSION: HeaderName = HeaderName::from_static("tus-extension");
+const TUS_MAX_SIZE: HeaderName = HeaderName::from_static("tus-max-size");
+const UPLOAD_LENGTH: HeaderName = HeaderName::from_static("upload-length");
+const UPLOAD_OFFSET: HeaderName = HeaderName::from_static("upload-offset");
+const UPLOAD_METADATA: HeaderName = HeaderName::from_static("upload-metadata");
+
+/// The state of an upload that hasn't finished yet
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadInfo {
+    /// The number of bytes the store has received so far
+    pub offset: u64,
+    /// The size of the whole file in bytes
+    pub length: u64,
+    /// The metadata the client sent with the `Upload-Metadata` header when it created the upload
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// A file that was uploaded completely. It is passed to the completion callback of [`ResumableUploads`]
+#[derive(Clone, Debug)]
+pub struct CompletedUpload {
+    /// The id of the upload
+    pub id: String,
+    /// The path of the complete file. The store removes the file once the completion callback succeeds, so move or copy
+    /// the file if you want to keep it
+    pub path: PathBuf,
+    /// The size of the file in bytes
+    pub length: u64,
+    /// The metadata the client sent with the `Upload-Metadata` header when it created the upload
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// Where [`ResumableUploads`] persists the chunks of uploads until they are complete.
+///
+/// [`DirectoryUploadStore`] keeps the uploads in a directory on disk. Implement this trait to keep them somewhere else,
+/// for example on a volume that is shared between multiple servers.
+#[async_trait::async_trait]
+pub trait UploadStore: Send + Sync + 'static {
+    /// Create a new empty upload
+    async fn create(&self, id: &str, info: &UploadInfo) -> io::Result<()>;
+
+    /// Read the state of an upload, or `None` if there is no upload with the id
+    async fn info(&self, id: &str) -> io::Result<Option<UploadInfo>>;
+
+    /// Append a chunk to the end of an upload and return the new offset
+    async fn append(&self, id: &str, chunk: Bytes) -> io::Result<u64>;
+
+    /// The path of the file of an upload that received all of its bytes
+    async fn file_path(&self, id: &str) -> io::Result<PathBuf>;
+
+    /// Remove an upload and its file. Removing an upload that doesn't exist is not an error
+    async fn remove(&self, id: &str) -> io::Result<()>;
+}
+
+/// An [`UploadStore`] that keeps every upload as a file in a directory, next to a json file with the state of the upload
+#[derive(Clone, Debug)]
+pub struct DirectoryUploadStore {
+    directory: PathBuf,
+}
+
+impl DirectoryUploadStore {
+    /// Keep uploads in a directory. The directory is created when the first upload is created
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    fn data_path(&self, id: &str) -> PathBuf {
+        self.directory.join(id)
+    }
+
+    fn info_path(&self, id: &str) -> PathBuf {
+        self.directory.join(format!("{id}.json"))
+    }
+}
+
+#[async_trait::async_trait]
+impl UploadStore for DirectoryUploadStore {
+    async fn create(&self, id: &str, info: &UploadInfo) -> io::Result<()> {
+        tokio::fs::create_dir_all(&self.directory).await?;
+        tokio::fs::write(self.data_path(id), b"").await?;
+        tokio::fs::write(self.info_path(id), serde_json::to_vec(info)?).await
+    }
+
+    async fn info(&self, id: &str) -> io::Result<Option<UploadInfo>> {
+        let info = match tokio::fs::read(self.info_path(id)).await {
+            Ok(info) => info,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let mut info: UploadInfo = serde_json::from_slice(&info)?;
+        // The file is the source of truth for the offset, so chunks that were written before a crash are not lost
+        info.offset = tokio::fs::metadata(self.data_path(id)).await?.len();
+        Ok(Some(info))
+    }
+
+    async fn append(&self, id: &str, chunk: Bytes) -> io::Result<u64> {
+        use tokio::io::AsyncWriteExt;
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(self.data_path(id))
+            .await?;
+        file.write_all(&chunk).await?;
+        file.flush().await?;
+        Ok(file.metadata().await?.len())
+    }
+
+    async fn file_path(&self, id: &str) -> io::Result<PathBuf> {
+        Ok(self.data_path(id))
+    }
+
+    async fn remove(&self, id: &str) -> io::Result<()> {
+        for path in [self.data_path(id), self.info_path(id)] {
+            match tokio::fs::remove_file(path).await {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+type CompletionCallback =
+    Arc<dyn Fn(CompletedUpload) -> BoxFuture<'static, Result<(), ServerFnError>> + Send + Sync>;
+
+/// Accept resumable uploads with the [tus protocol](https://tus.io/protocols/resumable-upload) and hand every completed
+/// file to a callback. Add the routes with
+/// [`DioxusRouterExt::with_resumable_uploads`](crate::prelude::DioxusRouterExt::with_resumable_uploads).
+///
+/// Clients create an upload with a `POST` to the route, send the file in as many `PATCH` requests as they need and ask
+/// how much of the file the server received with a `HEAD` request after the connection drops. The core protocol and the
+/// `creation` extension are supported, so any tus client like `tus-js-client` can upload to the route.
+///
+/// The completion callback runs inside a [`DioxusServerContext`] for the request that sent the last chunk, just like a
+/// server function. It can read the context with [`server_context`] or [`extract`], and headers it sets on the context
+/// are sent with the response to that request.
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus_lib::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// let uploads = ResumableUploads::new(
+///     DirectoryUploadStore::new("uploads"),
+///     |upload: CompletedUpload| async move {
+///         // The client picks the file name, so only keep its last component to stay inside the videos directory
+///         let name = upload
+///             .metadata
+///             .get("filename")
+///             .and_then(|name| std::path::Path::new(name).file_name())
+///             .map(|name| name.to_owned())
+///             .unwrap_or_else(|| upload.id.clone().into());
+///         tokio::fs::rename(&upload.path, std::path::Path::new("videos").join(name)).await?;
+///         Ok(())
+///     },
+/// )
+/// .max_size(1024 * 1024 * 1024);
+/// ```
+#[derive(Clone)]
+pub struct ResumableUploads {
+    store: Arc<dyn UploadStore>,
+    on_complete: CompletionCallback,
+    max_size: Option<u64>,
+    context_providers: ContextProviders,
+    // Uploads that are receiving a chunk right now. Chunks for the same upload must not be appended at the same time
+    in_progress: Arc<parking_lot::Mutex<HashSet<String>>>,
+}
+
+impl ResumableUploads {
+    /// Persist uploads in the store and call the callback with every file once all of its bytes arrived
+    pub fn new<F, Fut>(store: impl UploadStore, on_complete: F) -> Self
+    where
+        F: Fn(CompletedUpload) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = Result<(), ServerFnError>> + Send + 'static,
+    {
+        Self {
+            store: Arc::new(store),
+            on_complete: Arc::new(move |upload| Box::pin(on_complete(upload))),
+            max_size: None,
+            context_providers: Default::default(),
+            in_progress: Default::default(),
+        }
+    }
+
+    /// Reject uploads that are larger than the size in bytes. (defaults to no limit)
+    pub fn max_size(mut self, max_size: u64) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// Set the context providers that will be inserted into the [`DioxusServerContext`] of the completion callback
+    pub fn context_providers(mut self, context_providers: ContextProviders) -> Self {
+        self.context_providers = context_providers;
+        self
+    }
+
+    /// Add the routes for the uploads at the path to the router
+    pub(crate) fn route<S>(self, router: Router<S>, path: &str) -> Router<S>
+    where
+        S: Send + Sync + Clone + 'static,
+    {
+        let uploads = Arc::new(self);
+        let create = {
+            let uploads = uploads.clone();
+            move |request: Request<Body>| {
+                let uploads = uploads.clone();
+                async move { uploads.create(request).await }
+            }
+        };
+        let status = {
+            let uploads = uploads.clone();
+            move |Path(id): Path<String>, request: Request<Body>| {
+                let uploads = uploads.clone();
+                async move { uploads.status(&id, request).await }
+            }
+        };
+        let patch = {
+            let uploads = uploads.clone();
+            move |Path(id): Path<String>, request: Request<Body>| {
+                let uploads = uploads.clone();
+                async move { uploads.patch(&id, request).await }
+            }
+        };
+        let describe = move || {
+            let uploads = uploads.clone();
+            async move { uploads.describe() }
+        };
+
+        let path = path.trim_end_matches('/');
+        router
+            .route(path, post(create).options(describe.clone()))
+            .route(
+                &format!("{path}/:id"),
+                options(describe).head(status).patch(patch),
+            )
+    }
+
+    /// Answer an `OPTIONS` request with the versions and extensions the server supports
+    fn describe(&self) -> Response {
+        let mut response = tus_response(StatusCode::NO_CONTENT);
+        let headers = response.headers_mut();
+        headers.insert(TUS_VERSION_HEADER, HeaderValue::from_static(TUS_VERSION));
+        headers.insert(TUS_EXTENSION, HeaderValue::from_static("creation"));
+        if let Some(max_size) = self.max_size {
+            headers.insert(TUS_MAX_SIZE, HeaderValue::from(max_size));
+        }
+        response
+    }
+
+    /// Create a new upload and point the client to it
+    async fn create(&self, request: Request<Body>) -> Response {
+        if let Some(response) = reject_version(&request) {
+            return response;
+        }
+        let Some(length) = header_u64(&request, UPLOAD_LENGTH) else {
+            return error(StatusCode::BAD_REQUEST, "Missing or invalid Upload-Length");
+        };
+        if self.max_size.is_some_and(|max_size| length > max_size) {
+            return error(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "The upload is larger than Tus-Max-Size",
+            );
+        }
+        let metadata = match request.headers().get(UPLOAD_METADATA) {
+            Some(metadata) => match parse_metadata(metadata) {
+                Some(metadata) => metadata,
+                None => return error(StatusCode::BAD_REQUEST, "Invalid Upload-Metadata"),
+            },
+            None => BTreeMap::new(),
+        };
+
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        let info = UploadInfo {
+            offset: 0,
+            length,
+            metadata,
+        };
+        if let Err(err) = self.store.create(&id, &info).await {
+            tracing::error!("Failed to create upload {id}: {err}");
+            return error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to create upload");
+        }
+
+        // Point to the upload relative to the path the client requested, even if the router is nested
+        let base = request
+            .extensions()
+            .get::<OriginalUri>()
+            .map(|uri| uri.0.path())
+            .unwrap_or_else(|| request.uri().path());
+        let location = format!("{}/{id}", base.trim_end_matches('/'));
+
+        let mut response = tus_response(StatusCode::CREATED);
+        response.headers_mut().insert(
+            LOCATION,
+            HeaderValue::from_str(&location).expect("the location is always a valid header"),
+        );
+        response
+    }
+
+    /// Answer a `HEAD` request with how much of the upload the server received
+    async fn status(&self, id: &str, request: Request<Body>) -> Response {
+        if let Some(response) = reject_version(&request) {
+            return response;
+        }
+        let info = match self.info(id).await {
+            Ok(info) => info,
+            Err((status, message)) => return error(status, message),
+        };
+
+        let mut response = tus_response(StatusCode::OK);
+        let headers = response.headers_mut();
+        headers.insert(UPLOAD_OFFSET, HeaderValue::from(info.offset));
+        headers.insert(UPLOAD_LENGTH, HeaderValue::from(info.length));
+        if !info.metadata.is_empty() {
+            if let Ok(metadata) = HeaderValue::from_str(&encode_metadata(&info.metadata)) {
+                headers.insert(UPLOAD_METADATA, metadata);
+            }
+        }
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+        response
+    }
+
+    /// Append the body of a `PATCH` request to the upload and run the completion callback once the upload is complete
+    async fn patch(&self, id: &str, request: Request<Body>) -> Response {
+        if let Some(response) = reject_version(&request) {
+            return response;
+        }
+        let content_type = request
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok());
+        if content_type != Some(OFFSET_CONTENT_TYPE) {
+            return error(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "The Content-Type must be application/offset+octet-stream",
+            );
+        }
+        let Some(offset) = header_u64(&request, UPLOAD_OFFSET) else {
+            return error(StatusCode::BAD_REQUEST, "Missing or invalid Upload-Offset");
+        };
+
+        // Only one request may append to an upload at a time. The lock is released even if the client disconnects and
+        // the request is dropped halfway through
+        if !self.in_progress.lock().insert(id.to_string()) {
+            return error(
+                StatusCode::LOCKED,
+                "The upload is already receiving a chunk",
+            );
+        }
+        let _unlock = Unlock {
+            in_progress: &self.in_progress,
+            id,
+        };
+        self.append(id, offset, request).await
+    }
+
+    async fn append(&self, id: &str, offset: u64, request: Request<Body>) -> Response {
+        let mut info = match self.info(id).await {
+            Ok(info) => info,
+            Err((status, message)) => return error(status, message),
+        };
+        if offset != info.offset {
+            return error(
+                StatusCode::CONFLICT,
+                "Upload-Offset doesn't match the offset of the upload",
+            );
+        }
+        let content_length = header_u64(&request, CONTENT_LENGTH);
+        if content_length.is_some_and(|length| {
+            offset
+                .checked_add(length)
+                .map_or(true, |end| end > info.length)
+        }) {
+            return error(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "The chunk is larger than the rest of the upload",
+            );
+        }
+
+        // Append the chunks as they arrive, so everything received before the connection drops can be resumed
+        let (parts, body) = request.into_parts();
+        let mut body = body.into_data_stream();
+        while let Some(chunk) = body.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(err) => {
+                    tracing::trace!("The body of upload {id} ended early: {err}");
+                    break;
+                }
+            };
+            if info
+                .offset
+                .checked_add(chunk.len() as u64)
+                .map_or(true, |end| end > info.length)
+            {
+                return error(
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    "The chunk is larger than the rest of the upload",
+                );
+            }
+            match self.store.append(id, chunk).await {
+                Ok(offset) => info.offset = offset,
+                Err(err) => {
+                    tracing::error!("Failed to append to upload {id}: {err}");
+                    return error(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "Failed to store the chunk",
+                    );
+                }
+            }
+        }
+
+        let mut response = if info.offset == info.length {
+            self.complete(id, info.clone(), parts).await
+        } else {
+            tus_response(StatusCode::NO_CONTENT)
+        };
+        response
+            .headers_mut()
+            .insert(UPLOAD_OFFSET, HeaderValue::from(info.offset));
+        response
+    }
+
+    /// Run the completion callback inside the server context of the request that sent the last chunk
+    async fn complete(&self, id: &str, info: UploadInfo, parts: http::request::Parts) -> Response {
+        let path = match self.store.file_path(id).await {
+            Ok(path) => path,
+            Err(err) => {
+                tracing::error!("Failed to find the file of upload {id}: {err}");
+                return error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to complete the upload",
+                );
+            }
+        };
+        let upload = CompletedUpload {
+            id: id.to_string(),
+            path,
+            length: info.length,
+            metadata: info.metadata,
+        };
+
+        let server_context = DioxusServerContext::new(parts);
+        for index in 0..self.context_providers.len() {
+            let context_providers = self.context_providers.clone();
+            server_context.insert_boxed_factory(Box::new(move || context_providers[index]()));
+        }
+        let result =
+            ProvideServerContext::new((self.on_complete)(upload), server_context.clone()).await;
+
+        let mut response = match result {
+            Ok(()) => {
+                // The upload stays in the store if the callback fails, so the client can complete it again
+                if let Err(err) = self.store.remove(id).await {
+                    tracing::error!("Failed to remove completed upload {id}: {err}");
+                }
+                tus_response(StatusCode::NO_CONTENT)
+            }
+            Err(err) => {
+                tracing::error!("Failed to complete upload {id}: {err}");
+                error(StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+            }
+        };
+        let mut response_parts = server_context.response_parts_mut();
+        response
+            .headers_mut()
+            .extend(response_parts.headers.drain());
+        apply_response_metadata(&mut response_parts, &mut response);
+        response
+    }
+
+    async fn info(&self, id: &str) -> Result<UploadInfo, (StatusCode, &'static str)> {
+        // Ids are always created by the server, so anything else can't name an upload. This also keeps the id from
+        // escaping the directory of a store
+        if !id.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err((StatusCode::NOT_FOUND, "Unknown upload"));
+        }
+        match self.store.info(id).await {
+            Ok(Some(info)) => Ok(info),
+            Ok(None) => Err((StatusCode::NOT_FOUND, "Unknown upload")),
+            Err(err) => {
+                tracing::error!("Failed to read upload {id}: {err}");
+                Err((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to read the upload",
+                ))
+            }
+        }
+    }
+}
+
+/// Removes an upload from the uploads that are receiving a chunk when it is dropped
+struct Unlock<'a> {
+    in_progress: &'a parking_lot::Mutex<HashSet<String>>,
+    id: &'a str,
+}
+
+impl Drop for Unlock<'_> {
+    fn drop(&mut self) {
+        self.in_progress.lock().remove(self.id);
+    }
+}
+
+/// Reject requests from clients that speak a different version of the protocol
+fn reject_version(request: &Request<Body>) -> Option<Response> {
+    let version = request.headers().get(TUS_RESUMABLE);
+    if version.is_some_and(|version| version.as_bytes() == TUS_VERSION.as_bytes()) {
+        return None;
+    }
+    let mut response = error(
+        StatusCode::PRECONDITION_FAILED,
+        "Unsupported Tus-Resumable version",
+    );
+    response
+        .headers_mut()
+        .insert(TUS_VERSION_HEADER, HeaderValue::from_static(TUS_VERSION));
+    Some(response)
+}
+
+fn header_u64(request: &Request<Body>, name: HeaderName) -> Option<u64> {
+    request.headers().get(name)?.to_str().ok()?.parse().ok()
+}
+
+fn tus_response(status: StatusCode) -> Response {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(TUS_RESUMABLE, HeaderValue::from_static(TUS_VERSION));
+    response
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    let mut response = tus_response(status);
+    *response.body_mut() = Body::from(message.to_string());
+    response
+}
+
+/// Parse `Upload-Metadata`, a comma separated list of keys and base64 encoded values
+fn parse_metadata(header: &HeaderValue) -> Option<BTreeMap<String, String>> {
+    let mut metadata = BTreeMap::new();
+    for pair in header.to_str().ok()?.split(',') {
+        let pair = pair.trim();
+        if pair.is_empty() {
+            continue;
+        }
+        let (key, value) = pair.split_once(' ').unwrap_or((pair, ""));
+        let value = base64::engine::general_purpose::STANDARD
+            .decode(value.trim())
+            .ok()?;
+        metadata.insert(key.to_string(), String::from_utf8(value).ok()?);
+    }
+    Some(metadata)
+}
+
+fn encode_metadata(metadata: &BTreeMap<String, String>) -> String {
+    metadata
+        .iter()
+        .map(|(key, value)| {
+            format!(
+                "{key} {}",
+                base64::engine::general_purpose::STANDARD.encode(value)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
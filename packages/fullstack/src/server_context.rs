@@ -1,6 +1,5 @@
 use parking_lot::RwLock;
 use std::any::Any;
-use std::collections::HashMap;
 use std::sync::Arc;
 
 type SendSyncAnyMap = std::collections::HashMap<std::any::TypeId, ContextType>;
@@ -24,35 +23,68 @@ type SendSyncAnyMap = std::collections::HashMap<std::any::TypeId, ContextType>;
 #[derive(Clone)]
 pub struct DioxusServerContext {
     shared_context: std::sync::Arc<RwLock<SendSyncAnyMap>>,
+    pending_factories: Arc<RwLock<Vec<BoxedFactory>>>,
+    running_factories: Arc<parking_lot::ReentrantMutex<()>>,
     response_parts: std::sync::Arc<RwLock<http::response::Parts>>,
     pub(crate) parts: Arc<RwLock<http::request::Parts>>,
+    raw_body: Arc<RwLock<Option<bytes::Bytes>>>,
+    request_id: Arc<str>,
+    csp_nonce: Arc<RwLock<Option<Arc<str>>>>,
+    locale: Arc<RwLock<Option<String>>>,
+    origin: Arc<RwLock<Option<Arc<str>>>>,
+    vary: Arc<RwLock<Vec<http::HeaderName>>>,
+    redirect: Arc<RwLock<Option<(http::HeaderValue, http::StatusCode)>>>,
+    render_duration: Arc<RwLock<Option<std::time::Duration>>>,
+    static_page: Arc<RwLock<bool>>,
+    robots_meta: Arc<RwLock<Option<String>>>,
+    trust_forwarded_for: Arc<RwLock<bool>>,
 }
 
+/// The header used to read and echo the id of the current request
+pub(crate) const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// The response headers that belong to the request that set them, so requests that share a render never copy them
+const PER_REQUEST_HEADERS: &[&str] = &["set-cookie", REQUEST_ID_HEADER];
+
+/// The header that tells search engines how to index a response
+const X_ROBOTS_TAG: http::HeaderName = http::HeaderName::from_static("x-robots-tag");
+
+/// A factory that creates a value for the shared server context
+type BoxedFactory = Box<dyn Fn() -> Box<dyn Any> + Send + Sync>;
+
 enum ContextType {
-    Factory(Box<dyn Fn() -> Box<dyn Any> + Send + Sync>),
+    /// A factory that hasn't run yet. It runs the first time its type is read and is replaced by the value it creates
+    Factory(BoxedFactory),
     Value(Box<dyn Any + Send + Sync>),
 }
 
-impl ContextType {
-    fn downcast<T: Clone + 'static>(&self) -> Option<T> {
-        match self {
-            ContextType::Value(value) => value.downcast_ref::<T>().cloned(),
-            ContextType::Factory(factory) => factory().downcast::<T>().ok().map(|v| *v),
-        }
+impl Default for DioxusServerContext {
+    fn default() -> Self {
+        Self::from_shared_parts(std::sync::Arc::new(RwLock::new(
+            http::request::Request::new(()).into_parts().0,
+        )))
     }
 }
 
-#[allow(clippy::derivable_impls)]
-impl Default for DioxusServerContext {
-    fn default() -> Self {
-        Self {
-            shared_context: std::sync::Arc::new(RwLock::new(HashMap::new())),
-            response_parts: std::sync::Arc::new(RwLock::new(
-                http::response::Response::new(()).into_parts().0,
-            )),
-            parts: std::sync::Arc::new(RwLock::new(http::request::Request::new(()).into_parts().0)),
-        }
+/// Read the request id from the `X-Request-Id` header of the request or generate a new random id if it is missing
+fn request_id_for(parts: &http::request::Parts) -> Arc<str> {
+    parts
+        .headers
+        .get(REQUEST_ID_HEADER)
+        .and_then(|id| id.to_str().ok())
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(Arc::from)
+        .unwrap_or_else(|| Arc::from(uuid::Uuid::new_v4().simple().to_string()))
+}
+
+/// Create the response parts for a new request. The request id is always echoed back to the client
+fn response_parts_for(request_id: &str) -> http::response::Parts {
+    let mut response_parts = http::response::Response::new(()).into_parts().0;
+    if let Ok(request_id) = http::HeaderValue::from_str(request_id) {
+        response_parts.headers.insert(REQUEST_ID_HEADER, request_id);
     }
+    response_parts
 }
 
 mod server_fn_impl {
@@ -63,27 +95,54 @@ mod server_fn_impl {
     impl DioxusServerContext {
         /// Create a new server context from a request
         pub fn new(parts: http::request::Parts) -> Self {
-            Self {
-                parts: Arc::new(RwLock::new(parts)),
-                shared_context: Arc::new(RwLock::new(SendSyncAnyMap::new())),
-                response_parts: std::sync::Arc::new(RwLock::new(
-                    http::response::Response::new(()).into_parts().0,
-                )),
-            }
+            Self::from_shared_parts(Arc::new(RwLock::new(parts)))
         }
 
         /// Create a server context from a shared parts
         #[allow(unused)]
         pub(crate) fn from_shared_parts(parts: Arc<RwLock<http::request::Parts>>) -> Self {
+            let request_id = request_id_for(&parts.read());
             Self {
                 parts,
                 shared_context: Arc::new(RwLock::new(SendSyncAnyMap::new())),
-                response_parts: std::sync::Arc::new(RwLock::new(
-                    http::response::Response::new(()).into_parts().0,
-                )),
+                pending_factories: Default::default(),
+                running_factories: Default::default(),
+                response_parts: std::sync::Arc::new(RwLock::new(response_parts_for(&request_id))),
+                raw_body: Default::default(),
+                request_id,
+                csp_nonce: Default::default(),
+                locale: Default::default(),
+                origin: Default::default(),
+                vary: Default::default(),
+                redirect: Default::default(),
+                render_duration: Default::default(),
+                static_page: Default::default(),
+                robots_meta: Default::default(),
+                trust_forwarded_for: Default::default(),
             }
         }
 
+        /// Get the id of the current request. The id is read from the `X-Request-Id` header of the request if it exists,
+        /// otherwise a random id is generated when the server context is created.
+        ///
+        /// The id is the same for the whole SSR render and any server functions it calls, and it is echoed back to the
+        /// client in the `X-Request-Id` header of the response.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[server]
+        /// async fn log_request() -> Result<(), ServerFnError> {
+        ///     let server_context = server_context();
+        ///     tracing::info!(request_id = server_context.request_id(), "Handling request");
+        ///     Ok(())
+        /// }
+        /// ```
+        pub fn request_id(&self) -> &str {
+            &self.request_id
+        }
+
         /// Clone a value from the shared server context. If you are using [`DioxusRouterExt`](crate::prelude::DioxusRouterExt), any values you insert into
         /// the launch context will also be available in the server context.
         ///
@@ -113,10 +172,64 @@ mod server_fn_impl {
         /// }
         /// ```
         pub fn get<T: Any + Send + Sync + Clone + 'static>(&self) -> Option<T> {
-            self.shared_context
-                .read()
-                .get(&TypeId::of::<T>())
-                .map(|v| v.downcast::<T>().unwrap())
+            if let Some(ContextType::Value(value)) =
+                self.shared_context.read().get(&TypeId::of::<T>())
+            {
+                return value.downcast_ref::<T>().cloned();
+            }
+            self.run_factory::<T>()
+        }
+
+        /// Run the factory for a type that was never read in this request and cache the value it creates
+        fn run_factory<T: Any + Send + Sync + Clone + 'static>(&self) -> Option<T> {
+            // Only one thread runs factories at a time, so a factory never runs twice because two threads read its type at
+            // once. The lock is reentrant so factories can read other values from the context
+            let _running = self.running_factories.lock();
+            let type_id = TypeId::of::<T>();
+
+            // Factories run without the context locked, so they are taken out of the context while they run
+            let mut shared_context = self.shared_context.write();
+            match shared_context.remove(&type_id) {
+                Some(ContextType::Value(value)) => {
+                    // Another thread ran the factory while we waited
+                    let cached = value.downcast_ref::<T>().cloned();
+                    shared_context.insert(type_id, ContextType::Value(value));
+                    return cached;
+                }
+                Some(ContextType::Factory(factory)) => {
+                    drop(shared_context);
+                    let value = *factory().downcast::<T>().ok()?;
+                    self.insert(value.clone());
+                    return Some(value);
+                }
+                None => drop(shared_context),
+            }
+
+            // Boxed factories don't know the type they create until they run, so run them in order until one creates the
+            // type. Factories that create a different type are kept for their type and run again when it is read
+            let mut pending = std::mem::take(&mut *self.pending_factories.write()).into_iter();
+            let mut value = None;
+            for factory in pending.by_ref() {
+                let created = factory();
+                let created_type = (*created).type_id();
+                if created_type == type_id {
+                    value = created.downcast::<T>().ok().map(|value| *value);
+                    break;
+                }
+                self.shared_context
+                    .write()
+                    .entry(created_type)
+                    .or_insert(ContextType::Factory(factory));
+            }
+            {
+                let mut pending_factories = self.pending_factories.write();
+                let inserted_while_running = std::mem::take(&mut *pending_factories);
+                *pending_factories = pending.chain(inserted_while_running).collect();
+            }
+
+            let value = value?;
+            self.insert(value.clone());
+            Some(value)
         }
 
         /// Insert a value into the shared server context
@@ -124,6 +237,37 @@ mod server_fn_impl {
             self.insert_any(Box::new(value));
         }
 
+        /// Insert a value that is not `Clone` into the shared server context. Read it back with [`DioxusServerContext::get_arc`].
+        ///
+        /// The value is stored behind an [`Arc`], so every clone of the server context for the request shares it. This
+        /// is useful to stash per request values like the parsed user in middleware and read them in the server function.
+        ///
+        /// # Example
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// struct User {
+        ///     name: String,
+        /// }
+        ///
+        /// #[server]
+        /// async fn greet() -> Result<String, ServerFnError> {
+        ///     let server_context = server_context();
+        ///     server_context.insert_arc(User { name: "Alice".to_string() });
+        ///     let user = server_context
+        ///         .get_arc::<User>()
+        ///         .ok_or_else(|| ServerFnError::new("not signed in"))?;
+        ///     Ok(format!("Hello {}", user.name))
+        /// }
+        /// ```
+        pub fn insert_arc<T: Any + Send + Sync + 'static>(&self, value: T) {
+            self.insert(Arc::new(value));
+        }
+
+        /// Get a value inserted with [`DioxusServerContext::insert_arc`]. Returns `None` if no value of the type was inserted
+        pub fn get_arc<T: Any + Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+            self.get::<Arc<T>>()
+        }
+
         /// Insert a boxed `Any` value into the shared server context
         pub fn insert_any(&self, value: Box<dyn Any + Send + Sync + 'static>) {
             self.shared_context
@@ -131,7 +275,10 @@ mod server_fn_impl {
                 .insert((*value).type_id(), ContextType::Value(value));
         }
 
-        /// Insert a factory that creates a non-sync value for the shared server context
+        /// Insert a factory that creates a non-sync value for the shared server context.
+        ///
+        /// The factory doesn't run until the type is first read with [`DioxusServerContext::get`], and the value it creates is
+        /// cached for the rest of the request. Every later read gets a clone of the same value.
         pub fn insert_factory<F, T>(&self, value: F)
         where
             F: Fn() -> T + Send + Sync + 'static,
@@ -143,11 +290,18 @@ mod server_fn_impl {
             );
         }
 
-        /// Insert a boxed factory that creates a non-sync value for the shared server context
+        /// Insert a boxed factory that creates a non-sync value for the shared server context. The context providers you
+        /// register server functions with are inserted with this for every request.
+        ///
+        /// The factory doesn't run when it is inserted. Boxed factories don't know the type they create until they run, so
+        /// the first time a type that isn't in the context is read, the boxed factories that haven't run yet run in the
+        /// order they were inserted until one creates the type. The value is cached for the rest of the request. A factory
+        /// that created a different type runs again the first time its own type is read.
+        ///
+        /// Values inserted with [`DioxusServerContext::insert`] take priority over boxed factories that create the same type,
+        /// and reading them never runs a boxed factory.
         pub fn insert_boxed_factory(&self, value: Box<dyn Fn() -> Box<dyn Any> + Send + Sync>) {
-            self.shared_context
-                .write()
-                .insert((*value()).type_id(), ContextType::Factory(value));
+            self.pending_factories.write().push(value);
         }
 
         /// Get the response parts from the server context
@@ -175,6 +329,9 @@ mod server_fn_impl {
 
         /// Get the response parts from the server context
         ///
+        /// The headers, status, version and extensions you set are applied to the response of the server function or the
+        /// server rendered page. The status and version are only applied if you change them from the defaults.
+        ///
         #[doc = include_str!("../docs/request_origin.md")]
         ///
         /// # Example
@@ -240,6 +397,508 @@ mod server_fn_impl {
             self.parts.write()
         }
 
+        /// Borrow the headers of the request without copying them. Like [`DioxusServerContext::request_parts`], the
+        /// request parts can't be modified until the returned guard is dropped.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[server]
+        /// async fn user_agent() -> Result<String, ServerFnError> {
+        ///     let server_context = server_context();
+        ///     let headers = server_context.request_headers();
+        ///     Ok(headers
+        ///         .get(http::header::USER_AGENT)
+        ///         .and_then(|user_agent| user_agent.to_str().ok())
+        ///         .unwrap_or_default()
+        ///         .to_string())
+        /// }
+        /// ```
+        pub fn request_headers(&self) -> parking_lot::MappedRwLockReadGuard<'_, http::HeaderMap> {
+            parking_lot::RwLockReadGuard::map(self.parts.read(), |parts| &parts.headers)
+        }
+
+        /// Get a copy of the full request that triggered this server function, including the body.
+        ///
+        /// The body of the request is consumed by the server function, so it is only kept around if the server functions
+        /// were registered with [`ServerFnConfig::raw_request`](crate::prelude::ServerFnConfig::raw_request) enabled. If
+        /// the body was not buffered, this will return `None`.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[server]
+        /// async fn read_request() -> Result<(), ServerFnError> {
+        ///     let server_context = server_context();
+        ///     let Some(request) = server_context.request() else {
+        ///         return Err(ServerFnError::new("the raw request was not buffered"));
+        ///     };
+        ///     println!("{:?} {:?}", request.headers(), request.body());
+        ///     Ok(())
+        /// }
+        /// ```
+        pub fn request(&self) -> Option<http::Request<bytes::Bytes>> {
+            let body = self.raw_body.read().clone()?;
+            let parts = self.parts.read().clone();
+            Some(http::Request::from_parts(parts, body))
+        }
+
+        /// Get the exact bytes of the body of the request that triggered this server function.
+        ///
+        /// The body is only kept around for server functions registered with [`ServerFnConfig::raw_body_for`](crate::prelude::ServerFnConfig::raw_body_for)
+        /// or [`ServerFnConfig::raw_request`](crate::prelude::ServerFnConfig::raw_request). If the body was not buffered,
+        /// this will return `None`.
+        pub fn raw_body(&self) -> Option<bytes::Bytes> {
+            self.raw_body.read().clone()
+        }
+
+        /// Get the [`ClientHints`](crate::prelude::ClientHints) the browser sent with the current request.
+        ///
+        #[doc = include_str!("../docs/request_origin.md")]
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[server]
+        /// async fn image_size() -> Result<u32, ServerFnError> {
+        ///     let hints = server_context().client_hints();
+        ///     let dpr = hints.device_pixel_ratio.unwrap_or(1.0);
+        ///     Ok((400.0 * dpr) as u32)
+        /// }
+        /// ```
+        pub fn client_hints(&self) -> crate::prelude::ClientHints {
+            crate::prelude::ClientHints::from_parts(&self.parts.read())
+        }
+
+        /// Store the buffered body of the request that triggered this server function
+        pub(crate) fn set_raw_body(&self, body: bytes::Bytes) {
+            *self.raw_body.write() = Some(body);
+        }
+
+        /// Get the nonce that every inline script in the current response must carry to pass the content security policy.
+        /// Returns `None` unless a nonce based policy is enabled with [`ServeConfigBuilder::strict_csp`](crate::prelude::ServeConfigBuilder::strict_csp)
+        /// or [`ServeConfigBuilder::content_security_policy`](crate::prelude::ServeConfigBuilder::content_security_policy).
+        ///
+        /// The scripts dioxus renders already carry the nonce. Use this for any other inline scripts or styles you render.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// # fn main() {}
+        /// fn Analytics() -> Element {
+        ///     let mut nonce: Option<String> = None;
+        ///     // The server context only exists while the page is rendered on the server
+        ///     server_only! {
+        ///         nonce = server_context().csp_nonce().map(|nonce| nonce.to_string());
+        ///     }
+        ///     rsx! {
+        ///         script { nonce, dangerous_inner_html: "window.analytics_loaded = true;" }
+        ///     }
+        /// }
+        /// ```
+        pub fn csp_nonce(&self) -> Option<Arc<str>> {
+            self.csp_nonce.read().clone()
+        }
+
+        /// Get the origin the client sent the current request to, like `https://example.com`. Use it to build absolute URLs
+        /// for canonical links or social media previews.
+        ///
+        /// Behind a reverse proxy, the origin is only correct if the proxy forwards it in the `Forwarded` or
+        /// `X-Forwarded-*` headers and [`ServeConfigBuilder::trust_forwarded_headers`](crate::prelude::ServeConfigBuilder::trust_forwarded_headers)
+        /// is enabled. Otherwise the origin is built from the `Host` header. Returns `None` if the origin is unknown or outside of
+        /// a server rendered page.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// fn app() -> Element {
+        ///     let mut canonical = String::new();
+        ///     server_only! {
+        ///         canonical = server_context().origin().unwrap_or_default().to_string();
+        ///     }
+        ///     rsx! {
+        ///         document::Link { rel: "canonical", href: "{canonical}/" }
+        ///     }
+        /// }
+        /// ```
+        pub fn origin(&self) -> Option<Arc<str>> {
+            self.origin.read().clone()
+        }
+
+        /// Set the origin of the current request
+        pub(crate) fn set_origin(&self, origin: Option<String>) {
+            *self.origin.write() = origin.map(Arc::from);
+        }
+
+        /// Get the locale the current page was rendered in if one was set with [`DioxusServerContext::set_locale`]
+        pub fn locale(&self) -> Option<String> {
+            self.locale.read().clone()
+        }
+
+        /// Set the locale the current page is rendered in. The locale should be a language tag like `fr` or `en-US`.
+        ///
+        /// If the locale is set during the initial render of a page, the response is sent with a matching `Content-Language`
+        /// header so caches and clients know the language of the page.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// fn app() -> Element {
+        ///     server_only! {
+        ///         server_context().set_locale("fr");
+        ///     }
+        ///     rsx! { "Bonjour le monde" }
+        /// }
+        /// ```
+        pub fn set_locale(&self, locale: impl Into<String>) {
+            *self.locale.write() = Some(locale.into());
+        }
+
+        /// Register a request header the response depends on. The response is sent with a `Vary` header that lists every
+        /// registered header, so caches keep a separate copy of the response for each value of the headers.
+        ///
+        /// Call this from any component or server function that branches on a request header, like `Accept-Language` or
+        /// `Cookie`. Otherwise a cache may serve the response rendered for one client to another. For server rendered
+        /// pages, the header must be registered during the initial render, before the first chunk of the page is sent.
+        /// Invalid header names are ignored.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[server]
+        /// async fn greeting() -> Result<String, ServerFnError> {
+        ///     let server_context = server_context();
+        ///     server_context.add_vary("Accept-Language");
+        ///     let french = server_context
+        ///         .request_parts()
+        ///         .headers
+        ///         .get(http::header::ACCEPT_LANGUAGE)
+        ///         .and_then(|language| language.to_str().ok())
+        ///         .is_some_and(|language| language.starts_with("fr"));
+        ///     Ok(if french { "Bonjour" } else { "Hello" }.to_string())
+        /// }
+        /// ```
+        pub fn add_vary(&self, header: impl AsRef<str>) {
+            let Ok(header) = http::HeaderName::try_from(header.as_ref()) else {
+                tracing::warn!("Ignored the invalid Vary header name {:?}", header.as_ref());
+                return;
+            };
+            let mut vary = self.vary.write();
+            if !vary.contains(&header) {
+                vary.push(header);
+            }
+        }
+
+        /// Get the request headers registered with [`DioxusServerContext::add_vary`]
+        pub fn vary(&self) -> Vec<http::HeaderName> {
+            self.vary.read().clone()
+        }
+
+        /// Combine the registered headers with any `Vary` header already on the response into a single `Vary` header
+        pub(crate) fn apply_vary(&self, headers: &mut http::HeaderMap) {
+            let registered = self.vary.read();
+            if registered.is_empty() {
+                return;
+            }
+            let mut names: Vec<String> = Vec::new();
+            let existing = headers
+                .get_all(http::header::VARY)
+                .iter()
+                .filter_map(|vary| vary.to_str().ok())
+                .flat_map(|vary| vary.split(','))
+                .map(|name| name.trim().to_ascii_lowercase())
+                .filter(|name| !name.is_empty());
+            for name in existing.chain(registered.iter().map(|name| name.as_str().to_string())) {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+            // `*` already says the response varies on everything
+            let vary = if names.iter().any(|name| name == "*") {
+                "*".to_string()
+            } else {
+                names.join(", ")
+            };
+            if let Ok(vary) = http::HeaderValue::from_str(&vary) {
+                headers.insert(http::header::VARY, vary);
+            }
+        }
+
+        /// Redirect the client to a location with a `303 See Other` response once the current server function or page is done.
+        ///
+        /// Plain form submissions are redirected with the status and a `Location` header. Server functions called from
+        /// client side code keep their response and also send the `Location` header with the `serverfnredirect` header, so
+        /// the client can navigate with its redirect hook instead of the browser silently following the redirect.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[server]
+        /// async fn login(username: String, password: String) -> Result<(), ServerFnError> {
+        ///     // Check the credentials...
+        ///     # let _ = (username, password);
+        ///     server_context().redirect(http::HeaderValue::from_static("/dashboard"));
+        ///     Ok(())
+        /// }
+        /// ```
+        pub fn redirect(&self, location: impl Into<http::HeaderValue>) {
+            self.redirect_with_status(location, http::StatusCode::SEE_OTHER);
+        }
+
+        /// Redirect the client to a location with a custom redirect status like `302 Found` or `307 Temporary Redirect`.
+        /// See [`DioxusServerContext::redirect`]
+        pub fn redirect_with_status(
+            &self,
+            location: impl Into<http::HeaderValue>,
+            status: http::StatusCode,
+        ) {
+            *self.redirect.write() = Some((location.into(), status));
+        }
+
+        /// Get the location and status of the redirect set with [`DioxusServerContext::redirect`]
+        pub(crate) fn redirect_location(&self) -> Option<(http::HeaderValue, http::StatusCode)> {
+            self.redirect.read().clone()
+        }
+
+        /// Copy everything a render set on the response of another server context into this one: the status, version,
+        /// headers and extensions of the response, the locale, the `Vary` headers, the redirect and whether the page is
+        /// static. Requests that share a render receive the response the render built for the first request. Headers
+        /// that belong to a single request, like the cookies and the request id, are not copied
+        pub(crate) fn copy_response_from(&self, other: &Self) {
+            {
+                let from = other.response_parts.read();
+                let mut to = self.response_parts.write();
+                to.status = from.status;
+                to.version = from.version;
+                let mut headers = from.headers.clone();
+                for name in PER_REQUEST_HEADERS {
+                    headers.remove(*name);
+                }
+                to.headers.extend(headers);
+                to.extensions.extend(from.extensions.clone());
+            }
+            if let Some(locale) = other.locale() {
+                self.set_locale(locale);
+            }
+            for header in other.vary.read().iter() {
+                self.add_vary(header);
+            }
+            if let Some(redirect) = other.redirect_location() {
+                *self.redirect.write() = Some(redirect);
+            }
+            if other.is_static() {
+                self.set_static();
+            }
+        }
+
+        /// Render the current page as static html without hydration. The page ships without any client side code, so it
+        /// won't be interactive. This must be called during the first render of the page, before any suspense resolves.
+        ///
+        /// See [`ServeConfigBuilder::static_routes`](crate::prelude::ServeConfigBuilder::static_routes) to make routes static
+        /// from the server config instead.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// fn terms_of_service() -> Element {
+        ///     server_only! {
+        ///         server_context().set_static();
+        ///     }
+        ///     rsx! { h1 { "Terms of service" } }
+        /// }
+        /// ```
+        pub fn set_static(&self) {
+            *self.static_page.write() = true;
+        }
+
+        /// Check if the current page is rendered as static html without hydration
+        pub fn is_static(&self) -> bool {
+            *self.static_page.read()
+        }
+
+        /// Tell search engines how to index the response with an `X-Robots-Tag` header, like `noindex` or
+        /// `noindex, nofollow`. Invalid directives are ignored. Use [`DioxusServerContext::set_robots_with_meta`] to also
+        /// add a `<meta name="robots">` tag to server rendered pages.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[server]
+        /// async fn private_report() -> Result<String, ServerFnError> {
+        ///     server_context().set_robots("noindex");
+        ///     Ok("Quarterly numbers".to_string())
+        /// }
+        /// ```
+        pub fn set_robots(&self, directive: &str) {
+            match http::HeaderValue::from_str(directive) {
+                Ok(directive) => {
+                    self.response_parts_mut()
+                        .headers
+                        .insert(X_ROBOTS_TAG, directive);
+                }
+                Err(_) => tracing::warn!("Ignored the invalid robots directive {directive:?}"),
+            }
+        }
+
+        /// Tell search engines how to index the current page with an `X-Robots-Tag` header and a `<meta name="robots">`
+        /// tag in the head of the page. For server rendered pages, this must be called during the initial render, before
+        /// the head of the page is sent.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// fn account_settings() -> Element {
+        ///     server_only! {
+        ///         server_context().set_robots_with_meta("noindex, nofollow");
+        ///     }
+        ///     rsx! { h1 { "Account settings" } }
+        /// }
+        /// ```
+        pub fn set_robots_with_meta(&self, directive: &str) {
+            self.set_robots(directive);
+            *self.robots_meta.write() = Some(directive.to_string());
+        }
+
+        /// Get the directive for the `<meta name="robots">` tag of the current page
+        pub(crate) fn robots_meta(&self) -> Option<String> {
+            self.robots_meta.read().clone()
+        }
+
+        /// Get the address of the peer the current request was received from. Behind a reverse proxy, this is the address
+        /// of the proxy. Use [`DioxusServerContext::client_ip`] to get the IP of the client instead.
+        ///
+        /// The address is only known if the router is served with
+        /// [`into_make_service_with_connect_info::<SocketAddr>`](axum::Router::into_make_service_with_connect_info).
+        /// Otherwise this returns `None`.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[server]
+        /// async fn log_peer() -> Result<(), ServerFnError> {
+        ///     let peer = server_context().peer_addr();
+        ///     tracing::info!("Request from {peer:?}");
+        ///     Ok(())
+        /// }
+        /// ```
+        #[cfg(feature = "axum")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "axum")))]
+        pub fn peer_addr(&self) -> Option<std::net::SocketAddr> {
+            self.parts
+                .read()
+                .extensions
+                .get::<axum::extract::ConnectInfo<std::net::SocketAddr>>()
+                .map(|connect_info| connect_info.0)
+        }
+
+        /// Get the IP of the client that sent the current request.
+        ///
+        /// If the `X-Forwarded-For` header is trusted, the IP is the first address in the header, which is the client the
+        /// first proxy received the request from. Trust the header with
+        /// [`ServeConfigBuilder::trust_forwarded_headers`](crate::prelude::ServeConfigBuilder::trust_forwarded_headers)
+        /// for server rendered pages and [`ServerFnConfig::trust_forwarded_headers`](crate::prelude::ServerFnConfig::trust_forwarded_headers)
+        /// for server functions. Only trust the header if every request passes through a proxy that overwrites it,
+        /// because anyone can send it. Otherwise, the IP is read from [`DioxusServerContext::peer_addr`].
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[server]
+        /// async fn country() -> Result<String, ServerFnError> {
+        ///     let ip = server_context()
+        ///         .client_ip()
+        ///         .ok_or_else(|| ServerFnError::new("the client IP is unknown"))?;
+        ///     Ok(format!("Looking up the country of {ip}"))
+        /// }
+        /// ```
+        #[cfg(feature = "axum")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "axum")))]
+        pub fn client_ip(&self) -> Option<std::net::IpAddr> {
+            if *self.trust_forwarded_for.read() {
+                let parts = self.parts.read();
+                let forwarded_for = parts
+                    .headers
+                    .get_all("x-forwarded-for")
+                    .iter()
+                    .filter_map(|header| header.to_str().ok())
+                    .flat_map(|header| header.split(','))
+                    .find_map(|ip| ip.trim().parse().ok());
+                if forwarded_for.is_some() {
+                    return forwarded_for;
+                }
+            }
+            self.peer_addr().map(|peer| peer.ip())
+        }
+
+        /// Set if the `X-Forwarded-For` header of the current request is trusted
+        #[allow(unused)]
+        pub(crate) fn set_trust_forwarded_for(&self, trust: bool) {
+            *self.trust_forwarded_for.write() = trust;
+        }
+
+        /// Deserialize the query string of the current request, like `?page=2&sort=name`. The query is parsed the same way as
+        /// the arguments of `GetUrl` server functions, so nested values like `filter[tag]=rust` are supported. Requests
+        /// without a query string deserialize from an empty query.
+        ///
+        /// # Example
+        ///
+        /// ```rust, no_run
+        /// # use dioxus::prelude::*;
+        /// #[derive(serde::Deserialize, Default)]
+        /// struct Pagination {
+        ///     page: Option<u32>,
+        /// }
+        ///
+        /// fn Posts() -> Element {
+        ///     let mut page = 1;
+        ///     server_only! {
+        ///         // A malformed query falls back to the first page instead of failing the render
+        ///         let pagination: Pagination = server_context().query().unwrap_or_default();
+        ///         page = pagination.page.unwrap_or(1);
+        ///     }
+        ///     rsx! { "Page {page}" }
+        /// }
+        /// ```
+        pub fn query<T: serde::de::DeserializeOwned>(&self) -> Result<T, InvalidQuery> {
+            let parts = self.parts.read();
+            let query = parts.uri.query().unwrap_or_default();
+            serde_qs::from_str(query).map_err(|err| InvalidQuery(err.to_string()))
+        }
+
+        /// Get how long the server took to render the initial frame of the current page. This is only set once the initial
+        /// frame is rendered, so it is meant for logging after the response is created.
+        pub fn render_duration(&self) -> Option<std::time::Duration> {
+            *self.render_duration.read()
+        }
+
+        /// Set how long the server took to render the initial frame of the current page
+        pub(crate) fn set_render_duration(&self, duration: std::time::Duration) {
+            *self.render_duration.write() = Some(duration);
+        }
+
+        /// Set the nonce for the current response and send the matching `Content-Security-Policy` header
+        pub(crate) fn set_csp_nonce(&self, nonce: &str, policy: &str) {
+            *self.csp_nonce.write() = Some(Arc::from(nonce));
+            if let Ok(policy) = http::HeaderValue::from_str(policy) {
+                self.response_parts_mut()
+                    .headers
+                    .insert(http::header::CONTENT_SECURITY_POLICY, policy);
+            }
+        }
+
         /// Extract part of the request.
         ///
         #[doc = include_str!("../docs/request_origin.md")]
@@ -348,6 +1007,18 @@ impl<T: 'static> std::fmt::Display for NotFoundInServerContext<T> {
 
 impl<T: 'static> std::error::Error for NotFoundInServerContext<T> {}
 
+/// The query string of the request could not be deserialized. Returned by [`DioxusServerContext::query`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidQuery(String);
+
+impl std::fmt::Display for InvalidQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid query string: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidQuery {}
+
 /// Extract a value from the server context provided through the launch builder context or [`DioxusServerContext::insert`]
 ///
 /// Example:
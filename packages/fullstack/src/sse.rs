@@ -0,0 +1,190 @@
+//! Server functions that respond with a stream of Server-Sent Events
+
+use futures_util::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+#[cfg(feature = "axum")]
+use serde::Serialize;
+#[cfg(feature = "axum")]
+use server_fn::codec::IntoRes;
+use server_fn::codec::{Encoding, FromRes};
+use server_fn::error::{NoCustomError, ServerFnErrorSerde};
+use server_fn::response::ClientRes;
+use server_fn::ServerFnError;
+use std::fmt::Display;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::task::{Context, Poll};
+
+/// An output encoding for server functions that stream [Server-Sent Events](https://developer.mozilla.org/en-US/docs/Web/API/Server-sent_events)
+/// to the client. Use it with an [`SseStream`] output.
+///
+/// Each item of the stream is sent as a JSON encoded event as soon as it is ready, and the server sends a keep-alive
+/// comment while the stream is idle so proxies don't close the connection. The stream is polled inside the server context
+/// of the request, and it is dropped as soon as the client disconnects.
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// # use futures_util::StreamExt;
+/// #[server(output = Sse)]
+/// async fn notifications() -> Result<SseStream<String>, ServerFnError> {
+///     let ticks = futures_util::stream::iter(0..).then(|i| async move {
+///         tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+///         format!("Notification {i}")
+///     });
+///     Ok(SseStream::new(ticks))
+/// }
+///
+/// fn app() -> Element {
+///     let mut latest = use_signal(String::new);
+///     use_future(move || async move {
+///         let mut stream = notifications().await?.into_inner();
+///         while let Some(Ok(notification)) = stream.next().await {
+///             latest.set(notification);
+///         }
+///         Ok::<_, ServerFnError>(())
+///     });
+///     rsx! { "{latest}" }
+/// }
+/// ```
+pub struct Sse;
+
+impl Encoding for Sse {
+    const CONTENT_TYPE: &'static str = "text/event-stream";
+    const METHOD: http::Method = http::Method::POST;
+}
+
+/// A stream of events a server function sends to the client with the [`Sse`] encoding
+pub struct SseStream<T, CustErr = NoCustomError>(
+    Pin<Box<dyn Stream<Item = Result<T, ServerFnError<CustErr>>> + Send>>,
+);
+
+impl<T: 'static, CustErr: 'static> SseStream<T, CustErr> {
+    /// Create a new event stream from a stream of items
+    pub fn new(stream: impl Stream<Item = T> + Send + 'static) -> Self {
+        Self(Box::pin(stream.map(Ok)))
+    }
+
+    /// Create a new event stream from a stream of items that may fail. Errors are sent to the client without ending the stream
+    pub fn try_new(
+        stream: impl Stream<Item = Result<T, ServerFnError<CustErr>>> + Send + 'static,
+    ) -> Self {
+        Self(Box::pin(stream))
+    }
+
+    /// Get the stream of events
+    pub fn into_inner(self) -> impl Stream<Item = Result<T, ServerFnError<CustErr>>> + Send {
+        self.0
+    }
+}
+
+impl<T, CustErr> Stream for SseStream<T, CustErr> {
+    type Item = Result<T, ServerFnError<CustErr>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0.poll_next_unpin(cx)
+    }
+}
+
+/// The name of the event errors are sent as
+const ERROR_EVENT: &str = "error";
+
+#[cfg(feature = "axum")]
+impl<T, CustErr> IntoRes<Sse, http::Response<axum::body::Body>, CustErr> for SseStream<T, CustErr>
+where
+    T: Serialize + Send + 'static,
+    CustErr: FromStr + Display + Send + 'static,
+{
+    async fn into_res(self) -> Result<http::Response<axum::body::Body>, ServerFnError<CustErr>> {
+        use axum::response::sse::{Event, KeepAlive};
+        use axum::response::IntoResponse;
+
+        // The response body is polled after the server function returns, so keep providing the server context of the
+        // request while the stream runs
+        let server_context = crate::prelude::server_context();
+        let mut stream = self.0;
+        let events = futures_util::stream::poll_fn(move |cx| {
+            crate::prelude::with_server_context(server_context.clone(), || {
+                stream.poll_next_unpin(cx)
+            })
+        })
+        .map(|item| match item {
+            Ok(item) => Event::default().json_data(item),
+            Err(err) => Ok(Event::default()
+                .event(ERROR_EVENT)
+                .data(err.ser().unwrap_or_default().replace('\r', ""))),
+        });
+
+        Ok(axum::response::sse::Sse::new(events)
+            .keep_alive(KeepAlive::default())
+            .into_response())
+    }
+}
+
+impl<T, CustErr, Response> FromRes<Sse, Response, CustErr> for SseStream<T, CustErr>
+where
+    T: DeserializeOwned + Send + 'static,
+    CustErr: FromStr + Display + Send + 'static,
+    Response: ClientRes<CustErr> + Send,
+{
+    async fn from_res(res: Response) -> Result<Self, ServerFnError<CustErr>> {
+        let body = res.try_into_stream()?;
+        let events = futures_util::stream::unfold(
+            (Box::pin(body), Vec::new()),
+            |(mut body, mut buffer)| async move {
+                loop {
+                    // Events are separated by a blank line. Chunks may split a character, so only decode complete events
+                    if let Some(end) = buffer.windows(2).position(|window| window == b"\n\n") {
+                        let event = String::from_utf8_lossy(&buffer[..end]).into_owned();
+                        buffer.drain(..end + 2);
+                        match parse_event::<T, CustErr>(&event) {
+                            Some(item) => return Some((item, (body, buffer))),
+                            None => continue,
+                        }
+                    }
+                    match body.next().await? {
+                        Ok(chunk) => buffer.extend(chunk.iter().filter(|byte| **byte != b'\r')),
+                        Err(err) => {
+                            return Some((
+                                Err(ServerFnError::Response(err.to_string())),
+                                (body, buffer),
+                            ))
+                        }
+                    }
+                }
+            },
+        );
+        Ok(Self(Box::pin(events)))
+    }
+}
+
+/// Parse one event. Returns `None` for events without data like keep-alive comments
+fn parse_event<T: DeserializeOwned, CustErr: FromStr + Display>(
+    event: &str,
+) -> Option<Result<T, ServerFnError<CustErr>>> {
+    let mut name = None;
+    let mut data: Option<String> = None;
+    for line in event.lines() {
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => name = Some(value),
+            "data" => match &mut data {
+                Some(data) => {
+                    data.push('\n');
+                    data.push_str(value);
+                }
+                None => data = Some(value.to_string()),
+            },
+            _ => {}
+        }
+    }
+
+    let data = data?;
+    Some(if name == Some(ERROR_EVENT) {
+        Err(ServerFnError::de(&data))
+    } else {
+        serde_json::from_str(&data).map_err(|err| ServerFnError::Deserialization(err.to_string()))
+    })
+}
@@ -61,6 +61,7 @@ impl Display for MountPath {
 pub(crate) struct StreamingRenderer<E = std::convert::Infallible> {
     channel: RwLock<Sender<Result<String, E>>>,
     current_path: RwLock<MountPath>,
+    csp_nonce: Option<Arc<str>>,
 }
 
 impl<E> StreamingRenderer<E> {
@@ -75,9 +76,16 @@ impl<E> StreamingRenderer<E> {
         Self {
             channel: render_into.into(),
             current_path: Default::default(),
+            csp_nonce: None,
         }
     }
 
+    /// Add a content security policy nonce to the scripts that hydrate resolved placeholders
+    pub(crate) fn with_csp_nonce(mut self, csp_nonce: Option<Arc<str>>) -> Self {
+        self.csp_nonce = csp_nonce;
+        self
+    }
+
     /// Render a new chunk of html that will never change
     pub(crate) fn render(&self, html: impl Display) {
         _ = self
@@ -119,9 +127,10 @@ impl<E> StreamingRenderer<E> {
         html(into)?;
         // Restore the old path
         *self.current_path.write().unwrap() = old_path;
+        let nonce = crate::render::NonceAttribute(self.csp_nonce.as_deref());
         write!(
             into,
-            r#"</div><script>window.dx_hydrate([{id}], "{data}")</script>"#
+            r#"</div><script{nonce}>window.dx_hydrate([{id}], "{data}")</script>"#
         )
     }
 
@@ -142,3 +151,46 @@ impl Display for Mount {
         write!(f, "{}", self.id)
     }
 }
+
+/// Group the chunks of a streaming render according to the [`StreamFlushStrategy`](crate::prelude::StreamFlushStrategy)
+pub(crate) fn flush_chunks<E: Send + 'static>(
+    strategy: crate::prelude::StreamFlushStrategy,
+    stream: impl futures_util::Stream<Item = Result<String, E>> + Send + 'static,
+) -> std::pin::Pin<Box<dyn futures_util::Stream<Item = Result<String, E>> + Send>> {
+    use crate::prelude::StreamFlushStrategy;
+    use futures_util::StreamExt;
+
+    match strategy {
+        StreamFlushStrategy::EveryBoundary => Box::pin(stream),
+        StreamFlushStrategy::Never => Box::pin(futures_util::stream::once(async move {
+            let mut stream = std::pin::pin!(stream);
+            let mut html = String::new();
+            while let Some(chunk) = stream.next().await {
+                html.push_str(&chunk?);
+            }
+            Ok(html)
+        })),
+        StreamFlushStrategy::Coalesce(window) => Box::pin(futures_util::stream::unfold(
+            (Box::pin(stream), None),
+            move |(mut stream, pending_error)| async move {
+                // An error that arrived while chunks were being collected is sent after those chunks
+                if let Some(err) = pending_error {
+                    return Some((Err(err), (stream, None)));
+                }
+                let mut html = match stream.next().await? {
+                    Ok(html) => html,
+                    Err(err) => return Some((Err(err), (stream, None))),
+                };
+                let deadline = tokio::time::Instant::now() + window;
+                loop {
+                    match tokio::time::timeout_at(deadline, stream.next()).await {
+                        Ok(Some(Ok(chunk))) => html.push_str(&chunk),
+                        Ok(Some(Err(err))) => return Some((Ok(html), (stream, Some(err)))),
+                        // The stream ended or the window closed. Send what we have
+                        Ok(None) | Err(_) => return Some((Ok(html), (stream, None))),
+                    }
+                }
+            },
+        )),
+    }
+}
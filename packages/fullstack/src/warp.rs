@@ -0,0 +1,287 @@
+//! Warp filters for serving Dioxus fullstack applications
+//!
+//! These filters let you embed server functions and server side rendering into an existing warp service. They share
+//! the [`ServeConfig`], [`SSRState`] and [`DioxusServerContext`] the [`DioxusRouterExt`] uses, so a component renders
+//! the same way with either integration.
+//!
+//! # Example
+//! ```rust, no_run
+//! # use dioxus_lib::prelude::*;
+//! # use dioxus_fullstack::prelude::*;
+//! use warp::Filter;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let api = warp::path!("api" / "health").map(|| "ok");
+//!     let routes = api
+//!         .or(dioxus_fullstack::warp::dioxus_filter(ServeConfig::new().unwrap(), app));
+//!     warp::serve(routes).run(([127, 0, 0, 1], 8080)).await;
+//! }
+//!
+//! fn app() -> Element {
+//!     rsx! { "Hello World" }
+//! }
+//! ```
+
+use crate::prelude::*;
+use axum::{body::Body, response::IntoResponse};
+use bytes::Buf;
+use dioxus_lib::prelude::Element;
+use futures_util::{Stream, StreamExt};
+use std::collections::HashMap;
+use std::sync::Arc;
+use warp::{filters::path::FullPath, Filter, Rejection};
+
+/// Serve all server functions registered with the default [`ServerFnConfig`]. Requests that don't match a server
+/// function are rejected so they fall through to the next filter.
+pub fn server_fn_filter(
+) -> impl Filter<Extract = (warp::reply::Response,), Error = Rejection> + Clone {
+    server_fn_filter_with_config(ServerFnConfig::new())
+}
+
+/// Serve all server functions with a set of context providers that are available in every server function through
+/// [`extract`](crate::prelude::extract).
+pub fn server_fn_filter_with_context(
+    context_providers: crate::server::ContextProviders,
+) -> impl Filter<Extract = (warp::reply::Response,), Error = Rejection> + Clone {
+    server_fn_filter_with_config(ServerFnConfig::new().context_providers(context_providers))
+}
+
+/// Serve the server functions a [`ServerFnConfig`] includes. This is the warp version of
+/// [`DioxusRouterExt::register_server_functions_with_config`].
+pub fn server_fn_filter_with_config(
+    config: ServerFnConfig,
+) -> impl Filter<Extract = (warp::reply::Response,), Error = Rejection> + Clone {
+    let config = Arc::new(config);
+
+    // Map each route to the server function it serves and the method the server function expects
+    let routes: Arc<HashMap<String, (&'static str, http::Method)>> = Arc::new(
+        server_fn::axum::server_fn_paths()
+            .filter(|(path, _)| config.includes(path))
+            .map(|(path, method)| (config.route_for(path), (path, method)))
+            .collect(),
+    );
+
+    request_parts().and(warp::body::stream()).and_then(
+        move |method: warp::http::Method,
+              path: FullPath,
+              query: Option<String>,
+              headers: warp::http::HeaderMap,
+              body| {
+            let config = config.clone();
+            let routes = routes.clone();
+            async move {
+                let Some((server_fn_path, server_fn_method)) = routes.get(path.as_str()) else {
+                    return Err(warp::reject::not_found());
+                };
+                if method.as_str() != server_fn_method.as_str() {
+                    return Err(warp::reject::not_found());
+                }
+
+                let request = into_axum_request(method, &path, query, headers, axum_body(body))?;
+                let context_providers = config.context_providers.clone();
+                let response = crate::server::handle_server_fns_inner(
+                    server_fn_path,
+                    config,
+                    move |server_context| {
+                        for index in 0..context_providers.len() {
+                            let context_providers = context_providers.clone();
+                            server_context
+                                .insert_boxed_factory(Box::new(move || context_providers[index]()));
+                        }
+                    },
+                    request,
+                )
+                .await
+                .into_response();
+                Ok(into_warp_response(response))
+            }
+        },
+    )
+}
+
+/// Serve the static assets in the public directory the CLI bundles with the server. This is the warp version of
+/// [`DioxusRouterExt::serve_static_assets`]. Requests for files that don't exist are rejected so they fall through to
+/// the next filter.
+pub fn static_assets_filter(
+) -> impl Filter<Extract = (warp::reply::Response,), Error = Rejection> + Clone {
+    static_assets_filter_with_config(StaticAssetsConfig::new())
+}
+
+/// Serve the static assets in the public directory with a [`StaticAssetsConfig`]. This is the warp version of
+/// [`DioxusRouterExt::serve_static_assets_with_config`].
+pub fn static_assets_filter_with_config(
+    config: StaticAssetsConfig,
+) -> impl Filter<Extract = (warp::reply::Response,), Error = Rejection> + Clone {
+    // Reuse the axum services so assets are served with the same compression and range support
+    let router = axum::Router::new().serve_static_assets_with_config(config);
+
+    request_parts().and_then(
+        move |method: warp::http::Method,
+              path: FullPath,
+              query: Option<String>,
+              headers: warp::http::HeaderMap| {
+            let router = router.clone();
+            async move {
+                use tower::ServiceExt;
+
+                let request = into_axum_request(method, &path, query, headers, Body::empty())?;
+                let response = router
+                    .oneshot(request)
+                    .await
+                    .unwrap_or_else(|err| match err {});
+                if response.status() == http::StatusCode::NOT_FOUND {
+                    return Err(warp::reject::not_found());
+                }
+                Ok(into_warp_response(response))
+            }
+        },
+    )
+}
+
+/// Server render the application for `GET` requests. This is the warp version of [`render_handler`].
+///
+/// # Example
+/// ```rust, no_run
+/// # use dioxus_lib::prelude::*;
+/// # use dioxus_fullstack::prelude::*;
+/// use warp::Filter;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let routes = dioxus_fullstack::warp::server_fn_filter()
+///         .or(dioxus_fullstack::warp::render_filter(RenderHandleState::new(
+///             ServeConfig::new().unwrap(),
+///             app,
+///         )));
+///     warp::serve(routes).run(([127, 0, 0, 1], 8080)).await;
+/// }
+///
+/// fn app() -> Element {
+///     rsx! { "Hello World" }
+/// }
+/// ```
+pub fn render_filter(
+    state: RenderHandleState,
+) -> impl Filter<Extract = (warp::reply::Response,), Error = Rejection> + Clone {
+    optional_render_filter(Some(state))
+}
+
+/// Serve server functions, static assets and the server rendered application. This is the warp version of
+/// [`DioxusRouterExt::serve_dioxus_application`].
+pub fn dioxus_filter<Cfg, Error>(
+    cfg: Cfg,
+    app: fn() -> Element,
+) -> impl Filter<Extract = (warp::reply::Response,), Error = Rejection> + Clone
+where
+    Cfg: TryInto<ServeConfig, Error = Error>,
+    Error: std::error::Error,
+{
+    let state = match cfg.try_into() {
+        Ok(cfg) => Some(RenderHandleState::new(cfg, app)),
+        Err(err) => {
+            tracing::trace!("Failed to create render handler. This is expected if you are only using fullstack for desktop/mobile server functions: {}", err);
+            None
+        }
+    };
+
+    server_fn_filter()
+        .or(static_assets_filter())
+        .unify()
+        .or(optional_render_filter(state))
+        .unify()
+}
+
+/// Server render the application if there is a render state or reject every request otherwise
+fn optional_render_filter(
+    state: Option<RenderHandleState>,
+) -> impl Filter<Extract = (warp::reply::Response,), Error = Rejection> + Clone {
+    warp::get().and(request_parts()).and_then(
+        move |method: warp::http::Method,
+              path: FullPath,
+              query: Option<String>,
+              headers: warp::http::HeaderMap| {
+            let state = state.clone();
+            async move {
+                let Some(state) = state else {
+                    return Err(warp::reject::not_found());
+                };
+                let request = into_axum_request(method, &path, query, headers, Body::empty())?;
+                let response = render_handler(axum::extract::State(state), request)
+                    .await
+                    .into_response();
+                Ok(into_warp_response(response))
+            }
+        },
+    )
+}
+
+/// Extract the parts of a request the filters need to rebuild it as an axum request
+fn request_parts() -> impl Filter<
+    Extract = (
+        warp::http::Method,
+        FullPath,
+        Option<String>,
+        warp::http::HeaderMap,
+    ),
+    Error = Rejection,
+> + Clone {
+    warp::method()
+        .and(warp::path::full())
+        .and(
+            warp::query::raw()
+                .map(Some)
+                .or(warp::any().map(|| None))
+                .unify(),
+        )
+        .and(warp::header::headers_cloned())
+}
+
+/// Convert a warp body stream into an axum body
+fn axum_body(body: impl Stream<Item = Result<impl Buf, warp::Error>> + Send + 'static) -> Body {
+    Body::from_stream(
+        body.map(|chunk| chunk.map(|mut chunk| chunk.copy_to_bytes(chunk.remaining()))),
+    )
+}
+
+/// Rebuild a warp request as an axum request. Warp uses an older version of the `http` crate, so every part of the
+/// request is converted by value
+fn into_axum_request(
+    method: warp::http::Method,
+    path: &FullPath,
+    query: Option<String>,
+    headers: warp::http::HeaderMap,
+    body: Body,
+) -> Result<http::Request<Body>, Rejection> {
+    let uri = match query {
+        Some(query) => format!("{}?{query}", path.as_str()),
+        None => path.as_str().to_string(),
+    };
+    let mut request = http::Request::builder().method(method.as_str()).uri(uri);
+    for (name, value) in headers.iter() {
+        request = request.header(name.as_str(), value.as_bytes());
+    }
+    request.body(body).map_err(|err| {
+        tracing::error!("Failed to convert a warp request: {err}");
+        warp::reject::reject()
+    })
+}
+
+/// Convert an axum response into a warp response. The body is streamed, so server rendered pages still stream
+/// suspense boundaries as they resolve
+fn into_warp_response(response: http::Response<Body>) -> warp::reply::Response {
+    let (parts, body) = response.into_parts();
+    let mut response =
+        warp::reply::Response::new(warp::hyper::Body::wrap_stream(body.into_data_stream()));
+    *response.status_mut() = warp::http::StatusCode::from_u16(parts.status.as_u16())
+        .unwrap_or(warp::http::StatusCode::INTERNAL_SERVER_ERROR);
+    for (name, value) in parts.headers.iter() {
+        if let (Ok(name), Ok(value)) = (
+            warp::http::HeaderName::from_bytes(name.as_str().as_bytes()),
+            warp::http::HeaderValue::from_bytes(value.as_bytes()),
+        ) {
+            response.headers_mut().append(name, value);
+        }
+    }
+    response
+}
@@ -0,0 +1,52 @@
+//! Make sure the Accept header is matched according to the configured mode
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use dioxus::prelude::*;
+use http::{header::ACCEPT, Request, StatusCode};
+
+mod common;
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+async fn status(accept_matching: AcceptMatching, accept: &str) -> StatusCode {
+    let cfg = common::serve_config()
+        .accept_matching(accept_matching)
+        .build()
+        .unwrap();
+    common::respond(
+        common::render_router(cfg, app),
+        Request::get("/")
+            .header(ACCEPT, accept)
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await
+    .status()
+}
+
+#[tokio::test]
+async fn lenient_matching_accepts_any_mention_of_html() {
+    assert_eq!(
+        status(AcceptMatching::Lenient, "text/html;q=0").await,
+        StatusCode::OK
+    );
+}
+
+#[tokio::test]
+async fn strict_matching_honors_quality_values() {
+    assert_eq!(
+        status(AcceptMatching::Strict, "text/html;q=0, */*").await,
+        StatusCode::NOT_ACCEPTABLE
+    );
+    assert_eq!(
+        status(AcceptMatching::Strict, "application/json, text/*;q=0.5").await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        status(AcceptMatching::Strict, "application/json").await,
+        StatusCode::NOT_ACCEPTABLE
+    );
+}
@@ -0,0 +1,57 @@
+//! Make sure the access log records every kind of route the router extension adds
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, routing::get, Router};
+use dioxus::prelude::*;
+use http::{header::CONTENT_TYPE, Request, StatusCode};
+
+mod common;
+use common::PublicAssets;
+
+#[server(endpoint = "access_log_ping")]
+async fn ping() -> Result<(), ServerFnError> {
+    Ok(())
+}
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+#[tokio::test]
+async fn every_route_is_logged_with_its_kind() {
+    common::capture_logs();
+    let assets = PublicAssets::new("access-log");
+    assets.write("main.js", "console.log('hello world');");
+
+    let router = Router::new()
+        .route("/version", get(|| async { "1.0.0" }))
+        .serve_dioxus_application(common::serve_config(), app)
+        .with_access_log(|record| format!("access-log-test {record}"));
+    let requests = [
+        Request::get("/about?tab=team")
+            .header("x-request-id", "page-request")
+            .body(Body::empty()),
+        Request::post("/api/access_log_ping")
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::empty()),
+        Request::get(assets.route("main.js")).body(Body::empty()),
+        Request::get("/version").body(Body::empty()),
+    ];
+    for request in requests {
+        let response = common::respond(router.clone(), request.unwrap()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let logs = common::logs();
+    let line = |needle: &str| {
+        logs.lines()
+            .find(|line| line.contains("access-log-test") && line.contains(needle))
+            .unwrap_or_else(|| panic!("no access log for {needle} in {logs}"))
+            .to_string()
+    };
+    let page = line("GET /about?tab=team 200");
+    assert!(page.contains("kind=ssr request_id=page-request"), "{page}");
+    assert!(line("POST /api/access_log_ping 200").contains("kind=server_fn"));
+    assert!(line(&format!("GET {} 200", assets.route("main.js"))).contains("kind=static"));
+    assert!(line("GET /version 200").contains("kind=other"));
+}
@@ -0,0 +1,58 @@
+//! Make sure applications can build their virtual dom with an async factory
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{Request, StatusCode};
+use std::time::Duration;
+
+mod common;
+
+#[derive(Clone)]
+struct Tenant(String);
+
+fn app() -> Element {
+    let tenant = use_context::<Tenant>();
+    rsx! { "Welcome to {tenant.0}" }
+}
+
+fn router() -> Router {
+    let cfg = common::serve_config().build().unwrap();
+    Router::new().serve_dioxus_application_with_async_factory(cfg, || {
+        Box::pin(async move {
+            // Load the configuration for the tenant of the request before building the root
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            let tenant = server_context()
+                .request_parts()
+                .headers
+                .get("x-tenant")
+                .and_then(|tenant| tenant.to_str().ok())
+                .map(str::to_string)
+                .ok_or("unknown tenant")?;
+            Ok(VirtualDom::new(app).with_root_context(Tenant(tenant)))
+        })
+    })
+}
+
+#[tokio::test]
+async fn the_factory_reads_the_request() {
+    let response = common::respond(
+        router(),
+        Request::get("/")
+            .header("x-tenant", "acme")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let page = common::body_string(response).await;
+    assert!(page.contains("Welcome to acme"), "{page}");
+}
+
+#[tokio::test]
+async fn factory_errors_respond_with_an_internal_server_error() {
+    let response = common::respond(router(), Request::get("/").body(Body::empty()).unwrap()).await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let page = common::body_string(response).await;
+    assert!(page.contains("unknown tenant"), "{page}");
+}
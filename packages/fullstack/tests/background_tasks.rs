@@ -0,0 +1,45 @@
+//! Make sure background tasks run while the router is alive and are cancelled when it is dropped
+#![cfg(feature = "axum")]
+
+use axum::Router;
+use dioxus_fullstack::prelude::*;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+/// Tells the test when the task it is moved into is dropped
+struct DropSignal(Option<oneshot::Sender<()>>);
+
+impl Drop for DropSignal {
+    fn drop(&mut self) {
+        if let Some(dropped) = self.0.take() {
+            _ = dropped.send(());
+        }
+    }
+}
+
+#[tokio::test]
+async fn tasks_run_until_the_router_is_dropped() {
+    let (started, mut task_started) = mpsc::unbounded_channel();
+    let (dropped, task_dropped) = oneshot::channel();
+    let dropped = std::sync::Mutex::new(Some(dropped));
+    let router: Router = Router::new().spawn_background(Default::default(), move |_| {
+        let started = started.clone();
+        let signal = DropSignal(dropped.lock().unwrap().take());
+        async move {
+            let _signal = signal;
+            _ = started.send(());
+            std::future::pending::<()>().await;
+        }
+    });
+
+    tokio::time::timeout(Duration::from_secs(5), task_started.recv())
+        .await
+        .expect("the task should start when it is spawned")
+        .unwrap();
+
+    drop(router);
+    tokio::time::timeout(Duration::from_secs(5), task_dropped)
+        .await
+        .expect("the task should be cancelled when the router is dropped")
+        .unwrap();
+}
@@ -0,0 +1,30 @@
+//! Make sure the backpressure channel makes producers wait for slow clients
+#![cfg(feature = "server")]
+
+use dioxus_fullstack::prelude::*;
+use futures_util::StreamExt;
+use std::time::Duration;
+
+#[tokio::test]
+async fn send_waits_until_the_client_reads() {
+    let (mut sender, mut receiver) = backpressure_channel(2);
+    sender.send(1).await.unwrap();
+    sender.send(2).await.unwrap();
+
+    // The channel is full, so the next send waits for the client
+    let blocked = tokio::time::timeout(Duration::from_millis(50), sender.send(3)).await;
+    assert!(blocked.is_err());
+
+    assert_eq!(receiver.next().await, Some(1));
+    sender.send(3).await.unwrap();
+    assert_eq!(receiver.next().await, Some(2));
+    assert_eq!(receiver.next().await, Some(3));
+}
+
+#[tokio::test]
+async fn send_fails_after_the_client_disconnects() {
+    let (mut sender, receiver) = backpressure_channel(2);
+    drop(receiver);
+    assert!(sender.is_closed());
+    assert_eq!(sender.send(1).await, Err(ClientDisconnected));
+}
@@ -0,0 +1,35 @@
+//! Make sure server functions with the Binary encoding send their bytes as the response body
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{header::CONTENT_TYPE, Request, StatusCode};
+use tower::ServiceExt;
+
+const PNG: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+#[server(endpoint = "binary_qr_code", output = Binary)]
+async fn qr_code() -> Result<BinaryResponse, ServerFnError> {
+    Ok(BinaryResponse::new("image/png", PNG))
+}
+
+#[tokio::test]
+async fn bytes_are_sent_as_the_body() {
+    let response = Router::new()
+        .register_server_functions()
+        .oneshot(
+            Request::post("/api/binary_qr_code")
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[CONTENT_TYPE], "image/png");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(&body[..], PNG);
+}
@@ -0,0 +1,44 @@
+//! Make sure server functions can opt into a binary encoding
+#![cfg(all(feature = "axum", feature = "cbor"))]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use dioxus_fullstack::prelude::*;
+use http::{header::CONTENT_TYPE, Request, StatusCode};
+use tower::ServiceExt;
+
+#[server(endpoint = "cbor_sum", input = Cbor, output = Cbor)]
+async fn sum(numbers: Vec<u32>) -> Result<u32, ServerFnError> {
+    Ok(numbers.iter().sum())
+}
+
+#[tokio::test]
+async fn cbor_arguments_and_responses() {
+    let mut body = Vec::new();
+    ciborium::into_writer(
+        &Sum {
+            numbers: vec![1, 2, 3],
+        },
+        &mut body,
+    )
+    .unwrap();
+
+    let response = Router::new()
+        .register_server_functions()
+        .oneshot(
+            Request::post("/api/cbor_sum")
+                .header(CONTENT_TYPE, "application/cbor")
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[CONTENT_TYPE], "application/cbor");
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let total: u32 = ciborium::from_reader(body.as_ref()).unwrap();
+    assert_eq!(total, 6);
+}
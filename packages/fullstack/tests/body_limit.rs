@@ -0,0 +1,79 @@
+//! Make sure server functions reject request bodies that are larger than the body limit
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{header::CONTENT_TYPE, Request, StatusCode};
+use tower::ServiceExt;
+
+#[server(endpoint = "body_limit_echo")]
+async fn echo(data: String) -> Result<usize, ServerFnError> {
+    Ok(data.len())
+}
+
+fn router(config: ServerFnConfig) -> Router {
+    Router::new().register_server_functions_with_config(config)
+}
+
+fn form_body(len: usize) -> String {
+    format!("data={}", "a".repeat(len))
+}
+
+async fn status(config: ServerFnConfig, body: Body) -> StatusCode {
+    router(config)
+        .oneshot(
+            Request::post("/api/body_limit_echo")
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(body)
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn small_bodies_are_accepted() {
+    let config = ServerFnConfig::new().body_limit(64);
+    assert_eq!(
+        status(config, Body::from(form_body(16))).await,
+        StatusCode::OK
+    );
+}
+
+#[tokio::test]
+async fn large_bodies_are_rejected() {
+    let config = ServerFnConfig::new().body_limit(64);
+    assert_eq!(
+        status(config, Body::from(form_body(128))).await,
+        StatusCode::PAYLOAD_TOO_LARGE
+    );
+}
+
+#[tokio::test]
+async fn body_limits_can_be_overridden_per_server_function() {
+    let config = ServerFnConfig::new()
+        .body_limit(64)
+        .body_limit_for("/api/body_limit_echo", 1024);
+    assert_eq!(
+        status(config, Body::from(form_body(128))).await,
+        StatusCode::OK
+    );
+}
+
+#[tokio::test]
+async fn large_streaming_bodies_are_rejected() {
+    // A streaming body doesn't have a content length, so the limit is enforced while the body is read
+    let chunks = futures_util::stream::iter(
+        form_body(128)
+            .into_bytes()
+            .chunks(16)
+            .map(|chunk| Ok::<_, std::io::Error>(chunk.to_vec()))
+            .collect::<Vec<_>>(),
+    );
+    let config = ServerFnConfig::new().body_limit(64);
+    assert_eq!(
+        status(config, Body::from_stream(chunks)).await,
+        StatusCode::PAYLOAD_TOO_LARGE
+    );
+}
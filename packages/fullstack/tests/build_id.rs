@@ -0,0 +1,51 @@
+//! Make sure the build id is added to asset URLs without a content hash
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use dioxus::prelude::*;
+use http::Request;
+use tower::ServiceExt;
+
+mod common;
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+#[tokio::test]
+async fn build_id_is_added_to_unhashed_assets() {
+    let cfg = ServeConfig::builder()
+        .index_html(
+            r#"<!DOCTYPE html><html><head>
+<link rel="stylesheet" href="/assets/main.css">
+<link rel="stylesheet" href="/assets/theme-a1b2c3d4.css">
+<link rel="icon" href="https://cdn.example.com/favicon.ico">
+<script src="/assets/analytics.js?v=1"></script>
+</head><body><div id="main"></div></body></html>"#
+                .to_string(),
+        )
+        .build_id("1234")
+        .build()
+        .unwrap();
+    let router = common::render_router(cfg, app);
+
+    let response = router
+        .oneshot(Request::get("/").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let html = String::from_utf8(body.to_vec()).unwrap();
+
+    assert!(html.contains(r#"href="/assets/main.css?v=1234""#), "{html}");
+    assert!(
+        html.contains(r#"href="/assets/theme-a1b2c3d4.css""#),
+        "{html}"
+    );
+    assert!(
+        html.contains(r#"href="https://cdn.example.com/favicon.ico""#),
+        "{html}"
+    );
+    assert!(html.contains(r#"src="/assets/analytics.js?v=1""#), "{html}");
+}
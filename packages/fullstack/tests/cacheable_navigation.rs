@@ -0,0 +1,66 @@
+//! Make sure only top level navigations are read from and stored in the incremental cache
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, routing::get, Router};
+use dioxus::prelude::*;
+use http::Request;
+use std::sync::atomic::Ordering;
+
+mod common;
+use common::TempDir;
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+/// Send a request with each set of headers in order and count how many times the page was rendered after each one
+async fn builds_after(requests: &[&[(&str, &str)]]) -> Vec<usize> {
+    let cache = TempDir::new("cacheable-navigation");
+    let cfg = common::cached_serve_config(&cache).build().unwrap();
+    let (state, builds) = common::counting_state(cfg, app);
+    let router = Router::new().fallback(get(render_handler).with_state(state));
+
+    let mut counts = Vec::new();
+    for headers in requests {
+        let mut request = Request::get("/");
+        for (name, value) in *headers {
+            request = request.header(*name, *value);
+        }
+        let page = common::body_string(
+            common::respond(router.clone(), request.body(Body::empty()).unwrap()).await,
+        )
+        .await;
+        assert!(page.contains("Hello World"), "{page}");
+        counts.push(builds.load(Ordering::SeqCst));
+    }
+    counts
+}
+
+#[tokio::test]
+async fn navigations_are_cached() {
+    assert_eq!(builds_after(&[&[], &[]]).await, [1, 1]);
+}
+
+#[tokio::test]
+async fn prefetches_do_not_fill_the_cache() {
+    assert_eq!(
+        builds_after(&[&[("sec-purpose", "prefetch")], &[], &[]]).await,
+        [1, 2, 2]
+    );
+    assert_eq!(
+        builds_after(&[&[("purpose", "prefetch")], &[]]).await,
+        [1, 2]
+    );
+}
+
+#[tokio::test]
+async fn subresource_requests_do_not_fill_the_cache() {
+    assert_eq!(
+        builds_after(&[
+            &[("sec-fetch-dest", "iframe")],
+            &[("sec-fetch-dest", "document")]
+        ])
+        .await,
+        [1, 2]
+    );
+}
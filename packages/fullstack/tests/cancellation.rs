@@ -0,0 +1,54 @@
+//! Make sure the render stops when the client disconnects
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, routing::get, Router};
+use dioxus::prelude::*;
+use http::Request;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tower::ServiceExt;
+
+mod common;
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+/// Tells the test when the render it is moved into is dropped
+struct DropSignal(Option<oneshot::Sender<()>>);
+
+impl Drop for DropSignal {
+    fn drop(&mut self) {
+        if let Some(dropped) = self.0.take() {
+            _ = dropped.send(());
+        }
+    }
+}
+
+#[tokio::test]
+async fn dropped_requests_cancel_the_render() {
+    let cfg = common::serve_config().build().unwrap();
+    let (dropped, render_dropped) = oneshot::channel();
+    let dropped = std::sync::Mutex::new(Some(dropped));
+    let state = RenderHandleState::new_with_async_virtual_dom_factory(cfg, move || {
+        let signal = DropSignal(dropped.lock().unwrap().take());
+        Box::pin(async move {
+            let _signal = signal;
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(VirtualDom::new(app))
+        })
+    });
+    let request = Router::new()
+        .fallback(get(render_handler).with_state(state))
+        .oneshot(Request::get("/").body(Body::empty()).unwrap());
+
+    // The client gives up before the page is rendered
+    assert!(tokio::time::timeout(Duration::from_millis(100), request)
+        .await
+        .is_err());
+
+    tokio::time::timeout(Duration::from_secs(5), render_dropped)
+        .await
+        .expect("the render should be cancelled when the request is dropped")
+        .unwrap();
+}
@@ -0,0 +1,76 @@
+//! Make sure a single client can only have a limited number of requests in flight
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, extract::ConnectInfo, response::Response, routing::get, Router};
+use dioxus::prelude::*;
+use http::{Request, StatusCode};
+use std::net::SocketAddr;
+
+mod common;
+
+const PROXY: &str = "10.0.0.1";
+
+fn router() -> Router {
+    Router::new()
+        .route("/", get(|| async { "Hello World" }))
+        .limit_concurrency_per_client(
+            ClientConcurrencyLimit::new(1).trusted_proxy(PROXY.parse().unwrap()),
+        )
+}
+
+/// Send a request from the peer. The response holds its slot until the body is dropped
+async fn request(router: &Router, peer: Option<&str>, forwarded_for: Option<&str>) -> Response {
+    let mut request = Request::get("/");
+    if let Some(peer) = peer {
+        let peer: SocketAddr = format!("{peer}:443").parse().unwrap();
+        request = request.extension(ConnectInfo(peer));
+    }
+    if let Some(forwarded_for) = forwarded_for {
+        request = request.header("x-forwarded-for", forwarded_for);
+    }
+    common::respond(router.clone(), request.body(Body::empty()).unwrap()).await
+}
+
+#[tokio::test]
+async fn clients_over_the_limit_are_rejected_until_a_request_finishes() {
+    let router = router();
+    let in_flight = request(&router, Some("1.2.3.4"), None).await;
+    assert_eq!(in_flight.status(), StatusCode::OK);
+
+    let rejected = request(&router, Some("1.2.3.4"), None).await;
+    assert_eq!(rejected.status(), StatusCode::TOO_MANY_REQUESTS);
+    // Other clients have their own limit
+    let other = request(&router, Some("5.6.7.8"), None).await;
+    assert_eq!(other.status(), StatusCode::OK);
+
+    assert_eq!(common::body_string(in_flight).await, "Hello World");
+    let next = request(&router, Some("1.2.3.4"), None).await;
+    assert_eq!(next.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn forwarded_for_is_ignored_from_untrusted_peers() {
+    let router = router();
+    let _in_flight = request(&router, Some("1.2.3.4"), Some("9.9.9.1")).await;
+    let spoofed = request(&router, Some("1.2.3.4"), Some("9.9.9.2")).await;
+    assert_eq!(spoofed.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn forwarded_for_identifies_clients_behind_trusted_proxies() {
+    let router = router();
+    let _in_flight = request(&router, Some(PROXY), Some("9.9.9.1")).await;
+    let other = request(&router, Some(PROXY), Some("9.9.9.2")).await;
+    assert_eq!(other.status(), StatusCode::OK);
+    // The proxy appends the address it received the request from, so the client can't pick the address it is limited by
+    let spoofed = request(&router, Some(PROXY), Some("9.9.9.3, 9.9.9.1")).await;
+    assert_eq!(spoofed.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn requests_without_connection_info_are_not_limited() {
+    let router = router();
+    let _in_flight = request(&router, None, None).await;
+    let other = request(&router, None, None).await;
+    assert_eq!(other.status(), StatusCode::OK);
+}
@@ -0,0 +1,64 @@
+//! Make sure the client hints a browser sends are available on the first server render
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use dioxus::prelude::*;
+use http::Request;
+
+mod common;
+
+fn app() -> Element {
+    let hints = try_consume_context::<ClientHints>().unwrap_or_default();
+    let dpr = hints.device_pixel_ratio.unwrap_or(1.0);
+    let width = hints.viewport_width.unwrap_or(0);
+    let save_data = hints.save_data;
+    rsx! { "dpr={dpr} width={width} save_data={save_data}" }
+}
+
+async fn page(request: http::request::Builder) -> String {
+    let cfg = common::serve_config().build().unwrap();
+    let response = common::respond(
+        common::render_router(cfg, app),
+        request.uri("/").body(Body::empty()).unwrap(),
+    )
+    .await;
+    common::body_string(response).await
+}
+
+#[tokio::test]
+async fn the_first_render_reads_the_client_hints() {
+    let page = page(
+        Request::builder()
+            .header("Sec-CH-DPR", "2")
+            .header("Viewport-Width", "390")
+            .header("Save-Data", "on"),
+    )
+    .await;
+    assert!(page.contains("dpr=2 width=390 save_data=true"), "{page}");
+}
+
+#[tokio::test]
+async fn invalid_hints_are_ignored() {
+    let page = page(
+        Request::builder()
+            .header("DPR", "-3")
+            .header("Sec-CH-Viewport-Width", "wide")
+            .header("Save-Data", "off"),
+    )
+    .await;
+    assert!(page.contains("dpr=1 width=0 save_data=false"), "{page}");
+}
+
+#[test]
+fn prefixed_hints_win_over_legacy_hints() {
+    let (parts, _) = Request::builder()
+        .header("Sec-CH-DPR", "1.5")
+        .header("DPR", "3")
+        .body(())
+        .unwrap()
+        .into_parts();
+    assert_eq!(
+        ClientHints::from_parts(&parts).device_pixel_ratio,
+        Some(1.5)
+    );
+}
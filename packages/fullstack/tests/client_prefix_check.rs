@@ -0,0 +1,48 @@
+//! Make sure a server function prefix that differs from the prefix the client calls is reported at startup
+#![cfg(feature = "axum")]
+
+use axum::Router;
+use dioxus::prelude::*;
+
+mod common;
+
+#[server(endpoint = "prefix_check_ping")]
+async fn ping() -> Result<(), ServerFnError> {
+    Ok(())
+}
+
+fn prefix_errors(logs: &str, prefix: &str) -> usize {
+    logs.lines()
+        .filter(|line| line.contains("ERROR") && line.contains(&format!("{prefix:?}")))
+        .count()
+}
+
+#[test]
+fn mismatched_prefixes_are_logged_when_registering() {
+    common::capture_logs();
+    let _router: Router = Router::new().register_server_functions_with_config(
+        ServerFnConfig::new()
+            .prefix("/tenant-a")
+            .expected_client_prefix("/tenant-b"),
+    );
+
+    let logs = common::logs();
+    let line = logs
+        .lines()
+        .find(|line| line.contains("\"/tenant-b\""))
+        .unwrap_or_else(|| panic!("{logs}"));
+    assert!(line.contains("ERROR"), "{line}");
+    assert!(line.contains("\"/tenant-a\""), "{line}");
+}
+
+#[test]
+fn matching_prefixes_are_not_logged() {
+    common::capture_logs();
+    // The prefixes are normalized before they are compared
+    let _router: Router = Router::new().register_server_functions_with_config(
+        ServerFnConfig::new()
+            .prefix("/tenant-c")
+            .expected_client_prefix("tenant-c/"),
+    );
+    assert_eq!(prefix_errors(&common::logs(), "/tenant-c"), 0);
+}
@@ -0,0 +1,154 @@
+//! Make sure identical concurrent page requests share one render
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, routing::get, Router};
+use dioxus::prelude::*;
+use http::{
+    header::{COOKIE, SET_COOKIE},
+    HeaderValue, Request, Response, StatusCode,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tower::ServiceExt;
+
+mod common;
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+/// Sets a header and status on the response while it renders
+fn not_found() -> Element {
+    let server_context = server_context();
+    server_context.response_parts_mut().status = StatusCode::NOT_FOUND;
+    server_context
+        .response_parts_mut()
+        .headers
+        .insert("x-rendered", HeaderValue::from_static("yes"));
+    rsx! { "Hello World" }
+}
+
+/// A router that coalesces renders of the app and counts how many times the page was rendered
+fn router(app: fn() -> Element) -> (Router, Arc<AtomicUsize>) {
+    let cfg = common::serve_config()
+        .coalesce_renders(true)
+        .build()
+        .unwrap();
+    let renders = Arc::new(AtomicUsize::new(0));
+    let state = RenderHandleState::new_with_async_virtual_dom_factory(cfg, {
+        let renders = renders.clone();
+        move || {
+            renders.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move {
+                // Keep the render running long enough for the second request to join it
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                Ok(VirtualDom::new(app))
+            })
+        }
+    });
+    (
+        Router::new().fallback(get(render_handler).with_state(state)),
+        renders,
+    )
+}
+
+/// Send two requests at once and count how many times the page was rendered
+async fn renders(requests: [Request<Body>; 2]) -> usize {
+    let (router, renders) = router(app);
+    let [first, second] = requests.map(|request| {
+        let router = router.clone();
+        async move {
+            let response = router.oneshot(request).await.unwrap();
+            axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap()
+        }
+    });
+    let (first, second) = tokio::join!(first, second);
+    assert_eq!(first, second);
+    assert!(String::from_utf8(first.to_vec())
+        .unwrap()
+        .contains("Hello World"));
+    renders.load(Ordering::SeqCst)
+}
+
+#[tokio::test]
+async fn identical_requests_share_a_render() {
+    let request = || Request::get("/").body(Body::empty()).unwrap();
+    assert_eq!(renders([request(), request()]).await, 1);
+}
+
+#[tokio::test]
+async fn requests_with_cookies_render_on_their_own() {
+    let request = || {
+        Request::get("/")
+            .header(COOKIE, "session=1")
+            .body(Body::empty())
+            .unwrap()
+    };
+    assert_eq!(renders([request(), request()]).await, 2);
+}
+
+#[tokio::test]
+async fn requests_that_share_a_render_share_its_response_parts() {
+    let (router, renders) = router(not_found);
+    let [first, second] = [(), ()].map(|_| {
+        router
+            .clone()
+            .oneshot(Request::get("/").body(Body::empty()).unwrap())
+    });
+    let (first, second) = tokio::join!(first, second);
+    assert_eq!(renders.load(Ordering::SeqCst), 1);
+    for response in [first.unwrap(), second.unwrap()] {
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.headers()["x-rendered"], "yes");
+    }
+}
+
+/// Sets a cookie for the request that renders the page
+fn sets_cookie() -> Element {
+    let server_context = server_context();
+    let cookie = format!("visitor={}", server_context.request_id());
+    server_context
+        .response_parts_mut()
+        .headers
+        .insert(SET_COOKIE, HeaderValue::from_str(&cookie).unwrap());
+    rsx! { "Hello World" }
+}
+
+/// Send two requests with different request ids at once
+async fn requests_with_ids(router: Router) -> [Response<Body>; 2] {
+    let [first, second] = ["first", "second"].map(|id| {
+        router.clone().oneshot(
+            Request::get("/")
+                .header("x-request-id", id)
+                .body(Body::empty())
+                .unwrap(),
+        )
+    });
+    let (first, second) = tokio::join!(first, second);
+    [first.unwrap(), second.unwrap()]
+}
+
+#[tokio::test]
+async fn requests_that_share_a_render_keep_their_own_request_id() {
+    let (router, renders) = router(app);
+    let [first, second] = requests_with_ids(router).await;
+    assert_eq!(renders.load(Ordering::SeqCst), 1);
+    assert_eq!(first.headers()["x-request-id"], "first");
+    assert_eq!(second.headers()["x-request-id"], "second");
+}
+
+#[tokio::test]
+async fn renders_that_set_cookies_are_not_shared() {
+    let (router, renders) = router(sets_cookie);
+    let [first, second] = requests_with_ids(router).await;
+    assert_eq!(renders.load(Ordering::SeqCst), 2);
+    assert_eq!(first.headers()[SET_COOKIE], "visitor=first");
+    assert_eq!(second.headers()[SET_COOKIE], "visitor=second");
+    for response in [first, second] {
+        assert_eq!(response.headers().get_all(SET_COOKIE).iter().count(), 1);
+        assert!(common::body_string(response).await.contains("Hello World"));
+    }
+}
@@ -0,0 +1,307 @@
+//! Fixtures shared by the integration tests
+#![allow(dead_code)]
+
+use axum::{
+    body::Body,
+    response::Response,
+    routing::{get, Router},
+};
+use bytes::Bytes;
+use dioxus::prelude::*;
+use futures_util::Stream;
+use http::Request;
+use server_fn::{client::Client, error::NoCustomError, request::ClientReq, response::ClientRes};
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+use tower::ServiceExt;
+
+/// The smallest index.html the server can render an application into
+pub const INDEX_HTML: &str =
+    r#"<!DOCTYPE html><html><head></head><body><div id="main"></div></body></html>"#;
+
+/// A config builder that renders into [`INDEX_HTML`]
+pub fn serve_config() -> ServeConfigBuilder {
+    ServeConfig::builder().index_html(INDEX_HTML.to_string())
+}
+
+/// A router that renders the application for every request
+pub fn render_router(cfg: ServeConfig, app: fn() -> Element) -> Router {
+    Router::new().fallback(get(render_handler).with_state(RenderHandleState::new(cfg, app)))
+}
+
+/// A render state for the app that counts how many virtual doms it builds
+pub fn counting_state(
+    cfg: ServeConfig,
+    app: fn() -> Element,
+) -> (RenderHandleState, Arc<AtomicUsize>) {
+    let builds = Arc::new(AtomicUsize::new(0));
+    let state = RenderHandleState::new_with_async_virtual_dom_factory(cfg, {
+        let builds = builds.clone();
+        move || {
+            builds.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move { Ok(VirtualDom::new(app)) })
+        }
+    });
+    (state, builds)
+}
+
+static LOGS: std::sync::Mutex<Vec<u8>> = std::sync::Mutex::new(Vec::new());
+
+/// Collects the logs of every test in the binary
+struct LogWriter;
+
+impl std::io::Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        LOGS.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Start collecting everything logged with `tracing` in the test binary. Renders run on other threads, so the logs of
+/// every test that runs at the same time are collected together
+pub fn capture_logs() {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+    INSTALL.call_once(|| {
+        _ = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .with_ansi(false)
+            .with_writer(|| LogWriter)
+            .try_init();
+    });
+}
+
+/// Everything logged since [`capture_logs`] was first called
+pub fn logs() -> String {
+    String::from_utf8_lossy(&LOGS.lock().unwrap()).into_owned()
+}
+
+/// Send one request to the router
+pub async fn respond(router: Router, request: Request<Body>) -> Response {
+    router.oneshot(request).await.unwrap()
+}
+
+/// Render the page at the path
+pub async fn render_page(cfg: ServeConfig, app: fn() -> Element, path: &str) -> String {
+    let request = Request::get(path).body(Body::empty()).unwrap();
+    body_string(respond(render_router(cfg, app), request).await).await
+}
+
+/// Collect the body of a response into a string
+pub async fn body_string(response: Response) -> String {
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8_lossy(&body).into_owned()
+}
+
+/// A directory of assets in the public directory the server reads static assets from. Every directory gets a unique
+/// name, so tests that run at the same time never share files, and it is removed once it is dropped.
+pub struct PublicAssets {
+    name: String,
+    dir: PathBuf,
+}
+
+impl PublicAssets {
+    /// Create an empty directory with a name that starts with `prefix`
+    pub fn new(prefix: &str) -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let name = format!(
+            "{prefix}-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        );
+        let dir = std::env::current_exe()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .join("public")
+            .join(&name);
+        std::fs::create_dir_all(&dir).unwrap();
+        Self { name, dir }
+    }
+
+    /// The name of the directory inside the public directory
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Write a file into the directory
+    pub fn write(&self, file: &str, contents: impl AsRef<[u8]>) -> &Self {
+        std::fs::write(self.dir.join(file), contents).unwrap();
+        self
+    }
+
+    /// The path of a file in the directory
+    pub fn path(&self, file: &str) -> PathBuf {
+        self.dir.join(file)
+    }
+
+    /// The route the server serves a file in the directory at
+    pub fn route(&self, file: &str) -> String {
+        format!("/{}/{file}", self.name)
+    }
+}
+
+impl Drop for PublicAssets {
+    fn drop(&mut self) {
+        _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// A directory in the temporary directory of the system that is removed once it is dropped
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// Create an empty directory with a name that starts with `prefix`
+    pub fn new(prefix: &str) -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "dioxus-{prefix}-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+
+    /// The path of the directory
+    pub fn path(&self) -> &std::path::Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// A config builder that stores rendered pages in an incremental cache in the directory
+pub fn cached_serve_config(cache: &TempDir) -> ServeConfigBuilder {
+    serve_config().incremental(
+        IncrementalRendererConfig::new()
+            .static_dir(cache.path())
+            .clear_cache(true),
+    )
+}
+
+thread_local! {
+    static FAKE_RESPONSES: std::cell::RefCell<std::collections::VecDeque<Result<u16, ServerFnError>>> =
+        Default::default();
+    static FAKE_CALLS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// A server function client that never reaches a server. Every call takes the next response queued with
+/// [`FakeClient::respond_with`] on the thread of the test.
+pub struct FakeClient;
+
+impl FakeClient {
+    /// Queue the responses of the next calls. `Ok` responds with the status code and `Err` fails the call
+    pub fn respond_with(responses: impl IntoIterator<Item = Result<u16, ServerFnError>>) {
+        FAKE_RESPONSES.with_borrow_mut(|queued| *queued = responses.into_iter().collect());
+        FAKE_CALLS.set(0);
+    }
+
+    /// The number of calls sent since the responses were queued
+    pub fn calls() -> usize {
+        FAKE_CALLS.get()
+    }
+}
+
+impl Client<NoCustomError> for FakeClient {
+    type Request = FakeRequest;
+    type Response = FakeResponse;
+
+    async fn send(_: FakeRequest) -> Result<FakeResponse, ServerFnError> {
+        FAKE_CALLS.set(FAKE_CALLS.get() + 1);
+        let status = FAKE_RESPONSES
+            .with_borrow_mut(|queued| queued.pop_front())
+            .expect("the fake client ran out of responses")?;
+        Ok(FakeResponse { status })
+    }
+}
+
+/// A request sent by the [`FakeClient`]
+pub struct FakeRequest;
+
+impl ClientReq<NoCustomError> for FakeRequest {
+    type FormData = ();
+
+    fn try_new_get(_: &str, _: &str, _: &str, _: &str) -> Result<Self, ServerFnError> {
+        Ok(Self)
+    }
+
+    fn try_new_post(_: &str, _: &str, _: &str, _: String) -> Result<Self, ServerFnError> {
+        Ok(Self)
+    }
+
+    fn try_new_post_bytes(_: &str, _: &str, _: &str, _: Bytes) -> Result<Self, ServerFnError> {
+        Ok(Self)
+    }
+
+    fn try_new_post_form_data(_: &str, _: &str, _: &str, _: ()) -> Result<Self, ServerFnError> {
+        Ok(Self)
+    }
+
+    fn try_new_multipart(_: &str, _: &str, _: ()) -> Result<Self, ServerFnError> {
+        Ok(Self)
+    }
+
+    fn try_new_streaming(
+        _: &str,
+        _: &str,
+        _: &str,
+        _: impl Stream<Item = Bytes> + Send + 'static,
+    ) -> Result<Self, ServerFnError> {
+        Ok(Self)
+    }
+}
+
+/// A response received by the [`FakeClient`] with an empty body
+pub struct FakeResponse {
+    status: u16,
+}
+
+impl ClientRes<NoCustomError> for FakeResponse {
+    async fn try_into_string(self) -> Result<String, ServerFnError> {
+        Ok(String::new())
+    }
+
+    async fn try_into_bytes(self) -> Result<Bytes, ServerFnError> {
+        Ok(Bytes::new())
+    }
+
+    fn try_into_stream(
+        self,
+    ) -> Result<
+        impl Stream<Item = Result<Bytes, ServerFnError>> + Send + Sync + 'static,
+        ServerFnError,
+    > {
+        Ok(futures_util::stream::empty())
+    }
+
+    fn status(&self) -> u16 {
+        self.status
+    }
+
+    fn status_text(&self) -> String {
+        String::new()
+    }
+
+    fn location(&self) -> String {
+        String::new()
+    }
+
+    fn has_redirect(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,74 @@
+//! Make sure compressed static assets are only served to the clients the config allows
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus_fullstack::prelude::*;
+use http::{
+    header::{ACCEPT_ENCODING, CONTENT_ENCODING, USER_AGENT},
+    Request, StatusCode, Version,
+};
+
+mod common;
+use common::PublicAssets;
+
+/// Request the precompressed asset and return the encoding it was served with
+async fn encoding(
+    config: StaticAssetsConfig,
+    version: Version,
+    user_agent: &str,
+) -> Option<String> {
+    let assets = PublicAssets::new("compression-clients");
+    assets.write("main.js", "console.log('hello world');");
+    assets.write("main.js.br", "brotli");
+
+    let response = common::respond(
+        Router::new().serve_static_assets_with_config(config),
+        Request::get(assets.route("main.js"))
+            .version(version)
+            .header(ACCEPT_ENCODING, "br")
+            .header(USER_AGENT, user_agent)
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    response
+        .headers()
+        .get(CONTENT_ENCODING)
+        .map(|encoding| encoding.to_str().unwrap().to_string())
+}
+
+#[tokio::test]
+async fn compressed_assets_require_the_minimum_version() {
+    let config = StaticAssetsConfig::new().min_compression_version(Version::HTTP_2);
+    assert_eq!(
+        encoding(config.clone(), Version::HTTP_11, "Mozilla/5.0").await,
+        None
+    );
+    assert_eq!(
+        encoding(config, Version::HTTP_2, "Mozilla/5.0")
+            .await
+            .as_deref(),
+        Some("br")
+    );
+}
+
+#[tokio::test]
+async fn compressed_assets_require_the_filter() {
+    let config = StaticAssetsConfig::new().compress_if(|request| {
+        !request.headers()[USER_AGENT]
+            .to_str()
+            .unwrap()
+            .contains("LegacyClient/1.")
+    });
+    assert_eq!(
+        encoding(config.clone(), Version::HTTP_11, "LegacyClient/1.2").await,
+        None
+    );
+    assert_eq!(
+        encoding(config, Version::HTTP_11, "Mozilla/5.0")
+            .await
+            .as_deref(),
+        Some("br")
+    );
+}
@@ -0,0 +1,38 @@
+//! Make sure pages rendered in a locale are sent with a matching Content-Language header
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use dioxus::prelude::*;
+use http::{header::CONTENT_LANGUAGE, Request};
+
+mod common;
+
+async fn content_language(app: fn() -> Element) -> Option<String> {
+    let cfg = common::serve_config().build().unwrap();
+    let response = common::respond(
+        common::render_router(cfg, app),
+        Request::get("/").body(Body::empty()).unwrap(),
+    )
+    .await;
+    response
+        .headers()
+        .get(CONTENT_LANGUAGE)
+        .map(|language| language.to_str().unwrap().to_string())
+}
+
+#[tokio::test]
+async fn pages_rendered_in_a_locale_send_it() {
+    fn french() -> Element {
+        server_context().set_locale("fr");
+        rsx! { "Bonjour le monde" }
+    }
+    assert_eq!(content_language(french).await.as_deref(), Some("fr"));
+}
+
+#[tokio::test]
+async fn pages_without_a_locale_send_no_language() {
+    fn app() -> Element {
+        rsx! { "Hello World" }
+    }
+    assert_eq!(content_language(app).await, None);
+}
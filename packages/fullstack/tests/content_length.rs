@@ -0,0 +1,44 @@
+//! Make sure pages that are rendered in one piece are sent with a Content-Length
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use dioxus::prelude::*;
+use http::{header::CONTENT_LENGTH, Request};
+
+mod common;
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+async fn content_length(strategy: StreamFlushStrategy) -> (Option<usize>, usize) {
+    let cfg = common::serve_config()
+        .stream_flush_strategy(strategy)
+        .build()
+        .unwrap();
+    let response = common::respond(
+        common::render_router(cfg, app),
+        Request::get("/").body(Body::empty()).unwrap(),
+    )
+    .await;
+    let content_length = response
+        .headers()
+        .get(CONTENT_LENGTH)
+        .map(|length| length.to_str().unwrap().parse().unwrap());
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (content_length, body.len())
+}
+
+#[tokio::test]
+async fn buffered_pages_have_a_content_length() {
+    let (content_length, body_length) = content_length(StreamFlushStrategy::Never).await;
+    assert_eq!(content_length, Some(body_length));
+}
+
+#[tokio::test]
+async fn streamed_pages_have_no_content_length() {
+    let (content_length, _) = content_length(StreamFlushStrategy::EveryBoundary).await;
+    assert_eq!(content_length, None);
+}
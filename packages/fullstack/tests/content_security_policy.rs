@@ -0,0 +1,70 @@
+//! Make sure custom nonce based content security policies match the nonce on every inline script
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use dioxus::prelude::*;
+use http::{header::CONTENT_SECURITY_POLICY, Request, StatusCode};
+
+mod common;
+
+fn app() -> Element {
+    let nonce = server_context().csp_nonce().unwrap_or_default();
+    rsx! {
+        document::Script { "window.loaded_from_head = true;" }
+        button { onclick: |_| {}, "nonce={nonce}" }
+    }
+}
+
+/// Render the page and return the policy header with the page
+async fn render() -> (String, String) {
+    let cfg = common::serve_config()
+        .content_security_policy("default-src 'self'; script-src 'nonce-{nonce}' 'strict-dynamic'")
+        .build()
+        .unwrap();
+    let response = common::respond(
+        common::render_router(cfg, app),
+        Request::get("/").body(Body::empty()).unwrap(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let policy = response.headers()[CONTENT_SECURITY_POLICY]
+        .to_str()
+        .unwrap()
+        .to_string();
+    (policy, common::body_string(response).await)
+}
+
+/// Read the nonce out of the policy
+fn nonce(policy: &str) -> &str {
+    let start = policy.find("'nonce-").unwrap_or_else(|| panic!("{policy}")) + "'nonce-".len();
+    let end = start + policy[start..].find('\'').unwrap();
+    &policy[start..end]
+}
+
+#[tokio::test]
+async fn every_inline_script_carries_the_nonce_of_the_policy() {
+    let (policy, page) = render().await;
+    assert!(
+        policy.starts_with("default-src 'self'; script-src 'nonce-"),
+        "{policy}"
+    );
+    let nonce = nonce(&policy);
+
+    let scripts = page.matches("<script").count();
+    assert!(scripts > 1, "{page}");
+    assert_eq!(
+        page.matches(&format!("nonce=\"{nonce}\"")).count(),
+        scripts,
+        "{page}"
+    );
+    assert!(page.contains("window.loaded_from_head = true;"), "{page}");
+    // Components read the same nonce from the server context
+    assert!(page.contains(&format!("nonce={nonce}")), "{page}");
+}
+
+#[tokio::test]
+async fn every_request_gets_a_new_nonce() {
+    let (first, _) = render().await;
+    let (second, _) = render().await;
+    assert_ne!(nonce(&first), nonce(&second));
+}
@@ -0,0 +1,56 @@
+//! Make sure axum handlers behind the layer can use the server context
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, routing::get, Router};
+use dioxus_fullstack::prelude::*;
+use http::{
+    header::{COOKIE, SET_COOKIE},
+    HeaderValue, Request, StatusCode,
+};
+use tower::ServiceExt;
+
+async fn session(server_context: DioxusServerContext) -> String {
+    let cookie = server_context
+        .request_parts()
+        .headers
+        .get(COOKIE)
+        .and_then(|cookie| cookie.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    server_context
+        .response_parts_mut()
+        .headers
+        .insert(SET_COOKIE, HeaderValue::from_static("session=renewed"));
+    cookie
+}
+
+#[tokio::test]
+async fn handlers_share_the_server_context() {
+    let response = Router::new()
+        .route("/session", get(session))
+        .layer(DioxusServerContextLayer::new())
+        .oneshot(
+            Request::get("/session")
+                .header(COOKIE, "session=abc")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[SET_COOKIE], "session=renewed");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "session=abc");
+}
+
+#[tokio::test]
+async fn the_extractor_requires_the_layer() {
+    let response = Router::new()
+        .route("/session", get(session))
+        .oneshot(Request::get("/session").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
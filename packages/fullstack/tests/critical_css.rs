@@ -0,0 +1,59 @@
+//! Make sure critical CSS is inlined into the head and the main stylesheet doesn't block the first paint
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use dioxus::prelude::*;
+use http::Request;
+
+mod common;
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+async fn page(cfg: ServeConfigBuilder) -> String {
+    let cfg = cfg
+        .index_html(
+            r#"<!DOCTYPE html><html><head></head><body><div id="main"></div></body></html>"#
+                .to_string(),
+        )
+        .build()
+        .unwrap();
+    let response = common::respond(
+        common::render_router(cfg, app),
+        Request::get("/").body(Body::empty()).unwrap(),
+    )
+    .await;
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn critical_css_is_inlined_before_the_body() {
+    let page = page(ServeConfig::builder().critical_css("body { margin: 0 }")).await;
+    let style = page.find("<style>body { margin: 0 }</style>").unwrap();
+    assert!(style < page.find("</head>").unwrap());
+}
+
+#[tokio::test]
+async fn closing_style_tags_are_escaped() {
+    let page =
+        page(ServeConfig::builder().critical_css("a::after { content: '</style><b>' }")).await;
+    assert!(!page.contains("</style><b>"));
+    assert!(page.contains(r"<\/style><b>"));
+}
+
+#[tokio::test]
+async fn main_stylesheet_is_preloaded_and_applied_after_main() {
+    let page = page(ServeConfig::builder().main_stylesheet("/assets/main.css")).await;
+    let preload = page
+        .find(r#"<link rel="preload" href="/assets/main.css" as="style">"#)
+        .unwrap();
+    let stylesheet = page
+        .find(r#"<link rel="stylesheet" href="/assets/main.css">"#)
+        .unwrap();
+    assert!(preload < page.find("</head>").unwrap());
+    assert!(stylesheet > page.find("Hello World").unwrap());
+}
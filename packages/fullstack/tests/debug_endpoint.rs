@@ -0,0 +1,52 @@
+//! Make sure the debug endpoint describes the server and is never served in production
+#![cfg(all(feature = "axum", feature = "debug-endpoint"))]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use dioxus_fullstack::prelude::*;
+use http::{Request, StatusCode};
+use tower::ServiceExt;
+
+mod common;
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+fn state(production: bool) -> RenderHandleState {
+    let cfg = common::serve_config()
+        .production(production)
+        .build()
+        .unwrap();
+    RenderHandleState::new(cfg, app)
+}
+
+async fn debug(production: bool) -> (StatusCode, Vec<u8>) {
+    let response = Router::new()
+        .with_debug_endpoint(&state(production))
+        .oneshot(Request::get("/__dioxus/debug").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, body.to_vec())
+}
+
+#[tokio::test]
+async fn debug_endpoint_describes_the_server() {
+    let (status, body) = debug(false).await;
+    assert_eq!(status, StatusCode::OK);
+    let summary: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(summary["config"]["root_id"], "main");
+    assert_eq!(summary["config"]["production"], false);
+    assert_eq!(summary["ssr_state_initialized"], false);
+    assert!(summary["server_functions"].is_array());
+}
+
+#[tokio::test]
+async fn debug_endpoint_is_not_served_in_production() {
+    let (status, _) = debug(true).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,106 @@
+//! Make sure the server renders deferred boundaries so the client can hydrate them later
+#![cfg(feature = "axum")]
+
+use dioxus::prelude::*;
+
+mod common;
+
+fn app() -> Element {
+    rsx! {
+        button { onclick: |_| {}, "Above the fold" }
+        DeferHydration { HeavyFooter {} }
+    }
+}
+
+#[component]
+fn HeavyFooter() -> Element {
+    let links = use_server_cached(|| ["About", "Careers", "Press"].map(String::from));
+    rsx! {
+        footer {
+            for link in links {
+                button { onclick: |_| {}, "{link}" }
+            }
+        }
+    }
+}
+
+async fn page(app: fn() -> Element) -> String {
+    let cfg = common::serve_config().build().unwrap();
+    common::render_page(cfg, app, "/").await
+}
+
+fn between<'a>(page: &'a str, start: &str, end: &str) -> &'a str {
+    let start = page.find(start).unwrap_or_else(|| panic!("{page}"));
+    let end = start + page[start..].find(end).unwrap_or_else(|| panic!("{page}"));
+    &page[start..end]
+}
+
+#[tokio::test]
+async fn deferred_children_are_rendered_without_hydration_markers() {
+    let page = page(app).await;
+
+    let boundary = between(&page, "<div data-dx-defer", ">");
+    assert!(boundary.contains(r#"data-dx-defer="0""#), "{page}");
+    assert!(
+        boundary.contains(r#"data-dx-hydrate-on="visible""#),
+        "{page}"
+    );
+    assert!(boundary.contains("data-node-hydration"), "{page}");
+
+    let footer = between(&page, "<footer", "</footer>");
+    assert!(footer.contains("Careers"), "{page}");
+    assert!(!footer.contains("data-node-hydration"), "{page}");
+    assert!(!footer.contains("<!--node-id"), "{page}");
+
+    // The content around the boundary is still hydrated
+    let above = between(&page, "<button", "Above the fold");
+    assert!(above.contains("data-node-hydration"), "{page}");
+}
+
+#[tokio::test]
+async fn pages_with_deferred_boundaries_send_the_bootstrap_script() {
+    assert!(page(app).await.contains("window.dx_watch_deferred"));
+
+    fn without_boundaries() -> Element {
+        rsx! { "Nothing to defer" }
+    }
+    assert!(!page(without_boundaries)
+        .await
+        .contains("window.dx_watch_deferred"));
+}
+
+#[tokio::test]
+async fn deferred_data_is_left_out_of_the_hydration_data() {
+    fn without_footer() -> Element {
+        rsx! {
+            button { onclick: |_| {}, "Above the fold" }
+            DeferHydration {}
+        }
+    }
+
+    let hydration_data =
+        |page: &str| between(page, "window.initial_dioxus_hydration_data", "</script>").to_string();
+    assert_eq!(
+        hydration_data(&page(app).await),
+        hydration_data(&page(without_footer).await)
+    );
+}
+
+#[tokio::test]
+async fn nested_boundaries_hydrate_with_their_parent() {
+    fn nested() -> Element {
+        rsx! {
+            DeferHydration { on: HydrateOn::Interaction,
+                DeferHydration { HeavyFooter {} }
+            }
+        }
+    }
+
+    let page = page(nested).await;
+    assert_eq!(page.matches("data-dx-hydrate-on=\"").count(), 1, "{page}");
+    assert!(
+        page.contains(r#"data-dx-hydrate-on="interaction""#),
+        "{page}"
+    );
+    assert!(page.contains("Careers"), "{page}");
+}
@@ -0,0 +1,60 @@
+//! Make sure deprecated server functions respond with deprecation headers
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{header::CONTENT_TYPE, Request, Response};
+use std::time::{Duration, UNIX_EPOCH};
+use tower::ServiceExt;
+
+#[server(endpoint = "v1/deprecated_echo")]
+async fn old_echo(data: String) -> Result<String, ServerFnError> {
+    Ok(data)
+}
+
+#[server(endpoint = "v2/deprecated_echo")]
+async fn new_echo(data: String) -> Result<String, ServerFnError> {
+    Ok(data)
+}
+
+async fn call(config: ServerFnConfig, path: &str) -> Response<Body> {
+    Router::new()
+        .register_server_functions_with_config(config)
+        .oneshot(
+            Request::post(path)
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::from("data=hello"))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+}
+
+fn config() -> ServerFnConfig {
+    ServerFnConfig::new().deprecate(
+        "/api/v1/deprecated_echo",
+        Deprecation::new()
+            .since(UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+            .sunset(UNIX_EPOCH + Duration::from_secs(1_800_000_000))
+            .link("https://example.com/migrate"),
+    )
+}
+
+#[tokio::test]
+async fn deprecated_server_functions_send_deprecation_headers() {
+    let response = call(config(), "/api/v1/deprecated_echo").await;
+    let headers = response.headers();
+    assert_eq!(headers["deprecation"], "@1700000000");
+    assert_eq!(headers["sunset"], "Fri, 15 Jan 2027 08:00:00 GMT");
+    assert_eq!(
+        headers["link"],
+        "<https://example.com/migrate>; rel=\"deprecation\""
+    );
+}
+
+#[tokio::test]
+async fn other_server_functions_are_unchanged() {
+    let response = call(config(), "/api/v2/deprecated_echo").await;
+    assert!(response.headers().get("deprecation").is_none());
+    assert!(response.headers().get("sunset").is_none());
+}
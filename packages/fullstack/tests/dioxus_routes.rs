@@ -0,0 +1,50 @@
+//! Make sure the Dioxus routes can be merged into an existing router
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, routing::get, Router};
+use dioxus::prelude::*;
+use http::{Request, StatusCode};
+use tower::ServiceExt;
+
+mod common;
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+fn router() -> Router {
+    let cfg = common::serve_config().build().unwrap();
+    Router::new()
+        .route("/custom", get(|| async { "custom" }))
+        .merge(dioxus_routes(cfg, app))
+        .fallback(|| async { StatusCode::IM_A_TEAPOT })
+}
+
+async fn get_body(path: &str) -> (StatusCode, String) {
+    let response = router()
+        .oneshot(Request::get(path).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn pages_are_rendered() {
+    for path in ["/", "/nested/page"] {
+        let (status, body) = get_body(path).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains("Hello World"));
+    }
+}
+
+#[tokio::test]
+async fn existing_routes_win() {
+    assert_eq!(
+        get_body("/custom").await,
+        (StatusCode::OK, "custom".to_string())
+    );
+}
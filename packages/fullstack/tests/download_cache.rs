@@ -0,0 +1,73 @@
+//! Make sure server function downloads are cached and can be resumed with range requests
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, response::Response, Router};
+use dioxus::prelude::*;
+use http::{
+    header::{CONTENT_RANGE, CONTENT_TYPE, ETAG, IF_RANGE, RANGE},
+    Request, StatusCode,
+};
+use server_fn::codec::{ByteStream, Streaming};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+mod common;
+
+static EXPORTS: AtomicUsize = AtomicUsize::new(0);
+
+#[server(endpoint = "cached_export", output = Streaming)]
+async fn export() -> Result<ByteStream, ServerFnError> {
+    EXPORTS.fetch_add(1, Ordering::SeqCst);
+    Ok(ByteStream::new(futures_util::stream::iter([
+        Ok("01234"),
+        Ok("56789"),
+    ])))
+}
+
+fn router() -> Router {
+    Router::new().register_server_functions_with_config(
+        ServerFnConfig::new()
+            .only_paths_starting_with("/api/cached_export")
+            .download_cache(DownloadCache::new(1024)),
+    )
+}
+
+async fn download(router: &Router, headers: &[(http::HeaderName, &str)]) -> Response {
+    let mut request = Request::post("/api/cached_export")
+        .header(CONTENT_TYPE, "application/x-www-form-urlencoded");
+    for (name, value) in headers {
+        request = request.header(name, *value);
+    }
+    common::respond(router.clone(), request.body(Body::empty()).unwrap()).await
+}
+
+// The export counter is shared, so every case runs in the same test
+#[tokio::test]
+async fn downloads_are_cached_and_resumable() {
+    let router = router();
+
+    let response = download(&router, &[]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = response.headers()[ETAG].to_str().unwrap().to_string();
+    assert_eq!(common::body_string(response).await, "0123456789");
+
+    // Resuming the download is answered from the cache
+    let response = download(&router, &[(RANGE, "bytes=4-"), (IF_RANGE, &etag)]).await;
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.headers()[CONTENT_RANGE], "bytes 4-9/10");
+    assert_eq!(common::body_string(response).await, "456789");
+
+    let response = download(&router, &[(RANGE, "bytes=-3")]).await;
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(common::body_string(response).await, "789");
+
+    let response = download(&router, &[(RANGE, "bytes=20-")]).await;
+    assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+    assert_eq!(response.headers()[CONTENT_RANGE], "bytes */10");
+
+    // A range for another version of the download gets the whole body
+    let response = download(&router, &[(RANGE, "bytes=4-"), (IF_RANGE, "\"stale\"")]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(common::body_string(response).await, "0123456789");
+
+    assert_eq!(EXPORTS.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,55 @@
+//! Make sure registering the server functions twice doesn't panic
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{header::CONTENT_TYPE, Request, StatusCode};
+use tower::ServiceExt;
+
+#[server(endpoint = "duplicate_registration_ping")]
+async fn ping() -> Result<String, ServerFnError> {
+    Ok("pong".to_string())
+}
+
+#[tokio::test]
+async fn registering_twice_keeps_the_first_registration() {
+    let router = Router::new()
+        .register_server_functions()
+        .register_server_functions();
+
+    let response = router
+        .oneshot(
+            Request::post("/api/duplicate_registration_ping")
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn routes_registered_by_the_user_are_kept() {
+    let router = Router::new()
+        .route(
+            "/api/duplicate_registration_ping",
+            axum::routing::post(|| async { "custom" }),
+        )
+        .register_server_functions();
+
+    let response = router
+        .oneshot(
+            Request::post("/api/duplicate_registration_ping")
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "custom");
+}
@@ -0,0 +1,29 @@
+//! Make sure critical assets are preloaded with Link headers
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use dioxus::prelude::*;
+use http::{header::LINK, Request};
+
+mod common;
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+#[tokio::test]
+async fn rendered_pages_preload_critical_assets() {
+    let cfg = common::serve_config()
+        .early_hints(["/wasm/app_bg.wasm", "/assets/main.css?v=2"])
+        .build()
+        .unwrap();
+    let response = common::respond(
+        common::render_router(cfg, app),
+        Request::get("/").body(Body::empty()).unwrap(),
+    )
+    .await;
+    assert_eq!(
+        response.headers()[LINK],
+        "</wasm/app_bg.wasm>; rel=preload; as=fetch; crossorigin, </assets/main.css?v=2>; rel=preload; as=style"
+    );
+}
@@ -0,0 +1,69 @@
+//! Make sure static assets can be served from files embedded in the server binary
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus_fullstack::prelude::*;
+use http::{
+    header::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+    Request, Response, StatusCode,
+};
+use tower::ServiceExt;
+
+mod common;
+
+static ASSETS: &[(&str, &[u8])] = &[
+    ("index.html", b"<html></html>"),
+    ("main.js", b"console.log('hello')"),
+    ("main.js.br", b"compressed"),
+    ("assets/nested/style.css", b"body { margin: 0; }"),
+    ("docs/index.html", b"<h1>Docs</h1>"),
+];
+
+async fn get(path: &str, headers: &[(http::HeaderName, &str)]) -> Response<Body> {
+    let mut request = Request::get(path);
+    for (name, value) in headers {
+        request = request.header(name, *value);
+    }
+    Router::new()
+        .serve_static_assets_embedded(ASSETS)
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+async fn body(response: Response<Body>) -> String {
+    common::body_string(response).await
+}
+
+#[tokio::test]
+async fn nested_assets_are_served() {
+    let response = get("/assets/nested/style.css", &[]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[CONTENT_TYPE], "text/css");
+    assert_eq!(body(response).await, "body { margin: 0; }");
+
+    assert_eq!(body(get("/docs/", &[]).await).await, "<h1>Docs</h1>");
+}
+
+#[tokio::test]
+async fn the_root_index_is_skipped() {
+    assert_eq!(
+        get("/index.html", &[]).await.status(),
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[tokio::test]
+async fn precompressed_variants_are_negotiated() {
+    let compressed = get("/main.js", &[(ACCEPT_ENCODING, "gzip, br")]).await;
+    assert_eq!(compressed.headers()[CONTENT_ENCODING], "br");
+    assert_eq!(body(compressed).await, "compressed");
+
+    let identity = get("/main.js", &[]).await;
+    assert!(identity.headers().get(CONTENT_ENCODING).is_none());
+    let etag = identity.headers()[ETAG].to_str().unwrap().to_string();
+    assert_eq!(body(identity).await, "console.log('hello')");
+
+    let revalidated = get("/main.js", &[(IF_NONE_MATCH, &etag)]).await;
+    assert_eq!(revalidated.status(), StatusCode::NOT_MODIFIED);
+}
@@ -0,0 +1,67 @@
+//! Make sure pages that fail to render respond with the error component
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, routing::get, Router};
+use dioxus::prelude::*;
+use http::{header::CONTENT_TYPE, Request, StatusCode};
+
+mod common;
+
+fn app() -> Element {
+    panic!("the database is on fire")
+}
+
+fn error_page() -> Element {
+    rsx! {
+        h1 { "Something went wrong" }
+        button { onclick: |_| {}, "Try again" }
+    }
+}
+
+fn broken_error_page() -> Element {
+    panic!("the error page is on fire too")
+}
+
+async fn render(error_component: fn() -> Element) -> (StatusCode, String, String) {
+    let cfg = common::serve_config()
+        .catch_render_panics(true)
+        .build()
+        .unwrap();
+    let state = RenderHandleState::new(cfg, app).with_error_component(error_component);
+    let response = common::respond(
+        Router::new().fallback(get(render_handler).with_state(state)),
+        Request::get("/").body(Body::empty()).unwrap(),
+    )
+    .await;
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .map(|value| value.to_str().unwrap().to_string())
+        .unwrap_or_default();
+    (
+        response.status(),
+        content_type,
+        common::body_string(response).await,
+    )
+}
+
+#[tokio::test]
+async fn the_error_component_is_rendered_without_hydration() {
+    let (status, content_type, page) = render(error_page).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(content_type.starts_with("text/html"), "{content_type}");
+    assert!(page.contains("<h1>Something went wrong</h1>"), "{page}");
+    assert!(!page.contains("data-node-hydration"), "{page}");
+    assert!(!page.contains("the database is on fire"), "{page}");
+}
+
+#[tokio::test]
+async fn a_failing_error_component_falls_back_to_plain_text() {
+    let (status, content_type, page) = render(broken_error_page).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(
+        !content_type.starts_with("text/html"),
+        "{content_type} {page}"
+    );
+    assert!(page.starts_with("Error: "), "{page}");
+}
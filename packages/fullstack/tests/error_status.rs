@@ -0,0 +1,76 @@
+//! Make sure server function errors are sent with a status code that matches the error
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{header::CONTENT_TYPE, Request, StatusCode};
+use tower::ServiceExt;
+
+#[derive(Debug, Clone)]
+pub struct Unauthorized;
+
+impl std::fmt::Display for Unauthorized {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unauthorized")
+    }
+}
+
+impl std::str::FromStr for Unauthorized {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        (s == "Unauthorized").then_some(Unauthorized).ok_or(())
+    }
+}
+
+impl ErrorStatus for Unauthorized {
+    fn status(&self) -> StatusCode {
+        StatusCode::UNAUTHORIZED
+    }
+}
+
+#[server(endpoint = "error_status_secret")]
+async fn secret(name: String) -> Result<String, ServerFnError<Unauthorized>> {
+    let _ = name;
+    Err(ServerFnError::WrappedServerError(Unauthorized))
+}
+
+async fn status(config: ServerFnConfig, body: &'static str) -> StatusCode {
+    Router::new()
+        .register_server_functions_with_config(config)
+        .oneshot(
+            Request::post("/api/error_status_secret")
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn errors_are_sent_with_500_by_default() {
+    assert_eq!(
+        status(ServerFnConfig::new(), "name=dioxus").await,
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+}
+
+#[tokio::test]
+async fn custom_errors_pick_their_status() {
+    let config = ServerFnConfig::new().error_status::<Unauthorized>();
+    assert_eq!(
+        status(config, "name=dioxus").await,
+        StatusCode::UNAUTHORIZED
+    );
+}
+
+#[tokio::test]
+async fn invalid_arguments_are_unprocessable() {
+    let config = ServerFnConfig::new().map_error_status(true);
+    assert_eq!(
+        status(config, "unknown=1").await,
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+}
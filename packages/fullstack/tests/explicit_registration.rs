@@ -0,0 +1,53 @@
+//! Make sure a single server function can be registered explicitly
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{header::CONTENT_TYPE, Request, StatusCode};
+use tower::ServiceExt;
+
+#[server(endpoint = "explicit_add")]
+async fn add(a: u32, b: u32) -> Result<u32, ServerFnError> {
+    Ok(a + b)
+}
+
+#[server(endpoint = "explicit_unregistered")]
+async fn unregistered() -> Result<(), ServerFnError> {
+    Ok(())
+}
+
+fn router() -> Router {
+    Router::new().register_server_function::<Add>(ServerFnConfig::new().prefix("/rpc"))
+}
+
+#[tokio::test]
+async fn registered_server_function_is_routed_with_the_prefix() {
+    let response = router()
+        .oneshot(
+            Request::post("/rpc/api/explicit_add")
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::from("a=1&b=2"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(&body[..], b"3");
+}
+
+#[tokio::test]
+async fn other_server_functions_are_not_routed() {
+    let response = router()
+        .oneshot(
+            Request::post("/rpc/api/explicit_unregistered")
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
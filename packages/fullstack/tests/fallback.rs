@@ -0,0 +1,64 @@
+//! Make sure requests the application doesn't render are sent to the custom fallback
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{header::ACCEPT, Request, StatusCode};
+use tower::ServiceExt;
+
+mod common;
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+async fn respond(request: Request<Body>) -> (StatusCode, String) {
+    let cfg = common::serve_config().build().unwrap();
+    let response = Router::new()
+        .serve_dioxus_application_with_fallback(cfg, app, |request: Request<Body>| async move {
+            (
+                StatusCode::NOT_FOUND,
+                format!(r#"{{"error":"{} not found"}}"#, request.uri().path()),
+            )
+        })
+        .oneshot(request)
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn html_requests_are_rendered() {
+    let (status, body) = respond(
+        Request::get("/about")
+            .header(ACCEPT, "text/html")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("Hello World"));
+}
+
+#[tokio::test]
+async fn json_requests_are_sent_to_the_fallback() {
+    let (status, body) = respond(
+        Request::get("/api/missing")
+            .header(ACCEPT, "application/json")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body, r#"{"error":"/api/missing not found"}"#);
+}
+
+#[tokio::test]
+async fn other_methods_are_sent_to_the_fallback() {
+    let (status, _) = respond(Request::delete("/about").body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,36 @@
+//! Make sure static assets without a precompressed variant are compressed when they are requested
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus_fullstack::prelude::*;
+use http::{
+    header::{ACCEPT_ENCODING, CONTENT_ENCODING},
+    Request, StatusCode,
+};
+
+mod common;
+use common::PublicAssets;
+
+#[tokio::test]
+async fn uncompressed_assets_are_compressed_and_cached() {
+    let assets = PublicAssets::new("fallback-compression");
+    assets.write("style.css", "body { color: red; }\n".repeat(64));
+
+    let router = Router::new().serve_static_assets_with_config(
+        StaticAssetsConfig::new()
+            .compress_on_the_fly(true)
+            .cache_compressed_assets(true),
+    );
+    let response = common::respond(
+        router,
+        Request::get(assets.route("style.css"))
+            .header(ACCEPT_ENCODING, "gzip, br")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[CONTENT_ENCODING], "br");
+    assert!(assets.path("style.css.br").exists());
+}
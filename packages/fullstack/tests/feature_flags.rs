@@ -0,0 +1,50 @@
+//! Make sure components render with the feature flags the server decides for the request
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, routing::get, Router};
+use dioxus::prelude::*;
+use http::Request;
+use tower::ServiceExt;
+
+mod common;
+
+fn app() -> Element {
+    let flags = use_flags();
+    rsx! {
+        if flags.is_enabled("new-header") {
+            "New header"
+        } else {
+            "Old header"
+        }
+    }
+}
+
+async fn render(beta: bool) -> String {
+    let cfg = common::serve_config().build().unwrap();
+    let state = RenderHandleState::new(cfg, app).with_feature_flags(|context| {
+        let flags = FeatureFlags::new();
+        if context.request_parts().headers.contains_key("x-beta") {
+            flags.enable("new-header")
+        } else {
+            flags
+        }
+    });
+    let mut request = Request::get("/");
+    if beta {
+        request = request.header("x-beta", "1");
+    }
+    let response = Router::new()
+        .fallback(get(render_handler).with_state(state))
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    common::body_string(response).await
+}
+
+#[tokio::test]
+async fn pages_render_with_the_flags_of_the_request() {
+    let beta = render(true).await;
+    assert!(beta.contains("New header"), "{beta}");
+    let stable = render(false).await;
+    assert!(stable.contains("Old header"), "{stable}");
+}
@@ -0,0 +1,74 @@
+//! Make sure server function guards run before the server function
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{header::CONTENT_TYPE, Request, StatusCode};
+use server_fn::codec::{ByteStream, Streaming};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tower::ServiceExt;
+
+fn require_admin(context: &DioxusServerContext) -> Result<(), StatusCode> {
+    let is_admin = context.request_parts().headers.contains_key("x-admin");
+    is_admin.then_some(()).ok_or(StatusCode::FORBIDDEN)
+}
+
+#[server(endpoint = "guarded_delete", guard = "require_admin")]
+async fn delete_everything() -> Result<(), ServerFnError> {
+    Ok(())
+}
+
+async fn call(admin: bool) -> StatusCode {
+    let mut request = Request::post("/api/guarded_delete")
+        .header(CONTENT_TYPE, "application/x-www-form-urlencoded");
+    if admin {
+        request = request.header("x-admin", "1");
+    }
+    Router::new()
+        .register_server_functions()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn guard_rejects_requests() {
+    assert_eq!(call(false).await, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn guard_allows_requests() {
+    assert_eq!(call(true).await, StatusCode::OK);
+}
+
+static EXPORTS: AtomicUsize = AtomicUsize::new(0);
+
+#[server(endpoint = "guarded_export", output = Streaming, guard = "require_admin")]
+async fn export() -> Result<ByteStream, ServerFnError> {
+    EXPORTS.fetch_add(1, Ordering::SeqCst);
+    Ok(ByteStream::new(futures_util::stream::iter([Ok("secret")])))
+}
+
+#[tokio::test]
+async fn guard_runs_before_the_download_cache() {
+    let router = Router::new().register_server_functions_with_config(
+        ServerFnConfig::new()
+            .only_paths_starting_with("/api/guarded_export")
+            .download_cache(DownloadCache::new(1024)),
+    );
+    let export = |admin: bool| {
+        let mut request = Request::post("/api/guarded_export")
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded");
+        if admin {
+            request = request.header("x-admin", "1");
+        }
+        router.clone().oneshot(request.body(Body::empty()).unwrap())
+    };
+
+    assert_eq!(export(true).await.unwrap().status(), StatusCode::OK);
+    // The cached export is only sent to callers the guard lets through
+    assert_eq!(export(false).await.unwrap().status(), StatusCode::FORBIDDEN);
+    assert_eq!(export(true).await.unwrap().status(), StatusCode::OK);
+    assert_eq!(EXPORTS.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,57 @@
+//! Make sure the head builder adds html to the head of every server rendered page
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, routing::get, Router};
+use dioxus::prelude::*;
+use http::Request;
+use tower::ServiceExt;
+
+mod common;
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+async fn render(head_builder: fn(&DioxusServerContext) -> String, path: &str) -> String {
+    let cfg = common::serve_config().build().unwrap();
+    let state = RenderHandleState::new(cfg, app).with_head_builder(head_builder);
+    let response = Router::new()
+        .fallback(get(render_handler).with_state(state))
+        .oneshot(Request::get(path).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn head_depends_on_the_path() {
+    let html = render(
+        |context| {
+            format!(
+                r#"<link rel="canonical" href="https://example.com{}">"#,
+                context.request_parts().uri.path()
+            )
+        },
+        "/blog/hello",
+    )
+    .await;
+    let head_end = html.find("</head>").unwrap();
+    let canonical = html
+        .find(r#"<link rel="canonical" href="https://example.com/blog/hello">"#)
+        .unwrap();
+    assert!(canonical < head_end);
+}
+
+#[tokio::test]
+async fn head_cannot_close_the_head() {
+    let html = render(
+        |_| r#"<meta name="a"></HEAD><body><script>alert(1)</script>"#.to_string(),
+        "/",
+    )
+    .await;
+    assert!(html.contains(r#"<meta name="a">&lt;/HEAD>&lt;body>"#));
+    assert_eq!(html.matches("</head>").count(), 1);
+}
@@ -0,0 +1,61 @@
+//! Make sure HEAD requests to server rendered pages get the headers of the page without the body
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use dioxus::prelude::*;
+use http::{header::CONTENT_LENGTH, Request, StatusCode};
+
+mod common;
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+async fn respond(
+    strategy: StreamFlushStrategy,
+    request: Request<Body>,
+) -> (StatusCode, Option<String>, Vec<u8>) {
+    let cfg = common::serve_config()
+        .stream_flush_strategy(strategy)
+        .build()
+        .unwrap();
+    let response = common::respond(common::render_router(cfg, app), request).await;
+    let status = response.status();
+    let content_length = response
+        .headers()
+        .get(CONTENT_LENGTH)
+        .map(|length| length.to_str().unwrap().to_string());
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, content_length, body.to_vec())
+}
+
+#[tokio::test]
+async fn head_requests_get_the_length_of_buffered_pages() {
+    let (_, get_length, get_body) = respond(
+        StreamFlushStrategy::Never,
+        Request::get("/").body(Body::empty()).unwrap(),
+    )
+    .await;
+    let (status, head_length, head_body) = respond(
+        StreamFlushStrategy::Never,
+        Request::head("/").body(Body::empty()).unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(head_length, get_length);
+    assert_eq!(head_length, Some(get_body.len().to_string()));
+    assert!(head_body.is_empty());
+}
+
+#[tokio::test]
+async fn head_requests_do_not_stream_pages() {
+    let (status, _, body) = respond(
+        StreamFlushStrategy::default(),
+        Request::head("/").body(Body::empty()).unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.is_empty());
+}
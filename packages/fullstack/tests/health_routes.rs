@@ -0,0 +1,53 @@
+//! Make sure the health routes respond without rendering the application
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{header::ACCEPT, Request, StatusCode};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+mod common;
+
+fn app() -> Element {
+    panic!("health checks should never render the app")
+}
+
+async fn check(router: &Router, path: &str) -> StatusCode {
+    common::respond(
+        router.clone(),
+        Request::get(path)
+            .header(ACCEPT, "application/json")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await
+    .status()
+}
+
+#[tokio::test]
+async fn health_routes_skip_the_server_render() {
+    let cfg = common::serve_config().build().unwrap();
+    let router = common::render_router(cfg, app).with_health_routes("/healthz", "/readyz");
+    assert_eq!(check(&router, "/healthz").await, StatusCode::OK);
+    assert_eq!(check(&router, "/readyz").await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn readiness_follows_the_check() {
+    let ready = Arc::new(AtomicBool::new(false));
+    let router = Router::new().with_health_routes_and_readiness("/healthz", "/readyz", {
+        let ready = ready.clone();
+        move || ready.load(Ordering::SeqCst)
+    });
+
+    assert_eq!(check(&router, "/healthz").await, StatusCode::OK);
+    assert_eq!(
+        check(&router, "/readyz").await,
+        StatusCode::SERVICE_UNAVAILABLE
+    );
+    ready.store(true, Ordering::SeqCst);
+    assert_eq!(check(&router, "/readyz").await, StatusCode::OK);
+}
@@ -0,0 +1,51 @@
+//! Make sure plain HTTP requests are only redirected to HTTPS in production
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, response::Response, Router};
+use dioxus::prelude::*;
+use http::{
+    header::{HOST, LOCATION},
+    Request, StatusCode,
+};
+
+mod common;
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+async fn request(production: bool, forwarded_proto: Option<&str>) -> Response {
+    let cfg = common::serve_config()
+        .redirect_to_https(true)
+        .production(production);
+    let mut request = Request::get("/checkout?step=2").header(HOST, "example.com");
+    if let Some(proto) = forwarded_proto {
+        request = request.header("x-forwarded-proto", proto);
+    }
+    common::respond(
+        Router::new().serve_dioxus_application(cfg, app),
+        request.body(Body::empty()).unwrap(),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn plain_http_is_redirected_in_production() {
+    let response = request(true, Some("http")).await;
+    assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(
+        response.headers()[LOCATION],
+        "https://example.com/checkout?step=2"
+    );
+}
+
+#[tokio::test]
+async fn plain_http_is_served_outside_of_production() {
+    assert_eq!(request(false, Some("http")).await.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn https_and_direct_requests_are_served() {
+    assert_eq!(request(true, Some("https")).await.status(), StatusCode::OK);
+    assert_eq!(request(true, None).await.status(), StatusCode::OK);
+}
@@ -0,0 +1,75 @@
+//! Make sure the index.html template can be built for every request
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use dioxus::prelude::*;
+use http::{header::HOST, Request, StatusCode};
+
+mod common;
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+async fn page(cfg: ServeConfig, host: &str) -> (StatusCode, String) {
+    let response = common::respond(
+        common::render_router(cfg, app),
+        Request::get("/")
+            .header(HOST, host)
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8_lossy(&body).into_owned())
+}
+
+#[tokio::test]
+async fn templates_are_built_from_the_request() {
+    let cfg = ServeConfig::builder()
+        .index_html_with(|server_context| {
+            let host = server_context.request_parts().headers[HOST]
+                .to_str()
+                .unwrap()
+                .to_string();
+            format!(
+                r#"<!DOCTYPE html><html><head><meta name="tenant" content="{host}"></head><body><div id="main"></div></body></html>"#
+            )
+        })
+        .build()
+        .unwrap();
+
+    let (status, acme) = page(cfg.clone(), "acme.example.com").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(acme.contains(r#"content="acme.example.com""#), "{acme}");
+    assert!(acme.contains("Hello World"), "{acme}");
+
+    let (_, globex) = page(cfg, "globex.example.com").await;
+    assert!(
+        globex.contains(r#"content="globex.example.com""#),
+        "{globex}"
+    );
+}
+
+#[tokio::test]
+async fn invalid_templates_fail_the_render() {
+    let cfg = ServeConfig::builder()
+        .index_html_with(|_| "<html><body>No mount</body></html>".to_string())
+        .build()
+        .unwrap();
+    let (status, _) = page(cfg, "acme.example.com").await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[test]
+fn invalid_static_templates_are_rejected() {
+    let err = ServeConfig::builder()
+        .index_html("<html><head></head><body></body></html>".to_string())
+        .build()
+        .err()
+        .expect("the template has no mount");
+    assert!(err.to_string().contains(r#"id="main""#), "{err}");
+}
@@ -0,0 +1,60 @@
+//! Make sure context factories only run when their type is read, and only once per request
+#![cfg(feature = "axum")]
+
+use dioxus_fullstack::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Database(u32);
+
+#[derive(Clone, Debug, PartialEq)]
+struct Theme(&'static str);
+
+/// A boxed factory that counts how many times it runs
+fn counted<T: Clone + Send + Sync + 'static>(
+    value: T,
+    runs: Arc<AtomicUsize>,
+) -> Box<dyn Fn() -> Box<dyn std::any::Any> + Send + Sync> {
+    Box::new(move || {
+        runs.fetch_add(1, Ordering::SeqCst);
+        Box::new(value.clone())
+    })
+}
+
+#[test]
+fn boxed_factories_run_on_the_first_read() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let context = DioxusServerContext::default();
+    context.insert_boxed_factory(counted(Database(1), runs.clone()));
+    assert_eq!(runs.load(Ordering::SeqCst), 0);
+
+    assert_eq!(context.get::<Database>(), Some(Database(1)));
+    assert_eq!(context.get::<Database>(), Some(Database(1)));
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn reading_inserted_values_runs_no_factories() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let context = DioxusServerContext::default();
+    context.insert_boxed_factory(counted(Database(1), runs.clone()));
+    context.insert(Theme("dark"));
+
+    assert_eq!(context.get::<Theme>(), Some(Theme("dark")));
+    assert_eq!(runs.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn typed_factories_are_cached() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let context = DioxusServerContext::default();
+    context.insert_factory({
+        let runs = runs.clone();
+        move || Database(runs.fetch_add(1, Ordering::SeqCst) as u32)
+    });
+
+    assert_eq!(context.get::<Database>(), Some(Database(0)));
+    assert_eq!(context.get::<Database>(), Some(Database(0)));
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+}
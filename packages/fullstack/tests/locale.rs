@@ -0,0 +1,62 @@
+//! Make sure the locale is negotiated from the Accept-Language header
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use dioxus::prelude::*;
+use http::{
+    header::{ACCEPT_LANGUAGE, CONTENT_LANGUAGE},
+    Request,
+};
+
+mod common;
+
+fn app() -> Element {
+    let locale = use_locale()
+        .map(|locale| locale.to_string())
+        .unwrap_or_default();
+    rsx! { "locale={locale}" }
+}
+
+async fn locale(accept_language: Option<&str>) -> (Option<String>, String) {
+    let cfg = common::serve_config()
+        .supported_locales(["en", "fr", "pt-BR"])
+        .default_locale("en")
+        .build()
+        .unwrap();
+    let mut request = Request::get("/");
+    if let Some(accept_language) = accept_language {
+        request = request.header(ACCEPT_LANGUAGE, accept_language);
+    }
+    let response = common::respond(
+        common::render_router(cfg, app),
+        request.body(Body::empty()).unwrap(),
+    )
+    .await;
+    let content_language = response
+        .headers()
+        .get(CONTENT_LANGUAGE)
+        .map(|language| language.to_str().unwrap().to_string());
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (content_language, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn best_match_by_quality() {
+    let (content_language, body) = locale(Some("de, en;q=0.5, fr-CH;q=0.9")).await;
+    assert_eq!(content_language.as_deref(), Some("fr"));
+    assert!(body.contains("locale=fr"));
+}
+
+#[tokio::test]
+async fn primary_language_matches_a_region() {
+    let (content_language, _) = locale(Some("pt")).await;
+    assert_eq!(content_language.as_deref(), Some("pt-BR"));
+}
+
+#[tokio::test]
+async fn falls_back_to_the_default() {
+    assert_eq!(locale(Some("de, ja;q=0.8")).await.0.as_deref(), Some("en"));
+    assert_eq!(locale(None).await.0.as_deref(), Some("en"));
+}
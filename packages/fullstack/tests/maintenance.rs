@@ -0,0 +1,63 @@
+//! Make sure the maintenance switch takes every route offline while it is on
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, routing::get, Router};
+use dioxus::prelude::*;
+use http::{header::CONTENT_TYPE, Request, StatusCode};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+#[server(endpoint = "maintenance_greet")]
+async fn greet(name: String) -> Result<String, ServerFnError> {
+    Ok(format!("Hello {name}"))
+}
+
+async fn respond(router: &Router, request: Request<Body>) -> (StatusCode, String) {
+    let response = router.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8_lossy(&body).into_owned())
+}
+
+#[tokio::test]
+async fn every_route_is_unavailable_while_the_switch_is_on() {
+    let maintenance = Arc::new(AtomicBool::new(false));
+    let router = Router::new()
+        .route("/page", get(|| async { "page" }))
+        .register_server_functions()
+        .with_maintenance_switch(
+            MaintenanceSwitch::new(maintenance.clone()).page("<h1>Upgrading</h1>"),
+        )
+        .route("/admin", get(|| async { "admin" }));
+    let page = || Request::get("/page").body(Body::empty()).unwrap();
+    let server_fn = || {
+        Request::post("/api/maintenance_greet")
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from("name=dioxus"))
+            .unwrap()
+    };
+
+    assert_eq!(
+        respond(&router, page()).await,
+        (StatusCode::OK, "page".to_string())
+    );
+
+    maintenance.store(true, Ordering::Relaxed);
+    let unavailable = (
+        StatusCode::SERVICE_UNAVAILABLE,
+        "<h1>Upgrading</h1>".to_string(),
+    );
+    assert_eq!(respond(&router, page()).await, unavailable);
+    assert_eq!(respond(&router, server_fn()).await, unavailable);
+    // Routes added after the switch stay available
+    assert_eq!(
+        respond(&router, Request::get("/admin").body(Body::empty()).unwrap()).await,
+        (StatusCode::OK, "admin".to_string())
+    );
+
+    maintenance.store(false, Ordering::Relaxed);
+    assert_eq!(respond(&router, server_fn()).await.0, StatusCode::OK);
+}
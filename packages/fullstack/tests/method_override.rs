@@ -0,0 +1,57 @@
+//! Make sure POST requests can call server functions that use other methods with a method override header
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{HeaderName, Method, Request, StatusCode};
+use server_fn::codec::GetUrl;
+use tower::ServiceExt;
+
+#[server(endpoint = "method_override_greet", input = GetUrl)]
+async fn greet(name: String) -> Result<String, ServerFnError> {
+    Ok(format!("Hello {name}"))
+}
+
+async fn post(config: ServerFnConfig, method_override: &str) -> StatusCode {
+    Router::new()
+        .register_server_functions_with_config(config)
+        .oneshot(
+            Request::post("/api/method_override_greet?name=dioxus")
+                .header("x-http-method-override", method_override)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+}
+
+fn config(methods: impl IntoIterator<Item = Method>) -> ServerFnConfig {
+    ServerFnConfig::new()
+        .method_override(HeaderName::from_static("x-http-method-override"), methods)
+}
+
+#[tokio::test]
+async fn overridden_posts_call_the_server_function() {
+    assert_eq!(post(config([Method::GET]), "GET").await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn overrides_are_opt_in() {
+    assert_eq!(
+        post(ServerFnConfig::new(), "GET").await,
+        StatusCode::METHOD_NOT_ALLOWED
+    );
+    assert_eq!(
+        post(config([Method::DELETE]), "GET").await,
+        StatusCode::METHOD_NOT_ALLOWED
+    );
+}
+
+#[tokio::test]
+async fn overrides_must_name_the_method() {
+    assert_eq!(
+        post(config([Method::GET]), "DELETE").await,
+        StatusCode::METHOD_NOT_ALLOWED
+    );
+}
@@ -0,0 +1,104 @@
+//! Make sure requests are recorded with the metrics crate
+#![cfg(all(feature = "axum", feature = "metrics"))]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use dioxus_fullstack::prelude::*;
+use http::{header::CONTENT_TYPE, Request, StatusCode};
+use metrics::{Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+use std::sync::{Mutex, OnceLock};
+use tower::ServiceExt;
+
+/// A recorder that remembers the name and labels of every metric that is registered
+#[derive(Default)]
+struct KeyRecorder(Mutex<Vec<String>>);
+
+impl KeyRecorder {
+    fn record(&self, key: &Key) {
+        let mut labels = key
+            .labels()
+            .map(|label| format!("{}={}", label.key(), label.value()))
+            .collect::<Vec<_>>();
+        labels.sort();
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("{} {}", key.name(), labels.join(",")));
+    }
+}
+
+impl Recorder for KeyRecorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        self.record(key);
+        Counter::noop()
+    }
+
+    fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+        self.record(key);
+        Gauge::noop()
+    }
+
+    fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+        self.record(key);
+        Histogram::noop()
+    }
+}
+
+fn recorder() -> &'static KeyRecorder {
+    static RECORDER: OnceLock<&'static KeyRecorder> = OnceLock::new();
+    RECORDER.get_or_init(|| {
+        let recorder: &'static KeyRecorder = Box::leak(Box::default());
+        metrics::set_global_recorder(recorder).unwrap();
+        recorder
+    })
+}
+
+#[server(endpoint = "metrics_ping")]
+async fn ping() -> Result<String, ServerFnError> {
+    Ok("pong".to_string())
+}
+
+#[tokio::test]
+async fn server_functions_are_labeled_with_their_path() {
+    let recorder = recorder();
+    let response = Router::new()
+        .register_server_functions()
+        .with_metrics()
+        .oneshot(
+            Request::post("/api/metrics_ping")
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let keys = recorder.0.lock().unwrap();
+    let labels = "kind=server_fn,method=POST,route=/api/metrics_ping,status=200";
+    assert!(keys.contains(&format!("dioxus_fullstack_requests_total {labels}")));
+    assert!(keys.contains(&format!(
+        "dioxus_fullstack_request_duration_seconds {labels}"
+    )));
+}
+
+#[tokio::test]
+async fn metrics_endpoint_serves_the_rendered_metrics() {
+    let response = Router::new()
+        .with_metrics_endpoint("/metrics", || "requests_total 1\n".to_string())
+        .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(
+        response.headers()[CONTENT_TYPE],
+        "text/plain; version=0.0.4"
+    );
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "requests_total 1\n");
+}
@@ -0,0 +1,56 @@
+//! Make sure static assets are served with the content type the resolver picks
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus_fullstack::prelude::*;
+use http::{header::CONTENT_TYPE, Request, StatusCode};
+
+mod common;
+use common::PublicAssets;
+
+/// Write a few assets to the public directory the server reads static assets from
+fn assets() -> PublicAssets {
+    let assets = PublicAssets::new("mime-assets");
+    assets.write("app_bg.wasm", b"\0asm\x01\0\0\0");
+    assets.write("scene.glb", "glTF");
+    assets.write("main.js", "console.log('hello world');");
+    assets
+}
+
+async fn content_type(assets: &PublicAssets, file: &str) -> String {
+    let router =
+        Router::new().serve_static_assets_with_mime(|path| match path.extension()?.to_str()? {
+            "glb" => Some("model/gltf-binary"),
+            _ => None,
+        });
+    let request = Request::get(assets.route(file))
+        .body(Body::empty())
+        .unwrap();
+    let response = common::respond(router, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    response.headers()[CONTENT_TYPE]
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn wasm_is_served_as_application_wasm() {
+    let assets = assets();
+    assert_eq!(
+        content_type(&assets, "app_bg.wasm").await,
+        "application/wasm"
+    );
+}
+
+#[tokio::test]
+async fn the_resolver_overrides_the_guess() {
+    let assets = assets();
+    assert_eq!(
+        content_type(&assets, "scene.glb").await,
+        "model/gltf-binary"
+    );
+    assert!(content_type(&assets, "main.js")
+        .await
+        .contains("javascript"));
+}
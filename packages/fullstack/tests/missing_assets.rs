@@ -0,0 +1,52 @@
+//! Make sure unmatched requests explain that the static assets were never built if the public directory is empty
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus_fullstack::prelude::*;
+use http::{Request, StatusCode};
+
+mod common;
+use common::PublicAssets;
+
+async fn missing_asset(config: StaticAssetsConfig) -> (StatusCode, String) {
+    let response = common::respond(
+        Router::new().serve_static_assets_with_config(config),
+        Request::get("/assets/main.css")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    (response.status(), common::body_string(response).await)
+}
+
+// The public directory is shared by the whole test binary, so every case runs in the same test
+#[tokio::test]
+async fn the_diagnostic_only_covers_a_missing_or_empty_public_directory() {
+    let public = std::env::current_exe()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("public");
+    std::fs::create_dir_all(&public).unwrap();
+    assert!(
+        std::fs::read_dir(&public).unwrap().next().is_none(),
+        "remove the leftover files in {public:?}"
+    );
+
+    let diagnostic = StaticAssetsConfig::new().missing_assets_diagnostic(true);
+    let (status, body) = missing_asset(diagnostic.clone()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(body.contains("No static assets were found"), "{body}");
+
+    // The diagnostic is opt in
+    let (status, body) = missing_asset(StaticAssetsConfig::new()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(body.is_empty(), "{body}");
+
+    // Once the assets are built, missing files are a plain 404
+    let assets = PublicAssets::new("missing-assets");
+    assets.write("main.js", "console.log('hello world');");
+    let (status, body) = missing_asset(diagnostic).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(body.is_empty(), "{body}");
+}
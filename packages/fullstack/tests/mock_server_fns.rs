@@ -0,0 +1,68 @@
+//! Make sure components that call server functions through the mock client get the canned responses
+#![cfg(feature = "axum")]
+
+use dioxus::prelude::*;
+use server_fn::ServerFn;
+use std::time::Duration;
+
+mod common;
+use common::FakeClient;
+
+#[server(endpoint = "mock_user_name", client = MockClient<FakeClient>)]
+async fn user_name() -> Result<String, ServerFnError> {
+    Ok("Alice".to_string())
+}
+
+fn app() -> Element {
+    // With the server feature, calling the server function runs its body, so send the call through the client like a
+    // client build does
+    let name = use_resource(|| UserName {}.run_on_client());
+    let page = match &*name.read() {
+        Some(Ok(name)) => rsx! { "Hello {name}" },
+        Some(Err(err)) => rsx! { "Failed: {err}" },
+        None => rsx! { "Loading" },
+    };
+    page
+}
+
+/// Render the app with the canned responses until the server function call resolves
+async fn render(mocks: MockServerFns) -> String {
+    let mut dom = VirtualDom::new(app);
+    dom.provide_root_context(mocks);
+    dom.rebuild_in_place();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while dioxus_ssr::render(&dom) == "Loading" {
+            dom.wait_for_work().await;
+            dom.render_immediate(&mut dioxus_core::NoOpMutations);
+        }
+    })
+    .await
+    .expect("the server function call never resolved");
+    dioxus_ssr::render(&dom)
+}
+
+#[tokio::test]
+async fn components_get_the_canned_response() {
+    let mocks = MockServerFns::new().respond_json("/api/mock_user_name", &"Bob");
+    assert_eq!(render(mocks).await, "Hello Bob");
+}
+
+#[tokio::test]
+async fn components_get_the_canned_error() {
+    let mocks = MockServerFns::new().respond_error(
+        "/api/mock_user_name",
+        ServerFnError::<server_fn::error::NoCustomError>::ServerError("no user".to_string()),
+    );
+    let page = render(mocks).await;
+    assert!(page.starts_with("Failed"), "{page}");
+    assert!(page.contains("no user"), "{page}");
+}
+
+#[tokio::test]
+async fn calls_without_a_canned_response_use_the_wrapped_client() {
+    FakeClient::respond_with([Ok(503)]);
+    let mocks = MockServerFns::new().respond_json("/api/another_function", &"Bob");
+    let page = render(mocks).await;
+    assert!(page.starts_with("Failed"), "{page}");
+    assert_eq!(FakeClient::calls(), 1);
+}
@@ -0,0 +1,55 @@
+//! Make sure server functions can read multipart forms as a stream
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{header::CONTENT_TYPE, Request, StatusCode};
+use tower::ServiceExt;
+
+#[server(input = MultipartFormData, endpoint = "multipart_sizes")]
+async fn field_sizes(data: MultipartData) -> Result<Vec<(String, usize)>, ServerFnError> {
+    let mut data = data.into_inner().unwrap();
+    let mut sizes = Vec::new();
+    while let Some(mut field) = data.next_field().await? {
+        let name = field.name().unwrap_or_default().to_string();
+        let mut size = 0;
+        while let Some(chunk) = field.chunk().await? {
+            size += chunk.len();
+        }
+        sizes.push((name, size));
+    }
+    Ok(sizes)
+}
+
+#[tokio::test]
+async fn multipart_fields_are_streamed_to_the_server_function() {
+    let avatar = vec![7u8; 4096];
+    let mut body = Vec::new();
+    body.extend_from_slice(
+        b"--boundary\r\nContent-Disposition: form-data; name=\"username\"\r\n\r\nferris\r\n",
+    );
+    body.extend_from_slice(b"--boundary\r\nContent-Disposition: form-data; name=\"avatar\"; filename=\"avatar.png\"\r\nContent-Type: image/png\r\n\r\n");
+    body.extend_from_slice(&avatar);
+    body.extend_from_slice(b"\r\n--boundary--\r\n");
+
+    let response = Router::new()
+        .register_server_functions()
+        .oneshot(
+            Request::post("/api/multipart_sizes")
+                .header(CONTENT_TYPE, "multipart/form-data; boundary=boundary")
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let sizes: Vec<(String, usize)> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        sizes,
+        vec![("username".to_string(), 6), ("avatar".to_string(), 4096)]
+    );
+}
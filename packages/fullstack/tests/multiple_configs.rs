@@ -0,0 +1,69 @@
+//! Make sure applications with different configs can share one SSRState when they are mounted under different routes
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, routing::get, Router};
+use dioxus::prelude::*;
+use http::{Request, StatusCode};
+use tower::ServiceExt;
+
+fn public_app() -> Element {
+    rsx! { "public site" }
+}
+
+fn admin_app() -> Element {
+    rsx! { "admin dashboard" }
+}
+
+fn config(title: &str) -> ServeConfig {
+    ServeConfig::builder()
+        .index_html(format!(
+            r#"<!DOCTYPE html><html><head><title>{title}</title></head><body><div id="main"></div></body></html>"#
+        ))
+        .build()
+        .unwrap()
+}
+
+fn router() -> Router {
+    let public_cfg = config("Public");
+    let admin_cfg = config("Admin");
+
+    let ssr_state = SSRState::new(&public_cfg);
+    let admin = RenderHandleState::new(admin_cfg, admin_app).with_ssr_state(ssr_state.clone());
+    let public = RenderHandleState::new(public_cfg, public_app).with_ssr_state(ssr_state);
+
+    Router::new()
+        .nest(
+            "/admin",
+            Router::new().fallback(get(render_handler).with_state(admin)),
+        )
+        .fallback(get(render_handler).with_state(public))
+}
+
+async fn render(router: Router, uri: &str) -> String {
+    let response = router
+        .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn configs_under_different_routes_share_ssr_state() {
+    let router = router();
+
+    for _ in 0..2 {
+        let admin = render(router.clone(), "/admin/users").await;
+        assert!(admin.contains("<title>Admin</title>"), "{admin}");
+        assert!(admin.contains("admin dashboard"), "{admin}");
+        assert!(!admin.contains("public site"), "{admin}");
+
+        let public = render(router.clone(), "/").await;
+        assert!(public.contains("<title>Public</title>"), "{public}");
+        assert!(public.contains("public site"), "{public}");
+        assert!(!public.contains("admin dashboard"), "{public}");
+    }
+}
@@ -0,0 +1,44 @@
+//! Make sure server functions with the Ndjson encoding stream their items as newline delimited JSON
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{header::CONTENT_TYPE, Request, StatusCode};
+use tower::ServiceExt;
+
+#[server(endpoint = "ndjson_users", output = Ndjson)]
+async fn users() -> Result<NdjsonStream<String>, ServerFnError> {
+    Ok(NdjsonStream::try_new(futures_util::stream::iter([
+        Ok("first".to_string()),
+        Err(ServerFnError::ServerError("missing".to_string())),
+        Ok("second".to_string()),
+    ])))
+}
+
+#[tokio::test]
+async fn items_are_sent_as_lines() {
+    let response = Router::new()
+        .register_server_functions()
+        .oneshot(
+            Request::post("/api/ndjson_users")
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[CONTENT_TYPE], "application/x-ndjson");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let lines = String::from_utf8_lossy(&body)
+        .lines()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    assert_eq!(lines.len(), 3, "{lines:?}");
+    assert_eq!(lines[0], "\"first\"");
+    assert!(lines[1].starts_with(r#"{"$error":"#), "{lines:?}");
+    assert_eq!(lines[2], "\"second\"");
+}
@@ -0,0 +1,78 @@
+//! Make sure negotiated server functions respond with HTML or JSON depending on the Accept header
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{
+    header::{ACCEPT, CONTENT_TYPE, LOCATION, REFERER},
+    Request, StatusCode,
+};
+use tower::ServiceExt;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct Todo {
+    pub title: String,
+}
+
+impl HtmlFragment for Todo {
+    fn to_html(&self) -> String {
+        format!("<li>{}</li>", self.title)
+    }
+}
+
+#[server(endpoint = "negotiate_todo", output = Negotiated)]
+async fn negotiated_todo() -> Result<HtmlOrJson<Todo>, ServerFnError> {
+    Ok(HtmlOrJson(Todo {
+        title: "Milk".to_string(),
+    }))
+}
+
+async fn call(accept: Option<&str>) -> (StatusCode, Option<String>, String) {
+    let mut request = Request::post("/api/negotiate_todo")
+        .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .header(REFERER, "http://localhost/todos");
+    if let Some(accept) = accept {
+        request = request.header(ACCEPT, accept);
+    }
+    let response = Router::new()
+        .register_server_functions()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert!(response.headers().get(LOCATION).is_none());
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .map(|content_type| content_type.to_str().unwrap().to_string());
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        content_type,
+        String::from_utf8(body.to_vec()).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn html_requests_get_a_fragment() {
+    let (status, content_type, body) =
+        call(Some("text/html,application/xhtml+xml,*/*;q=0.8")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(content_type.unwrap().starts_with("text/html"));
+    assert_eq!(body, "<li>Milk</li>");
+}
+
+#[tokio::test]
+async fn json_requests_get_json() {
+    let (_, content_type, body) = call(Some("application/json")).await;
+    assert_eq!(content_type.as_deref(), Some("application/json"));
+    assert_eq!(body, r#"{"title":"Milk"}"#);
+}
+
+#[tokio::test]
+async fn requests_without_accept_get_json() {
+    let (_, content_type, _) = call(None).await;
+    assert_eq!(content_type.as_deref(), Some("application/json"));
+}
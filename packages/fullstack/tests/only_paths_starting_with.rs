@@ -0,0 +1,73 @@
+//! Make sure server functions can be registered separately by the route they are declared under
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{header::CONTENT_TYPE, Request, StatusCode};
+use tower::ServiceExt;
+
+#[server(prefix = "/api/billing", endpoint = "charge")]
+async fn charge() -> Result<String, ServerFnError> {
+    Ok("charged".to_string())
+}
+
+#[server(prefix = "/api/billing_v2", endpoint = "charge")]
+async fn charge_v2() -> Result<String, ServerFnError> {
+    Ok("charged".to_string())
+}
+
+#[server(prefix = "/api/auth", endpoint = "login")]
+async fn login() -> Result<String, ServerFnError> {
+    Ok("logged in".to_string())
+}
+
+async fn status(router: &Router, path: &str) -> StatusCode {
+    router
+        .clone()
+        .oneshot(
+            Request::post(path)
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn only_server_functions_starting_with_the_path_are_registered() {
+    let router =
+        Router::new().register_server_functions_starting_with("/api/billing", Default::default());
+
+    assert_eq!(status(&router, "/api/billing/charge").await, StatusCode::OK);
+    assert_eq!(
+        status(&router, "/api/billing_v2/charge").await,
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        status(&router, "/api/auth/login").await,
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[tokio::test]
+async fn each_path_can_be_registered_with_its_own_config() {
+    let router = Router::new()
+        .register_server_functions_with_config(
+            ServerFnConfig::new()
+                .only_paths_starting_with("/api/billing")
+                .prefix("/tenant"),
+        )
+        .register_server_functions_starting_with("/api/auth", Default::default());
+
+    assert_eq!(
+        status(&router, "/tenant/api/billing/charge").await,
+        StatusCode::OK
+    );
+    assert_eq!(status(&router, "/api/auth/login").await, StatusCode::OK);
+    assert_eq!(
+        status(&router, "/api/billing/charge").await,
+        StatusCode::NOT_FOUND
+    );
+}
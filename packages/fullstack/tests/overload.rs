@@ -0,0 +1,81 @@
+//! Make sure pages are rendered shell only while the runtime is overloaded
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, routing::get, Router};
+use dioxus::prelude::*;
+use http::{Request, StatusCode};
+use std::sync::atomic::Ordering;
+
+mod common;
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+/// Render the page and count how many times the app was rendered
+async fn render(policy: OverloadPolicy) -> (String, usize) {
+    let cfg = common::serve_config()
+        .shed_load_when(policy)
+        .build()
+        .unwrap();
+    let (state, builds) = common::counting_state(cfg, app);
+    let response = common::respond(
+        Router::new().fallback(get(render_handler).with_state(state)),
+        Request::get("/").body(Body::empty()).unwrap(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let page = common::body_string(response).await;
+    (page, builds.load(Ordering::SeqCst))
+}
+
+/// Fill the global queue of the runtime with tasks it hasn't had the chance to run yet
+fn fill_global_queue(tasks: usize) {
+    let handle = tokio::runtime::Handle::current();
+    std::thread::spawn(move || {
+        for _ in 0..tasks {
+            handle.spawn(async {});
+        }
+    })
+    .join()
+    .unwrap();
+}
+
+#[tokio::test]
+async fn requests_past_the_queue_depth_skip_the_server_render() {
+    fill_global_queue(64);
+    let (page, builds) = render(OverloadPolicy {
+        max_global_queue_depth: Some(8),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(builds, 0);
+    assert!(page.contains(r#"<div id="main"></div>"#), "{page}");
+    assert!(!page.contains("Hello World"), "{page}");
+}
+
+#[tokio::test]
+async fn requests_under_the_thresholds_render_the_app() {
+    let (page, builds) = render(OverloadPolicy {
+        max_global_queue_depth: Some(8),
+        max_alive_tasks: Some(64),
+    })
+    .await;
+    assert_eq!(builds, 1);
+    assert!(page.contains("Hello World"), "{page}");
+}
+
+#[tokio::test]
+async fn requests_past_the_alive_tasks_skip_the_server_render() {
+    let pending = (0..4)
+        .map(|_| tokio::spawn(std::future::pending::<()>()))
+        .collect::<Vec<_>>();
+    let (page, builds) = render(OverloadPolicy {
+        max_alive_tasks: Some(2),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(builds, 0);
+    assert!(!page.contains("Hello World"), "{page}");
+    pending.iter().for_each(|task| task.abort());
+}
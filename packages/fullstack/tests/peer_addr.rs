@@ -0,0 +1,60 @@
+//! Make sure server functions can read the address of the client
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, extract::ConnectInfo, Router};
+use dioxus::prelude::*;
+use http::{header::CONTENT_TYPE, Request};
+use std::net::SocketAddr;
+use tower::ServiceExt;
+
+#[server(endpoint = "peer_addr_whoami")]
+async fn whoami() -> Result<String, ServerFnError> {
+    let server_context = server_context();
+    Ok(format!(
+        "{:?} {:?}",
+        server_context.peer_addr(),
+        server_context.client_ip()
+    ))
+}
+
+async fn whoami_with(config: ServerFnConfig, peer: Option<SocketAddr>) -> String {
+    let mut request = Request::post("/api/peer_addr_whoami")
+        .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .header("x-forwarded-for", "203.0.113.7, 10.0.0.1");
+    if let Some(peer) = peer {
+        request = request.extension(ConnectInfo(peer));
+    }
+    let response = Router::new()
+        .register_server_functions_with_config(config)
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn the_peer_is_the_client_by_default() {
+    let peer: SocketAddr = "10.0.0.2:4000".parse().unwrap();
+    assert_eq!(
+        whoami_with(ServerFnConfig::new(), Some(peer)).await,
+        "Some(10.0.0.2:4000) Some(10.0.0.2)"
+    );
+}
+
+#[tokio::test]
+async fn trusted_forwarded_headers_name_the_client() {
+    let peer: SocketAddr = "10.0.0.2:4000".parse().unwrap();
+    let config = ServerFnConfig::new().trust_forwarded_headers(true);
+    assert_eq!(
+        whoami_with(config, Some(peer)).await,
+        "Some(10.0.0.2:4000) Some(203.0.113.7)"
+    );
+}
+
+#[tokio::test]
+async fn the_peer_is_unknown_without_connect_info() {
+    assert_eq!(whoami_with(ServerFnConfig::new(), None).await, "None None");
+}
@@ -0,0 +1,55 @@
+//! Make sure configured preload links are merged with the links components add
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use dioxus::prelude::*;
+use http::{header::LINK, HeaderValue, Request};
+
+mod common;
+
+fn app() -> Element {
+    if let Some(context) = use_server_context() {
+        let mut response = context.response_parts_mut();
+        response.headers.append(
+            LINK,
+            HeaderValue::from_static(
+                r#"</assets/inter.woff2>; rel="preload"; as="font"; crossorigin"#,
+            ),
+        );
+        response.headers.append(
+            LINK,
+            HeaderValue::from_static("</assets/app.js>; rel=modulepreload"),
+        );
+    }
+    rsx! { "Hello World" }
+}
+
+async fn links(path: &str) -> String {
+    let cfg = common::serve_config()
+        .preload_links(["/assets/inter.woff2"])
+        .preload_links_for(|path| path == "/", ["/assets/hero.avif"])
+        .build()
+        .unwrap();
+    let response = common::respond(
+        common::render_router(cfg, app),
+        Request::get(path).body(Body::empty()).unwrap(),
+    )
+    .await;
+    response.headers()[LINK].to_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn links_are_merged_without_duplicates() {
+    assert_eq!(
+        links("/?ref=home").await,
+        "</assets/inter.woff2>; rel=preload; as=font; crossorigin, </assets/hero.avif>; rel=preload; as=image, </assets/app.js>; rel=modulepreload"
+    );
+}
+
+#[tokio::test]
+async fn route_links_only_apply_to_matching_pages() {
+    assert_eq!(
+        links("/about").await,
+        "</assets/inter.woff2>; rel=preload; as=font; crossorigin, </assets/app.js>; rel=modulepreload"
+    );
+}
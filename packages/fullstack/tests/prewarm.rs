@@ -0,0 +1,94 @@
+//! Make sure prewarming a route fills the renderer pool without filling the incremental cache
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, routing::get, Router};
+use dioxus::prelude::*;
+use http::Request;
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+mod common;
+use common::TempDir;
+
+fn app() -> Element {
+    rsx! {
+        main { h1 { "Hello World" } }
+    }
+}
+
+/// A render state that counts how many virtual doms it builds and keeps its [`SSRState`] once it is created
+struct Prewarmed {
+    state: RenderHandleState,
+    builds: Arc<AtomicUsize>,
+    ssr_state: Arc<Mutex<Option<SSRState>>>,
+}
+
+impl Prewarmed {
+    fn new(cache: &TempDir) -> Self {
+        let cfg = common::cached_serve_config(cache).build().unwrap();
+        let builds = Arc::new(AtomicUsize::new(0));
+        let ssr_state = Arc::new(Mutex::new(None));
+        let state = RenderHandleState::new_with_async_virtual_dom_factory(cfg, {
+            let builds = builds.clone();
+            move || {
+                builds.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async { Ok(VirtualDom::new(app)) })
+            }
+        })
+        .on_ssr_state_init({
+            let ssr_state = ssr_state.clone();
+            move |state, _| *ssr_state.lock() = Some(state.clone())
+        });
+        Self {
+            state,
+            builds,
+            ssr_state,
+        }
+    }
+
+    fn builds(&self) -> usize {
+        self.builds.load(Ordering::SeqCst)
+    }
+
+    fn cached_templates(&self) -> usize {
+        self.ssr_state.lock().as_ref().unwrap().cached_templates()
+    }
+
+    async fn get(&self, path: &str) -> String {
+        let router = Router::new().fallback(get(render_handler).with_state(self.state.clone()));
+        let response = router
+            .oneshot(Request::get(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        common::body_string(response).await
+    }
+}
+
+#[tokio::test]
+async fn prewarming_does_not_fill_the_incremental_cache() {
+    let cache = TempDir::new("prewarm-cache");
+    let prewarmed = Prewarmed::new(&cache);
+    prewarmed.state.prewarm_route("/").await.unwrap();
+    assert_eq!(prewarmed.builds(), 1);
+
+    // The first request renders the page instead of reading the prewarmed render from the cache
+    assert!(prewarmed.get("/").await.contains("Hello World"));
+    assert_eq!(prewarmed.builds(), 2);
+
+    // It is stored in the cache for the next request like any other request
+    assert!(prewarmed.get("/").await.contains("Hello World"));
+    assert_eq!(prewarmed.builds(), 2);
+}
+
+#[tokio::test]
+async fn prewarming_fills_the_template_cache_of_the_renderers() {
+    let cache = TempDir::new("prewarm-templates");
+    let prewarmed = Prewarmed::new(&cache);
+    prewarmed.state.prewarm();
+    assert_eq!(prewarmed.cached_templates(), 0);
+
+    prewarmed.state.prewarm_route("/").await.unwrap();
+    assert!(prewarmed.cached_templates() > 0);
+}
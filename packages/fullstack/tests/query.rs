@@ -0,0 +1,65 @@
+//! Make sure the query string of a request can be deserialized from the server context
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use dioxus::prelude::*;
+use http::Request;
+
+mod common;
+
+#[derive(serde::Deserialize, Debug, Default, PartialEq)]
+struct Pagination {
+    page: Option<u32>,
+    tags: Option<Vec<String>>,
+}
+
+fn context(uri: &str) -> DioxusServerContext {
+    let (parts, _) = Request::get(uri).body(()).unwrap().into_parts();
+    DioxusServerContext::new(parts)
+}
+
+#[test]
+fn queries_are_deserialized() {
+    let pagination: Pagination = context("/posts?page=2&tags[0]=rust&tags[1]=web")
+        .query()
+        .unwrap();
+    assert_eq!(
+        pagination,
+        Pagination {
+            page: Some(2),
+            tags: Some(vec!["rust".to_string(), "web".to_string()]),
+        }
+    );
+}
+
+#[test]
+fn missing_queries_are_empty() {
+    let pagination: Pagination = context("/posts").query().unwrap();
+    assert_eq!(pagination, Pagination::default());
+}
+
+#[test]
+fn malformed_queries_are_errors() {
+    let pagination: Result<Pagination, InvalidQuery> = context("/posts?page=two").query();
+    assert!(pagination.is_err());
+}
+
+fn app() -> Element {
+    let pagination: Pagination = server_context().query().unwrap_or_default();
+    let page = pagination.page.unwrap_or(1);
+    rsx! { "Page {page}" }
+}
+
+#[tokio::test]
+async fn queries_are_available_while_rendering() {
+    let cfg = common::serve_config().build().unwrap();
+    let response = common::respond(
+        common::render_router(cfg, app),
+        Request::get("/posts?page=3").body(Body::empty()).unwrap(),
+    )
+    .await;
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert!(String::from_utf8(body.to_vec()).unwrap().contains("Page 3"));
+}
@@ -0,0 +1,42 @@
+//! Make sure server functions can opt into reading the exact bytes of the request body
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{header::CONTENT_TYPE, Request};
+use tower::ServiceExt;
+
+#[server(endpoint = "raw_body_webhook")]
+async fn webhook(data: String) -> Result<String, ServerFnError> {
+    let _ = data;
+    let body = server_context().raw_body().unwrap_or_default();
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+async fn raw_body(config: ServerFnConfig) -> String {
+    let response = Router::new()
+        .register_server_functions_with_config(config)
+        .oneshot(
+            Request::post("/api/raw_body_webhook")
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::from("data=hello%20world"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn raw_body_is_kept_for_listed_server_functions() {
+    let config = ServerFnConfig::new().raw_body_for("/api/raw_body_webhook");
+    assert_eq!(raw_body(config).await, "data=hello%20world");
+}
+
+#[tokio::test]
+async fn raw_body_is_not_kept_by_default() {
+    assert_eq!(raw_body(ServerFnConfig::new()).await, "");
+}
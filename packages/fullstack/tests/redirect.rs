@@ -0,0 +1,50 @@
+//! Make sure server functions can redirect the client explicitly
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{
+    header::{ACCEPT, CONTENT_TYPE, LOCATION, REFERER},
+    HeaderValue, Request, StatusCode,
+};
+use tower::ServiceExt;
+
+#[server(endpoint = "redirect_login")]
+async fn login(username: String) -> Result<String, ServerFnError> {
+    server_context().redirect(HeaderValue::from_static("/dashboard"));
+    Ok(username)
+}
+
+async fn call(accept: &str) -> axum::response::Response {
+    Router::new()
+        .register_server_functions()
+        .oneshot(
+            Request::post("/api/redirect_login")
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .header(ACCEPT, accept)
+                .header(REFERER, "http://localhost/login")
+                .body(Body::from("username=dioxus"))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn form_posts_follow_the_redirect() {
+    let response = call("text/html").await;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(response.headers()[LOCATION], "/dashboard");
+}
+
+#[tokio::test]
+async fn client_calls_keep_the_response() {
+    let response = call("application/json").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[LOCATION], "/dashboard");
+    assert!(response.headers().contains_key("serverfnredirect"));
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(serde_json::from_slice::<String>(&body).unwrap(), "dioxus");
+}
@@ -0,0 +1,44 @@
+//! Make sure form posts are only redirected to the referer when the redirect is enabled
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{
+    header::{ACCEPT, CONTENT_TYPE, LOCATION, REFERER},
+    Request, StatusCode,
+};
+use tower::ServiceExt;
+
+#[server(endpoint = "referer_redirect_fragment")]
+async fn fragment() -> Result<String, ServerFnError> {
+    Ok("<li>Saved</li>".to_string())
+}
+
+async fn call(config: ServerFnConfig) -> axum::response::Response {
+    Router::new()
+        .register_server_functions_with_config(config)
+        .oneshot(
+            Request::post("/api/referer_redirect_fragment")
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .header(ACCEPT, "text/html")
+                .header(REFERER, "http://localhost/todos")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn form_posts_redirect_by_default() {
+    let response = call(ServerFnConfig::new()).await;
+    assert_eq!(response.status(), StatusCode::FOUND);
+    assert_eq!(response.headers()[LOCATION], "http://localhost/todos");
+}
+
+#[tokio::test]
+async fn disabled_redirect_returns_the_response() {
+    let response = call(ServerFnConfig::new().auto_referer_redirect(false)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get(LOCATION).is_none());
+}
@@ -0,0 +1,19 @@
+//! Make sure the registered server functions can be listed
+#![cfg(feature = "axum")]
+
+use dioxus::prelude::*;
+use http::Method;
+
+#[server(endpoint = "registered_greet")]
+async fn greet(name: String) -> Result<String, ServerFnError> {
+    Ok(format!("Hello {name}"))
+}
+
+#[test]
+fn server_functions_are_listed() {
+    let server_functions = registered_server_functions();
+    assert!(
+        server_functions.contains(&("/api/registered_greet".to_string(), Method::POST)),
+        "{server_functions:?}"
+    );
+}
@@ -0,0 +1,43 @@
+//! Make sure the render diff finds the components that render differently on the server and the client
+#![cfg(feature = "server")]
+
+use dioxus::prelude::*;
+
+#[derive(Clone, Copy)]
+struct DarkMode(bool);
+
+fn app() -> Element {
+    let dark_mode = try_consume_context::<DarkMode>()
+        .map(|mode| mode.0)
+        .unwrap_or_default();
+    rsx! {
+        h1 { "Hello World" }
+        div { class: if dark_mode { "dark" } else { "light" } }
+    }
+}
+
+#[tokio::test]
+async fn matching_renders_have_no_diff() {
+    let diff = diff_route_renders("/", || VirtualDom::new(app), || VirtualDom::new(app)).await;
+    assert_eq!(diff, None);
+}
+
+#[tokio::test]
+async fn mismatched_renders_are_reported() {
+    let diff = diff_route_renders(
+        "/",
+        || VirtualDom::new(app),
+        || VirtualDom::new(app).with_root_context(DarkMode(true)),
+    )
+    .await
+    .expect("the renders should differ");
+    assert!(diff.expected.contains("light"), "{diff}");
+    assert!(diff.found.contains("dark"), "{diff}");
+}
+
+#[test]
+fn diff_reports_the_line_and_column() {
+    let diff = diff_html("<div>\n<p>one</p>", "<div>\n<p>two</p>").unwrap();
+    assert_eq!((diff.line, diff.column), (2, 4));
+    assert_eq!(diff_html("<div></div>", "<div></div>"), None);
+}
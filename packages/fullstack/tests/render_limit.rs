@@ -0,0 +1,72 @@
+//! Make sure the number of pages rendering at once is limited
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, routing::get, Router};
+use dioxus::prelude::*;
+use http::{header::RETRY_AFTER, Request, StatusCode};
+use std::time::Duration;
+use tower::ServiceExt;
+
+mod common;
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+/// Request two slow pages at once with a limit of one render
+async fn statuses(overflow: RenderOverflow) -> Vec<(StatusCode, Option<String>)> {
+    let cfg = common::serve_config()
+        .max_concurrent_renders(1, overflow)
+        .build()
+        .unwrap();
+    let state = RenderHandleState::new_with_async_virtual_dom_factory(cfg, || {
+        Box::pin(async {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            Ok(VirtualDom::new(app))
+        })
+    });
+    let router = Router::new().fallback(get(render_handler).with_state(state));
+
+    let [first, second] = [(), ()].map(|_| {
+        let router = router.clone();
+        async move {
+            let response = router
+                .oneshot(Request::get("/").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .map(|retry_after| retry_after.to_str().unwrap().to_string());
+            let status = response.status();
+            // Read the whole page so the render slot is released
+            axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            (status, retry_after)
+        }
+    });
+    let (first, second) = tokio::join!(first, second);
+    let mut statuses = vec![first, second];
+    statuses.sort();
+    statuses
+}
+
+#[tokio::test]
+async fn renders_over_the_limit_are_shed() {
+    assert_eq!(
+        statuses(RenderOverflow::Shed).await,
+        [
+            (StatusCode::OK, None),
+            (StatusCode::SERVICE_UNAVAILABLE, Some("1".to_string()))
+        ]
+    );
+}
+
+#[tokio::test]
+async fn queued_renders_wait_for_a_slot() {
+    assert_eq!(
+        statuses(RenderOverflow::Queue(Duration::from_secs(5))).await,
+        [(StatusCode::OK, None), (StatusCode::OK, None)]
+    );
+}
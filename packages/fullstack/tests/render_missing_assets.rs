@@ -0,0 +1,70 @@
+//! Make sure missing assets requested as html render the page while other requests get a bare 404
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{header::ACCEPT, Request, StatusCode};
+
+mod common;
+use common::PublicAssets;
+
+fn app() -> Element {
+    rsx! { "This page moved" }
+}
+
+async fn request(router: Router, path: &str, accept: &str) -> (StatusCode, String) {
+    let response = common::respond(
+        router,
+        Request::get(path)
+            .header(ACCEPT, accept)
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    (response.status(), common::body_string(response).await)
+}
+
+#[tokio::test]
+async fn missing_assets_render_the_page_for_html_requests() {
+    let assets = PublicAssets::new("render-missing-assets");
+    assets.write("main-abc123.js", "console.log('hello world');");
+    let state = RenderHandleState::new(common::serve_config().build().unwrap(), app);
+    let router = Router::new()
+        .serve_static_assets_with_config(StaticAssetsConfig::new().render_missing_assets(state));
+
+    let (status, page) = request(router.clone(), &assets.route("main-old.js"), "text/html").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(page.contains("This page moved"), "{page}");
+
+    let (status, body) = request(router.clone(), &assets.route("main-old.js"), "*/*").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(body.is_empty(), "{body}");
+
+    // Assets that exist are still served
+    let (status, body) = request(router, &assets.route("main-abc123.js"), "text/html").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "console.log('hello world');");
+}
+
+#[tokio::test]
+async fn missing_assets_are_bare_404s_by_default() {
+    let assets = PublicAssets::new("render-missing-assets-default");
+    assets.write("main-abc123.js", "console.log('hello world');");
+    let router = Router::new().serve_dioxus_application(common::serve_config(), app);
+
+    let (status, body) = request(router, &assets.route("main-old.js"), "text/html").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(!body.contains("This page moved"), "{body}");
+}
+
+#[tokio::test]
+async fn the_serve_config_renders_missing_assets() {
+    let assets = PublicAssets::new("render-missing-assets-config");
+    assets.write("main-abc123.js", "console.log('hello world');");
+    let router = Router::new()
+        .serve_dioxus_application(common::serve_config().render_missing_assets(true), app);
+
+    let (status, page) = request(router, &assets.route("main-old.js"), "text/html").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(page.contains("This page moved"), "{page}");
+}
@@ -0,0 +1,60 @@
+//! Make sure requests the render mode picks shell only rendering for get the html shell without a server render
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, routing::get, Router};
+use dioxus::prelude::*;
+use http::{header::USER_AGENT, Request, StatusCode};
+use std::sync::atomic::Ordering;
+
+mod common;
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+/// Render the page for the user agent and count how many times the app was rendered
+async fn render(user_agent: &str) -> (String, usize) {
+    let cfg = common::serve_config()
+        .render_mode_for(|parts| {
+            let is_bot = parts
+                .headers
+                .get(USER_AGENT)
+                .and_then(|agent| agent.to_str().ok())
+                .is_some_and(|agent| agent.contains("bot"));
+            if is_bot {
+                RenderMode::Full
+            } else {
+                RenderMode::ShellOnly
+            }
+        })
+        .build()
+        .unwrap();
+    let (state, builds) = common::counting_state(cfg, app);
+    let response = common::respond(
+        Router::new().fallback(get(render_handler).with_state(state)),
+        Request::get("/")
+            .header(USER_AGENT, user_agent)
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let page = common::body_string(response).await;
+    (page, builds.load(Ordering::SeqCst))
+}
+
+#[tokio::test]
+async fn shell_only_requests_skip_the_server_render() {
+    let (page, builds) = render("Mozilla/5.0").await;
+    assert_eq!(builds, 0);
+    assert!(page.contains(r#"<div id="main"></div>"#), "{page}");
+    assert!(!page.contains("Hello World"), "{page}");
+    assert!(!page.contains("data-node-hydration"), "{page}");
+}
+
+#[tokio::test]
+async fn full_requests_render_the_app() {
+    let (page, builds) = render("Googlebot/2.1").await;
+    assert_eq!(builds, 1);
+    assert!(page.contains("Hello World"), "{page}");
+}
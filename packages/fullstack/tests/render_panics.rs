@@ -0,0 +1,38 @@
+//! Make sure panics while rendering become error responses when they are caught
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, routing::get, Router};
+use dioxus::prelude::*;
+use http::{Request, StatusCode};
+use tower::ServiceExt;
+
+mod common;
+
+fn app() -> Element {
+    panic!("the database is on fire")
+}
+
+fn error_page() -> Element {
+    rsx! { "Something went wrong" }
+}
+
+#[tokio::test]
+async fn caught_panics_render_the_error_component() {
+    let cfg = common::serve_config()
+        .catch_render_panics(true)
+        .build()
+        .unwrap();
+    let state = RenderHandleState::new(cfg, app).with_error_component(error_page);
+    let response = Router::new()
+        .fallback(get(render_handler).with_state(state))
+        .oneshot(Request::get("/").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert!(String::from_utf8(body.to_vec())
+        .unwrap()
+        .contains("Something went wrong"));
+}
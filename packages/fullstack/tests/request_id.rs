@@ -0,0 +1,64 @@
+//! Make sure every response echoes the id of its request in the X-Request-Id header
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{header::CONTENT_TYPE, Request};
+
+mod common;
+
+fn app() -> Element {
+    let request_id = server_context().request_id().to_string();
+    rsx! { "Request {request_id}" }
+}
+
+#[server(endpoint = "request_id_echo")]
+async fn echo() -> Result<String, ServerFnError> {
+    Ok(server_context().request_id().to_string())
+}
+
+#[tokio::test]
+async fn pages_echo_the_request_id() {
+    let response = common::respond(
+        common::render_router(common::serve_config().build().unwrap(), app),
+        Request::get("/")
+            .header("x-request-id", "abc-123")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(response.headers()["x-request-id"], "abc-123");
+    let page = common::body_string(response).await;
+    assert!(page.contains("Request abc-123"), "{page}");
+}
+
+#[tokio::test]
+async fn requests_without_an_id_get_a_new_one() {
+    let request = || Request::get("/").body(Body::empty()).unwrap();
+    let router = common::render_router(common::serve_config().build().unwrap(), app);
+    let first = common::respond(router.clone(), request()).await;
+    let second = common::respond(router, request()).await;
+
+    let first = first.headers()["x-request-id"]
+        .to_str()
+        .unwrap()
+        .to_string();
+    let second = second.headers()["x-request-id"].to_str().unwrap();
+    assert!(!first.is_empty());
+    assert_ne!(first, second);
+}
+
+#[tokio::test]
+async fn server_functions_echo_the_request_id() {
+    let response = common::respond(
+        Router::new().register_server_functions(),
+        Request::post("/api/request_id_echo")
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header("x-request-id", "fn-42")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(response.headers()["x-request-id"], "fn-42");
+    assert_eq!(common::body_string(response).await, r#""fn-42""#);
+}
@@ -0,0 +1,57 @@
+//! Make sure pages can read the origin the client sent the request to
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use dioxus::prelude::*;
+use http::{header::HOST, Request};
+
+mod common;
+
+fn app() -> Element {
+    let origin = server_context().origin().unwrap_or_default();
+    rsx! { "origin={origin}" }
+}
+
+async fn origin(trust_forwarded_headers: bool, headers: &[(&str, &str)]) -> String {
+    let cfg = common::serve_config()
+        .trust_forwarded_headers(trust_forwarded_headers)
+        .build()
+        .unwrap();
+    let mut request = Request::get("/").header(HOST, "internal:8080");
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    let page = common::body_string(
+        common::respond(
+            common::render_router(cfg, app),
+            request.body(Body::empty()).unwrap(),
+        )
+        .await,
+    )
+    .await;
+    let start = page.find("origin=").unwrap_or_else(|| panic!("{page}")) + "origin=".len();
+    let end = start + page[start..].find('<').unwrap();
+    page[start..end].to_string()
+}
+
+const FORWARDED: &[(&str, &str)] = &[
+    ("x-forwarded-proto", "https"),
+    ("x-forwarded-host", "example.com, proxy.local"),
+];
+
+#[tokio::test]
+async fn forwarded_headers_are_ignored_by_default() {
+    assert_eq!(origin(false, FORWARDED).await, "http://internal:8080");
+}
+
+#[tokio::test]
+async fn trusted_forwarded_headers_build_the_origin() {
+    assert_eq!(origin(true, FORWARDED).await, "https://example.com");
+}
+
+#[tokio::test]
+async fn the_forwarded_header_wins_over_the_x_forwarded_headers() {
+    let mut headers = FORWARDED.to_vec();
+    headers.push(("forwarded", r#"proto=https;host="shop.example.com""#));
+    assert_eq!(origin(true, &headers).await, "https://shop.example.com");
+}
@@ -0,0 +1,82 @@
+//! Make sure the status, version and extensions set in the response parts are applied to the response
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, response::Response, Router};
+use dioxus::prelude::*;
+use http::{header::CONTENT_TYPE, Request, StatusCode, Version};
+
+mod common;
+
+/// A trace context a downstream layer reads from the response
+#[derive(Clone, Debug, PartialEq)]
+struct TraceContext(&'static str);
+
+fn set_response_parts() {
+    let context = server_context();
+    let mut parts = context.response_parts_mut();
+    parts.status = StatusCode::CREATED;
+    parts.version = Version::HTTP_2;
+    parts.extensions.insert(TraceContext("trace-1"));
+}
+
+#[server(endpoint = "response_parts_create")]
+async fn create() -> Result<(), ServerFnError> {
+    set_response_parts();
+    Ok(())
+}
+
+#[server(endpoint = "response_parts_read")]
+async fn read() -> Result<(), ServerFnError> {
+    Ok(())
+}
+
+async fn call(path: &str) -> Response {
+    common::respond(
+        Router::new().register_server_functions(),
+        Request::post(path)
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn server_functions_apply_the_response_parts() {
+    let response = call("/api/response_parts_create").await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(response.version(), Version::HTTP_2);
+    assert_eq!(
+        response.extensions().get::<TraceContext>(),
+        Some(&TraceContext("trace-1"))
+    );
+}
+
+#[tokio::test]
+async fn untouched_response_parts_keep_the_defaults() {
+    let response = call("/api/response_parts_read").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.version(), Version::HTTP_11);
+    assert!(response.extensions().get::<TraceContext>().is_none());
+}
+
+#[tokio::test]
+async fn pages_apply_the_response_parts() {
+    fn app() -> Element {
+        use_hook(set_response_parts);
+        rsx! { "Created" }
+    }
+
+    let cfg = common::serve_config().build().unwrap();
+    let response = common::respond(
+        common::render_router(cfg, app),
+        Request::get("/").body(Body::empty()).unwrap(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(response.version(), Version::HTTP_2);
+    assert_eq!(
+        response.extensions().get::<TraceContext>(),
+        Some(&TraceContext("trace-1"))
+    );
+}
@@ -0,0 +1,99 @@
+//! Make sure the retry client resends failed GET calls according to the retry policy
+#![cfg(feature = "axum")]
+
+use dioxus_fullstack::prelude::*;
+use server_fn::{client::Client, error::NoCustomError, request::ClientReq, ServerFnError};
+use std::time::{Duration, Instant};
+
+mod common;
+use common::{FakeClient, FakeRequest};
+
+/// Every test follows the same global policy, so tests that run at the same time don't change it under each other.
+/// Without the cap, the backoff of the four retries would add up to 20 + 40 + 80 + 160 = 300ms.
+fn set_policy() {
+    set_retry_policy(
+        RetryPolicy::new()
+            .max_attempts(5)
+            .initial_backoff(Duration::from_millis(20))
+            .max_backoff(Duration::from_millis(30)),
+    );
+}
+
+async fn get(responses: impl IntoIterator<Item = Result<u16, ServerFnError>>) -> Option<u16> {
+    set_policy();
+    FakeClient::respond_with(responses);
+    let request = <RetryRequest<FakeRequest> as ClientReq<NoCustomError>>::try_new_get(
+        "/api/posts",
+        "",
+        "",
+        "",
+    )
+    .unwrap();
+    <RetryClient<FakeClient> as Client<NoCustomError>>::send(request)
+        .await
+        .ok()
+        .map(|response| server_fn::response::ClientRes::<NoCustomError>::status(&response))
+}
+
+#[tokio::test]
+async fn failed_calls_are_retried_until_they_succeed() {
+    let status = get([
+        Ok(503),
+        Err(ServerFnError::Request("connection reset".to_string())),
+        Ok(200),
+    ])
+    .await;
+    assert_eq!(status, Some(200));
+    assert_eq!(FakeClient::calls(), 3);
+}
+
+#[tokio::test]
+async fn calls_stop_after_the_max_attempts() {
+    let status = get([500; 6].map(Ok)).await;
+    assert_eq!(status, Some(500));
+    assert_eq!(FakeClient::calls(), 5);
+}
+
+#[tokio::test]
+async fn rejected_calls_are_not_retried() {
+    assert_eq!(get([Ok(404), Ok(200)]).await, Some(404));
+    assert_eq!(FakeClient::calls(), 1);
+
+    let status = get([
+        Err(ServerFnError::ServerError("invalid input".to_string())),
+        Ok(200),
+    ])
+    .await;
+    assert_eq!(status, None);
+    assert_eq!(FakeClient::calls(), 1);
+}
+
+#[tokio::test]
+async fn posts_are_not_retried() {
+    set_policy();
+    FakeClient::respond_with([Ok(500), Ok(200)]);
+    let request = <RetryRequest<FakeRequest> as ClientReq<NoCustomError>>::try_new_post(
+        "/api/posts",
+        "",
+        "",
+        String::new(),
+    )
+    .unwrap();
+    assert!(
+        <RetryClient<FakeClient> as Client<NoCustomError>>::send(request)
+            .await
+            .is_ok()
+    );
+    assert_eq!(FakeClient::calls(), 1);
+}
+
+#[tokio::test]
+async fn the_backoff_doubles_up_to_the_max_backoff() {
+    let start = Instant::now();
+    get([500; 5].map(Ok)).await;
+    let waited = start.elapsed();
+    assert_eq!(FakeClient::calls(), 5);
+    // 20 + 30 + 30 + 30ms with the cap
+    assert!(waited >= Duration::from_millis(110), "{waited:?}");
+    assert!(waited < Duration::from_millis(300), "{waited:?}");
+}
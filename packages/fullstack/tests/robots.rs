@@ -0,0 +1,56 @@
+//! Make sure robots directives from the server context are sent as a header and a meta tag
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{header::CONTENT_TYPE, Request};
+use tower::ServiceExt;
+
+mod common;
+
+fn app() -> Element {
+    server_context().set_robots_with_meta("noindex, nofollow");
+    rsx! { "Account settings" }
+}
+
+#[server(endpoint = "robots_report")]
+async fn report() -> Result<String, ServerFnError> {
+    server_context().set_robots("noindex");
+    Ok("Quarterly numbers".to_string())
+}
+
+#[tokio::test]
+async fn pages_get_a_header_and_a_meta_tag() {
+    let cfg = common::serve_config().build().unwrap();
+    let response = common::respond(
+        common::render_router(cfg, app),
+        Request::get("/").body(Body::empty()).unwrap(),
+    )
+    .await;
+
+    assert_eq!(response.headers()["x-robots-tag"], "noindex, nofollow");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body = String::from_utf8_lossy(&body);
+    let meta = body
+        .find(r#"<meta name="robots" content="noindex, nofollow">"#)
+        .expect("the page should have a robots meta tag");
+    assert!(meta < body.find("</head>").unwrap(), "{body}");
+}
+
+#[tokio::test]
+async fn server_functions_get_a_header() {
+    let response = Router::new()
+        .register_server_functions()
+        .oneshot(
+            Request::post("/api/robots_report")
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.headers()["x-robots-tag"], "noindex");
+}
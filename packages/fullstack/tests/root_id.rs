@@ -0,0 +1,43 @@
+//! Make sure a custom root id is used for the server rendered markup and passed on to the client
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use dioxus::prelude::*;
+use http::Request;
+
+mod common;
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+#[tokio::test]
+async fn the_client_is_told_the_root_id() {
+    let root_id = String::from("embedded-app");
+    let cfg = ServeConfig::builder()
+        .index_html(
+            r#"<!DOCTYPE html><html><head></head><body><div id="embedded-app"></div></body></html>"#
+                .to_string(),
+        )
+        .with_root_id(&root_id)
+        .build()
+        .unwrap();
+    let response = common::respond(
+        common::render_router(cfg, app),
+        Request::get("/").body(Body::empty()).unwrap(),
+    )
+    .await;
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body = String::from_utf8_lossy(&body);
+
+    let mount = body
+        .find(r#"<div id="embedded-app">"#)
+        .expect("the app should be rendered into the custom root");
+    assert!(body[mount..].contains("Hello World"), "{body}");
+    let script = body
+        .find(r#"window.dioxus_root_id="embedded-app";"#)
+        .expect("the client should be told the custom root id");
+    assert!(script < mount, "{body}");
+}
@@ -0,0 +1,61 @@
+//! Make sure request timeouts vary by route pattern
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{header::CONTENT_TYPE, Request, StatusCode};
+use std::time::Duration;
+
+mod common;
+
+#[server(prefix = "/api", endpoint = "route_timeout_query")]
+async fn query(millis: u64) -> Result<u64, ServerFnError> {
+    tokio::time::sleep(Duration::from_millis(millis)).await;
+    Ok(millis)
+}
+
+#[server(prefix = "/export", endpoint = "route_timeout_archive")]
+async fn archive(millis: u64) -> Result<u64, ServerFnError> {
+    tokio::time::sleep(Duration::from_millis(millis)).await;
+    Ok(millis)
+}
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+fn router() -> Router {
+    let cfg = common::serve_config()
+        .request_timeout(Duration::from_millis(50))
+        .request_timeout_for("/export/*", Duration::from_secs(5))
+        .request_timeout_for("/api/*", Duration::from_millis(50));
+    Router::new().serve_dioxus_application(cfg, app)
+}
+
+async fn call(path: &str, millis: u64) -> StatusCode {
+    let request = Request::post(path)
+        .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(Body::from(format!("millis={millis}")))
+        .unwrap();
+    tokio::time::timeout(Duration::from_secs(5), common::respond(router(), request))
+        .await
+        .expect("the request should finish before the test times out")
+        .status()
+}
+
+#[tokio::test]
+async fn routes_that_match_a_pattern_use_its_timeout() {
+    assert_eq!(
+        call("/export/route_timeout_archive", 200).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        call("/api/route_timeout_query", 200).await,
+        StatusCode::GATEWAY_TIMEOUT
+    );
+}
+
+#[tokio::test]
+async fn fast_requests_respond() {
+    assert_eq!(call("/api/route_timeout_query", 0).await, StatusCode::OK);
+}
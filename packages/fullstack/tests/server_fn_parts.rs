@@ -0,0 +1,59 @@
+//! Make sure server functions see the same request parts through the server context and their extractors
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{
+    header::{ACCEPT, CONTENT_TYPE, LOCATION, REFERER},
+    HeaderMap, Request, StatusCode,
+};
+
+mod common;
+
+#[server(endpoint = "parts_tenant")]
+async fn tenant() -> Result<(String, String), ServerFnError> {
+    let tenant = |headers: &HeaderMap| {
+        headers
+            .get("x-tenant")
+            .and_then(|tenant| tenant.to_str().ok())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let extracted: HeaderMap = extract().await?;
+    let from_context = tenant(&server_context().request_parts().headers);
+    Ok((tenant(&extracted), from_context))
+}
+
+fn call(headers: &[(http::HeaderName, &str)]) -> Request<Body> {
+    let mut request = Request::post("/api/parts_tenant")
+        .header(CONTENT_TYPE, "application/x-www-form-urlencoded");
+    for (name, value) in headers {
+        request = request.header(name, *value);
+    }
+    request.body(Body::empty()).unwrap()
+}
+
+#[tokio::test]
+async fn the_context_and_the_extractors_read_the_same_headers() {
+    let response = common::respond(
+        Router::new().register_server_functions(),
+        call(&[(http::HeaderName::from_static("x-tenant"), "acme")]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(common::body_string(response).await, r#"["acme","acme"]"#);
+}
+
+#[tokio::test]
+async fn form_submissions_still_redirect_to_the_referer() {
+    let response = common::respond(
+        Router::new().register_server_functions(),
+        call(&[
+            (ACCEPT, "text/html"),
+            (REFERER, "https://example.com/settings"),
+        ]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FOUND);
+    assert_eq!(response.headers()[LOCATION], "https://example.com/settings");
+}
@@ -0,0 +1,43 @@
+//! Make sure server functions can be registered under a route prefix
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{header::CONTENT_TYPE, Request, StatusCode};
+
+mod common;
+
+#[server(endpoint = "prefixed_tenant")]
+async fn tenant() -> Result<String, ServerFnError> {
+    Ok("tenant-a".to_string())
+}
+
+fn router() -> Router {
+    Router::new().register_server_functions_with_prefix("tenant-a", Default::default())
+}
+
+async fn call(path: &str) -> (StatusCode, String) {
+    let response = common::respond(
+        router(),
+        Request::post(path)
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    (response.status(), common::body_string(response).await)
+}
+
+#[tokio::test]
+async fn server_functions_are_served_under_the_prefix() {
+    assert_eq!(
+        call("/tenant-a/api/prefixed_tenant").await,
+        (StatusCode::OK, "\"tenant-a\"".to_string())
+    );
+}
+
+#[tokio::test]
+async fn server_functions_are_not_served_at_their_original_path() {
+    let (status, _) = call("/api/prefixed_tenant").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,43 @@
+//! Make sure server functions that take longer than the timeout respond with 504 Gateway Timeout
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{header::CONTENT_TYPE, Request, StatusCode};
+use std::time::Duration;
+use tower::ServiceExt;
+
+#[server(endpoint = "timeout_sleep")]
+async fn sleep(millis: u64) -> Result<u64, ServerFnError> {
+    tokio::time::sleep(Duration::from_millis(millis)).await;
+    Ok(millis)
+}
+
+async fn status(config: ServerFnConfig, millis: u64) -> StatusCode {
+    Router::new()
+        .register_server_functions_with_config(config)
+        .oneshot(
+            Request::post("/api/timeout_sleep")
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::from(format!("millis={millis}")))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn fast_server_functions_respond() {
+    let config = ServerFnConfig::new().timeout(Duration::from_secs(5));
+    assert_eq!(status(config, 0).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn slow_server_functions_time_out() {
+    let config = ServerFnConfig::new().timeout(Duration::from_millis(50));
+    let status = tokio::time::timeout(Duration::from_secs(5), status(config, 60_000))
+        .await
+        .expect("the server function should be cut off at the timeout");
+    assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+}
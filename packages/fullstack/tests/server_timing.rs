@@ -0,0 +1,37 @@
+//! Make sure server rendered pages report how long the render took
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, routing::get, Router};
+use dioxus::prelude::*;
+use http::Request;
+use std::time::Duration;
+use tower::ServiceExt;
+
+mod common;
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+#[tokio::test]
+async fn render_time_is_sent_in_server_timing() {
+    let cfg = common::serve_config().build().unwrap();
+    let state = RenderHandleState::new_with_async_virtual_dom_factory(cfg, || {
+        Box::pin(async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok(VirtualDom::new(app))
+        })
+    });
+    let response = Router::new()
+        .fallback(get(render_handler).with_state(state))
+        .oneshot(Request::get("/").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let timing = response.headers()["server-timing"].to_str().unwrap();
+    let millis: f64 = timing
+        .strip_prefix("ssr;dur=")
+        .expect("the render is reported as ssr")
+        .parse()
+        .unwrap();
+    assert!(millis >= 50.0, "{timing}");
+}
@@ -0,0 +1,57 @@
+//! Make sure in-flight server functions are drained when the server shuts down
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{header::CONTENT_TYPE, Request, StatusCode};
+use std::time::Duration;
+use tower::ServiceExt;
+
+#[server(endpoint = "shutdown_slow")]
+async fn slow() -> Result<(), ServerFnError> {
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    Ok(())
+}
+
+async fn call(router: Router) -> StatusCode {
+    router
+        .oneshot(
+            Request::post("/api/shutdown_slow")
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn running_calls_finish_and_new_calls_are_rejected() {
+    let (router, shutdown) =
+        Router::new().register_server_functions_with_shutdown(Default::default());
+
+    let running = tokio::spawn(call(router.clone()));
+    // Give the call time to start
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(shutdown.active(), 1);
+
+    shutdown.shutdown();
+    assert_eq!(call(router).await, StatusCode::SERVICE_UNAVAILABLE);
+
+    assert!(shutdown.wait_for_idle(Duration::from_secs(5)).await);
+    assert_eq!(running.await.unwrap(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn waiting_times_out_if_calls_take_too_long() {
+    let (router, shutdown) =
+        Router::new().register_server_functions_with_shutdown(Default::default());
+
+    let running = tokio::spawn(call(router));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    shutdown.shutdown();
+    assert!(!shutdown.wait_for_idle(Duration::from_millis(10)).await);
+    running.await.unwrap();
+}
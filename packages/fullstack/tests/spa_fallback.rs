@@ -0,0 +1,49 @@
+//! Make sure the SPA fallback serves the index file for client side routes
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, routing::get, Router};
+use dioxus_fullstack::prelude::*;
+use http::{header::ACCEPT, Request, StatusCode};
+
+mod common;
+use common::PublicAssets;
+
+const INDEX: &str = "<!DOCTYPE html><html><body>spa</body></html>";
+
+/// Request the path and read the response before the index file is removed
+async fn request(path: &str, accept: &str) -> (StatusCode, String) {
+    let assets = PublicAssets::new("spa");
+    assets.write("index.html", INDEX);
+
+    let router = Router::new()
+        .route("/api/hello", get(|| async { "hello" }))
+        .serve_static_assets_with_spa_fallback(&format!("{}/index.html", assets.name()));
+    let response = common::respond(
+        router,
+        Request::get(path)
+            .header(ACCEPT, accept)
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    (response.status(), common::body_string(response).await)
+}
+
+#[tokio::test]
+async fn client_routes_get_the_index() {
+    let (status, body) = request("/blog/post/1", "text/html,*/*;q=0.8").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, INDEX);
+}
+
+#[tokio::test]
+async fn routes_match_before_the_fallback() {
+    let (_, body) = request("/api/hello", "text/html").await;
+    assert_eq!(body, "hello");
+}
+
+#[tokio::test]
+async fn requests_that_do_not_accept_html_get_a_404() {
+    let (status, _) = request("/missing.png", "image/*").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
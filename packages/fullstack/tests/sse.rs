@@ -0,0 +1,38 @@
+//! Make sure server functions with the Sse encoding stream their items as Server-Sent Events
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus::prelude::*;
+use http::{header::CONTENT_TYPE, Request, StatusCode};
+use tower::ServiceExt;
+
+#[server(endpoint = "sse_notifications", output = Sse)]
+async fn notifications() -> Result<SseStream<String>, ServerFnError> {
+    Ok(SseStream::new(futures_util::stream::iter([
+        "first".to_string(),
+        "second".to_string(),
+    ])))
+}
+
+#[tokio::test]
+async fn items_are_sent_as_events() {
+    let response = Router::new()
+        .register_server_functions()
+        .oneshot(
+            Request::post("/api/sse_notifications")
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[CONTENT_TYPE], "text/event-stream");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body = String::from_utf8_lossy(&body);
+    assert!(body.contains("data: \"first\"\n\n"), "{body}");
+    assert!(body.contains("data: \"second\"\n\n"), "{body}");
+}
@@ -0,0 +1,38 @@
+//! Make sure the SSRState init callback runs exactly once
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, routing::get, Router};
+use dioxus::prelude::*;
+use http::Request;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+mod common;
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+#[tokio::test]
+async fn init_callback_runs_once_across_requests() {
+    let cfg = common::serve_config().build().unwrap();
+    let inits = Arc::new(AtomicUsize::new(0));
+    let state = RenderHandleState::new(cfg, app).on_ssr_state_init({
+        let inits = inits.clone();
+        move |_, _| {
+            inits.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+    let router = Router::new().fallback(get(render_handler).with_state(state));
+
+    for _ in 0..3 {
+        router
+            .clone()
+            .oneshot(Request::get("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(inits.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,69 @@
+//! Make sure a RenderHandleState built with the builder renders the page
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, routing::get, Router};
+use dioxus::prelude::*;
+use http::Request;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+mod common;
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+fn config() -> ServeConfig {
+    common::serve_config().build().unwrap()
+}
+
+async fn render(state: RenderHandleState) -> String {
+    let response = Router::new()
+        .fallback(get(render_handler).with_state(state))
+        .oneshot(Request::get("/").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn builder_renders_the_root() {
+    let state = RenderHandleState::builder(config())
+        .head_builder(|_| r#"<meta name="built">"#.to_string())
+        .root(app)
+        .build();
+    let page = render(state).await;
+    assert!(page.contains("Hello World"));
+    assert!(page.contains(r#"<meta name="built">"#));
+}
+
+#[tokio::test]
+async fn shared_ssr_state_is_initialized_once() {
+    let inits = Arc::new(AtomicUsize::new(0));
+    let first = RenderHandleState::builder(config())
+        .root(app)
+        .on_ssr_state_init({
+            let inits = inits.clone();
+            move |_, _| {
+                inits.fetch_add(1, Ordering::SeqCst);
+            }
+        })
+        .build();
+    let second = RenderHandleState::builder(config())
+        .root(app)
+        .share_ssr_state_with(&first)
+        .on_ssr_state_init({
+            let inits = inits.clone();
+            move |_, _| {
+                inits.fetch_add(1, Ordering::SeqCst);
+            }
+        })
+        .build();
+    render(first).await;
+    render(second).await;
+    assert_eq!(inits.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,68 @@
+//! Make sure precompressed static assets get an ETag that depends on the encoding they were served with
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus_fullstack::prelude::*;
+use http::{
+    header::{ACCEPT_ENCODING, CONTENT_ENCODING, ETAG, IF_NONE_MATCH, VARY},
+    Request, Response, StatusCode,
+};
+
+mod common;
+use common::PublicAssets;
+
+/// Write an asset and its brotli variant to the public directory the server reads static assets from
+fn assets() -> PublicAssets {
+    let assets = PublicAssets::new("etag-assets");
+    assets.write("main.js", "console.log('hello world');");
+    // The content doesn't need to be valid brotli, the server only serves the file
+    assets.write("main.js.br", "compressed");
+    assets
+}
+
+async fn get(assets: &PublicAssets, headers: &[(http::HeaderName, &str)]) -> Response<Body> {
+    let mut request = Request::get(assets.route("main.js"));
+    for (name, value) in headers {
+        request = request.header(name, *value);
+    }
+    common::respond(
+        Router::new().serve_static_assets(),
+        request.body(Body::empty()).unwrap(),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn etags_differ_between_encodings() {
+    let assets = assets();
+    let identity = get(&assets, &[]).await;
+    let brotli = get(&assets, &[(ACCEPT_ENCODING, "br")]).await;
+
+    assert_eq!(brotli.headers()[CONTENT_ENCODING], "br");
+    assert!(identity.headers().get(CONTENT_ENCODING).is_none());
+    for response in [&identity, &brotli] {
+        assert!(response.headers()[VARY]
+            .to_str()
+            .unwrap()
+            .to_ascii_lowercase()
+            .contains("accept-encoding"));
+    }
+    assert_ne!(identity.headers()[ETAG], brotli.headers()[ETAG]);
+}
+
+#[tokio::test]
+async fn if_none_match_only_matches_the_same_encoding() {
+    let assets = assets();
+    let brotli = get(&assets, &[(ACCEPT_ENCODING, "br")]).await;
+    let brotli_etag = brotli.headers()[ETAG].to_str().unwrap().to_string();
+
+    let same_encoding = get(
+        &assets,
+        &[(ACCEPT_ENCODING, "br"), (IF_NONE_MATCH, &brotli_etag)],
+    )
+    .await;
+    assert_eq!(same_encoding.status(), StatusCode::NOT_MODIFIED);
+
+    let other_encoding = get(&assets, &[(IF_NONE_MATCH, &brotli_etag)]).await;
+    assert_eq!(other_encoding.status(), StatusCode::OK);
+}
@@ -0,0 +1,50 @@
+//! Make sure static pages are rendered without hydration or client side code
+#![cfg(feature = "axum")]
+
+use dioxus::prelude::*;
+
+mod common;
+
+const INDEX: &str = r#"<!DOCTYPE html><html><head><link rel="modulepreload" href="/app.js"><script type="module">import init from "/app.js"; init();</script></head><body><div id="main"></div></body></html>"#;
+
+fn app() -> Element {
+    rsx! { div { onclick: |_| {}, "Terms of service" } }
+}
+
+fn self_static_app() -> Element {
+    if let Some(context) = use_server_context() {
+        context.set_static();
+    }
+    app()
+}
+
+async fn page(cfg: ServeConfigBuilder, app: fn() -> Element, path: &str) -> String {
+    let cfg = cfg.index_html(INDEX.to_string()).build().unwrap();
+    common::render_page(cfg, app, path).await
+}
+
+fn assert_static(page: &str) {
+    assert!(page.contains("Terms of service"), "{page}");
+    assert!(!page.contains("<script"), "{page}");
+    assert!(!page.contains("modulepreload"), "{page}");
+    assert!(!page.contains("data-node-hydration"), "{page}");
+}
+
+#[tokio::test]
+async fn static_routes_ship_no_client_code() {
+    let cfg = ServeConfig::builder().static_routes(|path| path == "/terms");
+    assert_static(&page(cfg, app, "/terms?lang=en").await);
+}
+
+#[tokio::test]
+async fn components_can_make_their_page_static() {
+    assert_static(&page(ServeConfig::builder(), self_static_app, "/").await);
+}
+
+#[tokio::test]
+async fn other_pages_are_hydrated() {
+    let cfg = ServeConfig::builder().static_routes(|path| path == "/terms");
+    let page = page(cfg, app, "/").await;
+    assert!(page.contains("<script"));
+    assert!(page.contains("data-node-hydration"));
+}
@@ -0,0 +1,43 @@
+//! Make sure byte range requests for static assets are served from the uncompressed file
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus_fullstack::prelude::*;
+use http::{
+    header::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_RANGE, RANGE},
+    Request, StatusCode,
+};
+
+mod common;
+use common::PublicAssets;
+
+/// The contents of the asset, where every byte is its offset in the file modulo 256
+fn contents() -> Vec<u8> {
+    (0..300).map(|byte| byte as u8).collect()
+}
+
+#[tokio::test]
+async fn ranges_are_served_from_the_identity_encoding() {
+    let assets = PublicAssets::new("range-assets");
+    assets.write("video.bin", contents());
+    // The content doesn't need to be valid brotli, the server only serves the file
+    assets.write("video.bin.br", "compressed");
+
+    let response = common::respond(
+        Router::new().serve_static_assets(),
+        Request::get(assets.route("video.bin"))
+            .header(RANGE, "bytes=100-199")
+            .header(ACCEPT_ENCODING, "br")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.headers()[CONTENT_RANGE], "bytes 100-199/300");
+    assert!(response.headers().get(CONTENT_ENCODING).is_none());
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, contents()[100..200]);
+}
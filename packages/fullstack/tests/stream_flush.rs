@@ -0,0 +1,52 @@
+//! Make sure the flush strategy controls how many chunks a render is sent in
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use dioxus::prelude::*;
+use futures_util::StreamExt;
+use http::Request;
+use std::time::Duration;
+
+mod common;
+
+fn app() -> Element {
+    rsx! { "Hello World" }
+}
+
+async fn chunks(strategy: StreamFlushStrategy) -> Vec<String> {
+    let cfg = common::serve_config()
+        .stream_flush_strategy(strategy)
+        .build()
+        .unwrap();
+    let response = common::respond(
+        common::render_router(cfg, app),
+        Request::get("/").body(Body::empty()).unwrap(),
+    )
+    .await;
+    response
+        .into_body()
+        .into_data_stream()
+        .map(|chunk| String::from_utf8(chunk.unwrap().to_vec()).unwrap())
+        .collect()
+        .await
+}
+
+#[tokio::test]
+async fn every_boundary_sends_chunks_separately() {
+    assert!(chunks(StreamFlushStrategy::EveryBoundary).await.len() > 1);
+}
+
+#[tokio::test]
+async fn never_sends_one_chunk() {
+    let chunks = chunks(StreamFlushStrategy::Never).await;
+    assert_eq!(chunks.len(), 1);
+    assert!(chunks[0].contains("Hello World"));
+    assert!(chunks[0].ends_with("</html>"));
+}
+
+#[tokio::test]
+async fn coalesce_joins_chunks_within_the_window() {
+    let coalesced = chunks(StreamFlushStrategy::Coalesce(Duration::from_secs(1))).await;
+    assert_eq!(coalesced.len(), 1);
+    assert!(coalesced[0].contains("Hello World"));
+}
@@ -0,0 +1,53 @@
+//! Make sure the server logs how long every suspense boundary took to resolve
+#![cfg(feature = "axum")]
+
+use dioxus::prelude::*;
+use std::time::Duration;
+
+mod common;
+
+fn app() -> Element {
+    rsx! { CommentSection {} }
+}
+
+#[component]
+fn CommentSection() -> Element {
+    rsx! {
+        SuspenseBoundary { fallback: |_| rsx! { "Loading comments" },
+            Comments {}
+        }
+    }
+}
+
+#[component]
+fn Comments() -> Element {
+    let comments = use_server_future(|| async {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        "First!".to_string()
+    })?;
+    rsx! { "{comments:?}" }
+}
+
+#[tokio::test]
+async fn resolved_boundaries_are_logged_with_the_component_that_renders_them() {
+    common::capture_logs();
+    let page = common::render_page(common::serve_config().build().unwrap(), app, "/comments").await;
+    assert!(page.contains("First!"), "{page}");
+
+    let logs = common::logs();
+    let line = logs
+        .lines()
+        .find(|line| line.contains("Resolved suspense boundary") && line.contains("/comments"))
+        .unwrap_or_else(|| panic!("{logs}"));
+    assert!(
+        line.contains(r#"boundary="suspense_timing::CommentSection""#),
+        "{line}"
+    );
+    let duration_ms: f64 = line
+        .split("duration_ms=")
+        .nth(1)
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|duration| duration.parse().ok())
+        .unwrap_or_else(|| panic!("{line}"));
+    assert!(duration_ms >= 50., "{line}");
+}
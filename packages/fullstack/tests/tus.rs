@@ -0,0 +1,192 @@
+//! Make sure files can be uploaded in chunks with the tus protocol
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, Router};
+use dioxus_fullstack::prelude::*;
+use http::{
+    header::{CONTENT_LENGTH, CONTENT_TYPE, LOCATION},
+    Request, StatusCode,
+};
+use std::sync::{Arc, Mutex};
+use tower::ServiceExt;
+
+/// The name and contents of every completed upload
+type Completed = Arc<Mutex<Vec<(String, Vec<u8>)>>>;
+
+/// A router that accepts uploads at `/uploads` and records the contents of every completed upload
+fn router(name: &str) -> (Router, Completed) {
+    let directory = std::env::temp_dir().join(format!("dioxus-tus-{name}-{}", std::process::id()));
+    let completed = Arc::new(Mutex::new(Vec::new()));
+    let uploads = ResumableUploads::new(DirectoryUploadStore::new(directory), {
+        let completed = completed.clone();
+        move |upload: CompletedUpload| {
+            let completed = completed.clone();
+            async move {
+                // The callback runs inside the server context of the last chunk
+                let user = server_context().request_parts().headers["x-user"]
+                    .to_str()
+                    .unwrap()
+                    .to_string();
+                let contents = tokio::fs::read(&upload.path).await?;
+                completed.lock().unwrap().push((
+                    format!("{} {}", upload.metadata["filename"], user),
+                    contents,
+                ));
+                Ok(())
+            }
+        }
+    })
+    .max_size(1024);
+    (
+        Router::new().with_resumable_uploads("/uploads", uploads),
+        completed,
+    )
+}
+
+async fn create(router: &Router, length: u64) -> String {
+    let response = router
+        .clone()
+        .oneshot(
+            Request::post("/uploads")
+                .header("tus-resumable", "1.0.0")
+                .header("upload-length", length)
+                // "filename" is "notes.txt"
+                .header("upload-metadata", "filename bm90ZXMudHh0")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    response.headers()[LOCATION].to_str().unwrap().to_string()
+}
+
+async fn patch(router: &Router, location: &str, offset: u64, chunk: &'static str) -> StatusCode {
+    router
+        .clone()
+        .oneshot(
+            Request::patch(location)
+                .header("tus-resumable", "1.0.0")
+                .header("upload-offset", offset)
+                .header("x-user", "ferris")
+                .header(CONTENT_TYPE, "application/offset+octet-stream")
+                .body(Body::from(chunk))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+}
+
+async fn offset(router: &Router, location: &str) -> u64 {
+    let response = router
+        .clone()
+        .oneshot(
+            Request::head(location)
+                .header("tus-resumable", "1.0.0")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    response.headers()["upload-offset"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn uploads_resume_from_the_offset() {
+    let (router, completed) = router("resume");
+    let location = create(&router, 11).await;
+    assert!(location.starts_with("/uploads/"));
+
+    assert_eq!(
+        patch(&router, &location, 0, "hello ").await,
+        StatusCode::NO_CONTENT
+    );
+    assert_eq!(offset(&router, &location).await, 6);
+    assert!(completed.lock().unwrap().is_empty());
+
+    assert_eq!(
+        patch(&router, &location, 6, "world").await,
+        StatusCode::NO_CONTENT
+    );
+    assert_eq!(
+        *completed.lock().unwrap(),
+        [("notes.txt ferris".to_string(), b"hello world".to_vec())]
+    );
+}
+
+#[tokio::test]
+async fn chunks_at_the_wrong_offset_conflict() {
+    let (router, _) = router("conflict");
+    let location = create(&router, 11).await;
+    assert_eq!(
+        patch(&router, &location, 3, "hello").await,
+        StatusCode::CONFLICT
+    );
+    assert_eq!(offset(&router, &location).await, 0);
+}
+
+#[tokio::test]
+async fn content_lengths_that_overflow_the_offset_are_rejected() {
+    let (router, completed) = router("overflow");
+    let location = create(&router, 11).await;
+    assert_eq!(
+        patch(&router, &location, 0, "hello ").await,
+        StatusCode::NO_CONTENT
+    );
+
+    let response = router
+        .clone()
+        .oneshot(
+            Request::patch(&location)
+                .header("tus-resumable", "1.0.0")
+                .header("upload-offset", 6)
+                .header(CONTENT_LENGTH, u64::MAX - 2)
+                .header(CONTENT_TYPE, "application/offset+octet-stream")
+                .body(Body::from("world"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(offset(&router, &location).await, 6);
+    assert!(completed.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn uploads_over_the_max_size_are_rejected() {
+    let (router, _) = router("max_size");
+    let response = router
+        .oneshot(
+            Request::post("/uploads")
+                .header("tus-resumable", "1.0.0")
+                .header("upload-length", 4096)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn other_protocol_versions_are_rejected() {
+    let (router, _) = router("version");
+    let response = router
+        .oneshot(
+            Request::post("/uploads")
+                .header("tus-resumable", "0.2.2")
+                .header("upload-length", 11)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+    assert_eq!(response.headers()["tus-version"], "1.0.0");
+}
@@ -0,0 +1,24 @@
+//! Make sure values that are not `Clone` can be shared through the server context
+#![cfg(feature = "axum")]
+
+use dioxus_fullstack::prelude::*;
+use std::sync::Arc;
+
+struct User {
+    name: String,
+}
+
+#[test]
+fn arc_values_are_shared_between_clones() {
+    let (parts, _) = http::Request::new(()).into_parts();
+    let context = DioxusServerContext::new(parts);
+    assert!(context.get_arc::<User>().is_none());
+
+    context.clone().insert_arc(User {
+        name: "Alice".to_string(),
+    });
+    let first = context.get_arc::<User>().unwrap();
+    let second = context.clone().get_arc::<User>().unwrap();
+    assert_eq!(first.name, "Alice");
+    assert!(Arc::ptr_eq(&first, &second));
+}
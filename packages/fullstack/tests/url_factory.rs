@@ -0,0 +1,40 @@
+//! Make sure the url-aware virtual dom factory receives the url of the request
+#![cfg(feature = "axum")]
+
+use axum::{body::Body, routing::get, Router};
+use dioxus::prelude::*;
+use http::Request;
+use tower::ServiceExt;
+
+mod common;
+
+#[derive(Clone)]
+struct InitialRoute(String);
+
+fn app() -> Element {
+    let InitialRoute(route) = use_context();
+    rsx! { "route={route}" }
+}
+
+#[tokio::test]
+async fn factory_receives_the_path_and_query() {
+    let cfg = common::serve_config().build().unwrap();
+    let state = RenderHandleState::new_with_url_aware_virtual_dom_factory(cfg, |url| {
+        VirtualDom::new(app).with_root_context(InitialRoute(url.to_string()))
+    });
+    let response = Router::new()
+        .fallback(get(render_handler).with_state(state))
+        .oneshot(
+            Request::get("/blog/post?page=2")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert!(String::from_utf8(body.to_vec())
+        .unwrap()
+        .contains("route=/blog/post?page=2"));
+}
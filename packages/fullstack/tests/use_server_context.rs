@@ -0,0 +1,42 @@
+//! Make sure components can read the request they render through `use_server_context`
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use dioxus::prelude::*;
+use http::{header::ACCEPT_LANGUAGE, Request};
+
+mod common;
+
+fn app() -> Element {
+    let language = use_server_context()
+        .and_then(|context| {
+            let parts = context.request_parts();
+            Some(
+                parts
+                    .headers
+                    .get(ACCEPT_LANGUAGE)?
+                    .to_str()
+                    .ok()?
+                    .to_string(),
+            )
+        })
+        .unwrap_or_default();
+    rsx! { "language={language}" }
+}
+
+#[tokio::test]
+async fn components_read_the_request_headers() {
+    let cfg = common::serve_config().build().unwrap();
+    let response = common::respond(
+        common::render_router(cfg, app),
+        Request::get("/")
+            .header(ACCEPT_LANGUAGE, "fr-CH")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert!(String::from_utf8_lossy(&body).contains("language=fr-CH"));
+}
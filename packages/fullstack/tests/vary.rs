@@ -0,0 +1,46 @@
+//! Make sure responses list the request headers they depend on in a single `Vary` header
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use dioxus::prelude::*;
+use http::{header::VARY, Request};
+
+mod common;
+
+fn plain() -> Element {
+    rsx! { "Hello World" }
+}
+
+fn personalized() -> Element {
+    if let Some(context) = use_server_context() {
+        context.add_vary("Accept-Language");
+        context.add_vary("Cookie");
+        context.add_vary("accept-language");
+    }
+    rsx! { "Hello World" }
+}
+
+async fn vary(app: fn() -> Element) -> Vec<String> {
+    let cfg = common::serve_config().build().unwrap();
+    let response = common::respond(
+        common::render_router(cfg, app),
+        Request::get("/").body(Body::empty()).unwrap(),
+    )
+    .await;
+    response
+        .headers()
+        .get_all(VARY)
+        .iter()
+        .map(|vary| vary.to_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn responses_do_not_vary_by_default() {
+    assert!(vary(plain).await.is_empty());
+}
+
+#[tokio::test]
+async fn registered_headers_are_combined() {
+    assert_eq!(vary(personalized).await, ["accept-language, cookie"]);
+}
@@ -0,0 +1,67 @@
+//! Make sure the drop hook runs for the virtual dom of every rendered page
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use dioxus::prelude::*;
+use http::Request;
+use std::time::Duration;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+mod common;
+
+/// The number of items a page rendered, read back from the virtual dom when it is dropped
+#[derive(Clone, Copy)]
+struct RenderedItems(usize);
+
+fn app() -> Element {
+    let items = 3;
+    use_hook(|| provide_context(RenderedItems(items)));
+    rsx! {
+        for i in 0..items {
+            p { "Item {i}" }
+        }
+    }
+}
+
+/// Render the page at the path and return the page with a receiver for the path and items the drop hook saw
+async fn render(path: &str) -> (axum::response::Response, UnboundedReceiver<(String, usize)>) {
+    let (dropped, receiver) = unbounded_channel();
+    let cfg = common::serve_config()
+        .on_virtual_dom_drop(move |dom| {
+            let path = server_context().request_parts().uri.path().to_string();
+            let items = dom.in_runtime(|| ScopeId::APP.consume_context::<RenderedItems>());
+            _ = dropped.send((path, items.map_or(0, |items| items.0)));
+        })
+        .build()
+        .unwrap();
+    let response = common::respond(
+        common::render_router(cfg, app),
+        Request::get(path).body(Body::empty()).unwrap(),
+    )
+    .await;
+    (response, receiver)
+}
+
+async fn next_drop(receiver: &mut UnboundedReceiver<(String, usize)>) -> (String, usize) {
+    tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+        .await
+        .expect("the drop hook should run")
+        .unwrap()
+}
+
+#[tokio::test]
+async fn the_hook_runs_once_the_page_is_rendered() {
+    let (response, mut dropped) = render("/items").await;
+    let page = common::body_string(response).await;
+    assert!(page.contains("Item 2"), "{page}");
+    assert_eq!(next_drop(&mut dropped).await, ("/items".to_string(), 3));
+    // The hook only runs once per page
+    assert!(dropped.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn the_hook_runs_when_the_client_disconnects() {
+    let (response, mut dropped) = render("/cancelled").await;
+    drop(response);
+    assert_eq!(next_drop(&mut dropped).await.0, "/cancelled");
+}
@@ -0,0 +1,50 @@
+//! Make sure the warp filters render the application and reject requests they don't handle
+#![cfg(feature = "warp")]
+
+use dioxus::prelude::*;
+use dioxus_fullstack::prelude::*;
+use warp::Filter;
+
+fn app() -> Element {
+    rsx! { "Hello from warp" }
+}
+
+fn state() -> RenderHandleState {
+    let cfg = ServeConfig::builder()
+        .index_html(
+            r#"<!DOCTYPE html><html><head><title>Warp</title></head><body><div id="main"></div></body></html>"#
+                .to_string(),
+        )
+        .build()
+        .unwrap();
+    RenderHandleState::new(cfg, app)
+}
+
+#[tokio::test]
+async fn renders_the_application() {
+    let filter = dioxus_fullstack::warp::render_filter(state());
+
+    let response = warp::test::request()
+        .path("/")
+        .header("accept", "text/html")
+        .reply(&filter)
+        .await;
+
+    assert_eq!(response.status(), 200);
+    let body = String::from_utf8_lossy(response.body());
+    assert!(body.contains("Hello from warp"), "{body}");
+}
+
+#[tokio::test]
+async fn falls_through_to_other_filters() {
+    let other = warp::path!("api" / "health").map(|| "ok");
+    let filter = dioxus_fullstack::warp::server_fn_filter().or(other);
+
+    let response = warp::test::request()
+        .path("/api/health")
+        .reply(&filter)
+        .await;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.body(), "ok");
+}
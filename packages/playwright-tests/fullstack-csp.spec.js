@@ -0,0 +1,55 @@
+// @ts-check
+const { test, expect } = require("@playwright/test");
+
+test("hydration under a strict content security policy", async ({ page }) => {
+  const violations = [];
+  page.on("console", (message) => {
+    if (message.text().includes("Content Security Policy")) {
+      violations.push(message.text());
+    }
+  });
+
+  const response = await page.goto("http://localhost:6060");
+
+  // Expect the page to be sent with a nonce based policy
+  const policy = (await response?.headerValue("content-security-policy")) ?? "";
+  const nonce = policy.match(/'nonce-([^']+)'/)?.[1];
+  expect(nonce).toBeTruthy();
+
+  // Every script in the page must carry the nonce from the header
+  const scripts = await page.locator("script").evaluateAll((scripts) =>
+    scripts.map((script) => script.nonce)
+  );
+  expect(scripts.length).toBeGreaterThan(0);
+  for (const scriptNonce of scripts) {
+    expect(scriptNonce).toBe(nonce);
+  }
+
+  // Expect the streamed suspense boundary to resolve
+  const main = page.locator("#main");
+  await expect(main).toContainText("Hello from a streamed suspense boundary!");
+
+  // The initial page should be hydrated
+  await expect(main).toContainText("count: 0");
+  await page.locator("button.increment-button").click();
+  await expect(main).toContainText("count: 1");
+
+  // The streamed suspense boundary should be hydrated
+  await page.locator("button.streamed-button").click();
+  await expect(page.locator("#streamed-clicked")).toContainText(
+    "streamed boundary hydrated"
+  );
+
+  expect(violations).toEqual([]);
+});
+
+test("every response gets a new nonce", async ({ request }) => {
+  const nonce = async () => {
+    const response = await request.get("http://localhost:6060", {
+      headers: { Accept: "text/html" },
+    });
+    return response.headers()["content-security-policy"];
+  };
+
+  expect(await nonce()).not.toEqual(await nonce());
+});
@@ -0,0 +1,55 @@
+// This test is used by playwright configured in the root of the repo
+// Tests:
+// - SSR with a strict nonce based content security policy
+// - Hydration of the initial page under the policy
+// - Hydration of streamed suspense boundaries under the policy
+
+#![allow(non_snake_case)]
+use dioxus::prelude::*;
+
+fn main() {
+    #[allow(unused_mut)]
+    let mut builder = LaunchBuilder::new();
+
+    #[cfg(feature = "server")]
+    {
+        builder = builder.with_cfg(ServeConfigBuilder::default().strict_csp(true));
+    }
+
+    builder.launch(app);
+}
+
+fn app() -> Element {
+    let mut count = use_signal(|| 0);
+
+    rsx! {
+        h1 { "count: {count}" }
+        button { class: "increment-button", onclick: move |_| count += 1, "Increment" }
+        SuspenseBoundary {
+            fallback: |_: SuspenseContext| rsx! {
+                div { "Loading..." }
+            },
+            Streamed {}
+        }
+    }
+}
+
+#[component]
+fn Streamed() -> Element {
+    let message = use_server_future(slow_message)?;
+    let mut clicked = use_signal(|| false);
+
+    rsx! {
+        div { id: "streamed", "{message().unwrap().unwrap()}" }
+        button { class: "streamed-button", onclick: move |_| clicked.set(true), "Click" }
+        if clicked() {
+            div { id: "streamed-clicked", "streamed boundary hydrated" }
+        }
+    }
+}
+
+#[server]
+async fn slow_message() -> Result<String, ServerFnError> {
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    Ok("Hello from a streamed suspense boundary!".to_string())
+}
@@ -6,6 +6,7 @@
 //! See the [server_fn_macro] crate for more information.
 
 use proc_macro::TokenStream;
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
 use server_fn_macro::server_macro_impl;
 use syn::__private::ToTokens;
 
@@ -48,6 +49,9 @@ use syn::__private::ToTokens;
 ///     - `"GetCbor"`: `GET` request with URL-encoded arguments and CBOR response
 /// - `req` and `res` specify the HTTP request and response types to be used on the server (these
 ///   should usually only be necessary if you are integrating with a server other than Actix/Axum)
+/// - `guard`: the path to a function that decides if a request may call the server function. The guard
+///   runs against the server context before the server function and rejects the request with the status
+///   code it returns (see [Guarding server functions](#guarding-server-functions))
 /// ```rust,ignore
 /// #[server(
 ///   name = SomeStructName,
@@ -98,6 +102,33 @@ use syn::__private::ToTokens;
 /// }
 /// ```
 ///
+/// ## Guarding server functions
+///
+/// A guard is a function that takes the `DioxusServerContext` of the request and returns
+/// `Result<(), StatusCode>`. If the guard returns an error, the server responds with that status
+/// code without running the server function. Guards let you check authorization in one place
+/// instead of at the top of every server function:
+///
+/// ```rust,ignore
+/// # use dioxus::prelude::*;
+/// #[cfg(feature = "server")]
+/// fn require_admin(context: &DioxusServerContext) -> Result<(), http::StatusCode> {
+///     let is_admin = context
+///         .request_parts()
+///         .headers
+///         .get("cookie")
+///         .and_then(|cookie| cookie.to_str().ok())
+///         .is_some_and(|cookie| cookie.contains("role=admin"));
+///     is_admin.then_some(()).ok_or(http::StatusCode::FORBIDDEN)
+/// }
+///
+/// #[server(guard = "require_admin")]
+/// pub async fn delete_user(id: u32) -> Result<(), ServerFnError> {
+///     // Only admins get here
+///     Ok(())
+/// }
+/// ```
+///
 /// ## Extracting additional data from requests
 ///
 /// Server functions automatically handle serialization and deserialization of arguments and responses.
@@ -143,8 +174,12 @@ use syn::__private::ToTokens;
 /// ```
 #[proc_macro_attribute]
 pub fn server(args: proc_macro::TokenStream, s: TokenStream) -> TokenStream {
+    let (args, guard) = match take_guard(args.into()) {
+        Ok(split) => split,
+        Err(e) => return e.to_compile_error().into(),
+    };
     match server_macro_impl(
-        args.into(),
+        args,
         s.into(),
         Some(syn::parse_quote!(server_fn)),
         "/api",
@@ -152,6 +187,91 @@ pub fn server(args: proc_macro::TokenStream, s: TokenStream) -> TokenStream {
         None,
     ) {
         Err(e) => e.to_compile_error().into(),
-        Ok(s) => s.to_token_stream().into(),
+        Ok(s) => match guard {
+            Some(guard) => match register_guard(&s, &guard) {
+                Ok(registration) => quote::quote! { #s #registration }.into(),
+                Err(e) => e.to_compile_error().into(),
+            },
+            None => s.to_token_stream().into(),
+        },
+    }
+}
+
+/// Remove the `guard = "..."` argument from the arguments of the server macro. The rest of the
+/// arguments are passed to the server_fn macro unchanged
+fn take_guard(args: TokenStream2) -> syn::Result<(TokenStream2, Option<syn::Path>)> {
+    let mut rest = Vec::new();
+    let mut guard = None;
+    for arg in split_args(args) {
+        match arg.as_slice() {
+            [TokenTree::Ident(ident), TokenTree::Punct(eq), value @ ..]
+                if ident == "guard" && eq.as_char() == '=' =>
+            {
+                let value: TokenStream2 = value.iter().cloned().collect();
+                let path = match syn::parse2::<syn::LitStr>(value.clone()) {
+                    Ok(lit) => lit.parse()?,
+                    Err(_) => syn::parse2(value)?,
+                };
+                if guard.replace(path).is_some() {
+                    return Err(syn::Error::new(ident.span(), "guard can only be set once"));
+                }
+            }
+            _ => rest.push(arg.into_iter().collect::<TokenStream2>()),
+        }
+    }
+    Ok((quote::quote! { #(#rest),* }, guard))
+}
+
+/// Split the arguments at the commas that are not nested inside generics or groups
+fn split_args(args: TokenStream2) -> Vec<Vec<TokenTree>> {
+    let mut split = vec![Vec::new()];
+    let mut depth = 0usize;
+    for token in args {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' && depth == 0 => {
+                split.push(Vec::new());
+                continue;
+            }
+            TokenTree::Punct(punct) if punct.as_char() == '<' => depth += 1,
+            TokenTree::Punct(punct) if punct.as_char() == '>' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        split.last_mut().unwrap().push(token);
+    }
+    split.retain(|arg| !arg.is_empty());
+    split
+}
+
+/// Register the guard for the path of the server function the server_fn macro generated
+fn register_guard(expanded: &TokenStream2, guard: &syn::Path) -> syn::Result<TokenStream2> {
+    let file: syn::File = syn::parse2(expanded.clone())?;
+    let server_fn = file
+        .items
+        .iter()
+        .find_map(|item| match item {
+            syn::Item::Impl(item) => item
+                .trait_
+                .as_ref()
+                .filter(|(_, path, _)| {
+                    path.segments
+                        .last()
+                        .is_some_and(|segment| segment.ident == "ServerFn")
+                })
+                .map(|_| &item.self_ty),
+            _ => None,
+        })
+        .ok_or_else(|| {
+            syn::Error::new_spanned(guard, "could not find the server function to guard")
+        })?;
+
+    // The guard only runs on the server
+    if cfg!(feature = "server") {
+        Ok(quote::quote! {
+            server_fn::inventory::submit! {
+                ServerFnGuard::new(<#server_fn as server_fn::ServerFn>::PATH, #guard)
+            }
+        })
+    } else {
+        Ok(TokenStream2::new())
     }
 }
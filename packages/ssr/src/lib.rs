@@ -8,6 +8,7 @@ pub mod renderer;
 pub mod template;
 
 use dioxus_core::{Element, VirtualDom};
+use std::any::Any;
 
 pub use crate::renderer::Renderer;
 
@@ -25,6 +26,47 @@ pub fn render(dom: &VirtualDom) -> String {
     Renderer::new().render(dom)
 }
 
+/// The maximum number of times [`render_to_string_with_context`] re-renders the dom while it waits for it to settle
+const MAX_SETTLE_RENDERS: usize = 64;
+
+/// A convenience function to render a component to a string with some contexts provided to the root of the app
+///
+/// The dom is rebuilt and then re-rendered until synchronous effects and tasks stop changing it, so state that is set in
+/// an effect shows up in the output. Suspended components render their suspense fallback instead of waiting for their
+/// futures. This is useful for snapshot testing components with an injected theme or locale.
+///
+/// ```rust
+/// # use dioxus::prelude::*;
+/// #[derive(Clone)]
+/// struct Theme(&'static str);
+///
+/// fn app() -> Element {
+///     let theme: Theme = use_context();
+///     rsx! { div { class: theme.0 } }
+/// }
+///
+/// let html = dioxus_ssr::render_to_string_with_context(app, vec![Box::new(Theme("dark"))]);
+/// assert_eq!(html, r#"<div class="dark"></div>"#);
+/// ```
+pub fn render_to_string_with_context(root: fn() -> Element, contexts: Vec<Box<dyn Any>>) -> String {
+    let mut dom = VirtualDom::new(root);
+    for context in contexts {
+        dom.insert_any_root_context(context);
+    }
+    dom.rebuild_in_place();
+
+    // Run any effects and tasks that are ready and re-render until the output stops changing. Components that change
+    // every render are cut off after a fixed number of renders so this never hangs
+    for _ in 0..MAX_SETTLE_RENDERS {
+        dom.process_events();
+        if dom.render_immediate_to_vec().edits.is_empty() {
+            break;
+        }
+    }
+
+    render(&dom)
+}
+
 /// A convenience function to pre-render an existing VirtualDom to a string
 ///
 /// We generally recommend creating a new `Renderer` to take advantage of template caching.
@@ -88,6 +88,11 @@ impl Renderer {
         self.dynamic_node_id = 0;
     }
 
+    /// The number of templates the renderer has cached
+    pub fn cached_templates(&self) -> usize {
+        self.template_cache.len()
+    }
+
     pub fn render_scope<W: Write + ?Sized>(
         &mut self,
         buf: &mut W,
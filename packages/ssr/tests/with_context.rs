@@ -0,0 +1,50 @@
+use dioxus::prelude::*;
+
+#[derive(Clone)]
+struct Locale(&'static str);
+
+#[test]
+fn contexts_are_provided_to_the_root() {
+    fn app() -> Element {
+        let locale: Locale = use_context();
+        rsx! { p { "{locale.0}" } }
+    }
+
+    assert_eq!(
+        dioxus_ssr::render_to_string_with_context(app, vec![Box::new(Locale("fr"))]),
+        "<p>fr</p>"
+    );
+}
+
+#[test]
+fn effects_settle_before_rendering() {
+    fn app() -> Element {
+        let mut loaded = use_signal(|| false);
+        use_effect(move || loaded.set(true));
+        rsx! { "loaded: {loaded}" }
+    }
+
+    assert_eq!(
+        dioxus_ssr::render_to_string_with_context(app, Vec::new()),
+        "loaded: true"
+    );
+}
+
+#[test]
+fn suspense_renders_the_fallback() {
+    fn app() -> Element {
+        rsx! {
+            SuspenseBoundary { fallback: |_| rsx! { "loading" },
+                Pending {}
+            }
+        }
+    }
+
+    #[component]
+    fn Pending() -> Element {
+        let value = use_resource(|| std::future::pending::<()>()).suspend()?;
+        rsx! { "{value:?}" }
+    }
+
+    assert!(dioxus_ssr::render_to_string_with_context(app, Vec::new()).contains("loading"));
+}
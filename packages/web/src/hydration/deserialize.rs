@@ -18,6 +18,12 @@ pub fn take_server_data<T: DeserializeOwned>() -> Result<Option<T>, TakeDataErro
     })
 }
 
+/// Check if the components that are running are hydrating html the server rendered. This is only true during the
+/// first run of components before hydration, when [`take_server_data`] can read the data the server sent down.
+pub fn is_hydrating() -> bool {
+    SERVER_DATA.with_borrow(|data| data.is_some())
+}
+
 /// Run a closure with the server data
 pub(crate) fn with_server_data<O>(server_data: HTMLDataCursor, f: impl FnOnce() -> O) -> O {
     // Set the server data that will be used during hydration
@@ -27,6 +27,49 @@ pub(crate) enum RehydrationError {
     ElementNotFound,
 }
 
+/// Compare the shape of the tree the server rendered with the tree the client hydrated and warn about the first node that differs
+#[cfg(debug_assertions)]
+fn warn_about_hydration_mismatch(vdom: &VirtualDom) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let Ok(server_paths) = js_sys::Reflect::get(&window, &"dioxus_hydration_paths".into()) else {
+        return;
+    };
+    // The server only sends the paths from debug builds
+    if !server_paths.is_array() {
+        return;
+    }
+    let server_paths: Vec<String> = js_sys::Array::from(&server_paths)
+        .iter()
+        .filter_map(|path| path.as_string())
+        .collect();
+    let client_paths = dioxus_core::internal::hydration_node_paths(vdom);
+
+    let mismatch = server_paths
+        .iter()
+        .zip(client_paths.iter())
+        .position(|(server, client)| server != client);
+    match mismatch {
+        Some(index) => tracing::warn!(
+            "Hydration mismatch at node {index}: the server rendered `{}` but the client rendered `{}`. Make sure the first render on the client matches the server render.",
+            server_paths[index],
+            client_paths[index]
+        ),
+        None if server_paths.len() > client_paths.len() => tracing::warn!(
+            "Hydration mismatch at node {}: the server rendered `{}` but the client rendered nothing there. Make sure the first render on the client matches the server render.",
+            client_paths.len(),
+            server_paths[client_paths.len()]
+        ),
+        None if client_paths.len() > server_paths.len() => tracing::warn!(
+            "Hydration mismatch at node {}: the client rendered `{}` but the server rendered nothing there. Make sure the first render on the client matches the server render.",
+            server_paths.len(),
+            client_paths[server_paths.len()]
+        ),
+        None => {}
+    }
+}
+
 #[derive(Debug)]
 struct SuspenseHydrationIdsNode {
     /// The scope id of the suspense boundary
@@ -220,6 +263,9 @@ impl WebsysDom {
         // Any suspense boundaries we run into are stored for hydration later.
         self.start_hydration_at_scope(vdom.base_scope(), vdom, vec![self.root.clone()])?;
 
+        #[cfg(debug_assertions)]
+        warn_about_hydration_mismatch(vdom);
+
         Ok(rx)
     }
 
@@ -62,7 +62,7 @@ pub use hydration::*;
 /// let app_fut = dioxus_web::run_with_props(App, RootProps { name: String::from("foo") });
 /// wasm_bindgen_futures::spawn_local(app_fut);
 /// ```
-pub async fn run(mut virtual_dom: VirtualDom, web_config: Config) -> ! {
+pub async fn run(mut virtual_dom: VirtualDom, mut web_config: Config) -> ! {
     #[cfg(feature = "document")]
     virtual_dom.in_runtime(document::init_document);
 
@@ -76,7 +76,16 @@ pub async fn run(mut virtual_dom: VirtualDom, web_config: Config) -> ! {
 
     let runtime = virtual_dom.runtime();
 
-    let should_hydrate = web_config.hydrate;
+    // The server may skip rendering the app and only send down the html shell without any hydration data.
+    // If there is no hydration data, we need to render the app from scratch instead
+    let should_hydrate = web_config.hydrate && has_initial_hydration_data();
+
+    // A server rendered page names the element the server rendered into. Mount there so the ids can't drift apart
+    if let (cfg::ConfigRoot::RootName(rootname), Some(server_root_id)) =
+        (&mut web_config.root, server_root_id())
+    {
+        *rootname = server_root_id;
+    }
 
     let mut websys_dom = WebsysDom::new(web_config, runtime);
 
@@ -212,3 +221,36 @@ pub async fn run(mut virtual_dom: VirtualDom, web_config: Config) -> ! {
         websys_dom.flush_edits();
     }
 }
+
+/// Get the id of the root element the server rendered the page into, if the page was server rendered
+fn server_root_id() -> Option<String> {
+    #[wasm_bindgen::prelude::wasm_bindgen(inline_js = r#"
+        export function get_server_root_id() {
+            return typeof window.dioxus_root_id === "string" ? window.dioxus_root_id : undefined;
+        }
+    "#)]
+    extern "C" {
+        fn get_server_root_id() -> Option<String>;
+    }
+    get_server_root_id()
+}
+
+/// Check if the server sent down any initial data to hydrate the app with
+fn has_initial_hydration_data() -> bool {
+    #[cfg(feature = "hydrate")]
+    {
+        #[wasm_bindgen::prelude::wasm_bindgen(inline_js = r#"
+            export function initial_hydration_data_exists() {
+                return typeof window.initial_dioxus_hydration_data === "string";
+            }
+        "#)]
+        extern "C" {
+            fn initial_hydration_data_exists() -> bool;
+        }
+        initial_hydration_data_exists()
+    }
+    #[cfg(not(feature = "hydrate"))]
+    {
+        true
+    }
+}